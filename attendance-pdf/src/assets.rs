@@ -0,0 +1,168 @@
+// Loading and caching of raster assets (logos, sponsor images)
+
+use crate::net::{self, require_network};
+use crate::{AppError, LOGO_MAX_HEIGHT_MM, LOGO_MAX_WIDTH_MM};
+use ::image::imageops::FilterType;
+use ::image::{DynamicImage, Rgba, RgbImage};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Logos are downsampled to this resolution before embedding. printpdf keeps
+/// the whole document in memory until save and stores an uncompressed copy of
+/// the pixels per page, so an unbounded source image dominates memory use on
+/// long documents.
+const LOGO_MAX_DPI: f32 = 300.0;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// Raster image already flattened to RGB, ready to embed in any number of documents
+#[derive(Debug)]
+pub struct PreparedImage {
+    pub width_px: u32,
+    pub height_px: u32,
+    pub rgb: Vec<u8>,
+}
+
+/// Decoded assets shared across every document generated in one run.
+///
+/// Logos are fetched, decoded and composited once per source, and QR codes
+/// rasterized once per payload (see [`QrCache`]). Fonts are compiled into the
+/// binary; printpdf registers them with each document, so there is nothing
+/// to load between documents.
+#[derive(Default)]
+pub struct AssetCache {
+    images: HashMap<String, Arc<PreparedImage>>,
+    qr_codes: QrCache,
+}
+
+/// Rasterized QR codes keyed by payload, size and resolution. Clones share
+/// one store, so the event configs cloned from a run's base config reuse the
+/// feedback link and any sheet rendered more than once.
+#[derive(Clone, Default)]
+pub struct QrCache {
+    codes: Arc<Mutex<HashMap<String, Arc<PreparedImage>>>>,
+}
+
+// ============================================================================
+// Asset Cache
+// ============================================================================
+
+impl AssetCache {
+    /// Returns the prepared logo for `source`, loading it on first use
+    pub fn logo(&mut self, source: &str) -> Result<Arc<PreparedImage>, AppError> {
+        self.image(source, LOGO_MAX_WIDTH_MM, LOGO_MAX_HEIGHT_MM)
+    }
+
+    /// Returns `source` prepared to print within a `max_width_mm` by
+    /// `max_height_mm` box, loading it on first use
+    pub fn image(
+        &mut self,
+        source: &str,
+        max_width_mm: f32,
+        max_height_mm: f32,
+    ) -> Result<Arc<PreparedImage>, AppError> {
+        let key = format!("{}@{}x{}", source, max_width_mm, max_height_mm);
+        if let Some(image) = self.images.get(&key) {
+            return Ok(Arc::clone(image));
+        }
+        let loaded = downsample_to_dpi(load_logo(source)?, max_width_mm, max_height_mm);
+        let image = Arc::new(PreparedImage::from_rgba_on_white(&loaded));
+        self.images.insert(key, Arc::clone(&image));
+        Ok(image)
+    }
+
+    /// The QR code store to hand to every config of this run
+    pub fn qr_codes(&self) -> QrCache {
+        self.qr_codes.clone()
+    }
+}
+
+impl QrCache {
+    /// Returns the code stored under `key`, rasterizing it with `render` on
+    /// first use
+    pub fn get_or_render(
+        &self,
+        key: String,
+        render: impl FnOnce() -> Result<DynamicImage, AppError>,
+    ) -> Result<Arc<PreparedImage>, AppError> {
+        let mut codes = self.codes.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(code) = codes.get(&key) {
+            return Ok(Arc::clone(code));
+        }
+        let code = Arc::new(PreparedImage::from_opaque(&render()?));
+        codes.insert(key, Arc::clone(&code));
+        Ok(code)
+    }
+}
+
+impl PreparedImage {
+    /// Composite against a white background so transparent logos print cleanly
+    pub fn from_rgba_on_white(image: &DynamicImage) -> Self {
+        let rgba_image = image.to_rgba8();
+        let (width_px, height_px) = rgba_image.dimensions();
+
+        let mut rgb_image = RgbImage::new(width_px, height_px);
+        for (x, y, pixel) in rgba_image.enumerate_pixels() {
+            let Rgba([r, g, b, a]) = *pixel;
+            let alpha = a as f32 / 255.0;
+            let bg = 255.0; // White background
+            let out_r = (r as f32 * alpha + bg * (1.0 - alpha)) as u8;
+            let out_g = (g as f32 * alpha + bg * (1.0 - alpha)) as u8;
+            let out_b = (b as f32 * alpha + bg * (1.0 - alpha)) as u8;
+            rgb_image.put_pixel(x, y, ::image::Rgb([out_r, out_g, out_b]));
+        }
+
+        PreparedImage {
+            width_px,
+            height_px,
+            rgb: rgb_image.into_raw(),
+        }
+    }
+
+    /// Convert an opaque image (such as a rendered QR code) to raw RGB
+    pub fn from_opaque(image: &DynamicImage) -> Self {
+        let rgb_image = image.to_rgb8();
+        let (width_px, height_px) = rgb_image.dimensions();
+        PreparedImage {
+            width_px,
+            height_px,
+            rgb: rgb_image.into_raw(),
+        }
+    }
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+pub fn load_logo(source: &str) -> Result<DynamicImage, AppError> {
+    let image_bytes = if net::is_url(source) {
+        // Load from URL
+        require_network(source)?;
+        let response = net::call(net::get(source))
+            .map_err(|e| AppError::LogoError(format!("Failed to fetch URL: {}", e)))?;
+
+        net::read_bytes(response)
+            .map_err(|e| AppError::LogoError(format!("Failed to read response: {}", e)))?
+    } else {
+        // Load from file
+        std::fs::read(source)
+            .map_err(|e| AppError::LogoError(format!("{}: {}", source, e)))?
+    };
+
+    ::image::load_from_memory(&image_bytes)
+        .map_err(|e| AppError::LogoError(format!("Failed to decode image: {}", e)))
+}
+
+/// Shrink `image` so it has no more pixels than needed to print at
+/// `LOGO_MAX_DPI` within the given box; smaller images are returned unchanged
+fn downsample_to_dpi(image: DynamicImage, max_width_mm: f32, max_height_mm: f32) -> DynamicImage {
+    let max_width_px = (max_width_mm / 25.4 * LOGO_MAX_DPI).ceil() as u32;
+    let max_height_px = (max_height_mm / 25.4 * LOGO_MAX_DPI).ceil() as u32;
+    if image.width() <= max_width_px && image.height() <= max_height_px {
+        return image;
+    }
+    image.resize(max_width_px, max_height_px, FilterType::Triangle)
+}
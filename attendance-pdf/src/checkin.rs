@@ -0,0 +1,105 @@
+// Front-desk check-in: codes read from class passes are recorded in a
+// check-in log, one JSON line per student per event.
+//
+// Handheld QR scanners in keyboard mode type each decoded code followed by
+// Enter, so `scan` reads codes a line at a time from standard input.
+//
+// A webcam kiosk (`scan --live`) needs camera capture and QR decoding in
+// process (nokhwa and a decoder such as rqrr); neither crate is a dependency
+// yet, so until they are only keyboard-mode scanners are supported.
+
+use crate::AppError;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One student checked in at one event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckIn {
+    pub event_id: String,
+    /// Pass serial, "A1B2C3D4-007"
+    pub serial: String,
+    /// Name printed on the pass; blank passes have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student: Option<String>,
+    /// RFC 3339 local time of the scan
+    pub time: String,
+}
+
+impl CheckIn {
+    /// The student's name, or the pass serial for a blank pass
+    pub fn label(&self) -> &str {
+        self.student.as_deref().unwrap_or(&self.serial)
+    }
+}
+
+/// What a scanned code turned out to be
+#[derive(Debug, PartialEq)]
+pub enum ScanOutcome {
+    CheckedIn(CheckIn),
+    /// The same pass was already scanned for this event
+    AlreadyCheckedIn(CheckIn),
+}
+
+/// The fields of a pass QR code needed for a check-in
+#[derive(Deserialize)]
+struct ScannedPass {
+    app: String,
+    #[serde(rename = "type")]
+    kind: String,
+    serial: String,
+    student: Option<String>,
+}
+
+/// `checkins-<EVENT_ID>.jsonl`, next to the sheets
+pub fn default_log_path(event_id: &str) -> PathBuf {
+    PathBuf::from(format!("checkins-{}.jsonl", event_id))
+}
+
+/// Check-ins recorded so far; none when the log does not exist yet
+pub fn read_checkins(path: &Path) -> Result<Vec<CheckIn>, AppError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::CheckInError(format!("{}: {}", path.display(), e))),
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| AppError::CheckInError(format!("{} line {}: {}", path.display(), i + 1, e)))
+        })
+        .collect()
+}
+
+/// Record the pass in `code` as checked in to `event_id`, unless it already is
+pub fn record_scan(log: &Path, event_id: &str, code: &str, time: &str) -> Result<ScanOutcome, AppError> {
+    let pass: ScannedPass = serde_json::from_str(code.trim())
+        .ok()
+        .filter(|pass: &ScannedPass| pass.app == "bridge-attendance" && pass.kind == "pass")
+        .ok_or_else(|| AppError::CheckInError("not a class pass code".to_string()))?;
+
+    if let Some(existing) = read_checkins(log)?
+        .into_iter()
+        .find(|checkin| checkin.event_id == event_id && checkin.serial == pass.serial)
+    {
+        return Ok(ScanOutcome::AlreadyCheckedIn(existing));
+    }
+
+    let checkin = CheckIn {
+        event_id: event_id.to_string(),
+        serial: pass.serial,
+        student: pass.student,
+        time: time.to_string(),
+    };
+    let line = serde_json::to_string(&checkin).map_err(|e| AppError::CheckInError(e.to_string()))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .map_err(|e| AppError::CheckInError(format!("{}: {}", log.display(), e)))?;
+    writeln!(file, "{}", line).map_err(|e| AppError::CheckInError(format!("{}: {}", log.display(), e)))?;
+    Ok(ScanOutcome::CheckedIn(checkin))
+}
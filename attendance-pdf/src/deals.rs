@@ -0,0 +1,477 @@
+// Bridge deals from PBN and LIN files, drawn as hand diagrams: North at the
+// top, South at the bottom, West and East either side of a box giving the
+// board, dealer and vulnerability. Handouts and lesson plans build on
+// `layout_deal_diagram` and `layout_deal_pages`.
+//
+//     [Board "1"]
+//     [Dealer "N"]
+//     [Vulnerable "None"]
+//     [Deal "N:AKQ2.K73.Q85.A42 T96.QJ52.K94.KJ6 ..."]
+//
+// LIN, as saved by Bridge Base Online, gives the same deal as
+//
+//     md|3SJ85HAT9DAJT7CQ98,S743H864D632CT753,SAKQ2HK73DQ85CA42,|sv|o|ah|Board 1|
+
+use crate::layout::{FontStyle, Page, SheetLayout, BORDER_STROKE, OUTLINE_STYLE};
+use crate::{
+    AppError, HEADER_FONT_SIZE, MARGIN_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM, PAGE_WIDTH_MM, SMALL_FONT_SIZE,
+    TITLE_FONT_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Spades, hearts, diamonds, clubs: the order PBN lists a hand's suits in
+pub const SUIT_LETTERS: [&str; 4] = ["S", "H", "D", "C"];
+
+/// The same suits as symbols, set in [`FontStyle::Symbol`]
+pub const SUIT_SYMBOLS: [&str; 4] = ["\u{2660}", "\u{2665}", "\u{2666}", "\u{2663}"];
+
+const RANKS: &str = "AKQJT98765432";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Seat {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Seat {
+    /// Clockwise from North, as PBN deals are written
+    pub const ALL: [Seat; 4] = [Seat::North, Seat::East, Seat::South, Seat::West];
+
+    pub fn from_letter(letter: &str) -> Option<Seat> {
+        match letter.trim().to_ascii_uppercase().as_str() {
+            "N" => Some(Seat::North),
+            "E" => Some(Seat::East),
+            "S" => Some(Seat::South),
+            "W" => Some(Seat::West),
+            _ => None,
+        }
+    }
+
+    pub fn letter(self) -> &'static str {
+        match self {
+            Seat::North => "N",
+            Seat::East => "E",
+            Seat::South => "S",
+            Seat::West => "W",
+        }
+    }
+
+    fn index(self) -> usize {
+        Seat::ALL.iter().position(|seat| *seat == self).expect("every seat is listed")
+    }
+}
+
+/// One player's cards: the ranks held in each suit, spades first, as written
+/// in the file ("AKT2"); a hand the file leaves out has four empty suits
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hand {
+    pub suits: [String; 4],
+}
+
+impl Hand {
+    pub fn is_empty(&self) -> bool {
+        self.suits.iter().all(String::is_empty)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deal {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub board: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dealer: Option<Seat>,
+    /// "None", "NS", "EW" or "All"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerable: Option<String>,
+    /// North, East, South, West
+    pub hands: [Hand; 4],
+}
+
+impl Deal {
+    pub fn hand(&self, seat: Seat) -> &Hand {
+        &self.hands[seat.index()]
+    }
+}
+
+/// Vulnerability of boards 1 to 16 in the standard duplicate cycle
+const BOARD_VULNERABILITY: [&str; 16] = [
+    "None", "NS", "EW", "All", "NS", "EW", "All", "None", "EW", "All", "None", "NS", "All", "None", "NS", "EW",
+];
+
+/// Dealer of a numbered duplicate board: North deals board 1, then round
+/// the table
+pub fn board_dealer(board: u32) -> Seat {
+    Seat::ALL[(board.max(1) as usize - 1) % 4]
+}
+
+/// Vulnerability of a numbered duplicate board, as printed on the board
+pub fn board_vulnerability(board: u32) -> &'static str {
+    BOARD_VULNERABILITY[(board.max(1) as usize - 1) % 16]
+}
+
+/// Deals from a PBN file, or a LIN file when the name ends in `.lin`
+pub fn load_deals(path: &Path) -> Result<Vec<Deal>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::DealError(format!("{}: {}", path.display(), e)))?;
+    let is_lin = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lin"));
+    let deals = if is_lin { parse_lin_deals(&content) } else { parse_games(&content) };
+    let deals = deals.map_err(|e| AppError::DealError(format!("{}: {}", path.display(), e)))?;
+    if deals.is_empty() {
+        return Err(AppError::DealError(format!("{}: no deals found", path.display())));
+    }
+    Ok(deals)
+}
+
+/// Every game in `content` that has a `Deal` tag, in file order. Only the
+/// board, dealer and vulnerability tags are read; commentary, auctions and
+/// play are skipped.
+pub fn parse_pbn(content: &str) -> Result<Vec<Deal>, AppError> {
+    parse_games(content).map_err(AppError::DealError)
+}
+
+fn parse_games(content: &str) -> Result<Vec<Deal>, String> {
+    let mut deals = Vec::new();
+    let mut game: Vec<(String, String)> = Vec::new();
+    let mut in_comment = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if in_comment {
+            in_comment = !line.contains('}');
+            continue;
+        }
+        if line.starts_with('%') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('{') {
+            in_comment = !line.contains('}');
+            continue;
+        }
+        // Games are separated by a blank line; a repeated tag also starts one
+        let tag = parse_tag(line);
+        let repeated = tag.as_ref().is_some_and(|(name, _)| game.iter().any(|(seen, _)| seen == name));
+        if line.is_empty() || repeated {
+            deals.extend(deal_from_tags(&game)?);
+            game.clear();
+        }
+        if let Some(tag) = tag {
+            game.push(tag);
+        }
+    }
+    deals.extend(deal_from_tags(&game)?);
+    Ok(deals)
+}
+
+/// `[Name "value"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_string(), value.to_string()))
+}
+
+fn deal_from_tags(tags: &[(String, String)]) -> Result<Option<Deal>, String> {
+    let tag = |name: &str| {
+        tags.iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty() && *value != "?" && *value != "#")
+    };
+    let Some(cards) = tag("Deal") else {
+        return Ok(None);
+    };
+    let board = tag("Board").map(String::from);
+    let hands = parse_deal(cards).map_err(|e| match &board {
+        Some(board) => format!("board {}: {}", board, e),
+        None => e,
+    })?;
+    Ok(Some(Deal {
+        board,
+        dealer: tag("Dealer").and_then(Seat::from_letter),
+        vulnerable: tag("Vulnerable").map(String::from),
+        hands,
+    }))
+}
+
+/// "N:AKQ2.K73.Q85.A42 - ...": the first seat, then four hands clockwise,
+/// "-" for one the file does not give
+fn parse_deal(cards: &str) -> Result<[Hand; 4], String> {
+    let (first, rest) = cards
+        .split_once(':')
+        .ok_or_else(|| format!("deal \"{}\" does not start with a seat", cards))?;
+    let first = Seat::from_letter(first).ok_or_else(|| format!("unknown seat \"{}\"", first))?;
+    let written: Vec<&str> = rest.split_whitespace().collect();
+    if written.len() != 4 {
+        return Err(format!("deal \"{}\" needs four hands", cards));
+    }
+
+    let mut hands: [Hand; 4] = Default::default();
+    for (offset, text) in written.iter().enumerate() {
+        hands[(first.index() + offset) % 4] = parse_hand(text)?;
+    }
+    Ok(hands)
+}
+
+fn parse_hand(text: &str) -> Result<Hand, String> {
+    if text == "-" {
+        return Ok(Hand::default());
+    }
+    let suits: Vec<&str> = text.split('.').collect();
+    if suits.len() != 4 {
+        return Err(format!("hand \"{}\" needs four suits", text));
+    }
+    let mut hand = Hand::default();
+    for (held, suit) in hand.suits.iter_mut().zip(suits) {
+        let ranks = suit.to_ascii_uppercase();
+        if let Some(bad) = ranks.chars().find(|rank| !RANKS.contains(*rank)) {
+            return Err(format!("hand \"{}\" has an unknown card \"{}\"", text, bad));
+        }
+        *held = ranks;
+    }
+    if card_count(&hand) > 13 {
+        return Err(format!("hand \"{}\" has {} cards", text, card_count(&hand)));
+    }
+    Ok(hand)
+}
+
+// ============================================================================
+// LIN
+// ============================================================================
+
+/// Every deal (`md`) in a LIN file, with the board from `ah` or `qx` and the
+/// vulnerability from `sv`. Bidding, play and player names are skipped.
+pub fn parse_lin(content: &str) -> Result<Vec<Deal>, AppError> {
+    parse_lin_deals(content).map_err(AppError::DealError)
+}
+
+fn parse_lin_deals(content: &str) -> Result<Vec<Deal>, String> {
+    let mut deals: Vec<Deal> = Vec::new();
+    // Board and vulnerability seen before the deal they belong to
+    let mut next_board: Option<String> = None;
+    let mut next_vulnerable: Option<String> = None;
+
+    let mut fields = content.split('|').map(|field| field.trim());
+    while let Some(key) = fields.next() {
+        let value = fields.next().unwrap_or_default();
+        let current = deals.last_mut();
+        match key.to_ascii_lowercase().as_str() {
+            "md" => {
+                let mut deal = parse_lin_deal(value)?;
+                deal.board = next_board.take();
+                deal.vulnerable = next_vulnerable.take();
+                deals.push(deal);
+            }
+            // "o12" (open room) or "c12" (closed room) comes before its deal
+            "qx" => next_board = Some(value.trim_start_matches(['o', 'c', 'O', 'C']).to_string()),
+            "ah" => {
+                let board = value.trim_start_matches("Board").trim().to_string();
+                match current {
+                    Some(deal) if deal.board.is_none() => deal.board = Some(board),
+                    _ => next_board = Some(board),
+                }
+            }
+            "sv" => {
+                let vulnerable = lin_vulnerability(value)?;
+                match current {
+                    Some(deal) if deal.vulnerable.is_none() => deal.vulnerable = Some(vulnerable),
+                    _ => next_vulnerable = Some(vulnerable),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(deals)
+}
+
+fn lin_vulnerability(value: &str) -> Result<String, String> {
+    let vulnerable = match value.to_ascii_lowercase().as_str() {
+        "o" | "0" | "-" => "None",
+        "n" => "NS",
+        "e" => "EW",
+        "b" => "All",
+        _ => return Err(format!("unknown vulnerability \"{}\"", value)),
+    };
+    Ok(vulnerable.to_string())
+}
+
+/// "3SJ85HAT9DAJT7CQ98,S743H864...,...,": the dealer (1 South, 2 West,
+/// 3 North, 4 East), then the hands from South clockwise. The last hand is
+/// often left out; when the other three are complete it gets the rest.
+fn parse_lin_deal(value: &str) -> Result<Deal, String> {
+    let dealer = match value.chars().next() {
+        Some('1') => Some(Seat::South),
+        Some('2') => Some(Seat::West),
+        Some('3') => Some(Seat::North),
+        Some('4') => Some(Seat::East),
+        _ => None,
+    };
+    let cards = if dealer.is_some() { &value[1..] } else { value };
+
+    let mut hands: [Hand; 4] = Default::default();
+    let written: Vec<&str> = cards.split(',').collect();
+    if written.len() > 4 {
+        return Err(format!("deal \"{}\" has more than four hands", value));
+    }
+    for (offset, text) in written.iter().enumerate() {
+        hands[(Seat::South.index() + offset) % 4] = parse_lin_hand(text)?;
+    }
+
+    let complete = hands.iter().filter(|hand| card_count(hand) == 13).count();
+    if complete == 3 && hands[Seat::East.index()].is_empty() {
+        hands[Seat::East.index()] = remaining_cards(&hands);
+    }
+
+    Ok(Deal { board: None, dealer, vulnerable: None, hands })
+}
+
+/// "SAKQ2HK73DQ85CA42": each suit letter followed by its ranks
+fn parse_lin_hand(text: &str) -> Result<Hand, String> {
+    let mut hand = Hand::default();
+    let mut suit: Option<usize> = None;
+    for c in text.chars().map(|c| c.to_ascii_uppercase()) {
+        if let Some(index) = SUIT_LETTERS.iter().position(|letter| letter.starts_with(c)) {
+            suit = Some(index);
+        } else if RANKS.contains(c) {
+            let index = suit.ok_or_else(|| format!("hand \"{}\" has cards before a suit", text))?;
+            hand.suits[index].push(c);
+        } else if !c.is_whitespace() {
+            return Err(format!("hand \"{}\" has an unknown card \"{}\"", text, c));
+        }
+    }
+    if card_count(&hand) > 13 {
+        return Err(format!("hand \"{}\" has {} cards", text, card_count(&hand)));
+    }
+    Ok(hand)
+}
+
+fn card_count(hand: &Hand) -> usize {
+    hand.suits.iter().map(String::len).sum()
+}
+
+/// The cards none of `hands` hold, in rank order
+fn remaining_cards(hands: &[Hand; 4]) -> Hand {
+    let mut rest = Hand::default();
+    for (index, suit) in rest.suits.iter_mut().enumerate() {
+        *suit = RANKS
+            .chars()
+            .filter(|rank| !hands.iter().any(|hand| hand.suits[index].contains(*rank)))
+            .collect();
+    }
+    rest
+}
+
+// ============================================================================
+// Diagrams
+// ============================================================================
+
+/// Room one diagram takes on the page
+pub const DIAGRAM_WIDTH_MM: f32 = 90.0;
+pub const DIAGRAM_HEIGHT_MM: f32 = 70.0;
+
+const HAND_LINE_HEIGHT_MM: f32 = 4.6;
+/// The box in the middle giving board, dealer and vulnerability
+const CENTER_BOX_SIZE_MM: f32 = 24.0;
+
+/// Ranks as printed: "T" is written out as 10
+fn ranks_display(ranks: &str) -> String {
+    if ranks.is_empty() {
+        "\u{2013}".to_string()
+    } else {
+        ranks.replace('T', "10")
+    }
+}
+
+/// Draw `deal` in the [`DIAGRAM_WIDTH_MM`] by [`DIAGRAM_HEIGHT_MM`] area
+/// whose top-left corner is (`x`, `y_top`)
+pub fn layout_deal_diagram(page: &mut Page, deal: &Deal, x: f32, y_top: f32) {
+    let center_x = x + DIAGRAM_WIDTH_MM / 2.0;
+    let center_y = y_top - DIAGRAM_HEIGHT_MM / 2.0;
+    let half_box = CENTER_BOX_SIZE_MM / 2.0;
+
+    // Board, dealer and vulnerability in the middle
+    let (left, right) = (center_x - half_box, center_x + half_box);
+    let (top, bottom) = (center_y + half_box, center_y - half_box);
+    page.rect(left, bottom, CENTER_BOX_SIZE_MM, CENTER_BOX_SIZE_MM, OUTLINE_STYLE);
+    let mut info_y = top - 5.0;
+    if let Some(board) = &deal.board {
+        page.text(format!("Board {}", board), SMALL_FONT_SIZE, left + 2.0, info_y, FontStyle::Bold);
+        info_y -= 4.5;
+    }
+    if let Some(dealer) = deal.dealer {
+        page.text(format!("Dlr: {}", dealer.letter()), SMALL_FONT_SIZE, left + 2.0, info_y, FontStyle::Regular);
+        info_y -= 4.5;
+    }
+    if let Some(vulnerable) = &deal.vulnerable {
+        page.text(format!("Vul: {}", vulnerable), SMALL_FONT_SIZE, left + 2.0, info_y, FontStyle::Regular);
+    }
+
+    // Each hand's top line, around the box
+    let hand_height = 4.0 * HAND_LINE_HEIGHT_MM;
+    let positions = [
+        (Seat::North, center_x - half_box, top + hand_height + 1.0),
+        (Seat::South, center_x - half_box, bottom - 4.0),
+        (Seat::West, x, center_y + hand_height / 2.0),
+        (Seat::East, right + 4.0, center_y + hand_height / 2.0),
+    ];
+    for (seat, hand_x, hand_top) in positions {
+        let hand = deal.hand(seat);
+        if hand.is_empty() {
+            continue;
+        }
+        for (index, (symbol, ranks)) in SUIT_SYMBOLS.iter().zip(&hand.suits).enumerate() {
+            let line_y = hand_top - 3.5 - index as f32 * HAND_LINE_HEIGHT_MM;
+            page.text(*symbol, NORMAL_FONT_SIZE, hand_x, line_y, FontStyle::Symbol);
+            page.text(ranks_display(ranks), NORMAL_FONT_SIZE, hand_x + 5.0, line_y, FontStyle::Regular);
+        }
+    }
+}
+
+/// Pages of diagrams, two across and as many down as fit above
+/// `content_bottom`, each page headed by `title` (marked continued after the
+/// first) and `subtitle`
+pub fn layout_deal_pages(
+    sheet: &mut SheetLayout,
+    deals: &[Deal],
+    title: &str,
+    subtitle: &str,
+    content_width: f32,
+    content_bottom: f32,
+) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let grid_top = top_y - 22.0;
+    let columns = ((content_width / DIAGRAM_WIDTH_MM) as usize).max(1);
+    let rows = (((grid_top - content_bottom) / DIAGRAM_HEIGHT_MM) as usize).max(1);
+    let column_gap = (content_width - columns as f32 * DIAGRAM_WIDTH_MM) / columns as f32;
+
+    for (page_index, page_deals) in deals.chunks(columns * rows).enumerate() {
+        let page = sheet.new_page();
+        let heading = if page_index == 0 {
+            title.to_string()
+        } else {
+            format!("{} (continued)", title)
+        };
+        page.text(heading, TITLE_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+        page.text(subtitle, HEADER_FONT_SIZE, MARGIN_MM, top_y - 14.0, FontStyle::Bold);
+        page.line(MARGIN_MM, top_y - 18.0, MARGIN_MM + content_width, top_y - 18.0, BORDER_STROKE);
+
+        for (index, deal) in page_deals.iter().enumerate() {
+            let x = MARGIN_MM + (index % columns) as f32 * (DIAGRAM_WIDTH_MM + column_gap);
+            let y = grid_top - (index / columns) as f32 * DIAGRAM_HEIGHT_MM;
+            layout_deal_diagram(page, deal, x, y);
+        }
+    }
+}
+
+/// A stand-alone handout of `deals`, ready for any renderer
+pub fn layout_handout(title: &str, subtitle: &str, deals: &[Deal]) -> SheetLayout {
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+    layout_deal_pages(&mut sheet, deals, title, subtitle, PAGE_WIDTH_MM - 2.0 * MARGIN_MM, MARGIN_MM);
+    sheet
+}
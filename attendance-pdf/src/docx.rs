@@ -0,0 +1,397 @@
+// Word (.docx) export: an editable, table-based equivalent of the sheet for
+// venues that want to adjust it in a word processor. Built from the config
+// rather than the placed layout, since Word reflows text on its own.
+
+use crate::assets::PreparedImage;
+use crate::layout::{self, SheetLayout, TextAlign};
+use crate::render::SheetRenderer;
+use crate::{
+    format_date_display, instructor_line, qr, roster, sheet_title, venue_line, AppError, AttendanceConfig, Sponsor, Student,
+    HEADER_FONT_SIZE, LOGO_MAX_HEIGHT_MM, LOGO_MAX_WIDTH_MM, MARGIN_MM, NAME_COL_RATIO,
+    NORMAL_FONT_SIZE, PAGE_HEIGHT_MM, PAGE_WIDTH_MM, PHOTO_MAX_SIZE_MM, QR_SIZE_MM, SEAT_COL_RATIO, SMALL_FONT_SIZE,
+    SIGNATURE_COL_RATIO, SIGNATURE_MIN_ROW_HEIGHT_MM, SIGNATURE_NAME_COL_RATIO,
+    SIGNATURE_SEAT_COL_RATIO, SIGNATURE_TABLE_COL_RATIO, SPONSOR_MAX_HEIGHT_MM,
+    SPONSOR_MAX_WIDTH_MM, TABLE_COL_RATIO, TABLE_SEAT_ROW_HEIGHT_MM,
+    TITLE_FONT_SIZE,
+};
+use ::image::{DynamicImage, ImageFormat, RgbImage};
+use docx_rs::{
+    AlignmentType, Docx, HeightRule, PageMargin, Paragraph, Pic, Run, Table, TableCell, TableRow, WidthType,
+};
+use std::io::Cursor;
+
+/// Word documents measure page geometry in twentieths of a point
+const TWIPS_PER_MM: f32 = 1440.0 / 25.4;
+
+/// Image sizes are given in English Metric Units
+const EMU_PER_MM: f32 = 36_000.0;
+
+/// Blank rows left under the roster for walk-ins
+const WALK_IN_ROWS: usize = 8;
+
+/// Office Open XML document (`.docx`)
+pub struct DocxRenderer;
+
+impl SheetRenderer for DocxRenderer {
+    fn extension(&self) -> &'static str {
+        "docx"
+    }
+
+    fn render(&self, _layout: &SheetLayout, config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+        let margin = twips(MARGIN_MM) as i32;
+        let mut doc = Docx::new()
+            .page_size(twips(PAGE_WIDTH_MM) as u32, twips(PAGE_HEIGHT_MM) as u32)
+            .page_margin(
+                PageMargin::new()
+                    .top(margin)
+                    .bottom(margin)
+                    .left(margin)
+                    .right(margin),
+            )
+            .add_table(header_table(config)?)
+            .add_paragraph(Paragraph::new());
+
+        doc = match &config.roster {
+            Some(roster) => doc.add_table(roster_table(roster, config)?),
+            None => doc.add_table(table_seat_table(config.blank_rows)),
+        };
+
+        let legend = roster::legend(config);
+        if !legend.is_empty() {
+            doc = doc.add_paragraph(Paragraph::new().add_run(text_run(&legend.join("     "), SMALL_FONT_SIZE)));
+        }
+
+        if let Some(names) = config.online_attendees.as_deref().filter(|names| !names.is_empty()) {
+            doc = doc
+                .add_paragraph(Paragraph::new())
+                .add_paragraph(Paragraph::new().add_run(bold_run(layout::ONLINE_HEADING, NORMAL_FONT_SIZE)))
+                .add_table(online_table(names));
+        }
+
+        if config.helper_rows > 0 {
+            doc = doc
+                .add_paragraph(Paragraph::new())
+                .add_paragraph(Paragraph::new().add_run(bold_run("HELPERS & VOLUNTEERS", NORMAL_FONT_SIZE)))
+                .add_table(helper_table(config.helper_rows));
+        }
+
+        if config.mailing_list {
+            doc = doc
+                .add_paragraph(Paragraph::new())
+                .add_paragraph(Paragraph::new().add_run(bold_run(&roster::mailing_heading(config), NORMAL_FONT_SIZE)))
+                .add_table(mailing_table(config.mailing_rows));
+        }
+
+        if let Some(sponsor) = &config.sponsor {
+            doc = doc.add_paragraph(Paragraph::new()).add_paragraph(sponsor_paragraph(sponsor)?);
+        }
+
+        let mut out = Cursor::new(Vec::new());
+        doc.build()
+            .pack(&mut out)
+            .map_err(|e| AppError::ExportError(format!("Failed to write DOCX: {}", e)))?;
+        Ok(out.into_inner())
+    }
+}
+
+// ============================================================================
+// Sections
+// ============================================================================
+
+/// QR code on the left, event details on the right (plus the logo, if any)
+fn header_table(config: &AttendanceConfig) -> Result<Table, AppError> {
+    let qr_png = png_bytes(&qr::generate_qr_image(config, QR_SIZE_MM)?)?;
+    let qr_size = emu(QR_SIZE_MM);
+    let qr_cell = TableCell::new()
+        .add_paragraph(Paragraph::new().add_run(Run::new().add_image(Pic::new(&qr_png).size(qr_size, qr_size))));
+
+    let mut details = TableCell::new()
+        .add_paragraph(
+            Paragraph::new()
+                .add_run(bold_run(sheet_title(config), TITLE_FONT_SIZE))
+                .align(paragraph_alignment(config.title_align)),
+        )
+        .add_paragraph(Paragraph::new().add_run(bold_run(&config.class_name, HEADER_FONT_SIZE)))
+        .add_paragraph(Paragraph::new().add_run(text_run(&format_date_display(&config.date), NORMAL_FONT_SIZE)))
+        .add_paragraph(Paragraph::new().add_run(text_run(&instructor_line(config), NORMAL_FONT_SIZE)));
+    if !config.location.is_empty() {
+        details = details.add_paragraph(Paragraph::new().add_run(text_run(
+            &format!("Location: {}", config.location),
+            NORMAL_FONT_SIZE,
+        )));
+    }
+    details = details.add_paragraph(Paragraph::new().add_run(text_run(
+        &format!("ID: {}", config.event_id),
+        SMALL_FONT_SIZE,
+    )));
+    if let Some(venue) = venue_line(config) {
+        details = details.add_paragraph(Paragraph::new().add_run(text_run(&venue, SMALL_FONT_SIZE)));
+    }
+
+    let mut cells = vec![qr_cell, details];
+    if let Some(logo) = &config.logo {
+        // Fit within the same box the PDF header reserves for the logo
+        let pic = fitted_pic(logo, LOGO_MAX_WIDTH_MM, LOGO_MAX_HEIGHT_MM)?;
+        cells.push(TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_image(pic))));
+    }
+
+    Ok(Table::new(vec![TableRow::new(cells)]))
+}
+
+/// Sponsor image (if any) followed by the acknowledgment text
+fn sponsor_paragraph(sponsor: &Sponsor) -> Result<Paragraph, AppError> {
+    let mut paragraph = Paragraph::new();
+    if let Some(image) = &sponsor.image {
+        let pic = fitted_pic(image, SPONSOR_MAX_WIDTH_MM, SPONSOR_MAX_HEIGHT_MM)?;
+        paragraph = paragraph.add_run(Run::new().add_image(pic)).add_run(text_run("  ", SMALL_FONT_SIZE));
+    }
+    Ok(paragraph.add_run(text_run(&sponsor.text, SMALL_FONT_SIZE)))
+}
+
+/// One row per student with blank TABLE and SEAT cells, then walk-in rows.
+/// With a signature column the checkbox gives way to a wide SIGNATURE cell.
+fn roster_table(roster: &[Student], config: &AttendanceConfig) -> Result<Table, AppError> {
+    let signature = config.signature_column;
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let (widths, headings, checkbox): (Vec<f32>, &[&str], &str) = if signature {
+        (
+            vec![
+                content_width * SIGNATURE_NAME_COL_RATIO,
+                content_width * SIGNATURE_COL_RATIO,
+                content_width * SIGNATURE_TABLE_COL_RATIO,
+                content_width * SIGNATURE_SEAT_COL_RATIO,
+            ],
+            &["NAME", "SIGNATURE", "TABLE", "SEAT"],
+            "",
+        )
+    } else {
+        (
+            vec![
+                content_width * NAME_COL_RATIO,
+                content_width * TABLE_COL_RATIO,
+                content_width * SEAT_COL_RATIO,
+            ],
+            &["NAME", "TABLE", "SEAT"],
+            "\u{2610} ",
+        )
+    };
+    // Blank cells after the name, ending with the seat choices
+    let mut rest = vec![""; widths.len() - 2];
+    rest.push("N  S  E  W");
+
+    let mut rows = vec![grid_row(&widths, headings, true)];
+    let students = roster.iter().map(Some).chain((0..WALK_IN_ROWS).map(|_| None));
+    for student in students {
+        let photo = match student.and_then(|student| student.photo.as_ref()) {
+            Some(photo) => Some(fitted_pic(photo, PHOTO_MAX_SIZE_MM, PHOTO_MAX_SIZE_MM)?),
+            None => None,
+        };
+        let mut name = text_run(
+            &student.map(|student| roster::display_name(student, config)).unwrap_or_default(),
+            NORMAL_FONT_SIZE,
+        );
+        if student.is_some_and(|student| student.bold) {
+            name = name.bold();
+        }
+        if student.is_some_and(|student| student.highlight) {
+            name = name.highlight("yellow");
+        }
+        if let Some(pronouns) = student.and_then(|student| student.pronouns.as_deref()) {
+            name = name.add_text(format!(" ({})", pronouns));
+        }
+        if student.is_some_and(|student| student.is_new) {
+            name = name.add_text(format!("  {}", roster::NEW_BADGE));
+        }
+        if student.is_some_and(|student| student.birthday_in_week(config.date)) {
+            name = name.add_text(format!(" {}", roster::BIRTHDAY_CAKE));
+        }
+        let note = student.and_then(|student| student.note.as_deref());
+        let checkbox = if !signature && student.is_some_and(|student| student.confirmed) {
+            "\u{2611} "
+        } else {
+            checkbox
+        };
+        let mut row = roster_row(&widths, checkbox, name, photo, note, &rest);
+        if signature {
+            row = row
+                .row_height(twips(SIGNATURE_MIN_ROW_HEIGHT_MM))
+                .height_rule(HeightRule::AtLeast);
+        }
+        rows.push(row);
+    }
+
+    Ok(grid_table(&widths, rows))
+}
+
+/// Roster row: checkbox, optional photo and name (with any note under it)
+/// in the first cell, then `rest` in the remaining cells
+fn roster_row(
+    widths_mm: &[f32],
+    checkbox: &str,
+    name: Run,
+    photo: Option<Pic>,
+    note: Option<&str>,
+    rest: &[&str],
+) -> TableRow {
+    let mut name_paragraph = Paragraph::new().add_run(text_run(checkbox, NORMAL_FONT_SIZE));
+    if let Some(photo) = photo {
+        name_paragraph = name_paragraph
+            .add_run(Run::new().add_image(photo))
+            .add_run(text_run(" ", NORMAL_FONT_SIZE));
+    }
+    name_paragraph = name_paragraph.add_run(name);
+
+    let mut name_cell = TableCell::new()
+        .width(twips(widths_mm[0]) as usize, WidthType::Dxa)
+        .add_paragraph(name_paragraph);
+    if let Some(note) = note {
+        name_cell = name_cell.add_paragraph(Paragraph::new().add_run(text_run(note, SMALL_FONT_SIZE)));
+    }
+    let mut cells = vec![name_cell];
+    for (width, text) in widths_mm[1..].iter().zip(rest) {
+        cells.push(
+            TableCell::new()
+                .width(twips(*width) as usize, WidthType::Dxa)
+                .add_paragraph(Paragraph::new().add_run(text_run(text, NORMAL_FONT_SIZE))),
+        );
+    }
+    TableRow::new(cells)
+}
+
+/// Blank mode: four seats per table with room to write each player's name
+fn table_seat_table(blank_rows: u32) -> Table {
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let widths = [22.0, 22.0, content_width - 44.0];
+    let seats = ["North", "South", "East", "West"];
+
+    let mut rows = vec![grid_row(&widths, &["TABLE", "SEAT", "NAME"], true)];
+    for row in 0..blank_rows {
+        let seat = seats[(row % 4) as usize];
+        let table = if row % 4 == 0 { format!("Table {}", row / 4 + 1) } else { String::new() };
+        rows.push(
+            grid_row(&widths, &[&table, seat, ""], false)
+                .row_height(twips(TABLE_SEAT_ROW_HEIGHT_MM))
+                .height_rule(HeightRule::AtLeast)
+                .cant_split(),
+        );
+    }
+
+    grid_table(&widths, rows)
+}
+
+/// Volunteers, caddies and greeters sign in apart from the students
+fn helper_table(rows: u32) -> Table {
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let widths = [content_width * 0.6, content_width * 0.4];
+
+    let rows = (0..rows)
+        .map(|_| grid_row(&widths, &["Name:", "Role:"], false))
+        .collect();
+    grid_table(&widths, rows)
+}
+
+/// Zoom registrants down three columns, each with a box to tick
+fn online_table(names: &[String]) -> Table {
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let widths = [content_width / 3.0; 3];
+
+    let rows_needed = names.len().div_ceil(widths.len());
+    let rows = (0..rows_needed)
+        .map(|row| {
+            let cells: Vec<String> = (0..widths.len())
+                .map(|column| match names.get(column * rows_needed + row) {
+                    Some(name) => format!("\u{2610} {}", name),
+                    None => String::new(),
+                })
+                .collect();
+            let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+            grid_row(&widths, &cells, false)
+        })
+        .collect();
+    grid_table(&widths, rows)
+}
+
+fn mailing_table(rows: u32) -> Table {
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let widths = [content_width * 0.45, content_width * 0.55];
+
+    let rows = (0..rows)
+        .map(|_| grid_row(&widths, &["Name:", "Email:"], false))
+        .collect();
+    grid_table(&widths, rows)
+}
+
+// ============================================================================
+// DOCX Utilities
+// ============================================================================
+
+fn grid_table(widths_mm: &[f32], rows: Vec<TableRow>) -> Table {
+    let total: f32 = widths_mm.iter().sum();
+    Table::new(rows)
+        .set_grid(widths_mm.iter().map(|w| twips(*w) as usize).collect())
+        .width(twips(total) as usize, WidthType::Dxa)
+}
+
+fn grid_row(widths_mm: &[f32], cells: &[&str], header: bool) -> TableRow {
+    let cells = widths_mm
+        .iter()
+        .zip(cells)
+        .map(|(width, text)| {
+            let run = if header {
+                bold_run(text, SMALL_FONT_SIZE)
+            } else {
+                text_run(text, NORMAL_FONT_SIZE)
+            };
+            TableCell::new()
+                .width(twips(*width) as usize, WidthType::Dxa)
+                .add_paragraph(Paragraph::new().add_run(run))
+        })
+        .collect();
+    TableRow::new(cells)
+}
+
+fn text_run(text: &str, size_pt: f32) -> Run {
+    // Run sizes are in half-points
+    Run::new().add_text(text).size((size_pt * 2.0) as usize)
+}
+
+fn paragraph_alignment(align: TextAlign) -> AlignmentType {
+    match align {
+        TextAlign::Left => AlignmentType::Left,
+        TextAlign::Center => AlignmentType::Center,
+        TextAlign::Right => AlignmentType::Right,
+    }
+}
+
+fn bold_run(text: &str, size_pt: f32) -> Run {
+    text_run(text, size_pt).bold()
+}
+
+/// Embed a prepared image scaled to fit a `max_width_mm` by `max_height_mm` box
+fn fitted_pic(image: &PreparedImage, max_width_mm: f32, max_height_mm: f32) -> Result<Pic, AppError> {
+    let rgb = RgbImage::from_raw(image.width_px, image.height_px, image.rgb.clone())
+        .ok_or_else(|| AppError::ExportError("image buffer has the wrong size".to_string()))?;
+    let png = png_bytes(&DynamicImage::ImageRgb8(rgb))?;
+
+    let scale = (max_width_mm / image.width_px as f32).min(max_height_mm / image.height_px as f32);
+    Ok(Pic::new(&png).size(
+        emu(image.width_px as f32 * scale),
+        emu(image.height_px as f32 * scale),
+    ))
+}
+
+fn png_bytes(image: &DynamicImage) -> Result<Vec<u8>, AppError> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| AppError::ExportError(format!("Failed to encode image: {}", e)))?;
+    Ok(png)
+}
+
+fn twips(mm: f32) -> f32 {
+    (mm * TWIPS_PER_MM).round()
+}
+
+fn emu(mm: f32) -> u32 {
+    (mm * EMU_PER_MM).round() as u32
+}
@@ -0,0 +1,87 @@
+// Multi-event files: several classes (e.g. morning and afternoon sessions)
+// rendered as consecutive sheets in one document, each with its own header,
+// event ID and QR code
+
+use crate::assets::AssetCache;
+use crate::{
+    generate_short_id, load_roster, names, parse_date, parse_event_id, resolve_source, AppError, AttendanceConfig,
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// One entry of an events file. Fields left out fall back to the values
+/// given on the command line.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventSpec {
+    pub name: String,
+    pub teacher: Option<String>,
+    pub substitute: Option<String>,
+    pub date: Option<String>,
+    pub location: Option<String>,
+    pub event_id: Option<String>,
+    /// Roster file, relative to the events file
+    pub roster: Option<String>,
+    pub rows: Option<u32>,
+}
+
+/// Read a JSON array of [`EventSpec`]s
+pub fn load_events(path: &Path) -> Result<Vec<EventSpec>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::EventsError(format!("{}: {}", path.display(), e)))?;
+    let events: Vec<EventSpec> = serde_json::from_str(&content)
+        .map_err(|e| AppError::EventsError(format!("Invalid JSON: {}", e)))?;
+
+    if events.is_empty() {
+        return Err(AppError::EventsError(format!("{}: no events listed", path.display())));
+    }
+    Ok(events)
+}
+
+impl EventSpec {
+    /// Build this event's config on top of `base`; `base_dir` is the directory
+    /// holding the events file, used to resolve roster paths
+    pub fn to_config(
+        &self,
+        base: &AttendanceConfig,
+        base_dir: &Path,
+        assets: &mut AssetCache,
+    ) -> Result<AttendanceConfig, AppError> {
+        let mut config = base.clone();
+        config.class_name = self.name.clone();
+
+        if let Some(teacher) = &self.teacher {
+            config.teacher = teacher.clone();
+        }
+        if self.substitute.is_some() {
+            config.substitute = self.substitute.clone();
+        }
+        if self.date.is_some() {
+            config.date = parse_date(&self.date)?;
+        }
+        if let Some(location) = &self.location {
+            config.location = location.clone();
+        }
+        config.event_id = match &self.event_id {
+            Some(id) => parse_event_id(id)?,
+            None if config.reproducible => {
+                return Err(AppError::ConfigError(format!(
+                    "event \"{}\" needs an event_id for reproducible output",
+                    self.name
+                )))
+            }
+            None => generate_short_id(),
+        };
+        if let Some(roster) = &self.roster {
+            config.roster = load_roster(&Some(resolve_source(base_dir, roster)), None, assets)?;
+            if let Some(roster) = &mut config.roster {
+                names::sort_roster(roster, config.display_order);
+            }
+        }
+        if let Some(rows) = self.rows {
+            config.blank_rows = rows;
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,152 @@
+// Page geometry: where the cells of a grid go, as small values that can be
+// worked out and checked without laying out a page. Distances are in mm from
+// the bottom-left corner of the page, as everywhere in `layout`.
+
+use crate::{
+    AttendanceConfig, NAME_COL_RATIO, PHOTO_CONSENT_COL_RATIO, SEAT_COL_RATIO, SIGNATURE_COL_RATIO,
+    SIGNATURE_NAME_COL_RATIO, SIGNATURE_SEAT_COL_RATIO, SIGNATURE_TABLE_COL_RATIO, TABLE_COL_RATIO, TIME_COL_RATIO,
+};
+
+/// Text baseline below the middle of a row, for the fonts' cap height
+const TEXT_DROP_MM: f32 = 1.5;
+
+/// A rectangle on the page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageRect {
+    pub x: f32,
+    /// Bottom edge
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PageRect {
+    /// The rectangle hanging `height` down from `top`
+    pub fn from_top(x: f32, top: f32, width: f32, height: f32) -> PageRect {
+        PageRect { x, y: top - height, width, height }
+    }
+
+    pub fn top(&self) -> f32 {
+        self.y + self.height
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    /// `count` columns side by side, `gap` apart, sharing the width equally
+    pub fn columns(&self, count: usize, gap: f32) -> Vec<PageRect> {
+        let count = count.max(1);
+        let width = (self.width - gap * (count - 1) as f32) / count as f32;
+        (0..count)
+            .map(|index| PageRect {
+                x: self.x + index as f32 * (width + gap),
+                width,
+                ..*self
+            })
+            .collect()
+    }
+}
+
+/// How a roster column divides across: the name, any signature, photo
+/// consent and time cells (each taken from the name's share), then TABLE
+/// and SEAT
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnSpec {
+    pub x: f32,
+    pub name_width: f32,
+    pub signature_width: f32,
+    pub consent_width: f32,
+    pub time_width: f32,
+    pub table_width: f32,
+    pub seat_width: f32,
+}
+
+impl ColumnSpec {
+    pub fn new(x: f32, width: f32, signature: bool, photo_consent: bool, time_column: bool) -> ColumnSpec {
+        let (name_width, signature_width, table_width, seat_width) = if signature {
+            (
+                width * SIGNATURE_NAME_COL_RATIO,
+                width * SIGNATURE_COL_RATIO,
+                width * SIGNATURE_TABLE_COL_RATIO,
+                width * SIGNATURE_SEAT_COL_RATIO,
+            )
+        } else {
+            (width * NAME_COL_RATIO, 0.0, width * TABLE_COL_RATIO, width * SEAT_COL_RATIO)
+        };
+        let consent_width = if photo_consent { width * PHOTO_CONSENT_COL_RATIO } else { 0.0 };
+        let time_width = if time_column { width * TIME_COL_RATIO } else { 0.0 };
+        ColumnSpec {
+            x,
+            name_width: name_width - consent_width - time_width,
+            signature_width,
+            consent_width,
+            time_width,
+            table_width,
+            seat_width,
+        }
+    }
+
+    /// The cells `config` asks for, across `column`
+    pub fn for_config(config: &AttendanceConfig, column: &PageRect) -> ColumnSpec {
+        ColumnSpec::new(
+            column.x,
+            column.width,
+            config.signature_column,
+            config.photo_consent,
+            config.time_column,
+        )
+    }
+
+    pub fn signature_x(&self) -> f32 {
+        self.x + self.name_width
+    }
+
+    pub fn consent_x(&self) -> f32 {
+        self.signature_x() + self.signature_width
+    }
+
+    pub fn time_x(&self) -> f32 {
+        self.consent_x() + self.consent_width
+    }
+
+    pub fn table_x(&self) -> f32 {
+        self.time_x() + self.time_width
+    }
+
+    pub fn seat_x(&self) -> f32 {
+        self.table_x() + self.table_width
+    }
+
+    pub fn right(&self) -> f32 {
+        self.seat_x() + self.seat_width
+    }
+}
+
+/// A row across a grid: its top edge and height
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RowBand {
+    pub top: f32,
+    pub height: f32,
+}
+
+impl RowBand {
+    pub fn bottom(&self) -> f32 {
+        self.top - self.height
+    }
+
+    /// Baseline for a line of text centered in the row
+    pub fn text_y(&self) -> f32 {
+        self.top - self.height / 2.0 - TEXT_DROP_MM
+    }
+
+    /// The same row, `extra` mm deeper
+    pub fn extended(&self, extra: f32) -> RowBand {
+        RowBand { height: self.height + extra, ..*self }
+    }
+
+    /// A row of `height` directly under this one
+    pub fn next(&self, height: f32) -> RowBand {
+        RowBand { top: self.bottom(), height }
+    }
+}
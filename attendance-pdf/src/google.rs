@@ -0,0 +1,186 @@
+// Google Contacts roster: every contact carrying a label ("Bridge Students")
+// is fetched from the People API on each run, so teachers who keep their
+// students in Google Contacts never export a roster file.
+//
+// Authentication is an OAuth access token, either given directly in
+// GOOGLE_ACCESS_TOKEN or minted from the refresh token in the config file's
+// [google] table (client ID, client secret and a refresh token with the
+// contacts.readonly scope).
+
+use crate::net::{self, require_network};
+use crate::{AppError, RosterEntry};
+use serde::Deserialize;
+
+/// Takes precedence over the [google] refresh token
+pub const ACCESS_TOKEN_ENV: &str = "GOOGLE_ACCESS_TOKEN";
+
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const PEOPLE_API: &str = "https://people.googleapis.com/v1";
+/// The most people:batchGet accepts per call
+const BATCH_SIZE: usize = 200;
+/// Labels larger than a class roster would be are not expected
+const MAX_MEMBERS: u32 = 1000;
+
+/// `[google]`: OAuth client and refresh token for the People API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoogleSettings {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+}
+
+/// Contacts with `label`, sorted by name as the contacts app shows them
+pub fn fetch_label_roster(
+    label: &str,
+    settings: Option<&GoogleSettings>,
+) -> Result<Vec<RosterEntry>, AppError> {
+    require_network("Google Contacts")?;
+    let token = access_token(settings)?;
+
+    let groups: ContactGroups = get_json(&format!("{}/contactGroups", PEOPLE_API), &token, &[("pageSize", "1000")])?;
+    let group = groups
+        .contact_groups
+        .iter()
+        .find(|group| {
+            group.name.eq_ignore_ascii_case(label)
+                || group.formatted_name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(label))
+        })
+        .ok_or_else(|| AppError::ContactsError(format!("no contact label named \"{}\"", label)))?;
+
+    let members: ContactGroup = get_json(
+        &format!("{}/{}", PEOPLE_API, group.resource_name),
+        &token,
+        &[("maxMembers", &MAX_MEMBERS.to_string())],
+    )?;
+
+    let mut entries = Vec::new();
+    for batch in members.member_resource_names.chunks(BATCH_SIZE) {
+        let mut query: Vec<(&str, &str)> = vec![("personFields", "names,emailAddresses,phoneNumbers")];
+        query.extend(batch.iter().map(|name| ("resourceNames", name.as_str())));
+        let people: BatchGet = get_json(&format!("{}/people:batchGet", PEOPLE_API), &token, &query)?;
+        entries.extend(people.responses.into_iter().filter_map(|response| response.person?.into_entry()));
+    }
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+    Ok(entries)
+}
+
+fn access_token(settings: Option<&GoogleSettings>) -> Result<String, AppError> {
+    if let Ok(token) = std::env::var(ACCESS_TOKEN_ENV) {
+        return Ok(token);
+    }
+    let settings = settings.ok_or_else(|| {
+        AppError::ContactsError(format!(
+            "set {} or add a [google] client_id, client_secret and refresh_token to the config file",
+            ACCESS_TOKEN_ENV
+        ))
+    })?;
+
+    let form = [
+        ("client_id", settings.client_id.as_str()),
+        ("client_secret", settings.client_secret.as_str()),
+        ("refresh_token", settings.refresh_token.as_str()),
+        ("grant_type", "refresh_token"),
+    ];
+    let response = net::send_form(net::post(TOKEN_URL), &form)
+        .map_err(|e| AppError::ContactsError(format!("token refresh failed: {}", e)))?;
+    let token: TokenResponse = parse_response(response)?;
+    Ok(token.access_token)
+}
+
+fn get_json<T: for<'de> Deserialize<'de>>(url: &str, token: &str, query: &[(&str, &str)]) -> Result<T, AppError> {
+    let mut request = net::get(url).set("Authorization", &format!("Bearer {}", token));
+    for (key, value) in query {
+        request = request.query(key, value);
+    }
+    let response = net::call(request)
+        .map_err(|e| AppError::ContactsError(format!("{}: {}", url, e)))?;
+    parse_response(response)
+}
+
+fn parse_response<T: for<'de> Deserialize<'de>>(response: ureq::Response) -> Result<T, AppError> {
+    let body = net::read_string(response)
+        .map_err(|e| AppError::ContactsError(format!("Failed to read response: {}", e)))?;
+    serde_json::from_str(&body).map_err(|e| AppError::ContactsError(format!("Unexpected response: {}", e)))
+}
+
+// ============================================================================
+// People API Responses
+// ============================================================================
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContactGroups {
+    #[serde(default)]
+    contact_groups: Vec<ContactGroupSummary>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContactGroupSummary {
+    resource_name: String,
+    #[serde(default)]
+    name: String,
+    formatted_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContactGroup {
+    #[serde(default)]
+    member_resource_names: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchGet {
+    #[serde(default)]
+    responses: Vec<PersonResponse>,
+}
+
+#[derive(Deserialize)]
+struct PersonResponse {
+    person: Option<Person>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Person {
+    #[serde(default)]
+    names: Vec<Name>,
+    #[serde(default)]
+    email_addresses: Vec<Value>,
+    #[serde(default)]
+    phone_numbers: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Name {
+    display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Value {
+    value: Option<String>,
+}
+
+impl Person {
+    /// Contacts without a name (a bare phone number, say) are left out
+    fn into_entry(self) -> Option<RosterEntry> {
+        let name = self
+            .names
+            .into_iter()
+            .find_map(|name| name.display_name.filter(|name| !name.trim().is_empty()))?;
+        Some(RosterEntry {
+            name,
+            email: self.email_addresses.into_iter().find_map(|email| email.value),
+            phone: self.phone_numbers.into_iter().find_map(|phone| phone.value),
+            ..RosterEntry::default()
+        })
+    }
+}
@@ -0,0 +1,2729 @@
+// Sheet layout: places every element of the attendance sheet on its page.
+//
+// Coordinates are millimetres from the bottom-left corner of the page, the
+// same convention PDF uses, so the renderer can copy them straight through.
+
+use crate::assets::PreparedImage;
+use crate::deals::{layout_deal_pages, Deal};
+use crate::geometry::{ColumnSpec, PageRect, RowBand};
+use crate::metrics::{text_width_mm, truncate_to_width, wrap_hyphenated, wrap_text};
+use crate::qr::{cached_qr_image, cached_url_qr_image};
+use crate::roster;
+use crate::schedule::{Schedule, Session};
+use crate::{
+    format_date_display, instructor_line, serial_number, sheet_title, venue_line, AppError, AttendanceConfig, EmergencyInfo, PhaseTimings,
+    Student,
+    HEADER_FONT_SIZE, MARGIN_MM,
+    MAX_ROW_HEIGHT_MM, MIN_ROW_HEIGHT_MM, SIGNATURE_MIN_ROW_HEIGHT_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM,
+    PAGE_WIDTH_MM,
+    QR_SIZE_MM, SMALL_FONT_SIZE, TABLE_SEAT_ROW_HEIGHT_MM,
+    TITLE_FONT_SIZE, ECONOMY_MAX_ROW_HEIGHT_MM, ECONOMY_QR_SIZE_MM, ECONOMY_STROKE_SCALE,
+    ECONOMY_TABLE_SEAT_ROW_HEIGHT_MM, LOGO_MAX_HEIGHT_MM, LOGO_MAX_WIDTH_MM, SPONSOR_MAX_HEIGHT_MM,
+    SPONSOR_MAX_WIDTH_MM, PHOTO_MAX_SIZE_MM,
+};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Instant;
+
+// ============================================================================
+// Layout Representation
+// ============================================================================
+
+/// RGB color with components in 0.0..=1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::gray(0.0);
+
+    pub const fn gray(level: f32) -> Self {
+        Color { r: level, g: level, b: level }
+    }
+}
+
+/// Line color and thickness (thickness in points, as printpdf expects)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub color: Color,
+    pub thickness: f32,
+}
+
+/// Section borders and header rules
+pub(crate) const BORDER_STROKE: Stroke = Stroke { color: Color::BLACK, thickness: 0.5 };
+/// Checkboxes and the table write-in line next to them
+pub(crate) const CHECKBOX_STROKE: Stroke = Stroke { color: Color::BLACK, thickness: 0.4 };
+/// Checkbox outline, its corners slightly rounded
+pub(crate) const CHECKBOX_STYLE: RectStyle = RectStyle {
+    stroke: Some(CHECKBOX_STROKE),
+    fill: None,
+    radius: 0.4,
+};
+/// Box ticked before class for a student who confirmed online
+const CHECKED_BOX_STYLE: RectStyle = RectStyle {
+    fill: Some(Color::gray(0.85)),
+    ..CHECKBOX_STYLE
+};
+const CHECK_MARK_STROKE: Stroke = Stroke { color: Color::BLACK, thickness: 1.0 };
+/// Square-cornered outline for badges and boxed labels
+pub(crate) const OUTLINE_STYLE: RectStyle = RectStyle {
+    stroke: Some(CHECKBOX_STROKE),
+    fill: None,
+    radius: 0.0,
+};
+/// Light separators between roster rows
+pub(crate) const ROW_STROKE: Stroke = Stroke { color: Color::gray(0.8), thickness: 0.3 };
+/// Separators in blank table/seat mode
+const TABLE_SEAT_STROKE: Stroke = Stroke { color: Color::gray(0.7), thickness: 0.3 };
+/// Marker-pen yellow behind highlighted roster names
+const HIGHLIGHT_COLOR: Color = Color { r: 1.0, g: 0.95, b: 0.55 };
+
+/// Strokes this thick (points) are shading bands rather than rules
+const SHADING_MIN_THICKNESS: f32 = 2.0;
+/// Thinnest rule in mono mode; lighter ones vanish on old laser printers
+const MONO_MIN_THICKNESS: f32 = 0.6;
+
+/// Stroke thickness is in points; row geometry is in mm
+pub(crate) const PT_PER_MM: f32 = 72.0 / 25.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontStyle {
+    Regular,
+    Bold,
+    /// The card suit symbols (\u{2660} \u{2665} \u{2666} \u{2663}), which the
+    /// built-in fonts lack; the PDF embeds a small font for them
+    Symbol,
+}
+
+/// Counter-clockwise turn of a text element about its start point
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Upright,
+    /// 90°: reads up the page
+    Left,
+    /// 180°: reads correctly from the far side of a table
+    UpsideDown,
+    /// 270°: reads down the page, like a book spine
+    Right,
+}
+
+/// Which part of a text element its x coordinate gives
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Where the mailing list sign-up goes when the grid runs onto more pages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MailingPlacement {
+    /// Bottom of the first page, under whatever part of the grid is there
+    FirstPage,
+    /// Bottom of the page the grid ends on
+    #[default]
+    LastPage,
+    /// Directly under the grid's last row
+    AfterGrid,
+}
+
+impl TextAlign {
+    /// Where text `width` wide starts to be aligned this way at `x`
+    pub fn start_x(self, x: f32, width: f32) -> f32 {
+        match self {
+            TextAlign::Left => x,
+            TextAlign::Center => x - width / 2.0,
+            TextAlign::Right => x - width,
+        }
+    }
+}
+
+impl Rotation {
+    pub fn degrees(self) -> f32 {
+        match self {
+            Rotation::Upright => 0.0,
+            Rotation::Left => 90.0,
+            Rotation::UpsideDown => 180.0,
+            Rotation::Right => 270.0,
+        }
+    }
+}
+
+/// Text with its baseline starting at (x, y), running in the direction of
+/// its rotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextElement {
+    pub text: String,
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    pub font: FontStyle,
+    pub rotation: Rotation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineElement {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+    pub stroke: Stroke,
+}
+
+/// Outline and fill of a rectangle; either may be left off
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RectStyle {
+    pub stroke: Option<Stroke>,
+    pub fill: Option<Color>,
+    /// Corner radius in mm, 0 for square corners
+    pub radius: f32,
+}
+
+/// Rectangle whose bottom-left corner is (x, y)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RectElement {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub style: RectStyle,
+}
+
+/// Image scaled to fill the box whose bottom-left corner is (x, y)
+#[derive(Debug, Clone)]
+pub struct ImageElement {
+    pub image: Arc<PreparedImage>,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Smooth when scaling; off for QR codes so modules stay crisp
+    pub interpolate: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Element {
+    Text(TextElement),
+    Line(LineElement),
+    Rect(RectElement),
+    Image(ImageElement),
+}
+
+/// Everything drawn on one page, in drawing order
+#[derive(Debug, Default, Clone)]
+pub struct Page {
+    pub elements: Vec<Element>,
+}
+
+/// A fully laid-out attendance sheet
+#[derive(Debug)]
+pub struct SheetLayout {
+    pub width: f32,
+    pub height: f32,
+    pub pages: Vec<Page>,
+}
+
+impl Page {
+    pub fn text(&mut self, text: impl Into<String>, size: f32, x: f32, y: f32, font: FontStyle) {
+        self.rotated_text(text, size, x, y, font, Rotation::Upright);
+    }
+
+    /// Upright text placed by its left edge, middle or right edge at `x`,
+    /// measured with the font's own widths
+    pub fn aligned_text(
+        &mut self,
+        text: impl Into<String>,
+        size: f32,
+        x: f32,
+        y: f32,
+        font: FontStyle,
+        align: TextAlign,
+    ) {
+        let text = text.into();
+        let start_x = align.start_x(x, text_width_mm(&text, size, font));
+        self.text(text, size, start_x, y, font);
+    }
+
+    /// Upright text with its middle at `center_x`
+    pub fn centered_text(&mut self, text: impl Into<String>, size: f32, center_x: f32, y: f32, font: FontStyle) {
+        self.aligned_text(text, size, center_x, y, font, TextAlign::Center);
+    }
+
+    /// Upright text ending at `right_x`
+    pub fn right_aligned_text(&mut self, text: impl Into<String>, size: f32, right_x: f32, y: f32, font: FontStyle) {
+        self.aligned_text(text, size, right_x, y, font, TextAlign::Right);
+    }
+
+    pub fn rotated_text(
+        &mut self,
+        text: impl Into<String>,
+        size: f32,
+        x: f32,
+        y: f32,
+        font: FontStyle,
+        rotation: Rotation,
+    ) {
+        self.elements.push(Element::Text(TextElement {
+            text: text.into(),
+            x,
+            y,
+            size,
+            font,
+            rotation,
+        }));
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, stroke: Stroke) {
+        self.elements.push(Element::Line(LineElement { x1, y1, x2, y2, stroke }));
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, style: RectStyle) {
+        self.elements.push(Element::Rect(RectElement { x, y, width, height, style }));
+    }
+
+    pub fn image(
+        &mut self,
+        image: Arc<PreparedImage>,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        interpolate: bool,
+    ) {
+        self.elements.push(Element::Image(ImageElement {
+            image,
+            x,
+            y,
+            width,
+            height,
+            interpolate,
+        }));
+    }
+
+    pub fn texts(&self) -> impl Iterator<Item = &TextElement> {
+        self.elements.iter().filter_map(|e| match e {
+            Element::Text(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &LineElement> {
+        self.elements.iter().filter_map(|e| match e {
+            Element::Line(l) => Some(l),
+            _ => None,
+        })
+    }
+
+    pub fn rects(&self) -> impl Iterator<Item = &RectElement> {
+        self.elements.iter().filter_map(|e| match e {
+            Element::Rect(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    pub fn images(&self) -> impl Iterator<Item = &ImageElement> {
+        self.elements.iter().filter_map(|e| match e {
+            Element::Image(i) => Some(i),
+            _ => None,
+        })
+    }
+}
+
+impl SheetLayout {
+    /// All text on every page, with the index of the page it is on
+    pub fn texts(&self) -> impl Iterator<Item = (usize, &TextElement)> {
+        self.pages
+            .iter()
+            .enumerate()
+            .flat_map(|(index, page)| page.texts().map(move |t| (index, t)))
+    }
+
+    /// First text element whose content is exactly `text`
+    pub fn find_text(&self, text: &str) -> Option<(usize, &TextElement)> {
+        self.texts().find(|(_, t)| t.text == text)
+    }
+
+    /// Add the pages of another sheet after this one's
+    pub fn append(&mut self, other: SheetLayout) {
+        self.pages.extend(other.pages);
+    }
+
+    /// Multiply every rule's thickness by `factor`, leaving shading bands
+    pub fn scale_strokes(&mut self, factor: f32) {
+        for page in &mut self.pages {
+            for element in &mut page.elements {
+                match element {
+                    Element::Line(line) if line.stroke.thickness < SHADING_MIN_THICKNESS => {
+                        line.stroke.thickness *= factor;
+                    }
+                    Element::Rect(rect) => {
+                        if let Some(stroke) = &mut rect.style.stroke {
+                            stroke.thickness *= factor;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Pure black ink only: every rule black and at least
+    /// [`MONO_MIN_THICKNESS`], with shading bands and tinted fills dropped
+    pub fn make_mono(&mut self) {
+        let mono = |stroke: Stroke| Stroke {
+            color: Color::BLACK,
+            thickness: stroke.thickness.max(MONO_MIN_THICKNESS),
+        };
+        for page in &mut self.pages {
+            page.elements.retain(|element| {
+                !matches!(element, Element::Line(line) if line.stroke.thickness >= SHADING_MIN_THICKNESS)
+            });
+            for element in &mut page.elements {
+                match element {
+                    Element::Line(line) => line.stroke = mono(line.stroke),
+                    Element::Rect(rect) => {
+                        rect.style.stroke = rect.style.stroke.map(mono);
+                        rect.style.fill = rect.style.fill.filter(|fill| *fill == Color::BLACK);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    pub(crate) fn new_page(&mut self) -> &mut Page {
+        self.pages.push(Page::default());
+        self.pages.last_mut().expect("page was just pushed")
+    }
+}
+
+// ============================================================================
+// Pagination
+// ============================================================================
+
+/// How a run of rows may break across pages, so no heading is left alone at
+/// the foot of a page and no row or two is stranded at the top of the next
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keep {
+    /// Height of the heading over the rows (a month name, column headings),
+    /// repeated on each page they reach and kept with the first of them
+    pub heading: f32,
+    /// Fewest rows that may sit under the heading before a break, or carry
+    /// over after one
+    pub min_rows: usize,
+}
+
+impl Keep {
+    /// Rows that may break anywhere, headed by nothing
+    pub const ROWS: Keep = Keep { heading: 0.0, min_rows: 1 };
+    /// Never split: all on this page or all on the next
+    pub const WHOLE: Keep = Keep { heading: 0.0, min_rows: usize::MAX };
+
+    /// Under a heading of `heading` mm, at least `min_rows` either side of a
+    /// break
+    pub const fn with_next(heading: f32, min_rows: usize) -> Keep {
+        Keep { heading, min_rows }
+    }
+}
+
+/// Split rows of these heights into one run per page. The first run is what
+/// fits in `first_available`, the rest of the current page, and is empty when
+/// the rows should start on a new page instead; each later run gets a fresh
+/// page of `page_available`. A page too short for the rule takes what fits.
+pub fn paginate(heights: &[f32], keep: Keep, first_available: f32, page_available: f32) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut available = first_available;
+    while start < heights.len() {
+        let mut end = start;
+        let mut used = keep.heading;
+        while end < heights.len() && used + heights[end] <= available {
+            used += heights[end];
+            end += 1;
+        }
+        if end < heights.len() {
+            // Widows: leave the next page its share, then orphans: too few
+            // here and the whole run moves on
+            let carried = heights.len() - end;
+            if carried < keep.min_rows {
+                end = end.saturating_sub(keep.min_rows - carried).max(start);
+            }
+            if end - start < keep.min_rows {
+                end = start;
+            }
+        }
+        let fresh = !runs.is_empty() || first_available >= page_available;
+        if end == start && fresh {
+            // Nothing better to be had on a later page
+            end = start + 1;
+            let mut used = keep.heading + heights[start];
+            while end < heights.len() && used + heights[end] <= available {
+                used += heights[end];
+                end += 1;
+            }
+        }
+        runs.push(start..end);
+        start = end;
+        available = page_available;
+    }
+    runs
+}
+
+// ============================================================================
+// Page Plan
+// ============================================================================
+
+/// A band of the first page across the content width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Block {
+    Header,
+    Banner,
+    /// Whatever room the others leave, for the attendance grid
+    Grid,
+    Online,
+    Helpers,
+    Mailing,
+    Feedback,
+    Coupons,
+}
+
+/// The footer sections from the bottom up: coupons tear off the very bottom,
+/// online attendees sit nearest the room's grid
+const FOOTER_BLOCKS: [Block; 5] = [Block::Coupons, Block::Feedback, Block::Mailing, Block::Helpers, Block::Online];
+
+/// Where a block goes, in mm up from the bottom edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedBlock {
+    pub block: Block,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl PlacedBlock {
+    pub fn height(&self) -> f32 {
+        self.top - self.bottom
+    }
+}
+
+/// The first page as blocks: the header and banner down from the top margin,
+/// the footer sections up from the page footer strip, the grid between them
+#[derive(Debug, Clone, PartialEq)]
+pub struct PagePlan {
+    /// Top to bottom, without the blocks this sheet has none of
+    pub blocks: Vec<PlacedBlock>,
+    /// Where content stops above the page footer strip, on every page
+    pub content_bottom: f32,
+}
+
+impl PagePlan {
+    pub fn block(&self, block: Block) -> Option<PlacedBlock> {
+        self.blocks.iter().find(|placed| placed.block == block).copied()
+    }
+
+    /// The grid's share of the page; every plan has one, though it may be
+    /// too short to hold a row
+    pub fn grid(&self) -> PlacedBlock {
+        self.block(Block::Grid).expect("every page plan has a grid")
+    }
+}
+
+/// Plan the first page of the sheet described by `config`
+pub fn plan_first_page(config: &AttendanceConfig) -> PagePlan {
+    plan_first_page_with(config, mailing_on_first_page(config))
+}
+
+/// As [`plan_first_page`], with or without the mailing list in the footer
+fn plan_first_page_with(config: &AttendanceConfig, with_mailing: bool) -> PagePlan {
+    let content_bottom = MARGIN_MM + calculate_page_footer_height(config);
+    let mut blocks = Vec::new();
+
+    let mut y = PAGE_HEIGHT_MM - MARGIN_MM;
+    for block in [Block::Header, Block::Banner] {
+        let height = block_height(config, block);
+        if height > 0.0 {
+            blocks.push(PlacedBlock { block, top: y, bottom: y - height });
+            y -= height;
+        }
+    }
+    let grid_top = y;
+
+    let mut footer = Vec::new();
+    let mut y = content_bottom;
+    for block in FOOTER_BLOCKS {
+        let height = if block == Block::Mailing && !with_mailing { 0.0 } else { block_height(config, block) };
+        if height > 0.0 {
+            footer.push(PlacedBlock { block, top: y + height, bottom: y });
+            y += height;
+        }
+    }
+
+    blocks.push(PlacedBlock { block: Block::Grid, top: grid_top, bottom: y });
+    blocks.extend(footer.into_iter().rev());
+    PagePlan { blocks, content_bottom }
+}
+
+/// Room a block takes when the sheet has it, nothing when it doesn't; the
+/// grid's height is whatever is left
+fn block_height(config: &AttendanceConfig, block: Block) -> f32 {
+    match block {
+        Block::Header => header_height(config),
+        Block::Banner => banner_height(config),
+        Block::Grid => 0.0,
+        Block::Online => calculate_online_section_height(config),
+        Block::Helpers => calculate_helper_section_height(config.helper_rows),
+        Block::Mailing if config.mailing_list => calculate_mailing_section_height(config.mailing_rows),
+        Block::Mailing => 0.0,
+        Block::Feedback => calculate_feedback_height(config),
+        Block::Coupons => calculate_coupon_strip_height(config.coupons),
+    }
+}
+
+/// Draw one of the first page's blocks other than the grid in its place
+fn layout_block(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    placed: PlacedBlock,
+    content_width: f32,
+) -> Result<(), AppError> {
+    match placed.block {
+        Block::Header => layout_header_section(page, config, placed.top, content_width)?,
+        Block::Banner => {
+            if let Some(banner) = &config.banner {
+                layout_banner(page, banner, placed.top, content_width);
+            }
+        }
+        Block::Grid => {}
+        Block::Online => {
+            if let Some(names) = &config.online_attendees {
+                layout_online_section(page, names, placed.bottom, MARGIN_MM, content_width);
+            }
+        }
+        Block::Helpers => layout_helper_section(page, config.helper_rows, placed.bottom, MARGIN_MM, content_width),
+        Block::Mailing => {
+            let heading = roster::mailing_heading(config);
+            layout_mailing_section(page, config.mailing_rows, placed.bottom, MARGIN_MM, content_width, &heading)?;
+        }
+        Block::Feedback => {
+            if let Some(url) = &config.feedback_url {
+                layout_feedback_section(page, config, url, placed.bottom, content_width)?;
+            }
+        }
+        Block::Coupons => layout_coupon_strip(page, config, placed.bottom, content_width)?,
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Sheet Layout
+// ============================================================================
+
+/// Lay out the attendance sheet described by `config`
+pub fn layout_sheet(config: &AttendanceConfig) -> Result<SheetLayout, AppError> {
+    layout_sheet_timed(config, &mut PhaseTimings::default())
+}
+
+/// As [`layout_sheet`], adding the time spent in each phase to `timings`
+pub fn layout_sheet_timed(
+    config: &AttendanceConfig,
+    timings: &mut PhaseTimings,
+) -> Result<SheetLayout, AppError> {
+    let phase_start = Instant::now();
+
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: vec![Page::default()],
+    };
+
+    // The first page's blocks, the grid taking the room the others leave
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let plan = plan_first_page(config);
+    let grid = plan.grid();
+    let grid_index = plan.blocks.iter().position(|placed| placed.block == Block::Grid).unwrap_or(0);
+    let (above_grid, below_grid) = plan.blocks.split_at(grid_index);
+
+    // Header section (QR code + title + info) and banner
+    for placed in above_grid {
+        layout_block(&mut sheet.pages[0], config, *placed, content_width)?;
+    }
+    timings.header += phase_start.elapsed();
+    let phase_start = Instant::now();
+
+    // Content stops above the page footer strip, which repeats on every page
+    let content_bottom = plan.content_bottom;
+    let mut y_pos = grid.top;
+
+    // For blank mode, use fixed row height and support multiple pages
+    if config.roster.is_none() {
+        let row_height = blank_row_height(config);
+        let row_numbers = row_numbers(config);
+        let first_page_available = grid.height() - 5.0;
+        let continuation_top = PAGE_HEIGHT_MM - MARGIN_MM - CONTINUATION_HEADER_HEIGHT_MM;
+        let continuation_page_available = continuation_top - content_bottom;
+
+        let seats = ["North", "South", "East", "West"];
+        let num_tables = config.blank_rows.div_ceil(4);
+        let table_height = row_height * 4.0; // Height needed for one complete table
+
+        // Keep tables together - pages break only between them
+        let tables = vec![table_height; num_tables as usize];
+        let page_starts: Vec<u32> = paginate(&tables, Keep::ROWS, first_page_available, continuation_page_available)
+            .iter()
+            .skip(1)
+            .map(|run| run.start as u32 + 1)
+            .collect();
+        let mut page_index = 0;
+
+        for table in 1..=num_tables {
+            if page_starts.contains(&table) {
+                layout_continuation_header(sheet.new_page(), config, content_width);
+                page_index += 1;
+                y_pos = continuation_top;
+            }
+
+            // Draw all 4 seats for this table
+            for (seat_idx, seat) in seats.iter().enumerate() {
+                let current_row = (table - 1) * 4 + seat_idx as u32;
+                if current_row >= config.blank_rows {
+                    break;
+                }
+
+                let is_first_seat = seat_idx == 0;
+                let is_last_seat = seat_idx == 3;
+                let row = RowBand { top: y_pos, height: row_height };
+                layout_table_seat_row(
+                    &mut sheet.pages[page_index],
+                    row,
+                    MARGIN_MM,
+                    content_width,
+                    table,
+                    seat,
+                    is_first_seat,
+                    is_last_seat,
+                );
+                if let Some(numbers) = &row_numbers {
+                    let number = numbers[current_row as usize];
+                    layout_ticket_number(&mut sheet.pages[page_index], number, MARGIN_MM + content_width, row);
+                }
+                y_pos -= row_height;
+            }
+        }
+    } else if let Some(roster) = &config.roster {
+        // Roster mode - adaptive row height, overflowing into three columns
+        // or more pages when the names will not fit
+        let grid_bottom = layout_attendance_grid(&mut sheet, config, roster, y_pos, content_width)?;
+        y_pos = grid_bottom;
+        let legend = roster::legend(config);
+        if !legend.is_empty() {
+            let page = sheet.pages.last_mut().expect("sheet has a page");
+            layout_roster_legend(page, &legend, grid_bottom);
+            y_pos -= LEGEND_HEIGHT_MM;
+        }
+    }
+
+    // A grid that ran past the first page takes the mailing list with it
+    if config.mailing_list && plan.block(Block::Mailing).is_none() {
+        layout_flowing_mailing(&mut sheet, config, &plan, y_pos, content_width)?;
+    }
+
+    timings.grid += phase_start.elapsed();
+    let phase_start = Instant::now();
+
+    // The footer sections, from the coupons along the bottom edge up
+    for placed in below_grid.iter().skip(1).rev() {
+        layout_block(&mut sheet.pages[0], config, *placed, content_width)?;
+    }
+
+    if config.overflow_page {
+        layout_overflow_page(&mut sheet, config, content_width, content_bottom)?;
+    }
+
+    if config.raffle && !config.draw_numbers {
+        layout_raffle_tickets(&mut sheet, config, content_width, content_bottom);
+    }
+
+    if let Some(schedule) = &config.calendar {
+        layout_series_calendar(&mut sheet, config, schedule, content_width, content_bottom);
+    }
+
+    if let Some(text) = &config.waiver {
+        layout_waiver(&mut sheet, config, text, content_width, content_bottom);
+    }
+
+    if let Some(items) = &config.checklist {
+        layout_checklist(&mut sheet, config, items, content_width, content_bottom);
+    }
+
+    if let Some(deals) = &config.hands {
+        layout_practice_hands(&mut sheet, config, deals, content_width, content_bottom);
+    }
+
+    if let Some(info) = &config.emergency_page {
+        layout_emergency_page(sheet.new_page(), info, content_width);
+    }
+
+    // Copies repeat every page, so they are made before the footer numbers
+    // the physical sheets
+    let copy_pages = sheet.pages.clone();
+    for _ in 1..config.copies {
+        sheet.pages.extend(copy_pages.iter().cloned());
+    }
+
+    layout_page_footer(&mut sheet, config, content_width);
+
+    if config.economy {
+        sheet.scale_strokes(ECONOMY_STROKE_SCALE);
+    }
+    if config.mono {
+        sheet.make_mono();
+    }
+
+    timings.mailing += phase_start.elapsed();
+
+    Ok(sheet)
+}
+
+// ============================================================================
+// Header Section
+// ============================================================================
+
+/// Sizes and baseline offsets (mm below the top margin) of the header
+struct HeaderStyle {
+    qr_size: f32,
+    title_size: f32,
+    name_size: f32,
+    info_size: f32,
+    /// Title, class name, date and instructor line
+    baselines: [f32; 4],
+    /// Between the instructor and location lines
+    info_spacing: f32,
+}
+
+const FULL_HEADER: HeaderStyle = HeaderStyle {
+    qr_size: QR_SIZE_MM,
+    title_size: TITLE_FONT_SIZE,
+    name_size: HEADER_FONT_SIZE,
+    info_size: NORMAL_FONT_SIZE,
+    baselines: [6.0, 14.0, 20.0, 26.0],
+    info_spacing: 5.0,
+};
+
+/// `--economy`: everything fits beside the smaller QR code
+const ECONOMY_HEADER: HeaderStyle = HeaderStyle {
+    qr_size: ECONOMY_QR_SIZE_MM,
+    title_size: HEADER_FONT_SIZE,
+    name_size: NORMAL_FONT_SIZE,
+    info_size: SMALL_FONT_SIZE,
+    baselines: [4.5, 9.5, 13.5, 17.0],
+    info_spacing: 3.5,
+};
+
+fn layout_header_section(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    start_y: f32,
+    content_width: f32,
+) -> Result<(), AppError> {
+    let y_pos = start_y;
+    let style = if config.economy { &ECONOMY_HEADER } else { &FULL_HEADER };
+    let qr_size = style.qr_size;
+
+    // Generate and place QR code
+    page.image(
+        cached_qr_image(config, qr_size)?,
+        MARGIN_MM,
+        y_pos - qr_size,
+        qr_size,
+        qr_size,
+        false,
+    );
+
+    // Title and info to the right of QR code
+    let text_x = MARGIN_MM + qr_size + 8.0;
+    let [title_y, name_y, date_y, teacher_y] = style.baselines;
+
+    // Title, aligned within the room beside the QR code
+    let title_x = match config.title_align {
+        TextAlign::Left => text_x,
+        TextAlign::Center => text_x + header_text_width(config, style, content_width) / 2.0,
+        TextAlign::Right => text_x + header_text_width(config, style, content_width),
+    };
+    page.aligned_text(
+        sheet_title(config),
+        style.title_size,
+        title_x,
+        y_pos - title_y,
+        FontStyle::Bold,
+        config.title_align,
+    );
+
+    // Class name, over two lines if it is long; what follows moves down to
+    // make room for the second
+    let (name_lines, name_size) = fit_class_name(config, style, content_width);
+    let name_line_height = name_size / PT_PER_MM * NAME_LINE_SPACING;
+    for (index, line) in name_lines.iter().enumerate() {
+        let line_y = y_pos - name_y - index as f32 * name_line_height;
+        page.text(line, name_size, text_x, line_y, FontStyle::Bold);
+    }
+    let name_extra = name_extra_height(config, style, content_width);
+    let (date_y, teacher_y) = (date_y + name_extra, teacher_y + name_extra);
+
+    // Date
+    page.text(
+        format_date_display(&config.date),
+        style.info_size,
+        text_x,
+        y_pos - date_y,
+        FontStyle::Regular,
+    );
+
+    // Teacher
+    page.text(
+        instructor_line(config),
+        style.info_size,
+        text_x,
+        y_pos - teacher_y,
+        FontStyle::Regular,
+    );
+
+    // Location (if provided)
+    let mut info_y = y_pos - teacher_y;
+    if !config.location.is_empty() {
+        info_y -= style.info_spacing;
+        page.text(
+            format!("Location: {}", config.location),
+            style.info_size,
+            text_x,
+            info_y,
+            FontStyle::Regular,
+        );
+    }
+
+    // Venue details under the QR code
+    if let Some(venue) = venue_line(config) {
+        page.text(venue, SMALL_FONT_SIZE, MARGIN_MM, y_pos - qr_size - 5.5, FontStyle::Regular);
+    }
+
+    // Logo in top-right (if provided)
+    let right_edge = MARGIN_MM + content_width;
+
+    if let (Some(logo), false) = (&config.logo, config.mono || config.economy) {
+        layout_logo(page, logo, LOGO_MAX_WIDTH_MM, LOGO_MAX_HEIGHT_MM, right_edge, y_pos);
+    }
+
+    // Event ID (right-aligned, below logo area)
+    page.right_aligned_text(
+        format!("ID: {}", config.event_id),
+        SMALL_FONT_SIZE,
+        right_edge,
+        y_pos - qr_size - 2.0,
+        FontStyle::Regular,
+    );
+
+    Ok(())
+}
+
+/// Height of the header, including the space under the QR code, or under
+/// the text beside it when a long class name pushes that lower
+fn header_height(config: &AttendanceConfig) -> f32 {
+    let style = if config.economy { &ECONOMY_HEADER } else { &FULL_HEADER };
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let mut text_bottom = style.baselines[3] + name_extra_height(config, style, content_width);
+    if !config.location.is_empty() {
+        text_bottom += style.info_spacing;
+    }
+    (style.qr_size + 8.0).max(text_bottom + HEADER_TEXT_GAP_MM)
+}
+
+/// Room under the last line of header text before the grid
+const HEADER_TEXT_GAP_MM: f32 = 7.0;
+/// Baseline to baseline, as a multiple of the size, when the class name wraps
+const NAME_LINE_SPACING: f32 = 1.15;
+/// The class name shrinks this far to stay on one line before it wraps...
+const NAME_SHRINK_ONE_LINE: f32 = 0.85;
+/// ...and this far to fit on two before the rest is cut short
+const NAME_SHRINK_TWO_LINES: f32 = 0.7;
+const NAME_SIZE_STEP: f32 = 0.5;
+
+/// Width the title and class name may take beside the QR code, clear of the
+/// logo
+fn header_text_width(config: &AttendanceConfig, style: &HeaderStyle, content_width: f32) -> f32 {
+    let width = content_width - style.qr_size - 8.0;
+    if config.logo.is_some() && !(config.mono || config.economy) {
+        width - LOGO_MAX_WIDTH_MM - 4.0
+    } else {
+        width
+    }
+}
+
+/// The class name's lines and size: one line if a slightly smaller size
+/// fits it, else two with long words hyphenated, shrinking further as need
+/// be; past that the second line ends in an ellipsis
+fn fit_class_name(config: &AttendanceConfig, style: &HeaderStyle, content_width: f32) -> (Vec<String>, f32) {
+    let name = config.class_name.trim();
+    let width = header_text_width(config, style, content_width);
+    let full_size = style.name_size;
+
+    let mut size = full_size;
+    while size >= full_size * NAME_SHRINK_ONE_LINE {
+        if text_width_mm(name, size, FontStyle::Bold) <= width {
+            return (vec![name.to_string()], size);
+        }
+        size -= NAME_SIZE_STEP;
+    }
+
+    let min_size = full_size * NAME_SHRINK_TWO_LINES;
+    let mut size = full_size;
+    while size >= min_size {
+        let lines = wrap_hyphenated(name, size, FontStyle::Bold, width);
+        if lines.len() <= 2 {
+            return (lines, size);
+        }
+        size -= NAME_SIZE_STEP;
+    }
+
+    let lines = wrap_hyphenated(name, min_size, FontStyle::Bold, width);
+    let rest = truncate_to_width(&lines[1..].join(" "), min_size, FontStyle::Bold, width);
+    (vec![lines[0].clone(), rest], min_size)
+}
+
+/// How much further down the header text goes for a class name on two lines
+fn name_extra_height(config: &AttendanceConfig, style: &HeaderStyle, content_width: f32) -> f32 {
+    let (lines, size) = fit_class_name(config, style, content_width);
+    (lines.len() - 1) as f32 * size / PT_PER_MM * NAME_LINE_SPACING
+}
+
+/// Room the continuation header takes at the top of an added page; kept
+/// small so the page still holds five tables
+const CONTINUATION_HEADER_HEIGHT_MM: f32 = 9.0;
+
+/// Class name, date and event ID across the top of a page the sheet
+/// continues onto, so a loose page still says which class it belongs to
+fn layout_continuation_header(page: &mut Page, config: &AttendanceConfig, content_width: f32) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let baseline = top_y - 4.5;
+    let details = format!("{}   Event {}", format_date_display(&config.date), config.event_id);
+    let details_width = text_width_mm(&details, SMALL_FONT_SIZE, FontStyle::Regular);
+    page.right_aligned_text(details, SMALL_FONT_SIZE, MARGIN_MM + content_width, baseline, FontStyle::Regular);
+
+    let continued = " (continued)";
+    let name_width =
+        content_width - details_width - 6.0 - text_width_mm(continued, HEADER_FONT_SIZE, FontStyle::Bold);
+    let name = truncate_to_width(&config.class_name, HEADER_FONT_SIZE, FontStyle::Bold, name_width);
+    page.text(format!("{}{}", name, continued), HEADER_FONT_SIZE, MARGIN_MM, baseline, FontStyle::Bold);
+    page.line(MARGIN_MM, top_y - 6.5, MARGIN_MM + content_width, top_y - 6.5, BORDER_STROKE);
+}
+
+/// `--banner` notice: large bold text in a heavy box
+const BANNER_BOX_HEIGHT_MM: f32 = 14.0;
+const BANNER_GAP_MM: f32 = 4.0;
+const BANNER_FONT_SIZE: f32 = 22.0;
+const BANNER_STROKE: Stroke = Stroke { color: Color::BLACK, thickness: 2.0 };
+
+/// Room the banner takes above the grid, including the gap under it
+fn banner_height(config: &AttendanceConfig) -> f32 {
+    if config.banner.is_some() {
+        BANNER_BOX_HEIGHT_MM + BANNER_GAP_MM
+    } else {
+        0.0
+    }
+}
+
+/// Boxed notice across the full content width, the text shrunk if need be
+/// to fit on one line
+fn layout_banner(page: &mut Page, text: &str, top_y: f32, content_width: f32) {
+    let bottom_y = top_y - BANNER_BOX_HEIGHT_MM;
+    let right_x = MARGIN_MM + content_width;
+    page.line(MARGIN_MM, top_y, right_x, top_y, BANNER_STROKE);
+    page.line(right_x, top_y, right_x, bottom_y, BANNER_STROKE);
+    page.line(right_x, bottom_y, MARGIN_MM, bottom_y, BANNER_STROKE);
+    page.line(MARGIN_MM, bottom_y, MARGIN_MM, top_y, BANNER_STROKE);
+
+    let max_width = content_width - 8.0;
+    let natural_width = text_width_mm(text, BANNER_FONT_SIZE, FontStyle::Bold);
+    let size = if natural_width > max_width {
+        BANNER_FONT_SIZE * max_width / natural_width
+    } else {
+        BANNER_FONT_SIZE
+    };
+    // Cap height is about 0.7 of the point size
+    let text_y = bottom_y + (BANNER_BOX_HEIGHT_MM - 0.7 * size / PT_PER_MM) / 2.0;
+    page.centered_text(text, size, MARGIN_MM + content_width / 2.0, text_y, FontStyle::Bold);
+}
+
+pub(crate) fn layout_logo(
+    page: &mut Page,
+    logo_image: &Arc<PreparedImage>,
+    max_width_mm: f32,
+    max_height_mm: f32,
+    right_edge_x: f32,
+    top_y: f32,
+) {
+    let (width_px, height_px) = (logo_image.width_px, logo_image.height_px);
+
+    // Calculate dimensions preserving aspect ratio
+    let aspect_ratio = width_px as f32 / height_px as f32;
+    let (final_width_mm, final_height_mm) = if max_width_mm / max_height_mm > aspect_ratio {
+        // Height-constrained
+        (max_height_mm * aspect_ratio, max_height_mm)
+    } else {
+        // Width-constrained
+        (max_width_mm, max_width_mm / aspect_ratio)
+    };
+
+    // Calculate position (right-aligned, top-aligned)
+    let x = right_edge_x - final_width_mm;
+    let y = top_y - final_height_mm;
+
+    page.image(Arc::clone(logo_image), x, y, final_width_mm, final_height_mm, true);
+}
+
+// ============================================================================
+// Attendance Grid
+// ============================================================================
+
+/// Blank rows per roster column for walk-ins
+pub(crate) const ROSTER_WALK_IN_ROWS: u32 = 4;
+
+/// Gap between roster columns
+const ROSTER_COLUMN_GAP_MM: f32 = 6.0;
+/// Fewest rows the roster carries over onto a further page, as
+/// [`Keep::min_rows`]
+const ROSTER_MIN_ROWS: usize = 3;
+const ROSTER_HEADER_ROW_HEIGHT_MM: f32 = 6.0;
+
+/// What the roster grid did because the names would not fit in two columns
+/// at [`MIN_ROW_HEIGHT_MM`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterOverflow {
+    /// Three narrower columns on the first page
+    ThreeColumns { names: usize },
+    /// Two columns continued over `pages` pages
+    Pages { names: usize, pages: usize },
+}
+
+impl std::fmt::Display for RosterOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RosterOverflow::ThreeColumns { names } => write!(
+                f,
+                "{} names do not fit in two columns; the roster is printed in three narrower columns",
+                names
+            ),
+            RosterOverflow::Pages { names, pages } => write!(
+                f,
+                "{} names do not fit on one page, even in three columns; the roster continues over {} pages",
+                names, pages
+            ),
+        }
+    }
+}
+
+/// One column of the roster grid: a run of names, then walk-in rows
+struct RosterColumn<'a> {
+    students: &'a [Student],
+    blank_rows: u32,
+    /// Position of the column's first row among all the grid's rows, which is
+    /// where its row numbers start
+    first_row: usize,
+}
+
+/// How the roster grid is split into columns and pages
+struct RosterGrid<'a> {
+    row_height: f32,
+    /// Columns on each page, left to right
+    pages: Vec<Vec<RosterColumn<'a>>>,
+    overflow: Option<RosterOverflow>,
+}
+
+/// Height the grid's rows may fill on the first page and on each page the
+/// roster continues onto
+fn roster_grid_space(config: &AttendanceConfig) -> (f32, f32) {
+    roster_grid_space_with(config, mailing_on_first_page(config))
+}
+
+/// As [`roster_grid_space`], with or without room for the mailing list at the
+/// foot of the first page
+fn roster_grid_space_with(config: &AttendanceConfig, with_mailing: bool) -> (f32, f32) {
+    let plan = plan_first_page_with(config, with_mailing);
+    let legend_height = if roster::legend(config).is_empty() { 0.0 } else { LEGEND_HEIGHT_MM };
+    let reserved = ROSTER_HEADER_ROW_HEIGHT_MM + legend_height + 5.0;
+
+    let first = plan.grid().height() - reserved;
+    let continuation = PAGE_HEIGHT_MM - MARGIN_MM - plan.content_bottom - reserved;
+    (first, continuation)
+}
+
+/// How the grid would cope with this roster, or `None` if it fits as usual.
+/// Worth a warning: the sheet looks different from what the teacher expects.
+pub fn roster_overflow(config: &AttendanceConfig) -> Option<RosterOverflow> {
+    let roster = config.roster.as_ref()?;
+    let (first_available, continuation_available) = roster_grid_space(config);
+    plan_roster_grid(config, roster, first_available, continuation_available)
+        .ok()?
+        .overflow
+}
+
+fn plan_roster_grid<'a>(
+    config: &AttendanceConfig,
+    roster: &'a [Student],
+    first_available: f32,
+    continuation_available: f32,
+) -> Result<RosterGrid<'a>, AppError> {
+    let blank_rows = ROSTER_WALK_IN_ROWS;
+    let max_row_height = if config.economy { ECONOMY_MAX_ROW_HEIGHT_MM } else { MAX_ROW_HEIGHT_MM };
+
+    // Two columns, the left one getting the extra name if odd, each ending in
+    // walk-in rows
+    let (left, right) = roster.split_at(roster.len().div_ceil(2));
+    let left_total = left.len() as u32 + blank_rows;
+    let right_total = right.len() as u32 + blank_rows;
+
+    // Rows with a note grow by one small line; the rest share what's left
+    let row_height = ((first_available - note_lines(left)) / left_total as f32)
+        .min((first_available - note_lines(right)) / right_total as f32)
+        .min(max_row_height);
+    if config.signature_column && row_height < SIGNATURE_MIN_ROW_HEIGHT_MM {
+        let rows_per_column = (first_available / SIGNATURE_MIN_ROW_HEIGHT_MM) as u32;
+        let capacity = 2 * rows_per_column.saturating_sub(blank_rows);
+        return Err(AppError::ConfigError(format!(
+            "{} names do not leave room to sign; --signature-column fits at most {} on this sheet",
+            roster.len(),
+            capacity
+        )));
+    }
+    if row_height >= MIN_ROW_HEIGHT_MM {
+        let columns = vec![
+            RosterColumn {
+                students: left,
+                blank_rows,
+                first_row: 0,
+            },
+            RosterColumn {
+                students: right,
+                blank_rows,
+                first_row: left_total as usize,
+            },
+        ];
+        return Ok(RosterGrid {
+            row_height,
+            pages: vec![columns],
+            overflow: None,
+        });
+    }
+
+    // Too many names: all the walk-in rows go at the end and the rows flow
+    // down three narrower columns, assuming the worst case that every note
+    // lands in the same column
+    let walk_ins = 2 * blank_rows;
+    let rows_per_column = (roster.len() + walk_ins as usize).div_ceil(3);
+    let row_height = ((first_available - note_lines(roster)) / rows_per_column as f32).min(max_row_height);
+    let pages = if row_height >= MIN_ROW_HEIGHT_MM {
+        flow_roster_columns(roster, walk_ins, 3, rows_per_column, row_height, first_available, first_available)
+    } else {
+        // Even that is unreadable, so keep the usual columns and rows and
+        // continue onto more pages
+        let row_height = max_row_height;
+        let pages = flow_roster_columns(
+            roster,
+            walk_ins,
+            2,
+            usize::MAX,
+            row_height,
+            first_available,
+            continuation_available,
+        );
+        return Ok(RosterGrid {
+            row_height,
+            overflow: Some(RosterOverflow::Pages {
+                names: roster.len(),
+                pages: pages.len(),
+            }),
+            pages,
+        });
+    };
+    Ok(RosterGrid {
+        row_height,
+        pages,
+        overflow: Some(RosterOverflow::ThreeColumns { names: roster.len() }),
+    })
+}
+
+/// Fill `columns` columns per page with the names and then `blank_rows`
+/// walk-in rows, moving to the next column when one holds `max_rows` rows or
+/// runs out of height, and to a new page when the columns are full
+fn flow_roster_columns<'a>(
+    roster: &'a [Student],
+    blank_rows: u32,
+    columns: usize,
+    max_rows: usize,
+    row_height: f32,
+    first_available: f32,
+    continuation_available: f32,
+) -> Vec<Vec<RosterColumn<'a>>> {
+    let total_rows = roster.len() + blank_rows as usize;
+    let mut pages = Vec::new();
+    let mut row = 0;
+    while row < total_rows {
+        let available = if pages.is_empty() { first_available } else { continuation_available };
+        let mut page = Vec::new();
+        while page.len() < columns && row < total_rows {
+            let first_row = row;
+            let mut height = 0.0;
+            while row < total_rows && row - first_row < max_rows {
+                let height_with_row = height + row_height + roster.get(row).map_or(0.0, note_height);
+                // Every column takes at least one row, so the flow always ends
+                if row > first_row && height_with_row > available {
+                    break;
+                }
+                height = height_with_row;
+                row += 1;
+            }
+            // The page's last column leaves the next page enough rows to
+            // stand on
+            let carried = total_rows - row;
+            if page.len() + 1 == columns && carried > 0 && carried < ROSTER_MIN_ROWS {
+                row = row.saturating_sub(ROSTER_MIN_ROWS - carried).max(first_row + 1);
+            }
+            let names = first_row.min(roster.len())..row.min(roster.len());
+            page.push(RosterColumn {
+                blank_rows: (row - first_row - names.len()) as u32,
+                students: &roster[names],
+                first_row,
+            });
+        }
+        pages.push(page);
+    }
+    pages
+}
+
+/// Lay out the roster grid from `start_y` on the first page, adding pages if
+/// it continues. Returns the bottom of the grid on the last of them.
+fn layout_attendance_grid(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    roster: &[Student],
+    start_y: f32,
+    content_width: f32,
+) -> Result<f32, AppError> {
+    let (first_available, continuation_available) = roster_grid_space(config);
+    let grid = plan_roster_grid(config, roster, first_available, continuation_available)?;
+    let row_height = grid.row_height;
+    let row_numbers = row_numbers(config);
+    let content_bottom = MARGIN_MM + calculate_page_footer_height(config);
+
+    // Photos get a slot in every row so the names stay aligned
+    let photo_width = if roster.iter().any(|student| student.photo.is_some()) {
+        (row_height - 1.0).min(PHOTO_MAX_SIZE_MM) + 2.0
+    } else {
+        0.0
+    };
+
+    let mut grid_bottom = start_y;
+    for (page_index, columns) in grid.pages.iter().enumerate() {
+        let top_y = if page_index == 0 {
+            start_y
+        } else {
+            sheet.new_page();
+            PAGE_HEIGHT_MM - MARGIN_MM
+        };
+        let page = sheet.pages.last_mut().expect("sheet has a page");
+
+        let area = PageRect::from_top(MARGIN_MM, top_y, content_width, top_y - content_bottom);
+        for (column, rect) in columns.iter().zip(area.columns(columns.len(), ROSTER_COLUMN_GAP_MM)) {
+            let rows = column.students.len() + column.blank_rows as usize;
+            layout_roster_column(
+                page,
+                config,
+                column.students,
+                &ColumnSpec::for_config(config, &rect),
+                top_y,
+                row_height,
+                column.blank_rows,
+                photo_width,
+                row_numbers.as_ref().map(|numbers| &numbers[column.first_row..column.first_row + rows]),
+                None,
+            );
+        }
+
+        let column_height = columns
+            .iter()
+            .map(|column| {
+                (column.students.len() as u32 + column.blank_rows) as f32 * row_height
+                    + note_lines(column.students)
+            })
+            .fold(0.0, f32::max);
+        grid_bottom = top_y - ROSTER_HEADER_ROW_HEIGHT_MM - column_height - row_height; // row_height of spacing
+    }
+    Ok(grid_bottom)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn layout_roster_column(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    students: &[Student],
+    cells: &ColumnSpec,
+    start_y: f32,
+    row_height: f32,
+    blank_rows: u32,
+    photo_width: f32,
+    numbers: Option<&[u32]>,
+    count_from: Option<u32>,
+) {
+    // Draw header
+    let header = RowBand { top: start_y, height: ROSTER_HEADER_ROW_HEIGHT_MM };
+    layout_grid_header(page, cells, header);
+    let mut row = header.next(row_height);
+
+    // Draw roster names
+    let mut numbers = numbers.map(|numbers| numbers.iter());
+    for student in students.iter() {
+        layout_roster_row(
+            page,
+            cells,
+            row,
+            &roster::display_name(student, config),
+            student,
+            photo_width,
+            student.birthday_in_week(config.date),
+        );
+        if let Some(number) = numbers.as_mut().and_then(Iterator::next) {
+            layout_ticket_number(page, *number, cells.table_x(), row);
+        }
+        row = row.extended(note_height(student)).next(row_height);
+    }
+
+    // Draw blank rows for walk-ins
+    for i in 0..blank_rows {
+        let row_num = count_from.unwrap_or(1) + students.len() as u32 + i;
+        layout_blank_row(page, cells, row, row_num, count_from.is_some());
+        if let Some(number) = numbers.as_mut().and_then(Iterator::next) {
+            layout_ticket_number(page, *number, cells.table_x(), row);
+        }
+        row = row.next(row_height);
+    }
+}
+
+fn layout_grid_header(page: &mut Page, cells: &ColumnSpec, row: RowBand) {
+    let text_y = row.text_y();
+
+    // Column headers
+    page.text("NAME", NORMAL_FONT_SIZE, cells.x + 2.0, text_y, FontStyle::Bold);
+    if cells.signature_width > 0.0 {
+        page.text("SIGNATURE", SMALL_FONT_SIZE, cells.signature_x() + 2.0, text_y, FontStyle::Bold);
+    }
+    if cells.consent_width > 0.0 {
+        page.text("PHOTO OK?", SMALL_FONT_SIZE - 2.0, cells.consent_x() + 1.0, text_y, FontStyle::Bold);
+    }
+    if cells.time_width > 0.0 {
+        page.text("TIME", SMALL_FONT_SIZE - 2.0, cells.time_x() + 1.0, text_y, FontStyle::Bold);
+    }
+    page.text("TABLE", SMALL_FONT_SIZE, cells.table_x() + 2.0, text_y, FontStyle::Bold);
+    page.text("SEAT", SMALL_FONT_SIZE, cells.seat_x() + 2.0, text_y, FontStyle::Bold);
+
+    // Bottom line of header
+    page.line(cells.x, row.bottom(), cells.right(), row.bottom(), BORDER_STROKE);
+}
+
+fn layout_roster_row(
+    page: &mut Page,
+    cells: &ColumnSpec,
+    row: RowBand,
+    name: &str,
+    student: &Student,
+    photo_width: f32,
+    birthday_week: bool,
+) {
+    let text_y = row.text_y();
+    let checkbox_size = 3.0;
+    let full_row = row.extended(note_height(student));
+
+    // Highlighter band behind the name cell, drawn first so it stays underneath
+    if student.highlight {
+        let band = Stroke {
+            color: HIGHLIGHT_COLOR,
+            thickness: (full_row.height - 1.0) * PT_PER_MM,
+        };
+        let band_y = full_row.top - full_row.height / 2.0;
+        page.line(cells.x, band_y, cells.x + cells.name_width, band_y, band);
+    }
+
+    // Draw checkbox, or the signature cell that replaces it
+    let photo_x = if cells.signature_width > 0.0 {
+        layout_signature_cell(page, cells.signature_x(), cells.signature_width, full_row);
+        cells.x + 2.0
+    } else {
+        layout_checkbox(page, cells.x + 1.0, text_y - 0.5, checkbox_size, student.confirmed);
+        cells.x + checkbox_size + 3.0
+    };
+
+    // Draw photo thumbnail, centered in its slot
+    if let Some(photo) = &student.photo {
+        let size = photo_width - 2.0;
+        let aspect_ratio = photo.width_px as f32 / photo.height_px as f32;
+        let (width, height) = if aspect_ratio > 1.0 {
+            (size, size / aspect_ratio)
+        } else {
+            (size * aspect_ratio, size)
+        };
+        page.image(
+            Arc::clone(photo),
+            photo_x + (size - width) / 2.0,
+            row.top - (row.height + height) / 2.0,
+            width,
+            height,
+            true,
+        );
+    }
+
+    // Draw name, kept within its cell, and the marks that still fit after it
+    let font = if student.bold { FontStyle::Bold } else { FontStyle::Regular };
+    let name_x = photo_x + photo_width;
+    let room = cells.x + cells.name_width - name_x - NAME_CELL_PADDING_MM;
+    let mut marks = Vec::new();
+    if let Some(pronouns) = &student.pronouns {
+        marks.push(NameMark::Pronouns(format!("({})", pronouns)));
+    }
+    if student.is_new {
+        marks.push(NameMark::NewBadge);
+    }
+    if birthday_week {
+        marks.push(NameMark::Cake);
+    }
+    let (name, size, kept) = fit_roster_name(name, font, &marks, room);
+    page.text(&name, size, name_x, text_y, font);
+    let mut mark_x = name_x + text_width_mm(&name, size, font) + MARK_GAP_MM;
+    for mark in &marks[..kept] {
+        match mark {
+            NameMark::Pronouns(pronouns) => {
+                page.text(pronouns, SMALL_FONT_SIZE, mark_x, text_y, FontStyle::Regular)
+            }
+            NameMark::NewBadge => layout_badge(page, roster::NEW_BADGE, mark_x, text_y),
+            NameMark::Cake => layout_cake(page, mark_x, text_y),
+        }
+        mark_x += mark.width() + MARK_SPACING_MM;
+    }
+    if let Some(note) = &student.note {
+        let note_y = text_y - NOTE_LINE_HEIGHT_MM;
+        let note = truncate_to_width(note, SMALL_FONT_SIZE, FontStyle::Regular, room);
+        page.text(&note, SMALL_FONT_SIZE, name_x, note_y, FontStyle::Regular);
+    }
+
+    if cells.consent_width > 0.0 {
+        layout_consent_cell(page, cells.consent_x(), text_y);
+    }
+    if cells.time_width > 0.0 {
+        layout_signature_cell(page, cells.time_x(), cells.time_width, full_row);
+    }
+
+    // Draw table column line
+    let table_x = cells.table_x();
+    page.line(table_x + 2.0, text_y - 0.5, table_x + cells.table_width - 1.0, text_y - 0.5, CHECKBOX_STROKE);
+
+    // Draw seat options
+    layout_seat_options(page, cells, text_y);
+
+    // Draw row bottom line
+    page.line(cells.x, full_row.bottom(), cells.right(), full_row.bottom(), ROW_STROKE);
+}
+
+/// Room kept clear at the right of the name cell
+const NAME_CELL_PADDING_MM: f32 = 1.0;
+/// A roster name shrinks this far before it is cut short
+const ROSTER_NAME_MIN_SIZE: f32 = 8.0;
+/// Between the name and its first mark, and between marks
+const MARK_GAP_MM: f32 = 2.0;
+const MARK_SPACING_MM: f32 = 1.5;
+const BADGE_PADDING_MM: f32 = 0.8;
+const CAKE_WIDTH_MM: f32 = 3.6;
+
+/// What may follow a roster name on its line, in the order drawn
+enum NameMark {
+    Pronouns(String),
+    NewBadge,
+    Cake,
+}
+
+impl NameMark {
+    fn width(&self) -> f32 {
+        match self {
+            NameMark::Pronouns(pronouns) => text_width_mm(pronouns, SMALL_FONT_SIZE, FontStyle::Regular),
+            NameMark::NewBadge => badge_width(roster::NEW_BADGE),
+            NameMark::Cake => CAKE_WIDTH_MM,
+        }
+    }
+}
+
+/// Width `marks` take after a name, with the gaps before and between them
+fn marks_width(marks: &[NameMark]) -> f32 {
+    if marks.is_empty() {
+        return 0.0;
+    }
+    let spacing = MARK_SPACING_MM * (marks.len() - 1) as f32;
+    MARK_GAP_MM + spacing + marks.iter().map(NameMark::width).sum::<f32>()
+}
+
+/// A roster name's text and size within `room`, and how many of `marks` fit
+/// after it: the name shrinks stepwise to [`ROSTER_NAME_MIN_SIZE`] beside
+/// all its marks, then they are dropped from the last, and a name too wide
+/// even alone is cut short
+fn fit_roster_name(name: &str, font: FontStyle, marks: &[NameMark], room: f32) -> (String, f32, usize) {
+    for kept in (0..=marks.len()).rev() {
+        let name_room = room - marks_width(&marks[..kept]);
+        let mut size = NORMAL_FONT_SIZE;
+        while size >= ROSTER_NAME_MIN_SIZE {
+            if text_width_mm(name, size, font) <= name_room {
+                return (name.to_string(), size, kept);
+            }
+            size -= NAME_SIZE_STEP;
+        }
+    }
+    (truncate_to_width(name, ROSTER_NAME_MIN_SIZE, font, room), ROSTER_NAME_MIN_SIZE, 0)
+}
+
+fn badge_width(label: &str) -> f32 {
+    text_width_mm(label, SMALL_FONT_SIZE - 2.0, FontStyle::Bold) + 2.0 * BADGE_PADDING_MM
+}
+
+/// Small boxed label (e.g. "NEW") whose text baseline is at `y`
+fn layout_badge(page: &mut Page, label: &str, x: f32, y: f32) {
+    page.rect(x, y - 0.8, badge_width(label), 3.6, OUTLINE_STYLE);
+    page.text(label, SMALL_FONT_SIZE - 2.0, x + BADGE_PADDING_MM, y, FontStyle::Bold);
+}
+
+/// Birthday cake sitting on the text baseline at `y`: two tiers and a lit
+/// candle. The built-in fonts have no such glyph, so it is drawn.
+fn layout_cake(page: &mut Page, x: f32, y: f32) {
+    let (width, bottom, middle, top) = (CAKE_WIDTH_MM, y - 0.6, y + 0.6, y + 1.6);
+    page.line(x, bottom, x + width, bottom, CHECKBOX_STROKE);
+    page.line(x, bottom, x, middle, CHECKBOX_STROKE);
+    page.line(x + width, bottom, x + width, middle, CHECKBOX_STROKE);
+    page.line(x, middle, x + width, middle, CHECKBOX_STROKE);
+    page.line(x + 0.5, middle, x + 0.5, top, CHECKBOX_STROKE);
+    page.line(x + width - 0.5, middle, x + width - 0.5, top, CHECKBOX_STROKE);
+    page.line(x + 0.5, top, x + width - 0.5, top, CHECKBOX_STROKE);
+
+    // Candle and flame
+    let center = x + width / 2.0;
+    page.line(center, top, center, top + 1.2, CHECKBOX_STROKE);
+    page.line(center, top + 1.4, center - 0.35, top + 1.8, CHECKBOX_STROKE);
+    page.line(center - 0.35, top + 1.8, center, top + 2.4, CHECKBOX_STROKE);
+    page.line(center, top + 2.4, center + 0.35, top + 1.8, CHECKBOX_STROKE);
+    page.line(center + 0.35, top + 1.8, center, top + 1.4, CHECKBOX_STROKE);
+}
+
+/// A row's small second line for its note
+const NOTE_LINE_HEIGHT_MM: f32 = 3.5;
+
+fn note_height(student: &Student) -> f32 {
+    if student.note.is_some() {
+        NOTE_LINE_HEIGHT_MM
+    } else {
+        0.0
+    }
+}
+
+/// Extra height the notes in `students` add to their column
+fn note_lines(students: &[Student]) -> f32 {
+    students.iter().map(note_height).sum()
+}
+
+fn layout_blank_row(page: &mut Page, cells: &ColumnSpec, row: RowBand, row_num: u32, show_number: bool) {
+    let text_y = row.text_y();
+
+    if cells.signature_width > 0.0 {
+        layout_signature_cell(page, cells.signature_x(), cells.signature_width, row);
+    }
+    if cells.consent_width > 0.0 {
+        layout_consent_cell(page, cells.consent_x(), text_y);
+    }
+    if cells.time_width > 0.0 {
+        layout_signature_cell(page, cells.time_x(), cells.time_width, row);
+    }
+
+    // Row number or empty
+    if show_number {
+        page.text(
+            format!("{}.", row_num),
+            SMALL_FONT_SIZE,
+            cells.x + 1.0,
+            text_y,
+            FontStyle::Regular,
+        );
+    }
+
+    // Seat options
+    layout_seat_options(page, cells, text_y);
+
+    // Row bottom line
+    page.line(cells.x, row.bottom(), cells.right(), row.bottom(), ROW_STROKE);
+}
+
+fn blank_row_height(config: &AttendanceConfig) -> f32 {
+    if config.economy {
+        ECONOMY_TABLE_SEAT_ROW_HEIGHT_MM
+    } else {
+        TABLE_SEAT_ROW_HEIGHT_MM
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn layout_table_seat_row(
+    page: &mut Page,
+    row: RowBand,
+    x_start: f32,
+    content_width: f32,
+    table_num: u32,
+    seat: &str,
+    is_first_seat: bool,
+    is_last_seat: bool,
+) {
+    let text_y = row.text_y(); // Center text vertically in row
+    let table_col_width = 22.0; // Width for "Table X" column
+    let seat_col_start = x_start + table_col_width;
+
+    // Draw table number only on first seat (North)
+    if is_first_seat {
+        page.text(
+            format!("Table {}", table_num),
+            NORMAL_FONT_SIZE,
+            x_start + 2.0,
+            text_y,
+            FontStyle::Regular,
+        );
+    }
+
+    // Draw seat name
+    page.text(seat, NORMAL_FONT_SIZE, seat_col_start + 2.0, text_y, FontStyle::Regular);
+
+    // Draw row separator line (starts at seat column, not table column)
+    // For last seat (West), draw full-width line as table separator
+    let line_start = if is_last_seat { x_start } else { seat_col_start };
+    page.line(line_start, row.bottom(), x_start + content_width, row.bottom(), TABLE_SEAT_STROKE);
+}
+
+const SEAT_OPTIONS: &str = "N  S  E  W";
+
+/// Room under the roster grid for the marker legend
+const LEGEND_HEIGHT_MM: f32 = 6.0;
+
+/// Explain the roster markers in one small line under the grid, cut short
+/// if it would run past the margin
+fn layout_roster_legend(page: &mut Page, entries: &[String], grid_bottom: f32) {
+    let width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    page.text(
+        truncate_to_width(&entries.join("     "), SMALL_FONT_SIZE, FontStyle::Regular, width),
+        SMALL_FONT_SIZE,
+        MARGIN_MM,
+        grid_bottom - LEGEND_HEIGHT_MM + 2.0,
+        FontStyle::Regular,
+    );
+}
+
+/// Vertical rules either side of a row's signature or time cell
+fn layout_signature_cell(page: &mut Page, x: f32, width: f32, row: RowBand) {
+    page.line(x, row.top, x, row.bottom(), ROW_STROKE);
+    page.line(x + width, row.top, x + width, row.bottom(), ROW_STROKE);
+}
+
+/// "N  S  E  W" for the student to circle one, shrunk to fit the narrow seat
+/// column of a three-column grid
+fn layout_seat_options(page: &mut Page, cells: &ColumnSpec, text_y: f32) {
+    let room = cells.seat_width - 2.0;
+    let width = text_width_mm(SEAT_OPTIONS, NORMAL_FONT_SIZE, FontStyle::Regular);
+    let size = if width > room { NORMAL_FONT_SIZE * room / width } else { NORMAL_FONT_SIZE };
+    page.text(SEAT_OPTIONS, size, cells.seat_x() + 1.0, text_y, FontStyle::Regular);
+}
+
+/// "Y   N" for the student to circle one
+fn layout_consent_cell(page: &mut Page, x: f32, text_y: f32) {
+    page.text("Y   N", NORMAL_FONT_SIZE, x + 1.5, text_y, FontStyle::Regular);
+}
+
+/// Cutting guides for card and slip modes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Trim {
+    /// Marks in the page margins lined up with every cut, in place of the
+    /// drawn outlines
+    pub crop_marks: bool,
+    /// Gap left on each side of a cut (mm), so a slightly-off guillotine
+    /// stroke does not clip the neighbouring piece
+    pub bleed_mm: f32,
+}
+
+/// Most bleed a card or slip layout has room for
+pub const MAX_BLEED_MM: f32 = 5.0;
+
+const CROP_MARK_STROKE: Stroke = Stroke { color: Color::BLACK, thickness: 0.25 };
+const CROP_MARK_LENGTH_MM: f32 = 5.0;
+/// Marks stop short of the trimmed area so none is left on a piece
+const CROP_MARK_OFFSET_MM: f32 = 3.0;
+
+/// Crop marks outside the box `left..right` x `bottom..top` for cuts at
+/// each of `xs` (vertical) and `ys` (horizontal)
+pub(crate) fn layout_crop_marks(
+    page: &mut Page,
+    xs: &[f32],
+    ys: &[f32],
+    (left, right, bottom, top): (f32, f32, f32, f32),
+) {
+    for &x in xs {
+        let (near, far) = (top + CROP_MARK_OFFSET_MM, top + CROP_MARK_OFFSET_MM + CROP_MARK_LENGTH_MM);
+        page.line(x, near, x, far, CROP_MARK_STROKE);
+        let (near, far) = (bottom - CROP_MARK_OFFSET_MM, bottom - CROP_MARK_OFFSET_MM - CROP_MARK_LENGTH_MM);
+        page.line(x, near, x, far, CROP_MARK_STROKE);
+    }
+    for &y in ys {
+        let (near, far) = (left - CROP_MARK_OFFSET_MM, left - CROP_MARK_OFFSET_MM - CROP_MARK_LENGTH_MM);
+        page.line(near, y, far, y, CROP_MARK_STROKE);
+        let (near, far) = (right + CROP_MARK_OFFSET_MM, right + CROP_MARK_OFFSET_MM + CROP_MARK_LENGTH_MM);
+        page.line(near, y, far, y, CROP_MARK_STROKE);
+    }
+}
+
+/// Empty box to tick, or one already shaded and ticked when `checked`
+pub(crate) fn layout_checkbox(page: &mut Page, x: f32, y: f32, size: f32, checked: bool) {
+    if !checked {
+        page.rect(x, y, size, size, CHECKBOX_STYLE);
+        return;
+    }
+    page.rect(x, y, size, size, CHECKED_BOX_STYLE);
+    let (corner_x, corner_y) = (x + 0.4 * size, y + 0.2 * size);
+    page.line(x + 0.18 * size, y + 0.5 * size, corner_x, corner_y, CHECK_MARK_STROKE);
+    page.line(corner_x, corner_y, x + 0.85 * size, y + 0.85 * size, CHECK_MARK_STROKE);
+}
+
+// ============================================================================
+// Mailing List Section
+// ============================================================================
+
+fn calculate_mailing_section_height(_rows: u32) -> f32 {
+    // Fixed height regardless of row count (equivalent to old 6-row layout)
+    // This keeps the section size consistent while allowing variable row counts
+    47.0
+}
+
+/// Between the end of the grid and a mailing list flowed after it
+const MAILING_FLOW_GAP_MM: f32 = 5.0;
+
+/// Whether the mailing list goes at the foot of the first page: always for
+/// [`MailingPlacement::FirstPage`], otherwise only if the whole grid fits on
+/// that page with it
+fn mailing_on_first_page(config: &AttendanceConfig) -> bool {
+    if !config.mailing_list {
+        return false;
+    }
+    if config.mailing_placement == MailingPlacement::FirstPage {
+        return true;
+    }
+    match &config.roster {
+        Some(roster) => {
+            let (first, continuation) = roster_grid_space_with(config, true);
+            plan_roster_grid(config, roster, first, continuation).map_or(true, |grid| grid.pages.len() == 1)
+        }
+        None => {
+            let available = plan_first_page_with(config, true).grid().height() - 5.0;
+            config.blank_rows.div_ceil(4) as f32 * 4.0 * blank_row_height(config) <= available
+        }
+    }
+}
+
+/// Put the mailing list after a grid that ran past the first page, on the
+/// grid's last page if there is room under it, otherwise on a page of its own
+fn layout_flowing_mailing(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    plan: &PagePlan,
+    grid_end: f32,
+    content_width: f32,
+) -> Result<(), AppError> {
+    let height = calculate_mailing_section_height(config.mailing_rows);
+    let heading = roster::mailing_heading(config);
+    let content_bottom = plan.content_bottom;
+    let last = sheet.pages.len() - 1;
+    let floor = if last == 0 { plan.grid().bottom } else { content_bottom };
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM - CONTINUATION_HEADER_HEIGHT_MM;
+    let runs = paginate(&[height], Keep::WHOLE, grid_end - MAILING_FLOW_GAP_MM - floor, top_y - content_bottom);
+    if !runs[0].is_empty() {
+        let y_bottom = if config.mailing_placement == MailingPlacement::AfterGrid {
+            grid_end - MAILING_FLOW_GAP_MM - height
+        } else {
+            floor
+        };
+        let page = &mut sheet.pages[last];
+        return layout_mailing_section(page, config.mailing_rows, y_bottom, MARGIN_MM, content_width, &heading);
+    }
+
+    let page = sheet.new_page();
+    layout_continuation_header(page, config, content_width);
+    layout_mailing_section(page, config.mailing_rows, top_y - height, MARGIN_MM, content_width, &heading)
+}
+
+fn layout_mailing_section(
+    page: &mut Page,
+    rows: u32,
+    y_bottom: f32,
+    x_start: f32,
+    content_width: f32,
+    heading: &str,
+) -> Result<(), AppError> {
+    let section_height = calculate_mailing_section_height(rows);
+    let y_top = y_bottom + section_height;
+
+    // Top line
+    page.line(x_start, y_top, x_start + content_width, y_top, BORDER_STROKE);
+
+    // Section header
+    let header_y = y_top - 6.0;
+    page.centered_text(heading, NORMAL_FONT_SIZE, x_start + content_width / 2.0, header_y, FontStyle::Bold);
+
+    // Draw rows - spread evenly in available space
+    let header_space = 10.0; // Space used by header
+    let available_for_rows = section_height - header_space - 3.0; // minus padding
+    let row_height = available_for_rows / rows as f32;
+    let mut y = y_top - header_space;
+
+    for _ in 0..rows {
+        // Name field
+        page.text("Name:", SMALL_FONT_SIZE, x_start + 2.0, y, FontStyle::Regular);
+        page.line(x_start + 15.0, y - 0.5, x_start + content_width * 0.45, y - 0.5, BORDER_STROKE);
+
+        // Email field
+        page.text(
+            "Email:",
+            SMALL_FONT_SIZE,
+            x_start + content_width * 0.48,
+            y,
+            FontStyle::Regular,
+        );
+        page.line(
+            x_start + content_width * 0.48 + 12.0,
+            y - 0.5,
+            x_start + content_width - 2.0,
+            y - 0.5,
+            BORDER_STROKE,
+        );
+
+        y -= row_height;
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Helper Sign-In Section
+// ============================================================================
+
+/// Volunteers sign in with a row each; the gap keeps the block clear of the
+/// attendance grid above it
+const HELPER_ROW_HEIGHT_MM: f32 = 7.0;
+const HELPER_HEADER_HEIGHT_MM: f32 = 8.0;
+const HELPER_GAP_MM: f32 = 3.0;
+
+fn calculate_helper_section_height(rows: u32) -> f32 {
+    if rows == 0 {
+        0.0
+    } else {
+        HELPER_HEADER_HEIGHT_MM + rows as f32 * HELPER_ROW_HEIGHT_MM + HELPER_GAP_MM
+    }
+}
+
+fn layout_helper_section(page: &mut Page, rows: u32, y_bottom: f32, x_start: f32, content_width: f32) {
+    let y_top = y_bottom + calculate_helper_section_height(rows) - HELPER_GAP_MM;
+
+    page.line(x_start, y_top, x_start + content_width, y_top, BORDER_STROKE);
+    page.text(
+        "HELPERS & VOLUNTEERS",
+        SMALL_FONT_SIZE,
+        x_start + 2.0,
+        y_top - 5.0,
+        FontStyle::Bold,
+    );
+    page.text(
+        "(caddies, greeters, assistants)",
+        SMALL_FONT_SIZE,
+        x_start + 42.0,
+        y_top - 5.0,
+        FontStyle::Regular,
+    );
+
+    let mut y = y_top - HELPER_HEADER_HEIGHT_MM - HELPER_ROW_HEIGHT_MM + 2.0;
+    for _ in 0..rows {
+        page.text("Name:", SMALL_FONT_SIZE, x_start + 2.0, y, FontStyle::Regular);
+        page.line(x_start + 15.0, y - 0.5, x_start + content_width * 0.6, y - 0.5, ROW_STROKE);
+
+        page.text("Role:", SMALL_FONT_SIZE, x_start + content_width * 0.63, y, FontStyle::Regular);
+        page.line(
+            x_start + content_width * 0.63 + 11.0,
+            y - 0.5,
+            x_start + content_width - 2.0,
+            y - 0.5,
+            ROW_STROKE,
+        );
+
+        y -= HELPER_ROW_HEIGHT_MM;
+    }
+}
+
+// ============================================================================
+// Online Attendees Section
+// ============================================================================
+
+/// Heading over the Zoom registrants of a hybrid class
+pub const ONLINE_HEADING: &str = "ONLINE ATTENDEES";
+
+const ONLINE_ROW_HEIGHT_MM: f32 = 6.0;
+const ONLINE_HEADER_HEIGHT_MM: f32 = 8.0;
+const ONLINE_GAP_MM: f32 = 3.0;
+const ONLINE_BOX_SIZE_MM: f32 = 3.0;
+/// Columns widen from three to four before the section grows past this many
+/// rows
+const ONLINE_MIN_COLUMNS: usize = 3;
+const ONLINE_MAX_COLUMNS: usize = 4;
+const ONLINE_FULL_COLUMN: usize = 6;
+
+/// Columns and rows the names are arranged in
+fn online_section_grid(names: usize) -> (usize, usize) {
+    let columns = names.div_ceil(ONLINE_FULL_COLUMN).clamp(ONLINE_MIN_COLUMNS, ONLINE_MAX_COLUMNS);
+    (columns, names.div_ceil(columns))
+}
+
+fn calculate_online_section_height(config: &AttendanceConfig) -> f32 {
+    match &config.online_attendees {
+        Some(names) if !names.is_empty() => {
+            let (_, rows) = online_section_grid(names.len());
+            ONLINE_HEADER_HEIGHT_MM + rows as f32 * ONLINE_ROW_HEIGHT_MM + ONLINE_GAP_MM
+        }
+        _ => 0.0,
+    }
+}
+
+/// Zoom registrants in columns with a box each, so remote attendance is taken
+/// on the same sheet as the room's
+fn layout_online_section(page: &mut Page, names: &[String], y_bottom: f32, x_start: f32, content_width: f32) {
+    let (columns, rows) = online_section_grid(names.len());
+    let y_top = y_bottom + ONLINE_HEADER_HEIGHT_MM + rows as f32 * ONLINE_ROW_HEIGHT_MM;
+
+    page.line(x_start, y_top, x_start + content_width, y_top, BORDER_STROKE);
+    let heading_width = text_width_mm(ONLINE_HEADING, SMALL_FONT_SIZE, FontStyle::Bold);
+    page.text(ONLINE_HEADING, SMALL_FONT_SIZE, x_start + 2.0, y_top - 5.0, FontStyle::Bold);
+    page.text(
+        "(joining on Zoom \u{2013} tick when they join)",
+        SMALL_FONT_SIZE,
+        x_start + 4.0 + heading_width,
+        y_top - 5.0,
+        FontStyle::Regular,
+    );
+
+    // Down each column, then across
+    let column_width = content_width / columns as f32;
+    let name_width = column_width - ONLINE_BOX_SIZE_MM - 5.0;
+    for (index, name) in names.iter().enumerate() {
+        let x = x_start + (index / rows) as f32 * column_width + 2.0;
+        let y = y_top - ONLINE_HEADER_HEIGHT_MM - (index % rows + 1) as f32 * ONLINE_ROW_HEIGHT_MM + 2.0;
+        layout_checkbox(page, x, y - 0.5, ONLINE_BOX_SIZE_MM, false);
+        let name = truncate_to_width(name, SMALL_FONT_SIZE, FontStyle::Regular, name_width);
+        page.text(name, SMALL_FONT_SIZE, x + ONLINE_BOX_SIZE_MM + 2.0, y, FontStyle::Regular);
+    }
+}
+
+// ============================================================================
+// Bring-a-Friend Coupons
+// ============================================================================
+
+/// Most coupons that fit side by side with a readable QR code
+pub const MAX_COUPONS: u32 = 3;
+
+const COUPON_HEIGHT_MM: f32 = 28.0;
+const COUPON_QR_SIZE_MM: f32 = 22.0;
+/// Room above the tear line, so the coupons stay clear of what is above
+const COUPON_GAP_MM: f32 = 5.0;
+
+fn calculate_coupon_strip_height(coupons: u32) -> f32 {
+    if coupons == 0 {
+        0.0
+    } else {
+        COUPON_HEIGHT_MM + COUPON_GAP_MM
+    }
+}
+
+/// "First lesson free" coupons side by side along the bottom of the page,
+/// under a dashed tear line, each with the class QR code and its own serial
+/// so the club can count how many come back
+fn layout_coupon_strip(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    y_bottom: f32,
+    content_width: f32,
+) -> Result<(), AppError> {
+    let y_top = y_bottom + COUPON_HEIGHT_MM;
+    let right_x = MARGIN_MM + content_width;
+
+    // Tear line
+    let mut x = MARGIN_MM;
+    while x < right_x {
+        page.line(x, y_top, (x + 3.0).min(right_x), y_top, CHECKBOX_STROKE);
+        x += 5.0;
+    }
+    page.text("tear here", SMALL_FONT_SIZE - 2.0, right_x - 12.0, y_top + 1.0, FontStyle::Regular);
+
+    let coupons = config.coupons.min(MAX_COUPONS);
+    let width = content_width / coupons as f32;
+    let qr = cached_qr_image(config, COUPON_QR_SIZE_MM)?;
+    for index in 0..coupons {
+        let x = MARGIN_MM + index as f32 * width;
+        if index > 0 {
+            page.line(x, y_top, x, y_bottom, ROW_STROKE);
+        }
+        let qr_y = y_bottom + (COUPON_HEIGHT_MM - COUPON_QR_SIZE_MM) / 2.0;
+        page.image(Arc::clone(&qr), x + 2.0, qr_y, COUPON_QR_SIZE_MM, COUPON_QR_SIZE_MM, false);
+
+        let text_x = x + COUPON_QR_SIZE_MM + 4.0;
+        page.text("FIRST LESSON FREE", SMALL_FONT_SIZE, text_x, y_top - 7.0, FontStyle::Bold);
+        page.text("Bring a friend to", SMALL_FONT_SIZE - 1.0, text_x, y_top - 12.0, FontStyle::Regular);
+        page.text(&config.class_name, SMALL_FONT_SIZE - 1.0, text_x, y_top - 16.0, FontStyle::Regular);
+        let serial = format!("No. {}", serial_number(&config.event_id, index));
+        page.text(serial, SMALL_FONT_SIZE - 2.0, text_x, y_bottom + 4.0, FontStyle::Regular);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Lesson Feedback
+// ============================================================================
+
+const FEEDBACK_HEIGHT_MM: f32 = 26.0;
+const FEEDBACK_QR_SIZE_MM: f32 = 22.0;
+const FEEDBACK_GAP_MM: f32 = 4.0;
+
+fn calculate_feedback_height(config: &AttendanceConfig) -> f32 {
+    if config.feedback_url.is_some() {
+        FEEDBACK_HEIGHT_MM + FEEDBACK_GAP_MM
+    } else {
+        0.0
+    }
+}
+
+/// QR code for the club's feedback form beside a box for written comments,
+/// for students without a phone to hand
+fn layout_feedback_section(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    url: &str,
+    y_bottom: f32,
+    content_width: f32,
+) -> Result<(), AppError> {
+    let y_top = y_bottom + FEEDBACK_HEIGHT_MM;
+    let right_x = MARGIN_MM + content_width;
+    page.line(MARGIN_MM, y_top, right_x, y_top, BORDER_STROKE);
+
+    let qr_image = cached_url_qr_image(config, url, FEEDBACK_QR_SIZE_MM)?;
+    let qr_y = y_bottom + (FEEDBACK_HEIGHT_MM - FEEDBACK_QR_SIZE_MM) / 2.0;
+    page.image(qr_image, MARGIN_MM, qr_y, FEEDBACK_QR_SIZE_MM, FEEDBACK_QR_SIZE_MM, false);
+
+    let text_x = MARGIN_MM + FEEDBACK_QR_SIZE_MM + 4.0;
+    let heading = "HOW WAS TODAY'S LESSON?";
+    page.text(heading, SMALL_FONT_SIZE, text_x, y_top - 5.0, FontStyle::Bold);
+    page.text(
+        "Scan the code to tell us, or leave a comment here:",
+        SMALL_FONT_SIZE,
+        text_x + text_width_mm(heading, SMALL_FONT_SIZE, FontStyle::Bold) + 3.0,
+        y_top - 5.0,
+        FontStyle::Regular,
+    );
+
+    // Comments box
+    let (box_top, box_bottom) = (y_top - 8.0, y_bottom + 2.0);
+    page.line(text_x, box_top, right_x, box_top, CHECKBOX_STROKE);
+    page.line(right_x, box_top, right_x, box_bottom, CHECKBOX_STROKE);
+    page.line(right_x, box_bottom, text_x, box_bottom, CHECKBOX_STROKE);
+    page.line(text_x, box_bottom, text_x, box_top, CHECKBOX_STROKE);
+    Ok(())
+}
+
+// ============================================================================
+// Raffle Tickets
+// ============================================================================
+
+const TICKET_HEIGHT_MM: f32 = 24.0;
+const TICKET_STUB_WIDTH_MM: f32 = 32.0;
+
+/// One ticket per grid row, so each number printed in the grid has a match
+pub fn raffle_ticket_count(config: &AttendanceConfig) -> u32 {
+    match &config.roster {
+        Some(roster) => roster.len() as u32 + 2 * ROSTER_WALK_IN_ROWS,
+        None => config.blank_rows,
+    }
+}
+
+/// Number printed in each grid row: 1, 2, 3... matching the raffle tickets,
+/// or with `--draw-numbers` a shuffle of the same numbers, so the draw picks a
+/// number and the sheet says who holds it. The shuffle is seeded by the event
+/// ID, so a reprint (and the manifest) carry the same numbers.
+pub fn row_numbers(config: &AttendanceConfig) -> Option<Vec<u32>> {
+    if !config.raffle && !config.draw_numbers {
+        return None;
+    }
+    let mut numbers: Vec<u32> = (1..=raffle_ticket_count(config)).collect();
+    if config.draw_numbers {
+        // FNV-1a of the event ID
+        let mut state = config.event_id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+        });
+        // Fisher-Yates with a xorshift generator; no need for a crypto RNG
+        for i in (1..numbers.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            numbers.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+    }
+    Some(numbers)
+}
+
+/// Small "#N" at the right edge of a grid row, matching a raffle ticket
+fn layout_ticket_number(page: &mut Page, number: u32, right_x: f32, row: RowBand) {
+    page.text(format!("#{}", number), SMALL_FONT_SIZE, right_x - 8.0, row.text_y(), FontStyle::Regular);
+}
+
+/// Pages of numbered tickets, two across, each with a tear-off stub the
+/// student keeps while the main half goes in the draw
+fn layout_raffle_tickets(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    content_width: f32,
+    content_bottom: f32,
+) {
+    let ticket_width = content_width / 2.0;
+    let heading_height = 10.0;
+    let rows_per_page = ((PAGE_HEIGHT_MM - MARGIN_MM - content_bottom - heading_height)
+        / TICKET_HEIGHT_MM)
+        .floor()
+        .max(1.0) as u32;
+    let per_page = rows_per_page * 2;
+    let date = config.date.format("%b %-d, %Y").to_string();
+
+    for number in 1..=raffle_ticket_count(config) {
+        let index = number - 1;
+        if index % per_page == 0 {
+            let page = sheet.new_page();
+            page.text(
+                "DOOR PRIZE TICKETS",
+                HEADER_FONT_SIZE,
+                MARGIN_MM,
+                PAGE_HEIGHT_MM - MARGIN_MM - 5.0,
+                FontStyle::Bold,
+            );
+            page.text(
+                "Cut apart; keep the stub, drop the ticket in the draw",
+                SMALL_FONT_SIZE,
+                MARGIN_MM + 60.0,
+                PAGE_HEIGHT_MM - MARGIN_MM - 5.0,
+                FontStyle::Regular,
+            );
+        }
+
+        let slot = index % per_page;
+        let x = MARGIN_MM + (slot % 2) as f32 * ticket_width;
+        let y_top = PAGE_HEIGHT_MM - MARGIN_MM - heading_height - (slot / 2) as f32 * TICKET_HEIGHT_MM;
+        let page = sheet.pages.last_mut().expect("ticket page was just added");
+        layout_ticket(page, config, &date, number, x, y_top, ticket_width);
+    }
+}
+
+fn layout_ticket(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    date: &str,
+    number: u32,
+    x: f32,
+    y_top: f32,
+    width: f32,
+) {
+    let y_bottom = y_top - TICKET_HEIGHT_MM;
+
+    // Cut lines
+    page.line(x, y_top, x + width, y_top, ROW_STROKE);
+    page.line(x, y_bottom, x + width, y_bottom, ROW_STROKE);
+    page.line(x, y_top, x, y_bottom, ROW_STROKE);
+    page.line(x + width, y_top, x + width, y_bottom, ROW_STROKE);
+
+    // Stub
+    let label = format!("No. {}", number);
+    page.text(&label, NORMAL_FONT_SIZE, x + 2.0, y_top - 7.0, FontStyle::Bold);
+    page.text("Name:", SMALL_FONT_SIZE, x + 2.0, y_top - 14.0, FontStyle::Regular);
+    page.line(x + 2.0, y_top - 19.0, x + TICKET_STUB_WIDTH_MM - 2.0, y_top - 19.0, CHECKBOX_STROKE);
+
+    // Perforation between stub and ticket
+    let perforation_x = x + TICKET_STUB_WIDTH_MM;
+    let mut dash_y = y_top - 1.0;
+    while dash_y - 2.0 > y_bottom {
+        page.line(perforation_x, dash_y, perforation_x, dash_y - 2.0, ROW_STROKE);
+        dash_y -= 4.0;
+    }
+
+    // Ticket
+    let ticket_x = perforation_x + 4.0;
+    page.text(&label, TITLE_FONT_SIZE, ticket_x, y_top - 10.0, FontStyle::Bold);
+    page.text(&config.class_name, SMALL_FONT_SIZE, ticket_x, y_top - 16.0, FontStyle::Regular);
+    page.text(date, SMALL_FONT_SIZE, ticket_x, y_top - 20.0, FontStyle::Regular);
+}
+
+// ============================================================================
+// Series Calendar
+// ============================================================================
+
+const CALENDAR_ROW_HEIGHT_MM: f32 = 9.0;
+const CALENDAR_MONTH_HEIGHT_MM: f32 = 12.0;
+/// A month's name stays with at least two of its sessions
+const CALENDAR_KEEP: Keep = Keep::with_next(CALENDAR_MONTH_HEIGHT_MM, 2);
+
+/// Final page listing the sessions still to come, grouped by month, for
+/// students to photograph on their way out
+fn layout_series_calendar(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    schedule: &Schedule,
+    content_width: f32,
+    content_bottom: f32,
+) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let title = schedule.series.as_deref().unwrap_or(&config.class_name);
+
+    let page = sheet.new_page();
+    page.text("UPCOMING SESSIONS", TITLE_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+    page.text(title, HEADER_FONT_SIZE, MARGIN_MM, top_y - 14.0, FontStyle::Bold);
+    page.line(MARGIN_MM, top_y - 18.0, MARGIN_MM + content_width, top_y - 18.0, BORDER_STROKE);
+
+    let mut y = top_y - 18.0;
+    let sessions: Vec<_> = schedule.remaining_after(config.date).collect();
+    let continuation_available = top_y - 10.0 - content_bottom;
+    let same_month = |a: &&Session, b: &&Session| (a.date.year(), a.date.month()) == (b.date.year(), b.date.month());
+    for month in sessions.chunk_by(same_month) {
+        let heights = vec![CALENDAR_ROW_HEIGHT_MM; month.len()];
+        let runs = paginate(&heights, CALENDAR_KEEP, y - content_bottom, continuation_available);
+        for (index, run) in runs.into_iter().enumerate() {
+            if index > 0 {
+                let page = sheet.new_page();
+                page.text(
+                    format!("UPCOMING SESSIONS (continued): {}", title),
+                    HEADER_FONT_SIZE,
+                    MARGIN_MM,
+                    top_y - 7.0,
+                    FontStyle::Bold,
+                );
+                y = top_y - 10.0;
+            }
+            if run.is_empty() {
+                continue;
+            }
+            let page = sheet.pages.last_mut().expect("calendar page was just added");
+
+            // The month heading repeats on each page its sessions reach
+            page.text(
+                month[0].date.format("%B %Y").to_string(),
+                HEADER_FONT_SIZE,
+                MARGIN_MM,
+                y - CALENDAR_MONTH_HEIGHT_MM + 3.0,
+                FontStyle::Bold,
+            );
+            y -= CALENDAR_MONTH_HEIGHT_MM;
+
+            for session in &month[run] {
+                let text_y = y - CALENDAR_ROW_HEIGHT_MM / 2.0 - 1.5;
+                page.text(
+                    session.date.format("%a %b %-d").to_string(),
+                    NORMAL_FONT_SIZE,
+                    MARGIN_MM + 4.0,
+                    text_y,
+                    FontStyle::Bold,
+                );
+                page.text(&session.topic, NORMAL_FONT_SIZE, MARGIN_MM + 40.0, text_y, FontStyle::Regular);
+                y -= CALENDAR_ROW_HEIGHT_MM;
+                page.line(MARGIN_MM, y, MARGIN_MM + content_width, y, ROW_STROKE);
+            }
+        }
+    }
+
+    if sessions.is_empty() {
+        let page = sheet.pages.last_mut().expect("calendar page was just added");
+        page.text(
+            "This is the last session of the series. Thanks for coming!",
+            NORMAL_FONT_SIZE,
+            MARGIN_MM,
+            y - 10.0,
+            FontStyle::Regular,
+        );
+    }
+}
+
+// ============================================================================
+// Liability Waiver
+// ============================================================================
+
+const WAIVER_LINE_HEIGHT_MM: f32 = 5.0;
+const WAIVER_ROW_HEIGHT_MM: f32 = 14.0;
+const WAIVER_NAME_COL_RATIO: f32 = 0.4;
+/// No paragraph leaves a single line on either side of a page break
+const WAIVER_LINES_KEEP: Keep = Keep::with_next(0.0, 2);
+const WAIVER_HEADER_HEIGHT_MM: f32 = 8.0;
+/// The Name / Signature / Date headings stay with two signature rows
+const WAIVER_ROWS_KEEP: Keep = Keep::with_next(WAIVER_HEADER_HEIGHT_MM, 2);
+const WAIVER_SIGNATURE_COL_RATIO: f32 = 0.4;
+
+/// Waiver text, then a row per student (and a few for walk-ins) with a
+/// printed name, a signature line and a date line; blank sheets get blank
+/// rows for everyone. Runs onto as many pages as it needs.
+fn layout_waiver(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    text: &str,
+    content_width: f32,
+    content_bottom: f32,
+) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let title = format!("WAIVER \u{2013} {} \u{2013} {}", config.class_name, format_date_display(&config.date));
+
+    let page = sheet.new_page();
+    page.text(&title, HEADER_FONT_SIZE, MARGIN_MM, top_y - 5.0, FontStyle::Bold);
+    let mut y = top_y - 12.0;
+    let continuation_available = top_y - 10.0 - content_bottom;
+
+    // Paragraphs are separated by blank lines; lines within one are joined
+    let text = text.replace("\r\n", "\n");
+    for paragraph in text.split("\n\n").filter(|paragraph| !paragraph.trim().is_empty()) {
+        let lines = wrap_text(paragraph, NORMAL_FONT_SIZE, FontStyle::Regular, content_width);
+        let heights = vec![WAIVER_LINE_HEIGHT_MM; lines.len()];
+        let runs = paginate(&heights, WAIVER_LINES_KEEP, y - content_bottom, continuation_available);
+        for (index, run) in runs.into_iter().enumerate() {
+            if index > 0 {
+                y = new_waiver_page(sheet, &title);
+            }
+            let page = sheet.pages.last_mut().expect("waiver page was just added");
+            for line in &lines[run] {
+                y -= WAIVER_LINE_HEIGHT_MM;
+                page.text(line, NORMAL_FONT_SIZE, MARGIN_MM, y, FontStyle::Regular);
+            }
+        }
+        y -= WAIVER_LINE_HEIGHT_MM / 2.0;
+    }
+
+    let names: Vec<Option<String>> = match &config.roster {
+        Some(roster) => roster
+            .iter()
+            .map(|student| Some(roster::display_name(student, config)))
+            .chain((0..ROSTER_WALK_IN_ROWS).map(|_| None))
+            .collect(),
+        None => vec![None; config.blank_rows as usize],
+    };
+
+    let name_width = content_width * WAIVER_NAME_COL_RATIO;
+    let signature_width = content_width * WAIVER_SIGNATURE_COL_RATIO;
+    let heights = vec![WAIVER_ROW_HEIGHT_MM; names.len()];
+    let runs = paginate(&heights, WAIVER_ROWS_KEEP, y - content_bottom, continuation_available);
+    for (index, run) in runs.into_iter().enumerate() {
+        if index > 0 {
+            y = new_waiver_page(sheet, &title);
+        }
+        if run.is_empty() {
+            continue;
+        }
+        y = layout_waiver_header(sheet, y, name_width, signature_width);
+        for name in &names[run] {
+            let page = sheet.pages.last_mut().expect("waiver page was just added");
+            let line_y = y - WAIVER_ROW_HEIGHT_MM + 3.0;
+            if let Some(name) = name {
+                page.text(name, NORMAL_FONT_SIZE, MARGIN_MM, line_y + 1.0, FontStyle::Regular);
+            } else {
+                page.line(MARGIN_MM, line_y, MARGIN_MM + name_width - 4.0, line_y, CHECKBOX_STROKE);
+            }
+            let signature_x = MARGIN_MM + name_width;
+            page.line(signature_x, line_y, signature_x + signature_width - 4.0, line_y, CHECKBOX_STROKE);
+            let date_x = signature_x + signature_width;
+            page.line(date_x, line_y, MARGIN_MM + content_width, line_y, CHECKBOX_STROKE);
+            y -= WAIVER_ROW_HEIGHT_MM;
+        }
+    }
+}
+
+/// A further waiver page; returns where its content starts
+fn new_waiver_page(sheet: &mut SheetLayout, title: &str) -> f32 {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let page = sheet.new_page();
+    page.text(format!("{} (continued)", title), HEADER_FONT_SIZE, MARGIN_MM, top_y - 5.0, FontStyle::Bold);
+    top_y - 10.0
+}
+
+/// "Name / Signature / Date" over the signature rows
+fn layout_waiver_header(sheet: &mut SheetLayout, y: f32, name_width: f32, signature_width: f32) -> f32 {
+    let page = sheet.pages.last_mut().expect("waiver page was just added");
+    let text_y = y - 6.0;
+    page.text("Name", SMALL_FONT_SIZE, MARGIN_MM, text_y, FontStyle::Bold);
+    page.text("Signature", SMALL_FONT_SIZE, MARGIN_MM + name_width, text_y, FontStyle::Bold);
+    page.text("Date", SMALL_FONT_SIZE, MARGIN_MM + name_width + signature_width, text_y, FontStyle::Bold);
+    let bottom = y - WAIVER_HEADER_HEIGHT_MM;
+    page.line(MARGIN_MM, bottom, PAGE_WIDTH_MM - MARGIN_MM, bottom, BORDER_STROKE);
+    bottom
+}
+
+// ============================================================================
+// Late Arrivals Page
+// ============================================================================
+
+/// Title of the `--overflow-page`
+pub const OVERFLOW_PAGE_TITLE: &str = "Late arrivals / walk-ins";
+
+/// A page of numbered blank rows, down the left column then the right, under
+/// the same QR code as the sheet, so latecomers need not squeeze into the
+/// grid's walk-in rows
+fn layout_overflow_page(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    content_width: f32,
+    content_bottom: f32,
+) -> Result<(), AppError> {
+    let page = sheet.new_page();
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let style = if config.economy { &ECONOMY_HEADER } else { &FULL_HEADER };
+    let qr_size = style.qr_size;
+
+    page.image(cached_qr_image(config, qr_size)?, MARGIN_MM, top_y - qr_size, qr_size, qr_size, false);
+    let text_x = MARGIN_MM + qr_size + 8.0;
+    let [title_y, name_y, date_y, _] = style.baselines;
+    page.text(OVERFLOW_PAGE_TITLE, style.title_size, text_x, top_y - title_y, FontStyle::Bold);
+    page.text(&config.class_name, style.name_size, text_x, top_y - name_y, FontStyle::Bold);
+    page.text(
+        format_date_display(&config.date),
+        style.info_size,
+        text_x,
+        top_y - date_y,
+        FontStyle::Regular,
+    );
+
+    let start_y = top_y - header_height(config);
+    let mut row_height = if config.economy { ECONOMY_MAX_ROW_HEIGHT_MM } else { MAX_ROW_HEIGHT_MM };
+    if config.signature_column {
+        row_height = row_height.max(SIGNATURE_MIN_ROW_HEIGHT_MM);
+    }
+    let rows = ((start_y - ROSTER_HEADER_ROW_HEIGHT_MM - content_bottom) / row_height).floor() as u32;
+    let area = PageRect::from_top(MARGIN_MM, start_y, content_width, start_y - content_bottom);
+    for (column, rect) in (0..).zip(area.columns(2, ROSTER_COLUMN_GAP_MM)) {
+        let cells = ColumnSpec::for_config(config, &rect);
+        let count_from = Some(column * rows + 1);
+        layout_roster_column(page, config, &[], &cells, start_y, row_height, rows, 0.0, None, count_from);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Supplies Checklist
+// ============================================================================
+
+/// Items on the `--checklist` page when the config file sets none
+pub const DEFAULT_CHECKLIST: [&str; 5] = [
+    "Boards dealt",
+    "Bidding boxes",
+    "Table cards",
+    "Handouts",
+    "Name tags",
+];
+
+const CHECKLIST_ROW_HEIGHT_MM: f32 = 12.0;
+const CHECKLIST_BOX_SIZE_MM: f32 = 5.0;
+/// No item carried over alone onto a further page
+const CHECKLIST_KEEP: Keep = Keep::with_next(0.0, 2);
+
+/// Final page of supplies to tick off while setting up the room
+fn layout_checklist(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    items: &[String],
+    content_width: f32,
+    content_bottom: f32,
+) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let heading = |continued: bool| {
+        if continued {
+            "BEFORE CLASS (continued)"
+        } else {
+            "BEFORE CLASS"
+        }
+    };
+
+    let page = sheet.new_page();
+    page.text(heading(false), TITLE_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+    let subtitle = format!("{} \u{2013} {}", config.class_name, format_date_display(&config.date));
+    page.text(subtitle, HEADER_FONT_SIZE, MARGIN_MM, top_y - 14.0, FontStyle::Bold);
+    page.line(MARGIN_MM, top_y - 18.0, MARGIN_MM + content_width, top_y - 18.0, BORDER_STROKE);
+
+    let mut y = top_y - 18.0;
+    let heights = vec![CHECKLIST_ROW_HEIGHT_MM; items.len()];
+    let runs = paginate(&heights, CHECKLIST_KEEP, y - content_bottom, top_y - 10.0 - content_bottom);
+    for (index, run) in runs.into_iter().enumerate() {
+        if index > 0 {
+            let page = sheet.new_page();
+            page.text(heading(true), HEADER_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+            y = top_y - 10.0;
+        }
+        let page = sheet.pages.last_mut().expect("checklist page was just added");
+
+        for item in &items[run] {
+            let box_y = y - (CHECKLIST_ROW_HEIGHT_MM + CHECKLIST_BOX_SIZE_MM) / 2.0;
+            layout_checkbox(page, MARGIN_MM + 4.0, box_y, CHECKLIST_BOX_SIZE_MM, false);
+            let text_y = y - CHECKLIST_ROW_HEIGHT_MM / 2.0 - 1.5;
+            page.text(item, NORMAL_FONT_SIZE, MARGIN_MM + 14.0, text_y, FontStyle::Regular);
+            y -= CHECKLIST_ROW_HEIGHT_MM;
+            page.line(MARGIN_MM, y, MARGIN_MM + content_width, y, ROW_STROKE);
+        }
+    }
+}
+
+// ============================================================================
+// Practice Hands
+// ============================================================================
+
+pub const PRACTICE_HANDS_TITLE: &str = "PRACTICE HANDS";
+
+/// Deals from `--hands` on pages after the sheet for students to take home
+fn layout_practice_hands(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    deals: &[Deal],
+    content_width: f32,
+    content_bottom: f32,
+) {
+    let subtitle = format!("{} \u{2013} {}", config.class_name, format_date_display(&config.date));
+    layout_deal_pages(sheet, deals, PRACTICE_HANDS_TITLE, &subtitle, content_width, content_bottom);
+}
+
+// ============================================================================
+// Emergency Page
+// ============================================================================
+
+/// Large enough to read from across the room
+const EMERGENCY_HEADING_FONT_SIZE: f32 = 28.0;
+const EMERGENCY_FONT_SIZE: f32 = 20.0;
+const EMERGENCY_LINE_HEIGHT_MM: f32 = 9.5;
+
+/// Who to call, exits and AED, one labelled block each in large type
+fn layout_emergency_page(page: &mut Page, info: &EmergencyInfo, content_width: f32) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    page.text("IN AN EMERGENCY", EMERGENCY_HEADING_FONT_SIZE, MARGIN_MM, top_y - 11.0, FontStyle::Bold);
+    page.text(&info.location, HEADER_FONT_SIZE, MARGIN_MM, top_y - 19.0, FontStyle::Regular);
+    page.line(MARGIN_MM, top_y - 23.0, MARGIN_MM + content_width, top_y - 23.0, BORDER_STROKE);
+
+    let contacts = (!info.contacts.is_empty()).then(|| info.contacts.clone());
+    let blocks = [
+        ("CALL", contacts),
+        ("EXITS", info.exits.clone().map(|exits| vec![exits])),
+        ("DEFIBRILLATOR (AED)", info.aed.clone().map(|aed| vec![aed])),
+        ("ALSO", info.notes.clone().map(|notes| vec![notes])),
+    ];
+
+    let mut y = top_y - 23.0;
+    for (label, entries) in blocks {
+        let Some(entries) = entries else {
+            continue;
+        };
+        let lines: Vec<String> = entries
+            .iter()
+            .flat_map(|entry| wrap_text(entry, EMERGENCY_FONT_SIZE, FontStyle::Bold, content_width))
+            .collect();
+        page.text(label, HEADER_FONT_SIZE, MARGIN_MM, y - 9.0, FontStyle::Bold);
+        y -= 12.0;
+        for line in lines {
+            y -= EMERGENCY_LINE_HEIGHT_MM;
+            page.text(line, EMERGENCY_FONT_SIZE, MARGIN_MM, y + 2.0, FontStyle::Bold);
+        }
+    }
+}
+
+// ============================================================================
+// Page Footer
+// ============================================================================
+
+/// Height of the strip along the bottom margin of every page
+const SPONSOR_STRIP_HEIGHT_MM: f32 = SPONSOR_MAX_HEIGHT_MM + 3.0;
+
+fn calculate_page_footer_height(config: &AttendanceConfig) -> f32 {
+    if config.sponsor.is_some() {
+        SPONSOR_STRIP_HEIGHT_MM
+    } else {
+        0.0
+    }
+}
+
+/// "Sheet 2 of 3 – A1B2C3D4"
+pub fn sheet_stamp(sheet: usize, total: usize, event_id: &str) -> String {
+    format!("Sheet {} of {} \u{2013} {}", sheet, total, event_id)
+}
+
+/// Footer on every page: the sponsor strip, if any, and for documents of more
+/// than one page a "Sheet 2 of 3 – ID" stamp in the bottom margin so scanned
+/// paperwork can be put back together. Reprints say which revision they are
+/// opposite it. The event ID also reads up the left margin, so a page cut or
+/// torn from the rest can still be matched to its class.
+fn layout_page_footer(sheet: &mut SheetLayout, config: &AttendanceConfig, content_width: f32) {
+    for page in &mut sheet.pages {
+        page.rotated_text(
+            &config.event_id,
+            SMALL_FONT_SIZE,
+            MARGIN_MM / 2.0 + 1.0,
+            MARGIN_MM,
+            FontStyle::Regular,
+            Rotation::Left,
+        );
+    }
+
+    if let Some(revision) = config.revision.as_ref().filter(|revision| revision.number > 1) {
+        let stamp = revision.stamp();
+        for page in &mut sheet.pages {
+            page.text(&stamp, SMALL_FONT_SIZE, MARGIN_MM, MARGIN_MM / 2.0, FontStyle::Regular);
+        }
+    }
+
+    let total = sheet.pages.len();
+    if total > 1 {
+        for (index, page) in sheet.pages.iter_mut().enumerate() {
+            let stamp = sheet_stamp(index + 1, total, &config.event_id);
+            let right_x = MARGIN_MM + content_width;
+            page.right_aligned_text(stamp, SMALL_FONT_SIZE, right_x, MARGIN_MM / 2.0, FontStyle::Regular);
+        }
+    }
+
+    let Some(sponsor) = &config.sponsor else {
+        return;
+    };
+
+    let top_y = MARGIN_MM + SPONSOR_STRIP_HEIGHT_MM;
+    for page in &mut sheet.pages {
+        page.line(MARGIN_MM, top_y, MARGIN_MM + content_width, top_y, ROW_STROKE);
+
+        let mut text_x = MARGIN_MM;
+        if let Some(image) = &sponsor.image {
+            let aspect_ratio = image.width_px as f32 / image.height_px as f32;
+            let height = SPONSOR_MAX_HEIGHT_MM.min(SPONSOR_MAX_WIDTH_MM / aspect_ratio);
+            let width = height * aspect_ratio;
+            page.image(Arc::clone(image), MARGIN_MM, MARGIN_MM, width, height, true);
+            text_x += width + 3.0;
+        }
+
+        page.text(
+            &sponsor.text,
+            SMALL_FONT_SIZE,
+            text_x,
+            MARGIN_MM + SPONSOR_MAX_HEIGHT_MM / 2.0 - 1.0,
+            FontStyle::Regular,
+        );
+    }
+}
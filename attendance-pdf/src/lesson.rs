@@ -0,0 +1,284 @@
+// Lesson plans: the teacher's outline for a class, written in Markdown and
+// printed in the same style as the rest of the class paperwork
+//
+//     # Opening 1NT
+//     Balanced hands and the 15-17 range.
+//
+//     ## Welcome and review (10 min)
+//     - Collect homework
+//
+//     ## Play practice hands (25 min)
+//     Deals: 1, 2
+//     - Count points before bidding
+//
+// A `##` heading starts a timed segment, its length in brackets; `Deals:`
+// lists the boards from `--hands` to play, and `-` or `*` lines are key
+// points. Other text is kept as notes.
+
+use crate::deals::{layout_deal_pages, Deal};
+use crate::layout::{FontStyle, Page, SheetLayout, BORDER_STROKE, ROW_STROKE};
+use crate::metrics::wrap_text;
+use crate::{
+    format_date_display, AppError, AttendanceConfig, HEADER_FONT_SIZE, MARGIN_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM,
+    PAGE_WIDTH_MM, SMALL_FONT_SIZE, TITLE_FONT_SIZE,
+};
+use std::path::Path;
+
+const LESSON_PLAN_TITLE: &str = "LESSON PLAN";
+const LESSON_DEALS_TITLE: &str = "DEALS FOR THIS LESSON";
+
+/// Elapsed time down the left, the segment beside it
+const TIME_COLUMN_WIDTH_MM: f32 = 28.0;
+const LINE_HEIGHT_MM: f32 = 5.0;
+const POINT_INDENT_MM: f32 = 5.0;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LessonPlan {
+    /// From the `#` heading
+    pub title: Option<String>,
+    /// Text before the first segment
+    pub summary: Vec<String>,
+    pub segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Segment {
+    pub title: String,
+    pub minutes: Option<u32>,
+    /// Boards from the hands file, as numbered there
+    pub boards: Vec<String>,
+    pub notes: Vec<String>,
+    pub points: Vec<String>,
+}
+
+impl LessonPlan {
+    /// Every board the segments use, first mention first
+    pub fn boards(&self) -> Vec<&str> {
+        let mut boards: Vec<&str> = Vec::new();
+        for board in self.segments.iter().flat_map(|segment| &segment.boards) {
+            if !boards.contains(&board.as_str()) {
+                boards.push(board);
+            }
+        }
+        boards
+    }
+
+    pub fn total_minutes(&self) -> u32 {
+        self.segments.iter().filter_map(|segment| segment.minutes).sum()
+    }
+}
+
+pub fn load_lesson_plan(path: &Path) -> Result<LessonPlan, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::LessonPlanError(format!("{}: {}", path.display(), e)))?;
+    let plan = parse_lesson_plan(&content)?;
+    if plan.segments.is_empty() {
+        return Err(AppError::LessonPlanError(format!("{}: no \"##\" segments", path.display())));
+    }
+    Ok(plan)
+}
+
+pub fn parse_lesson_plan(content: &str) -> Result<LessonPlan, AppError> {
+    let mut plan = LessonPlan::default();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            plan.segments.push(parse_segment_heading(heading)?);
+            continue;
+        }
+        if let Some(title) = trimmed.strip_prefix("# ") {
+            if plan.title.is_none() && plan.segments.is_empty() {
+                plan.title = Some(title.trim().to_string());
+                continue;
+            }
+        }
+
+        let Some(segment) = plan.segments.last_mut() else {
+            plan.summary.push(trimmed.to_string());
+            continue;
+        };
+        let deals = trimmed.strip_prefix("Deals:").or_else(|| trimmed.strip_prefix("Boards:"));
+        if let Some(deals) = deals {
+            segment.boards.extend(
+                deals.split([',', ' '])
+                    .map(|board| board.trim().trim_start_matches('#'))
+                    .filter(|board| !board.is_empty())
+                    .map(String::from),
+            );
+        } else if let Some(point) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            segment.points.push(point.trim().to_string());
+        } else if line.starts_with(char::is_whitespace) && !segment.points.is_empty() {
+            // An indented line carries on the point above
+            let point = segment.points.last_mut().expect("checked above");
+            point.push(' ');
+            point.push_str(trimmed);
+        } else {
+            segment.notes.push(trimmed.to_string());
+        }
+    }
+    Ok(plan)
+}
+
+/// "Bidding practice (15 min)"
+fn parse_segment_heading(heading: &str) -> Result<Segment, AppError> {
+    let heading = heading.trim();
+    let timed = heading
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once('('))
+        .and_then(|(title, length)| {
+            let minutes = length.trim().trim_end_matches("minutes").trim_end_matches("min").trim();
+            Some((title, minutes.parse::<u32>().ok()?))
+        });
+    let (title, minutes) = match timed {
+        Some((title, minutes)) => (title.trim(), Some(minutes)),
+        None => (heading, None),
+    };
+    if title.is_empty() {
+        return Err(AppError::LessonPlanError(format!("segment \"{}\" has no title", heading)));
+    }
+    Ok(Segment {
+        title: title.to_string(),
+        minutes,
+        ..Segment::default()
+    })
+}
+
+/// "1:05"
+fn clock(minutes: u32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// The plan, then diagrams of the deals it uses when `deals` (from
+/// `--hands`) are given
+pub fn layout_lesson_plan(
+    config: &AttendanceConfig,
+    plan: &LessonPlan,
+    deals: Option<&[Deal]>,
+) -> Result<SheetLayout, AppError> {
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let text_x = MARGIN_MM + TIME_COLUMN_WIDTH_MM;
+    let text_width = content_width - TIME_COLUMN_WIDTH_MM - 2.0;
+
+    let mut y = layout_plan_heading(sheet.new_page(), config, false);
+    let page = sheet.pages.last_mut().expect("lesson plan page was just added");
+    if let Some(title) = &plan.title {
+        page.text(title, HEADER_FONT_SIZE, MARGIN_MM, y - 5.0, FontStyle::Bold);
+        y -= 8.0;
+    }
+    for text in &plan.summary {
+        for line in wrap_text(text, NORMAL_FONT_SIZE, FontStyle::Regular, content_width) {
+            page.text(line, NORMAL_FONT_SIZE, MARGIN_MM, y - 4.0, FontStyle::Regular);
+            y -= LINE_HEIGHT_MM;
+        }
+    }
+    y -= 2.0;
+
+    let mut elapsed = 0;
+    for segment in &plan.segments {
+        // Title, boards, notes and points, each a line within the segment
+        let mut lines = vec![(segment.title.clone(), HEADER_FONT_SIZE, FontStyle::Bold, 0.0)];
+        if !segment.boards.is_empty() {
+            let boards = format!("Deals: {}", segment.boards.join(", "));
+            lines.push((boards, SMALL_FONT_SIZE, FontStyle::Bold, 0.0));
+        }
+        for note in &segment.notes {
+            for line in wrap_text(note, NORMAL_FONT_SIZE, FontStyle::Regular, text_width) {
+                lines.push((line, NORMAL_FONT_SIZE, FontStyle::Regular, 0.0));
+            }
+        }
+        for point in &segment.points {
+            let wrapped = wrap_text(point, NORMAL_FONT_SIZE, FontStyle::Regular, text_width - POINT_INDENT_MM);
+            for (index, line) in wrapped.into_iter().enumerate() {
+                let line = if index == 0 { format!("\u{2022} {}", line) } else { line };
+                let indent = if index == 0 { 2.0 } else { POINT_INDENT_MM };
+                lines.push((line, NORMAL_FONT_SIZE, FontStyle::Regular, indent));
+            }
+        }
+
+        // Keep the title with at least two lines of what follows
+        let keep = lines.len().min(3) as f32 * LINE_HEIGHT_MM + 3.0;
+        if y - keep < MARGIN_MM {
+            y = layout_plan_heading(sheet.new_page(), config, true);
+        }
+        let page = sheet.pages.last_mut().expect("lesson plan page was just added");
+        let time = match segment.minutes {
+            Some(minutes) => format!("{}\u{2013}{}", clock(elapsed), clock(elapsed + minutes)),
+            None => clock(elapsed),
+        };
+        page.text(time, NORMAL_FONT_SIZE, MARGIN_MM, y - 5.5, FontStyle::Bold);
+        elapsed += segment.minutes.unwrap_or(0);
+        y -= 1.5;
+
+        for (text, size, font, indent) in lines {
+            if y - LINE_HEIGHT_MM < MARGIN_MM {
+                y = layout_plan_heading(sheet.new_page(), config, true);
+            }
+            let page = sheet.pages.last_mut().expect("lesson plan page was just added");
+            page.text(text, size, text_x + indent, y - 4.0, font);
+            y -= LINE_HEIGHT_MM;
+        }
+        y -= 1.5;
+        let page = sheet.pages.last_mut().expect("lesson plan page was just added");
+        page.line(MARGIN_MM, y, MARGIN_MM + content_width, y, ROW_STROKE);
+    }
+
+    if plan.total_minutes() > 0 {
+        if y - LINE_HEIGHT_MM - 2.0 < MARGIN_MM {
+            y = layout_plan_heading(sheet.new_page(), config, true);
+        }
+        let page = sheet.pages.last_mut().expect("lesson plan page was just added");
+        let total = format!("Total: {}", clock(plan.total_minutes()));
+        page.text(total, NORMAL_FONT_SIZE, MARGIN_MM, y - 5.5, FontStyle::Bold);
+    }
+
+    if let Some(deals) = deals {
+        let used = lesson_deals(plan, deals)?;
+        let subtitle = plan.title.clone().unwrap_or_else(|| config.class_name.clone());
+        layout_deal_pages(&mut sheet, &used, LESSON_DEALS_TITLE, &subtitle, content_width, MARGIN_MM);
+    }
+    Ok(sheet)
+}
+
+/// The deals the plan names, in the order it first uses them, or all of
+/// them when it names none
+fn lesson_deals(plan: &LessonPlan, deals: &[Deal]) -> Result<Vec<Deal>, AppError> {
+    let boards = plan.boards();
+    if boards.is_empty() {
+        return Ok(deals.to_vec());
+    }
+    boards
+        .into_iter()
+        .map(|board| {
+            deals
+                .iter()
+                .find(|deal| deal.board.as_deref() == Some(board))
+                .cloned()
+                .ok_or_else(|| AppError::LessonPlanError(format!("board {} is not in the hands file", board)))
+        })
+        .collect()
+}
+
+/// Title, then the class and date; returns where the content starts
+fn layout_plan_heading(page: &mut Page, config: &AttendanceConfig, continued: bool) -> f32 {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let title = if continued {
+        format!("{} (continued)", LESSON_PLAN_TITLE)
+    } else {
+        LESSON_PLAN_TITLE.to_string()
+    };
+    page.text(title, TITLE_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+    let subtitle = format!("{} \u{2013} {}", config.class_name, format_date_display(&config.date));
+    page.text(subtitle, HEADER_FONT_SIZE, MARGIN_MM, top_y - 14.0, FontStyle::Bold);
+    page.line(MARGIN_MM, top_y - 18.0, MARGIN_MM + content_width, top_y - 18.0, BORDER_STROKE);
+    top_y - 20.0
+}
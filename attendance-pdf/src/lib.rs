@@ -0,0 +1,725 @@
+// attendance-pdf: Generate attendance sheets for bridge classes
+//
+// Sheets are produced in two passes: `layout` places every piece of text,
+// rule and image on its page as plain data (`SheetLayout`), then `pdf` turns
+// that list into a document. Tests can assert on the layout directly instead
+// of rasterizing or parsing PDF output.
+
+pub mod assets;
+pub mod checkin;
+pub mod deals;
+pub mod docx;
+pub mod events;
+pub mod geometry;
+pub mod google;
+pub mod layout;
+pub mod lesson;
+pub mod manifest;
+pub mod metrics;
+pub mod names;
+pub mod net;
+pub mod nfc;
+pub mod output;
+pub mod pass;
+pub mod pdf;
+pub mod preset;
+pub mod print;
+pub mod progress;
+pub mod qr;
+pub mod registrations;
+pub mod receipt;
+pub mod render;
+pub mod report;
+pub mod roster;
+pub mod roster_file;
+pub mod rsvp;
+pub mod schedule;
+pub mod scoring;
+pub mod settings;
+pub mod sheet;
+pub mod tent;
+pub mod traveler;
+pub mod typst;
+pub mod vcard;
+pub mod wallet;
+pub mod xlsx;
+pub mod zoom;
+
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use assets::{AssetCache, PreparedImage, QrCache};
+pub use deals::Deal;
+pub use docx::DocxRenderer;
+pub use layout::{MailingPlacement, SheetLayout, TextAlign};
+pub use names::DisplayOrder;
+pub use render::{PdfRenderer, SheetRenderer};
+pub use roster::RosterMarker;
+pub use roster_file::{read_roster_entries, read_roster_source, RosterFormat};
+pub use schedule::Schedule;
+pub use typst::TypstRenderer;
+pub use xlsx::XlsxRenderer;
+pub use sheet::{AttendanceSheet, AttendanceSheetBuilder};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// US Letter dimensions in mm
+pub const PAGE_WIDTH_MM: f32 = 215.9;
+pub const PAGE_HEIGHT_MM: f32 = 279.4;
+
+/// Margins
+pub const MARGIN_MM: f32 = 15.0;
+
+/// QR code size
+pub const QR_SIZE_MM: f32 = 30.0;
+
+/// Largest area a logo may occupy in the header
+pub const LOGO_MAX_WIDTH_MM: f32 = 50.0;
+pub const LOGO_MAX_HEIGHT_MM: f32 = QR_SIZE_MM;
+
+/// Largest area a roster photo thumbnail may occupy beside a name
+pub const PHOTO_MAX_SIZE_MM: f32 = 8.0;
+
+/// Largest area a sponsor image may occupy in the footer strip
+pub const SPONSOR_MAX_WIDTH_MM: f32 = 30.0;
+pub const SPONSOR_MAX_HEIGHT_MM: f32 = 8.0;
+
+/// Maximum row height for roster mode
+pub const MAX_ROW_HEIGHT_MM: f32 = 9.0;
+
+/// Shortest roster row that still holds a legible name and check mark; a
+/// longer roster overflows into three columns or more pages
+pub const MIN_ROW_HEIGHT_MM: f32 = 6.0;
+
+/// Rows need room for a signature with --signature-column
+pub const SIGNATURE_MIN_ROW_HEIGHT_MM: f32 = 8.0;
+
+/// Row height for blank table/seat mode (larger for writing)
+pub const TABLE_SEAT_ROW_HEIGHT_MM: f32 = 12.0;
+
+/// `--economy` sizes: a smaller QR code and tighter rows
+pub const ECONOMY_QR_SIZE_MM: f32 = 20.0;
+pub const ECONOMY_MAX_ROW_HEIGHT_MM: f32 = 7.0;
+pub const ECONOMY_TABLE_SEAT_ROW_HEIGHT_MM: f32 = 8.0;
+/// `--economy` rules are this fraction of their usual thickness
+pub const ECONOMY_STROKE_SCALE: f32 = 0.6;
+
+/// Font sizes in points
+pub const TITLE_FONT_SIZE: f32 = 18.0;
+pub const HEADER_FONT_SIZE: f32 = 12.0;
+pub const NORMAL_FONT_SIZE: f32 = 11.0;
+pub const SMALL_FONT_SIZE: f32 = 9.0;
+
+/// Column widths (proportional)
+pub const NAME_COL_RATIO: f32 = 0.60;
+pub const TABLE_COL_RATIO: f32 = 0.15;
+pub const SEAT_COL_RATIO: f32 = 0.25;
+
+/// Column widths with a signature cell in place of the checkbox
+pub const SIGNATURE_NAME_COL_RATIO: f32 = 0.35;
+pub const SIGNATURE_COL_RATIO: f32 = 0.32;
+pub const SIGNATURE_TABLE_COL_RATIO: f32 = 0.11;
+pub const SIGNATURE_SEAT_COL_RATIO: f32 = 0.22;
+
+/// The "Photo OK?" column's share, taken from the name column
+pub const PHOTO_CONSENT_COL_RATIO: f32 = 0.12;
+
+/// The check-in "Time" column's share, also taken from the name column
+pub const TIME_COL_RATIO: f32 = 0.10;
+
+// ============================================================================
+// Error Handling
+// ============================================================================
+
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("Failed to create PDF: {0}")]
+    PdfError(String),
+    #[error("Failed to read roster file: {0}")]
+    RosterError(String),
+    #[error("Failed to read config file: {0}")]
+    SettingsError(String),
+    #[error("Failed to read schedule file: {0}")]
+    ScheduleError(String),
+    #[error("Preset error: {0}")]
+    PresetError(String),
+    #[error("Failed to fetch Google contacts: {0}")]
+    ContactsError(String),
+    #[error("Failed to read registrations: {0}")]
+    RegistrationError(String),
+    #[error("Check-in failed: {0}")]
+    CheckInError(String),
+    #[error("Failed to make wallet pass: {0}")]
+    WalletError(String),
+    #[error("Failed to read events file: {0}")]
+    EventsError(String),
+    #[error("Failed to read hands file: {0}")]
+    DealError(String),
+    #[error("Failed to read lesson plan: {0}")]
+    LessonPlanError(String),
+    #[error("Failed to read RSVP file: {0}")]
+    RsvpError(String),
+    #[error("Failed to generate QR code: {0}")]
+    QrError(String),
+    #[error("Invalid date format: {0}")]
+    DateError(String),
+    #[error("Failed to load logo: {0}")]
+    LogoError(String),
+    #[error("Invalid event ID (expected 8 hex characters): {0}")]
+    EventIdError(String),
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+    #[error("Failed to export: {0}")]
+    ExportError(String),
+    #[error("Output file already exists (use --force to overwrite): {0}")]
+    OutputExistsError(String),
+    #[error("Failed to print: {0}")]
+    PrintError(String),
+    #[error("{0} needs the network, which --offline forbids")]
+    OfflineError(String),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// Roster entry as stored in the JSON file; unset fields are left out when
+/// written back
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RosterEntry {
+    pub name: String,
+    /// Preferred first name ("Bob" for Robert), printed with --use-nicknames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    /// Headshot: file path (relative to the roster file) or URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub photo: Option<String>,
+    /// Membership expiry date (YYYY-MM-DD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    /// "bold" or "regular"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub highlight: bool,
+    /// "she/her", "they/them"; printed small after the name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pronouns: Option<String>,
+    /// Short reminder printed under the name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Dietary or accessibility needs; printed only on the teacher's copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needs: Option<String>,
+    /// First class: badge the name so the teacher welcomes them
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub new: bool,
+    /// Youth player, marked on the sheet and counted for grant reports
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub junior: bool,
+    /// Said they are coming (an online RSVP); their box is printed ticked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirmed: bool,
+    /// MM-DD or YYYY-MM-DD (the year is ignored)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birthday: Option<String>,
+    /// Contact details kept with the roster for the club's records; not
+    /// printed on the sheet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    /// ACBL player number, for masterpoint award sheets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acbl_number: Option<String>,
+    /// Playing level from the membership list ("Beginner", "Intermediate");
+    /// kept with the roster but not printed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+/// A student on the roster
+#[derive(Clone, Debug)]
+pub struct Student {
+    pub name: String,
+    /// Preferred first name, printed in place of the given name with
+    /// `use_nicknames`
+    pub nickname: Option<String>,
+    /// Headshot printed beside the name, so substitutes can put names to faces
+    pub photo: Option<Arc<PreparedImage>>,
+    /// Membership expiry; flagged on the sheet as the date approaches
+    pub expires: Option<NaiveDate>,
+    /// Print the name in bold
+    pub bold: bool,
+    /// Print the name over a highlighter band
+    pub highlight: bool,
+    /// Printed small after the name
+    pub pronouns: Option<String>,
+    /// Reminder printed small under the name ("owes $10")
+    pub note: Option<String>,
+    /// Dietary or accessibility needs, kept off the sign-in copy
+    pub needs: Option<String>,
+    /// New student, badged "NEW" beside the name
+    pub is_new: bool,
+    /// Youth player, marked after the name
+    pub junior: bool,
+    /// Confirmed ahead of class; the checkbox is printed ticked
+    pub confirmed: bool,
+    /// (month, day); a cake is drawn beside the name in the birthday week
+    pub birthday: Option<(u32, u32)>,
+}
+
+impl From<String> for Student {
+    fn from(name: String) -> Self {
+        Student {
+            name,
+            nickname: None,
+            photo: None,
+            expires: None,
+            bold: false,
+            highlight: false,
+            pronouns: None,
+            note: None,
+            needs: None,
+            is_new: false,
+            junior: false,
+            confirmed: false,
+            birthday: None,
+        }
+    }
+}
+
+impl From<&str> for Student {
+    fn from(name: &str) -> Self {
+        Student::from(name.to_string())
+    }
+}
+
+/// What to do in an emergency at the venue, printed large on its own page
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct EmergencyInfo {
+    /// Venue name and address
+    pub location: String,
+    /// Where the exits are
+    pub exits: Option<String>,
+    /// Where the defibrillator is
+    pub aed: Option<String>,
+    /// "Building manager: 555-0100"
+    pub contacts: Vec<String>,
+    /// Anything else: first aid kit, assembly point
+    pub notes: Option<String>,
+}
+
+/// Acknowledgment printed in the footer strip of every page
+#[derive(Clone, Debug)]
+pub struct Sponsor {
+    pub text: String,
+    pub image: Option<Arc<PreparedImage>>,
+}
+
+/// Which printing of an event's sheet this is. From the second on it is
+/// stamped in the footer, so the front desk can tell which printout is current.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Revision {
+    pub number: u32,
+    /// Local time the sheet was generated (RFC 3339)
+    pub generated: String,
+}
+
+impl Revision {
+    /// "Rev 2 – generated 09:12"
+    pub fn stamp(&self) -> String {
+        let time = chrono::DateTime::parse_from_rfc3339(&self.generated)
+            .map(|time| time.format("%H:%M").to_string())
+            .unwrap_or_else(|_| self.generated.clone());
+        format!("Rev {} \u{2013} generated {}", self.number, time)
+    }
+}
+
+/// Attendance sheet configuration
+#[derive(Clone)]
+pub struct AttendanceConfig {
+    pub class_name: String,
+    pub teacher: String,
+    /// Contact address printed on the instructor line
+    pub teacher_email: Option<String>,
+    /// Teacher standing in for `teacher`, who stays the instructor of record
+    pub substitute: Option<String>,
+    pub date: NaiveDate,
+    pub location: String,
+    /// Venue Wi-Fi details, printed under the header
+    pub wifi: Option<String>,
+    /// Venue emergency information, printed under the header
+    pub emergency: Option<String>,
+    /// One-off notice ("CLASS MOVED TO ROOM 204") boxed across the top of
+    /// the grid
+    pub banner: Option<String>,
+    /// The club's feedback form; its QR code and a comments box go at the
+    /// bottom of the first page
+    pub feedback_url: Option<String>,
+    /// Venue emergency page, added after the attendance pages
+    pub emergency_page: Option<EmergencyInfo>,
+    pub event_id: String,
+    pub roster: Option<Vec<Student>>,
+    /// Students joining a hybrid class over Zoom, listed with a box each in
+    /// an "Online attendees" section of the first page
+    pub online_attendees: Option<Vec<String>>,
+    pub blank_rows: u32,
+    pub mailing_list: bool,
+    pub mailing_rows: u32,
+    /// Mailing list heading in place of "JOIN MY MAILING LIST"
+    pub mailing_title: Option<String>,
+    pub mailing_placement: MailingPlacement,
+    /// Where the sheet title sits in the room beside the QR code
+    pub title_align: TextAlign,
+    /// Sign-in rows for volunteers, caddies and greeters (0 to omit)
+    pub helper_rows: u32,
+    /// "First lesson free" coupons on a tear-off strip at the bottom of the
+    /// first page (0 to omit, at most [`layout::MAX_COUPONS`])
+    pub coupons: u32,
+    /// Roster rows get a "Signature" cell instead of a checkbox
+    pub signature_column: bool,
+    /// Roster rows get a "Photo OK?" column to circle Y or N
+    pub photo_consent: bool,
+    /// Roster rows get a narrow "Time" column for the door volunteer to note
+    /// when each student checked in
+    pub time_column: bool,
+    /// Number each grid row and append matching tear-off draw tickets
+    pub raffle: bool,
+    /// Number each grid row with a shuffled lucky number for the door prize
+    /// draw, in place of raffle tickets
+    pub draw_numbers: bool,
+    /// Add a "Happy birthday" line for students whose birthday is this week
+    pub announce_birthdays: bool,
+    /// "Anna van der Berg" or "van der Berg, Anna" (sorted by family name)
+    pub display_order: DisplayOrder,
+    /// Print students' nicknames in place of their given names
+    pub use_nicknames: bool,
+    /// Roster name markers explained in the legend under the grid
+    pub markers: Vec<RosterMarker>,
+    /// Series schedule; its remaining sessions are listed on a final page
+    pub calendar: Option<Schedule>,
+    /// Liability waiver text, followed by a signature line per student on
+    /// pages after the sheet
+    pub waiver: Option<String>,
+    /// Pre-class supplies, printed with checkboxes on a final page
+    pub checklist: Option<Vec<String>>,
+    /// Practice deals, drawn as hand diagrams on pages after the sheet
+    pub hands: Option<Vec<Deal>>,
+    /// Add a page of numbered blank rows for late arrivals and walk-ins
+    pub overflow_page: bool,
+    /// Copies of the whole sheet in one document (at least 1)
+    pub copies: u32,
+    /// Pure black rules, no shading and no logo, for printers that lose
+    /// light gray
+    pub mono: bool,
+    /// Compact header, no logo, thinner rules and shorter rows, to use less
+    /// paper and toner
+    pub economy: bool,
+    /// Printer resolution the QR codes are rasterized for
+    pub qr_dpi: u32,
+    /// Rasterized QR codes, shared with every config cloned from this one
+    pub qr_codes: QrCache,
+    pub logo: Option<Arc<PreparedImage>>,
+    pub sponsor: Option<Sponsor>,
+    pub reproducible: bool,
+    /// This printing of the event's sheet, when earlier ones are on record
+    pub revision: Option<Revision>,
+    /// Title the sheet as the teacher's copy (see [`roster::teacher_copy`])
+    pub teacher_copy: bool,
+}
+
+/// Wall-clock time spent in each phase of building documents
+#[derive(Debug, Default)]
+pub struct PhaseTimings {
+    pub header: Duration,
+    pub grid: Duration,
+    pub mailing: Duration,
+    pub serialize: Duration,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// YYYY-MM-DD, or a date relative to today: "today", "tomorrow" or
+/// "next-<weekday>" (e.g. "next-tuesday")
+pub fn parse_date(date_str: &Option<String>) -> Result<NaiveDate, AppError> {
+    let today = Local::now().date_naive();
+    match date_str {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .ok()
+            .or_else(|| parse_relative_date(s, today))
+            .ok_or_else(|| AppError::DateError(s.clone())),
+        None => Ok(today),
+    }
+}
+
+/// "next-tuesday" is the first Tuesday after `today`, a week on if today is
+/// Tuesday
+pub fn parse_relative_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match text.to_ascii_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        text => {
+            let weekday: Weekday = text.strip_prefix("next-")?.parse().ok()?;
+            let days_ahead = (weekday.num_days_from_monday() + 7
+                - today.weekday().num_days_from_monday()
+                - 1)
+                % 7
+                + 1;
+            today.checked_add_days(Days::new(days_ahead as u64))
+        }
+    }
+}
+
+pub fn generate_short_id() -> String {
+    let uuid = Uuid::new_v4();
+    let hex = format!("{:x}", uuid);
+    hex[..8].to_uppercase()
+}
+
+/// Event IDs match the worker's format: 8 uppercase hex characters
+pub fn parse_event_id(id: &str) -> Result<String, AppError> {
+    if id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(id.to_uppercase())
+    } else {
+        Err(AppError::EventIdError(id.to_string()))
+    }
+}
+
+/// "A1B2C3D4-007": the event ID ties a batch of passes or receipts to the run
+/// that printed them, the sequence number tells them apart
+pub fn serial_number(event_id: &str, index: u32) -> String {
+    format!("{}-{:03}", event_id, index + 1)
+}
+
+pub fn format_date_display(date: &NaiveDate) -> String {
+    date.format("%A, %B %-d, %Y").to_string()
+}
+
+/// "CLASS ATTENDANCE", marked on the teacher's copy so it stays off the
+/// sign-in table
+pub fn sheet_title(config: &AttendanceConfig) -> &'static str {
+    if config.teacher_copy {
+        "CLASS ATTENDANCE \u{2013} TEACHER COPY"
+    } else {
+        "CLASS ATTENDANCE"
+    }
+}
+
+/// "Instructor: Rick", or "Instructor: Pat (sub for Rick)" with a substitute.
+/// The teacher's email follows their name when they teach the class.
+pub fn instructor_line(config: &AttendanceConfig) -> String {
+    match (&config.substitute, &config.teacher_email) {
+        (Some(substitute), _) => format!("Instructor: {} (sub for {})", substitute, config.teacher),
+        (None, Some(email)) => format!("Instructor: {} \u{2013} {}", config.teacher, email),
+        (None, None) => format!("Instructor: {}", config.teacher),
+    }
+}
+
+/// "Wi-Fi: …   Emergency: …" from the venue, if it has either
+pub fn venue_line(config: &AttendanceConfig) -> Option<String> {
+    let parts: Vec<String> = [("Wi-Fi", &config.wifi), ("Emergency", &config.emergency)]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_ref().map(|value| format!("{}: {}", label, value)))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("     "))
+}
+
+/// "MM-DD" or "YYYY-MM-DD" as (month, day)
+fn parse_birthday(text: &str) -> Option<(u32, u32)> {
+    let month_day = match text.len() {
+        5 => text,
+        10 => text.get(5..)?,
+        _ => return None,
+    };
+    // Parse against a leap year so Feb 29 is accepted
+    let date = NaiveDate::parse_from_str(&format!("2000-{}", month_day), "%Y-%m-%d").ok()?;
+    Some((date.month(), date.day()))
+}
+
+/// Load a roster file or URL, read as `format` or as its name suggests,
+/// preparing any photos through `assets` so a face shared by several events
+/// is only decoded once
+pub fn load_roster(
+    path: &Option<String>,
+    format: Option<RosterFormat>,
+    assets: &mut AssetCache,
+) -> Result<Option<Vec<Student>>, AppError> {
+    match path {
+        Some(p) => {
+            let entries = read_roster_source(p, format)?;
+            Ok(Some(roster_students(entries, &source_dir(p), assets)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Check roster entries and turn them into students; photo paths are
+/// relative to `base_dir`
+pub fn roster_students(
+    entries: Vec<RosterEntry>,
+    base_dir: &Path,
+    assets: &mut AssetCache,
+) -> Result<Vec<Student>, AppError> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let photo = match &entry.photo {
+                Some(source) => Some(assets.image(
+                    &resolve_source(base_dir, source),
+                    PHOTO_MAX_SIZE_MM,
+                    PHOTO_MAX_SIZE_MM,
+                )?),
+                None => None,
+            };
+            let expires = match &entry.expires {
+                Some(date) => Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+                    AppError::RosterError(format!("{}: invalid expires date {}", entry.name, date))
+                })?),
+                None => None,
+            };
+            let birthday = match &entry.birthday {
+                Some(text) => Some(parse_birthday(text).ok_or_else(|| {
+                    AppError::RosterError(format!("{}: invalid birthday {}", entry.name, text))
+                })?),
+                None => None,
+            };
+            let bold = match entry.style.as_deref() {
+                None | Some("regular") => false,
+                Some("bold") => true,
+                Some(other) => {
+                    return Err(AppError::RosterError(format!(
+                        "{}: unknown style \"{}\" (expected \"bold\" or \"regular\")",
+                        entry.name, other
+                    )))
+                }
+            };
+            Ok(Student {
+                name: entry.name,
+                nickname: entry.nickname.filter(|nickname| !nickname.trim().is_empty()),
+                photo,
+                expires,
+                bold,
+                highlight: entry.highlight,
+                pronouns: entry.pronouns.filter(|pronouns| !pronouns.trim().is_empty()),
+                note: entry.note.filter(|note| !note.trim().is_empty()),
+                needs: entry.needs.filter(|needs| !needs.trim().is_empty()),
+                is_new: entry.new,
+                junior: entry.junior,
+                confirmed: entry.confirmed,
+                birthday,
+            })
+        })
+        .collect()
+}
+
+/// URLs are used as-is; file paths are relative to `base_dir`
+pub fn resolve_source(base_dir: &Path, source: &str) -> String {
+    let base_url = base_dir.to_str().filter(|base| net::is_url(base));
+    if net::is_url(source) {
+        source.to_string()
+    } else if let Some(base_url) = base_url {
+        format!("{}/{}", base_url.trim_end_matches('/'), source)
+    } else {
+        base_dir.join(source).display().to_string()
+    }
+}
+
+/// What paths inside `source` are relative to: the file's directory, or for
+/// a URL everything before its last "/", so photos beside a roster on the
+/// club website are fetched from there
+pub fn source_dir(source: &str) -> PathBuf {
+    if net::is_url(source) {
+        PathBuf::from(source.rsplit_once('/').map_or(source, |(dir, _)| dir))
+    } else {
+        Path::new(source).parent().map(Path::to_path_buf).unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/// Lay out the attendance sheet and render it with `renderer`, adding the time
+/// spent in each phase to `timings`
+pub fn render_with(
+    renderer: &dyn SheetRenderer,
+    config: &AttendanceConfig,
+    timings: &mut PhaseTimings,
+) -> Result<Vec<u8>, AppError> {
+    let layout = layout::layout_sheet_timed(config, timings)?;
+
+    let phase_start = Instant::now();
+    let bytes = renderer.render(&layout, config)?;
+    timings.serialize += phase_start.elapsed();
+
+    Ok(bytes)
+}
+
+/// Lay out several sheets and render them as consecutive pages of one
+/// document. Document-level settings (reproducible IDs, metadata) come from
+/// the first sheet.
+pub fn render_events_with(
+    renderer: &dyn SheetRenderer,
+    configs: &[AttendanceConfig],
+    timings: &mut PhaseTimings,
+) -> Result<Vec<u8>, AppError> {
+    let (first, rest) = configs
+        .split_first()
+        .ok_or_else(|| AppError::ConfigError("no events to render".to_string()))?;
+
+    let mut combined = layout::layout_sheet_timed(first, timings)?;
+    for config in rest {
+        combined.append(layout::layout_sheet_timed(config, timings)?);
+    }
+
+    let phase_start = Instant::now();
+    let bytes = renderer.render(&combined, first)?;
+    timings.serialize += phase_start.elapsed();
+
+    Ok(bytes)
+}
+
+/// Lay out and render each sheet in turn as PDF, writing its pages to `out`
+/// before the next sheet is laid out, so a merged semester of sheets never
+/// sits in memory whole. Document-level settings come from the first sheet,
+/// as in [`render_events_with`].
+pub fn stream_events_pdf<W: Write>(
+    configs: &[AttendanceConfig],
+    out: W,
+    timings: &mut PhaseTimings,
+) -> Result<W, AppError> {
+    let mut stream = pdf::PdfStream::new(out);
+    for config in configs {
+        let layout = layout::layout_sheet_timed(config, timings)?;
+
+        let phase_start = Instant::now();
+        stream.append(&pdf::write_pdf(&layout, config)?)?;
+        timings.serialize += phase_start.elapsed();
+    }
+    stream.finish()
+}
+
+/// Lay out the attendance sheet and serialize it to PDF bytes
+pub fn render_pdf(config: &AttendanceConfig, timings: &mut PhaseTimings) -> Result<Vec<u8>, AppError> {
+    render_with(&PdfRenderer, config, timings)
+}
+
+/// Render the attendance sheet to a PDF file
+pub fn generate_pdf(config: &AttendanceConfig, output_path: &str) -> Result<(), AppError> {
+    let bytes = render_pdf(config, &mut PhaseTimings::default())?;
+    std::fs::write(output_path, bytes)?;
+    Ok(())
+}
@@ -1,84 +1,496 @@
 // attendance-pdf: Generate attendance sheets for bridge classes
 
-use chrono::{Local, NaiveDate};
-use clap::Parser;
-use ::image::{DynamicImage, Luma, Rgba, RgbImage};
-use printpdf::*;
-use qrcode::QrCode;
-use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufWriter, Read};
-use thiserror::Error;
-use uuid::Uuid;
+use attendance_pdf::assets::load_logo;
+use attendance_pdf::checkin::{default_log_path, read_checkins, record_scan, ScanOutcome};
+use attendance_pdf::deals::load_deals;
+use attendance_pdf::events::load_events;
+use attendance_pdf::google::fetch_label_roster;
+use attendance_pdf::layout::{roster_overflow, Trim, DEFAULT_CHECKLIST, MAX_BLEED_MM, MAX_COUPONS};
+use attendance_pdf::lesson::{layout_lesson_plan, load_lesson_plan};
+use attendance_pdf::manifest::{manifest_path, read_manifest, Manifest, ManifestEvent};
+use attendance_pdf::names::{sort_roster, with_nickname};
+use attendance_pdf::net;
+use attendance_pdf::nfc::{checkin_url, hex_bytes, ndef_tlv, ndef_uri_message, DEFAULT_CHECKIN_URL};
+use attendance_pdf::output::{
+    create_output, expand_name_template, resolve_output_path, slugify, teacher_copy_path, write_output,
+    DEFAULT_NAME_TEMPLATE,
+};
+use attendance_pdf::pass::{layout_passes, PassOptions, DEFAULT_BLANK_PASSES, DEFAULT_PUNCHES};
+use attendance_pdf::pdf::{append_pdfs, impose_booklet};
+use attendance_pdf::preset::{
+    absolute_source, list_presets, load_preset, presets_dir, save_preset, Preset,
+};
+use attendance_pdf::print::print_file;
+use attendance_pdf::progress::{layout_progress, ProgressOptions};
+use attendance_pdf::qr::DEFAULT_QR_DPI;
+use attendance_pdf::receipt::{layout_receipts, ReceiptOptions, DEFAULT_BLANK_RECEIPTS};
+use attendance_pdf::roster::{sign_in_copy, teacher_copy};
+use attendance_pdf::report::{
+    monthly_report_csv, reconcile, transcription_csv, MasterpointSheet, Reconciliation, SemesterSummary, TrendReport,
+};
+use attendance_pdf::roster_file::{
+    canonical_roster, csv_records, diff_rosters, read_roster_entries, read_roster_source, roster_csv, roster_json,
+    RosterFormat, Visibility,
+};
+use attendance_pdf::registrations::{fetch_eventbrite_roster, read_signupgenius_roster};
+use attendance_pdf::rsvp::{load_rsvps, mark_confirmed, RsvpMatch};
+use attendance_pdf::schedule::load_schedule;
+use attendance_pdf::scoring::{layout_scoring, ScoringOptions};
+use attendance_pdf::settings::{load_settings, Settings, TeacherProfile, DEFAULT_SETTINGS_FILE};
+use attendance_pdf::sheet::{DEFAULT_BLANK_ROWS, DEFAULT_TEACHER};
+use attendance_pdf::tent::{layout_table_tents, TentOptions};
+use attendance_pdf::traveler::{layout_travelers, TravelerOptions, DEFAULT_BOARDS, MAX_BOARDS};
+use attendance_pdf::vcard::import_vcard;
+use attendance_pdf::wallet::{WalletClass, WalletPass};
+use attendance_pdf::zoom::fetch_zoom_registrants;
+use attendance_pdf::{
+    format_date_display, generate_short_id, load_roster, parse_date, parse_event_id, render_pdf,
+    render_events_with, roster_students, serial_number, source_dir, stream_events_pdf, AppError, AssetCache,
+    AttendanceConfig, AttendanceSheet, DisplayOrder, DocxRenderer, MailingPlacement, PdfRenderer, PhaseTimings,
+    PreparedImage, Revision, SheetRenderer, Student, TextAlign, TypstRenderer, XlsxRenderer,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default file names for the other modes, unless --name-template is given
+const PASS_NAME_TEMPLATE: &str = "passes-{date}-{class}.{ext}";
+const RECEIPT_NAME_TEMPLATE: &str = "receipts-{date}-{class}.{ext}";
+const TENT_NAME_TEMPLATE: &str = "table-cards-{date}-{class}.{ext}";
+const PROGRESS_NAME_TEMPLATE: &str = "progress-{date}-{class}.{ext}";
+const LESSON_PLAN_NAME_TEMPLATE: &str = "lesson-plan-{date}-{class}.{ext}";
+const TRAVELERS_NAME_TEMPLATE: &str = "travelers-{date}-{class}.{ext}";
+const SCORING_NAME_TEMPLATE: &str = "scoring-{date}-{class}.{ext}";
 
 // ============================================================================
-// Constants
+// Data Structures
 // ============================================================================
 
-/// US Letter dimensions in mm
-const PAGE_WIDTH_MM: f32 = 215.9;
-const PAGE_HEIGHT_MM: f32 = 279.4;
+/// Without a subcommand, generates a sheet (the same as `generate`)
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about = "Generate attendance sheets for bridge classes",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
+    #[command(flatten)]
+    args: Args,
+
+    /// Never touch the network: a logo, photo, sponsor or roster URL, or a
+    /// Google, Eventbrite or Zoom import, stops the run before any fetch
+    #[arg(long, global = true)]
+    offline: bool,
+}
 
-/// Margins
-const MARGIN_MM: f32 = 15.0;
+impl Cli {
+    /// The config file given to the command, if it takes one
+    fn config(&self) -> Option<&Path> {
+        match &self.command {
+            None => self.args.config.as_deref(),
+            Some(CliCommand::Generate(args)) => args.config.as_deref(),
+            Some(CliCommand::WalletPass(args)) => args.config.as_deref(),
+            Some(_) => None,
+        }
+    }
+}
 
-/// QR code size
-const QR_SIZE_MM: f32 = 30.0;
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Generate a document (the default)
+    Generate(Box<Args>),
+    /// Save, show and list class presets
+    #[command(subcommand)]
+    Preset(PresetCommand),
+    /// Import and tidy roster files
+    #[command(subcommand)]
+    Roster(RosterCommand),
+    /// Check students in at the door by scanning their class passes with a
+    /// handheld QR scanner (keyboard mode); each code read from standard
+    /// input is recorded in the event's check-in log
+    Scan {
+        /// Event the students are checking in to
+        #[arg(long)]
+        event_id: String,
+        /// Check-in log (JSON lines) [default: checkins-<EVENT_ID>.jsonl]
+        #[arg(long, value_name = "FILE")]
+        log: Option<PathBuf>,
+    },
+    /// Write an Apple Wallet pass (.pkpass) and a Google Wallet pass object
+    /// (JSON) for each student on the roster, carrying a personal check-in
+    /// QR code for the class series
+    WalletPass(WalletArgs),
+    /// Print a sheet again exactly as before from its manifest: same event
+    /// ID, roster and options, so a lost printout can be replaced weeks later
+    Regenerate {
+        /// Manifest written with the sheet (from --manifest)
+        manifest: PathBuf,
+        /// Header logo (file path or URL); logos are not kept in the manifest
+        #[arg(long)]
+        logo: Option<String>,
+        /// File to write [default: the original file name, next to the
+        /// manifest]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Reports for the director after class
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// Export recorded attendance for other systems
+    #[command(subcommand)]
+    Export(ExportCommand),
+    /// Make the NDEF message for a tap-to-check-in NFC sticker: the event's
+    /// check-in URL as a URI record, printed as hex for an NFC writer app or
+    /// written raw to a file
+    Nfc {
+        /// Event the sticker checks students in to
+        #[arg(long)]
+        event_id: String,
+        /// Check-in URL; {event_id} is replaced
+        #[arg(long, default_value = DEFAULT_CHECKIN_URL)]
+        url: String,
+        /// Wrap the message in an NDEF TLV, as written to Type 2 tag memory
+        /// (NTAG213/215/216)
+        #[arg(long)]
+        tlv: bool,
+        /// Write the raw bytes to this file instead of printing hex
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Create a starter config file, example roster and schedule template
+    Init {
+        /// Directory to create the files in
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+}
 
-/// Maximum row height for roster mode
-const MAX_ROW_HEIGHT_MM: f32 = 9.0;
+#[derive(Subcommand, Debug)]
+enum PresetCommand {
+    /// Save class options under a name for `--preset`, replacing any preset
+    /// of that name
+    Save(PresetArgs),
+    /// Show a saved preset
+    Load {
+        /// Preset name
+        name: String,
+    },
+    /// List saved presets
+    List,
+}
 
-/// Row height for blank table/seat mode (larger for writing)
-const TABLE_SEAT_ROW_HEIGHT_MM: f32 = 12.0;
+#[derive(Subcommand, Debug)]
+enum RosterCommand {
+    /// Convert exported contacts (.vcf) into a roster file, keeping each
+    /// contact's name, first email and first phone number
+    Import {
+        /// vCard file exported from a phone or address book
+        file: PathBuf,
+        /// Only contacts in this group (vCard CATEGORIES)
+        #[arg(long)]
+        group: Option<String>,
+        /// Roster file to write (printed to stdout when not given)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the roster file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Write the roster tidied up: names normalized, duplicates merged and
+    /// sorted by name, ready to go back into the club spreadsheet
+    Export {
+        /// Roster file (JSON, or CSV when named .csv)
+        file: PathBuf,
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: RosterFormat,
+        /// Fields to include: public leaves only what the sign-in sheet
+        /// shows, teacher adds notes and needs, archive adds contact details
+        #[arg(long, value_enum, default_value = "archive")]
+        visibility: Visibility,
+        /// File to write (printed to stdout when not given)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// List students added, removed or renamed between two roster files;
+    /// a rename is a new name with the same email, phone or ACBL number
+    Diff {
+        /// Earlier roster file (JSON, or CSV when named .csv)
+        old: PathBuf,
+        /// Current roster file (JSON, or CSV when named .csv)
+        new: PathBuf,
+    },
+}
 
-/// Font sizes in points
-const TITLE_FONT_SIZE: f32 = 18.0;
-const HEADER_FONT_SIZE: f32 = 12.0;
-const NORMAL_FONT_SIZE: f32 = 11.0;
-const SMALL_FONT_SIZE: f32 = 9.0;
+#[derive(Subcommand, Debug)]
+enum ReportCommand {
+    /// Compare check-ins against the printed roster: who came, who did not,
+    /// walk-ins and table count, written as PDF and CSV
+    Reconcile {
+        /// Event to reconcile
+        #[arg(long)]
+        event_id: String,
+        /// Manifest of the printed sheet (from --manifest), for its roster
+        #[arg(long, value_name = "FILE")]
+        manifest: PathBuf,
+        /// Check-in logs from `scan`; may be given more than once
+        /// [default: checkins-<EVENT_ID>.jsonl]
+        #[arg(long, value_name = "FILE")]
+        checkins: Vec<PathBuf>,
+        /// Directory to write reconcile-<EVENT_ID>.pdf and .csv into
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Overwrite reports that already exist
+        #[arg(long)]
+        force: bool,
+    },
+    /// Supervised-play masterpoint award sheet (PDF) listing everyone who
+    /// checked in, with ACBL numbers from the roster's "acbl_number"
+    Masterpoints {
+        /// Event the awards are for
+        #[arg(long)]
+        event_id: String,
+        /// Manifest of the printed sheet (from --manifest)
+        #[arg(long, value_name = "FILE")]
+        manifest: PathBuf,
+        /// Check-in logs from `scan` [default: checkins-<EVENT_ID>.jsonl]
+        #[arg(long, value_name = "FILE")]
+        checkins: Vec<PathBuf>,
+        /// Roster file holding the players' ACBL numbers
+        #[arg(long, value_name = "FILE")]
+        roster: PathBuf,
+        /// ACBL club number
+        #[arg(long)]
+        club_number: String,
+        /// Masterpoints awarded to each player, e.g. 0.15
+        #[arg(long)]
+        award: String,
+        /// Director named on the certification line
+        #[arg(long)]
+        director: Option<String>,
+        /// Directory to write masterpoints-<EVENT_ID>.pdf into
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Overwrite the sheet if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Charts for the club board (PDF): players per week, and new against
+    /// returning players, from the sheets' manifests and check-in logs
+    Trends {
+        /// Manifests of the sheets to chart (from --manifest)
+        #[arg(required = true, value_name = "MANIFEST")]
+        manifests: Vec<PathBuf>,
+        /// Check-in logs from `scan` [default: checkins-<EVENT_ID>.jsonl for
+        /// each session]
+        #[arg(long, value_name = "FILE")]
+        checkins: Vec<PathBuf>,
+        /// Directory to write attendance-trends.pdf into
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Overwrite the report if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Semester summary (PDF) for the annual general meeting: each class's
+    /// attendance, the top attendees, total student-sessions and, with
+    /// --mailing-list, how the list grew
+    Semester {
+        /// Manifests of the semester's sheets (from --manifest)
+        #[arg(required = true, value_name = "MANIFEST")]
+        manifests: Vec<PathBuf>,
+        /// First day of the semester (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        /// Last day of the semester (YYYY-MM-DD)
+        #[arg(long)]
+        to: String,
+        /// Check-in logs from `scan` [default: checkins-<EVENT_ID>.jsonl for
+        /// each session]
+        #[arg(long, value_name = "FILE")]
+        checkins: Vec<PathBuf>,
+        /// Mailing list export (CSV) with a "joined" column holding each
+        /// member's sign-up date; members with none count from the start
+        #[arg(long, value_name = "CSV")]
+        mailing_list: Option<PathBuf>,
+        /// Directory to write semester-<FROM>-to-<TO>.pdf into
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Overwrite the report if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
 
-/// Column widths (proportional)
-const NAME_COL_RATIO: f32 = 0.60;
-const TABLE_COL_RATIO: f32 = 0.15;
-const SEAT_COL_RATIO: f32 = 0.25;
+#[derive(Subcommand, Debug)]
+enum ExportCommand {
+    /// The club's monthly report as CSV: one row per session with players,
+    /// tables and the players' ACBL numbers, from the sheets' manifests and
+    /// check-in logs
+    AcblReport {
+        /// Manifests of the month's sheets (from --manifest)
+        #[arg(required = true, value_name = "MANIFEST")]
+        manifests: Vec<PathBuf>,
+        /// Only sessions in this month (YYYY-MM)
+        #[arg(long)]
+        month: Option<String>,
+        /// Check-in logs from `scan` [default: checkins-<EVENT_ID>.jsonl for
+        /// each session]
+        #[arg(long, value_name = "FILE")]
+        checkins: Vec<PathBuf>,
+        /// Roster file holding the players' ACBL numbers
+        #[arg(long, value_name = "FILE")]
+        roster: Option<PathBuf>,
+        /// File to write (printed to stdout when not given)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// A CSV template for typing up a paper sheet: one row per grid row with
+    /// the printed name (or table and seat) filled in, and columns for
+    /// whether the student came and, with --photo-consent and --time-column,
+    /// their answer and check-in time
+    Transcription {
+        /// Manifest of the sheet (from --manifest)
+        manifest: PathBuf,
+        /// File to write (printed to stdout when not given)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
 
-// ============================================================================
-// Error Handling
-// ============================================================================
+/// The options a preset captures
+#[derive(clap::Args, Debug)]
+struct PresetArgs {
+    /// Preset name, e.g. tuesday-beginner
+    preset: String,
+
+    /// Class/event name
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// Teacher name or [teacher.<key>] profile
+    #[arg(short, long)]
+    teacher: Option<String>,
+
+    /// Location
+    #[arg(short, long)]
+    location: Option<String>,
+
+    /// [venue.<key>] profile
+    #[arg(long, value_name = "KEY")]
+    venue: Option<String>,
+
+    /// Student roster file (stored as an absolute path)
+    #[arg(long)]
+    roster: Option<String>,
 
-#[derive(Error, Debug)]
-pub enum AppError {
-    #[error("Failed to create PDF: {0}")]
-    PdfError(String),
-    #[error("Failed to read roster file: {0}")]
-    RosterError(String),
-    #[error("Failed to generate QR code: {0}")]
-    QrError(String),
-    #[error("Invalid date format: {0}")]
-    DateError(String),
-    #[error("Failed to load logo: {0}")]
-    LogoError(String),
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
+    /// Number of blank rows for students
+    #[arg(short, long)]
+    rows: Option<u32>,
+
+    /// Logo image file (stored as an absolute path) or URL
+    #[arg(long)]
+    logo: Option<String>,
 }
 
-// ============================================================================
-// Data Structures
-// ============================================================================
+/// Options for `wallet-pass`
+#[derive(clap::Args, Debug)]
+struct WalletArgs {
+    /// Config file with the [wallet] issuer and signing settings (defaults to
+    /// ./attendance.toml if present)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
 
-/// CLI Arguments
-#[derive(Parser, Debug)]
-#[command(author, version, about = "Generate attendance sheets for bridge classes")]
+    /// Class series name
+    #[arg(short, long)]
+    name: String,
+
+    /// Teacher name or [teacher.<key>] profile
+    #[arg(short, long)]
+    teacher: Option<String>,
+
+    /// Student roster file or URL; one pass is made per student
+    #[arg(long)]
+    roster: String,
+
+    /// Series ID encoded in every pass (8 hex characters; random when not
+    /// given)
+    #[arg(long)]
+    event_id: Option<String>,
+
+    /// Logo image (file path or URL) for the Apple Wallet icon
+    #[arg(long)]
+    logo: Option<String>,
+
+    /// Print each student's roster "nickname" in place of their given name
+    #[arg(long)]
+    use_nicknames: bool,
+
+    /// Directory to write the passes into (created if missing)
+    #[arg(long, value_name = "DIR", default_value = "wallet-passes")]
+    output_dir: PathBuf,
+
+    /// Overwrite passes that already exist
+    #[arg(long)]
+    force: bool,
+}
+
+/// Options for generating a document
+#[derive(clap::Args, Debug)]
 struct Args {
+    /// Config file (TOML) with club settings such as the [sponsor] footer
+    /// strip, [[marker]] roster legends and [teacher.<key>] profiles
+    /// (defaults to ./attendance.toml if present)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Saved class options (see `preset save`); options given here win
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
+
     /// Class/event name
+    #[arg(short, long, required_unless_present_any = ["events", "preset"])]
+    name: Option<String>,
+
+    /// Teacher name, or the key of a [teacher.<key>] profile in the config
+    /// file supplying the name, email, logo and mailing list heading
+    /// (default Rick)
     #[arg(short, long)]
-    name: String,
+    teacher: Option<String>,
 
-    /// Teacher name
-    #[arg(short, long, default_value = "Rick")]
-    teacher: String,
+    /// Substitute teacher; printed as "Instructor: <sub> (sub for <teacher>)"
+    /// with the regular teacher kept as instructor of record
+    #[arg(long, value_name = "NAME")]
+    substitute: Option<String>,
 
-    /// Date (YYYY-MM-DD format, defaults to today)
+    /// Date: YYYY-MM-DD, today, tomorrow or next-<weekday> such as
+    /// next-tuesday (defaults to today)
     #[arg(short, long)]
     date: Option<String>,
 
@@ -86,9 +498,29 @@ struct Args {
     #[arg(short, long, default_value = "")]
     location: String,
 
-    /// Number of blank rows for students (default 32)
-    #[arg(short, long, default_value = "32")]
-    rows: u32,
+    /// Key of a [venue.<key>] profile in the config file supplying the
+    /// location, table count, Wi-Fi and emergency details
+    #[arg(long, value_name = "KEY")]
+    venue: Option<String>,
+
+    /// One-off notice printed large in a box across the top of the grid,
+    /// e.g. --banner "CLASS MOVED TO ROOM 204"
+    #[arg(long, value_name = "TEXT")]
+    banner: Option<String>,
+
+    /// Where the sheet title sits beside the QR code
+    #[arg(long, value_enum, default_value = "left")]
+    title_align: TextAlign,
+
+    /// Link to the club's feedback form; adds a "How was today's lesson?"
+    /// QR code and a comments box at the bottom of the sheet
+    #[arg(long, value_name = "URL")]
+    feedback_url: Option<String>,
+
+    /// Number of blank rows for students (default 32, or four per table at
+    /// the --venue)
+    #[arg(short, long)]
+    rows: Option<u32>,
 
     /// Disable mailing list signup section
     #[arg(long)]
@@ -98,47 +530,366 @@ struct Args {
     #[arg(long, default_value = "4")]
     mailing_rows: u32,
 
-    /// Output filename (defaults to attendance-{date}.pdf)
-    #[arg(short, long)]
+    /// Where the mailing list goes when the grid runs onto more pages: the
+    /// bottom of the first page, the bottom of the page the grid ends on, or
+    /// directly under the grid
+    #[arg(long, value_enum, default_value = "last-page")]
+    mailing_placement: MailingPlacement,
+
+    /// Sign-in rows for volunteers, caddies and greeters, kept separate from
+    /// the students (0-3, default none)
+    #[arg(long, default_value = "0", value_parser = clap::value_parser!(u32).range(0..=3))]
+    helper_rows: u32,
+
+    /// Tear-off "first lesson free" coupons along the bottom of the sheet,
+    /// each with the class QR code and a serial number (0-3, default none)
+    #[arg(long, default_value = "0", value_parser = clap::value_parser!(u32).range(0..=MAX_COUPONS as i64))]
+    coupons: u32,
+
+    /// Replace the roster checkbox with a wide "Signature" cell, for venues
+    /// that need signed attendance (roster mode)
+    #[arg(long)]
+    signature_column: bool,
+
+    /// Add a "Photo OK?" column where students circle Y or N, for classes
+    /// where the club posts photos (roster mode); `export transcription`
+    /// gives the answer a column
+    #[arg(long)]
+    photo_consent: bool,
+
+    /// Add a narrow "Time" column where the door volunteer notes when each
+    /// student checked in (roster mode); `export transcription` gives it a
+    /// column
+    #[arg(long)]
+    time_column: bool,
+
+    /// Print names first-last ("Anna van der Berg") in roster order, or
+    /// last-first ("van der Berg, Anna", "Smith, John, Jr.") sorted by family
+    /// name for alphabetical sign-in
+    #[arg(long, value_enum, default_value = "first-last")]
+    display_order: DisplayOrder,
+
+    /// Print each student's "nickname" from the roster in place of their
+    /// given name ("Bob Smith" for Robert Smith) on the sheet and passes;
+    /// exports and manifests keep the full name
+    #[arg(long)]
+    use_nicknames: bool,
+
+    /// Add a "Happy birthday this week" line under the roster for students
+    /// whose "birthday" falls in the class week
+    #[arg(long)]
+    announce_birthdays: bool,
+
+    /// Number each grid row and append pages of matching tear-off tickets
+    /// for the door prize draw
+    #[arg(long)]
+    raffle: bool,
+
+    /// Print a pre-assigned lucky number in each grid row for the door prize
+    /// draw, instead of separate raffle tickets; the numbers are recorded in
+    /// the manifest
+    #[arg(long, conflicts_with = "raffle")]
+    draw_numbers: bool,
+
+    /// Copies of the sheet to put in the document (1-20); with more than one
+    /// page, each sheet is stamped "Sheet N of M" with the event ID
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=20))]
+    copies: u32,
+
+    /// Print in pure black ink: gray rules become black and thicker, the
+    /// name highlighting and the logo are left off (for printers that drop
+    /// light gray)
+    #[arg(long)]
+    mono: bool,
+
+    /// Draft printing for clubs going through a lot of paper: a compact
+    /// header with a smaller QR code and no logo, thinner rules, and shorter
+    /// rows so more fit on a page
+    #[arg(long)]
+    economy: bool,
+
+    /// Printer resolution to rasterize QR codes for, in dots per inch; each
+    /// module gets a whole number of dots so the code stays sharp
+    #[arg(long, value_name = "DPI", default_value_t = DEFAULT_QR_DPI, value_parser = clap::value_parser!(u32).range(72..=2400))]
+    qr_dpi: u32,
+
+    /// Existing PDF (e.g. a lesson handout) to add after the attendance
+    /// pages; may be given more than once
+    #[arg(long, value_name = "PDF")]
+    append: Vec<PathBuf>,
+
+    /// Reorder and print the pages two to a landscape sheet, so the packet
+    /// (sheet, calendar, appended handouts) prints double-sided and folds
+    /// into a saddle-stitched booklet
+    #[arg(long)]
+    booklet: bool,
+
+    /// Series schedule (TOML) listing each session's date and topic
+    #[arg(long, value_name = "FILE")]
+    schedule: Option<PathBuf>,
+
+    /// Add a final page listing the sessions remaining in the series, from
+    /// --schedule
+    #[arg(long, requires = "schedule")]
+    calendar: bool,
+
+    /// Add a final page with a pre-class checklist (boards dealt, bidding
+    /// boxes, ...); the items can be set with `checklist` in the config file
+    #[arg(long)]
+    checklist: bool,
+
+    /// Add a page of numbered blank rows titled "Late arrivals / walk-ins",
+    /// with the same QR code, for classes that fill the grid's walk-in rows
+    #[arg(long)]
+    overflow_page: bool,
+
+    /// Add pages with this waiver text (blank lines separate paragraphs)
+    /// followed by name, signature and date lines for each roster student,
+    /// or blank lines without a roster
+    #[arg(long, value_name = "FILE")]
+    waiver: Option<PathBuf>,
+
+    /// Add pages of practice deals from this PBN file (or LIN, for a
+    /// `.lin` file), drawn as hand diagrams for students to take home
+    #[arg(long, value_name = "FILE")]
+    hands: Option<PathBuf>,
+
+    /// Add a page with the --venue's exits, AED location and contact numbers
+    /// in large type, for hosts that want one posted at every event
+    #[arg(long)]
+    emergency_page: bool,
+
+    /// What to generate
+    #[arg(long, value_enum, default_value = "sheet")]
+    mode: Mode,
+
+    /// Classes per pass in --mode pass, one punch circle each (1-12)
+    #[arg(long, default_value_t = DEFAULT_PUNCHES, value_parser = clap::value_parser!(u32).range(1..=12))]
+    punches: u32,
+
+    /// Blank passes to print in --mode pass when there is no roster
+    #[arg(long, default_value_t = DEFAULT_BLANK_PASSES)]
+    passes: u32,
+
+    /// Amount printed on each receipt in --mode receipt, e.g. "$15.00"
+    /// (left blank to fill in by hand when not given)
+    #[arg(long, value_name = "AMOUNT")]
+    amount: Option<String>,
+
+    /// Blank receipts to print in --mode receipt when there is no roster
+    #[arg(long, default_value_t = DEFAULT_BLANK_RECEIPTS)]
+    receipts: u32,
+
+    /// Print the table number on both faces of --mode table-card tents, the
+    /// upper one upside down so it reads from the far side once folded
+    #[arg(long)]
+    two_sided: bool,
+
+    /// With --mode progress, a checklist page for each student in place of
+    /// the class grid
+    #[arg(long)]
+    per_student: bool,
+
+    /// Lesson outline for --mode lesson-plan, in Markdown: "## Segment
+    /// (10 min)" headings, "Deals: 1, 2" lines and "-" key points
+    #[arg(long, value_name = "FILE")]
+    plan: Option<PathBuf>,
+
+    /// Boards in play for --mode travelers and scoring (default: the
+    /// --hands deals, or 8)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=MAX_BOARDS as i64))]
+    boards: Option<u32>,
+
+    /// Crop marks in the margins for cutting --mode pass and receipt pages
+    /// on a guillotine, in place of the printed cut lines
+    #[arg(long)]
+    crop_marks: bool,
+
+    /// Gap to leave on each side of every cut in --mode pass and receipt, in
+    /// mm (0-5), so a slightly-off cut does not clip the next piece
+    #[arg(long, value_name = "MM", default_value_t = 0.0)]
+    bleed: f32,
+
+    /// Output filename (overrides --name-template)
+    #[arg(short, long, conflicts_with = "name_template")]
     output: Option<String>,
 
-    /// Student roster file (JSON array of names, optional)
+    /// Directory to write output into (created if missing)
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Output filename template; placeholders: {date}, {class}, {event_id},
+    /// {teacher}, {location}, {ext}
+    #[arg(long, value_name = "TEMPLATE", default_value = DEFAULT_NAME_TEMPLATE)]
+    name_template: String,
+
+    /// Keep accented and non-Latin characters in generated file names instead
+    /// of transliterating them to ASCII
+    #[arg(long)]
+    unicode_filenames: bool,
+
+    /// Overwrite the output file if it already exists
+    #[arg(long)]
+    force: bool,
+
+    /// Also write a teacher's copy (<output>-teacher) with each student's
+    /// notes and needs; the sign-in copy then leaves both out
+    #[arg(long)]
+    teacher_copy: bool,
+
+    /// Also write a JSON manifest (<output>.manifest.json) recording the
+    /// event details and roster each sheet was generated from
+    #[arg(long)]
+    manifest: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "pdf")]
+    format: OutputFormat,
+
+    /// Send the generated PDF to a printer (the system default when no name
+    /// is given)
+    #[arg(long, value_name = "PRINTER", num_args = 0..=1, default_missing_value = "")]
+    print: Option<String>,
+
+    /// Open the generated file in the system default viewer
+    #[arg(long)]
+    open: bool,
+
+    /// Student roster file or http(s) URL (JSON array of {"name": ...}
+    /// entries, optional); photo paths in a fetched roster are relative to
+    /// its URL.
+    /// Entries may add a "nickname" printed with --use-nicknames, "photo"
+    /// (path or URL) for a headshot beside the name,
+    /// "expires" (YYYY-MM-DD) to flag memberships due for renewal,
+    /// "style": "bold" or "highlight": true to make a name stand out, a
+    /// "note" printed small under the name, "new": true for a NEW badge,
+    /// "junior": true to mark and count youth players, "confirmed": true
+    /// to print the box ticked for students who said they are coming,
+    /// "pronouns" printed small after the name, and
+    /// "birthday" (MM-DD) for a cake beside the name in the birthday week;
+    /// "email" and "phone" are kept for the club's records but not printed,
+    /// "acbl_number" fills in masterpoint award sheets, and "needs"
+    /// (dietary, accessibility) is printed only with --teacher-copy
     #[arg(long)]
     roster: Option<String>,
 
+    /// Read the roster as CSV or JSON whatever its name; by default a name
+    /// ending in ".csv" is read as CSV (a header row with "name", or "first
+    /// name" and "last name", and any of the fields above as columns)
+    #[arg(long, value_enum, value_name = "FORMAT", requires = "roster")]
+    roster_format: Option<RosterFormat>,
+
+    /// Build the roster from the Google Contacts with this label (e.g.
+    /// "Bridge Students"), fetched fresh on each run; needs GOOGLE_ACCESS_TOKEN
+    /// or a [google] client_id, client_secret and refresh_token in the config
+    #[arg(long, value_name = "LABEL", conflicts_with = "roster")]
+    google_label: Option<String>,
+
+    /// Build the roster from the attendees of this Eventbrite event, fetched
+    /// fresh on each run; needs EVENTBRITE_TOKEN or an [eventbrite] token in
+    /// the config
+    #[arg(long, value_name = "EVENT_ID", conflicts_with_all = ["roster", "google_label"])]
+    eventbrite: Option<String>,
+
+    /// Build the roster from a SignUpGenius report exported as CSV
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["roster", "google_label", "eventbrite"])]
+    signupgenius: Option<PathBuf>,
+
+    /// Last week's roster file: students not on it get the NEW badge, so
+    /// enrollment changes stand out on the sheet
+    #[arg(long, value_name = "OLD_ROSTER")]
+    since: Option<PathBuf>,
+
+    /// CSV of online RSVPs with a "name" column (or "first name" and "last
+    /// name") and optionally a "response" column; students who said yes
+    /// have their box printed ticked, and who has not replied is listed
+    #[arg(long, value_name = "FILE")]
+    rsvp: Option<PathBuf>,
+
+    /// List the registrants of this Zoom meeting in an "Online attendees"
+    /// section, for hybrid classes; needs ZOOM_ACCESS_TOKEN or a [zoom]
+    /// account_id, client_id and client_secret in the config
+    #[arg(long, value_name = "MEETING_ID")]
+    zoom: Option<String>,
+
     /// Logo image (file path or URL) to display in header top-right
     #[arg(long)]
     logo: Option<String>,
+
+    /// If the logo cannot be fetched or read, warn and print the sheet
+    /// without it rather than stopping
+    #[arg(long)]
+    logo_optional: bool,
+
+    /// Event ID to print and encode instead of a random one (8 hex characters)
+    #[arg(long)]
+    event_id: Option<String>,
+
+    /// Make output byte-identical across runs: fixes the embedded timestamps
+    /// and document IDs (requires --event-id, or an event_id for every entry
+    /// of --events)
+    #[arg(long)]
+    reproducible: bool,
+
+    /// JSON file listing several classes to render as consecutive sheets in
+    /// one document; each entry needs a "name" and may override "teacher",
+    /// "substitute", "date", "location", "event_id", "roster" and "rows"
+    #[arg(long, value_name = "FILE", conflicts_with = "event_id")]
+    events: Option<PathBuf>,
+
+    /// Benchmark: render N sheets with synthetic rosters (nothing is written)
+    /// and report throughput and per-phase timings
+    #[arg(long, value_name = "N")]
+    bench: Option<u32>,
 }
 
-/// Roster entry from JSON file
-#[derive(Debug, Deserialize)]
-struct RosterEntry {
-    name: String,
+/// Documents the tool can generate
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    /// Attendance sign-in sheet
+    Sheet,
+    /// Wallet-size punch cards for lesson packages, one per roster name
+    Pass,
+    /// Numbered fee receipts, three per page, one per roster name
+    Receipt,
+    /// Fold-in-half table number tents, one per table of four (from the
+    /// roster, --rows or the venue's tables)
+    TableCard,
+    /// Convention card progress checklist of the --schedule's topics: a grid
+    /// for the class, or a page per student with --per-student
+    Progress,
+    /// The teacher's outline from --plan, with diagrams of its deals when
+    /// --hands is given
+    LessonPlan,
+    /// Traveler slips for supervised play, one per board with a row per
+    /// table of the class
+    Travelers,
+    /// The director's results grid, a row per pair and a column per board
+    Scoring,
 }
 
-/// QR code data payload
-#[derive(Debug, Serialize)]
-struct QrPayload {
-    app: String,
-    event_id: String,
-    name: String,
-    date: String,
-    teacher: String,
+/// Output document formats
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    /// Printable PDF
+    Pdf,
+    /// Typst source (.typ) for hand-tweaking the layout
+    Typst,
+    /// Editable Word document (.docx)
+    Docx,
+    /// Excel workbook (.xlsx) with the roster pre-filled
+    Xlsx,
 }
 
-/// Attendance sheet configuration
-struct AttendanceConfig {
-    class_name: String,
-    teacher: String,
-    date: NaiveDate,
-    location: String,
-    event_id: String,
-    roster: Option<Vec<String>>,
-    blank_rows: u32,
-    mailing_list: bool,
-    mailing_rows: u32,
-    logo: Option<DynamicImage>,
+impl OutputFormat {
+    fn renderer(self) -> Box<dyn SheetRenderer> {
+        match self {
+            OutputFormat::Pdf => Box::new(PdfRenderer),
+            OutputFormat::Typst => Box::new(TypstRenderer),
+            OutputFormat::Docx => Box::new(DocxRenderer),
+            OutputFormat::Xlsx => Box::new(XlsxRenderer),
+        }
+    }
 }
 
 // ============================================================================
@@ -146,912 +897,1202 @@ struct AttendanceConfig {
 // ============================================================================
 
 fn main() {
-    if let Err(e) = run() {
+    if let Err(e) = run(Cli::parse()) {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
 }
 
-fn run() -> Result<(), AppError> {
-    let args = Args::parse();
+fn run(cli: Cli) -> Result<(), AppError> {
+    // Any command may fetch a roster or logo by URL, so the network rules
+    // are in place before it starts
+    net::set_offline(cli.offline);
+    net::configure(load_settings(cli.config())?.network);
+
+    match cli.command {
+        None => generate(cli.args),
+        Some(CliCommand::Generate(args)) => generate(*args),
+        Some(CliCommand::Preset(command)) => run_preset(command),
+        Some(CliCommand::Roster(command)) => run_roster(command),
+        Some(CliCommand::Scan { event_id, log }) => run_scan(&event_id, log),
+        Some(CliCommand::WalletPass(args)) => run_wallet_pass(args),
+        Some(CliCommand::Regenerate { manifest, logo, output, force }) => {
+            run_regenerate(&manifest, logo.as_deref(), output, force)
+        }
+        Some(CliCommand::Report(command)) => run_report(command),
+        Some(CliCommand::Export(command)) => run_export(command),
+        Some(CliCommand::Nfc { event_id, url, tlv, output, force }) => {
+            run_nfc(&event_id, &url, tlv, output.as_deref(), force)
+        }
+        Some(CliCommand::Init { dir, force }) => run_init(&dir, force),
+    }
+}
+
+fn generate(args: Args) -> Result<(), AppError> {
+    let args = match args.preset.as_deref().map(|name| load_preset(&presets_dir(), name)) {
+        Some(preset) => apply_preset(args, preset?),
+        None => args,
+    };
+    let settings = load_settings(args.config.as_deref())?;
+
+    let assets_start = Instant::now();
+    let mut assets = AssetCache::default();
+    let config = base_config(&args, &settings, &mut assets)?;
+    let assets_elapsed = assets_start.elapsed();
+
+    if let Some(count) = args.bench {
+        return run_bench(config, count, assets_elapsed);
+    }
+
+    check_output_options(&args)?;
+
+    let mut configs = event_configs(&args, config, &mut assets)?;
+
+    // Word and Excel tables simply grow, but the printed grid has to give
+    // somewhere when the names will not fit
+    if args.mode == Mode::Sheet && !matches!(args.format, OutputFormat::Docx | OutputFormat::Xlsx) {
+        for config in &configs {
+            if let Some(overflow) = roster_overflow(config) {
+                eprintln!("⚠ Warning: {}", overflow);
+            }
+        }
+    }
+
+    let renderer = args.format.renderer();
+    let output_path = output_path(&args, &configs[0], renderer.extension())?;
 
+    // Printing an event again over its manifest makes the next revision
+    let previous_manifest = if args.manifest {
+        read_manifest(&manifest_path(&output_path)).ok()
+    } else {
+        None
+    };
+    if args.manifest {
+        stamp_revisions(&mut configs, previous_manifest.as_ref());
+    }
+    // Private notes and needs go on the teacher's copy only
+    let mut teacher_configs: Vec<AttendanceConfig> = Vec::new();
+    if args.teacher_copy {
+        teacher_configs = configs.iter().map(teacher_copy).collect();
+        configs = configs.iter().map(sign_in_copy).collect();
+    }
+    let config = &configs[0];
+
+    write_documents(&args, &configs, &teacher_configs, renderer.as_ref(), &output_path)?;
+    for config in &configs {
+        println!("  Class: {}", config.class_name);
+        println!("  Date: {}", format_date_display(&config.date));
+        println!("  Event ID: {}", config.event_id);
+    }
+
+    if args.manifest {
+        write_manifest(&configs, &output_path, previous_manifest.as_ref(), args.force)?;
+    }
+
+    if let Some(printer) = &args.print {
+        let printer = Some(printer.as_str()).filter(|p| !p.is_empty());
+        let job_title = format!("Attendance: {} ({})", config.class_name, config.date.format("%Y-%m-%d"));
+        print_file(&output_path, printer, &job_title)?;
+        println!("✓ Sent to printer: {}", printer.unwrap_or("default"));
+    }
+
+    if args.open {
+        open_in_viewer(&output_path)?;
+    }
+
+    Ok(())
+}
+
+/// The sheet's settings from the command line, the config file and the
+/// roster, logo and other sources they name
+fn base_config(args: &Args, settings: &Settings, assets: &mut AssetCache) -> Result<AttendanceConfig, AppError> {
     // Parse date
     let date = parse_date(&args.date)?;
 
-    // Generate event ID
-    let event_id = generate_short_id();
+    // Use the provided event ID or generate one
+    let event_id = match &args.event_id {
+        Some(id) => parse_event_id(id)?,
+        None => generate_short_id(),
+    };
 
-    // Load roster if provided
-    let roster = load_roster(&args.roster)?;
+    // Load roster and logo if provided
+    let roster = load_generate_roster(args, settings, assets)?;
+    let venue = match &args.venue {
+        Some(key) => Some((key.as_str(), settings.venue(key)?)),
+        None => None,
+    };
+    let location = match venue {
+        Some((key, venue)) if args.location.is_empty() => venue.location(key),
+        _ => args.location.clone(),
+    };
+    let emergency_page = match venue {
+        _ if !args.emergency_page => None,
+        Some((key, venue)) => Some(venue.emergency_info(key).ok_or_else(|| {
+            AppError::ConfigError(format!(
+                "--emergency-page: [venue.{}] has no exits, aed, contacts or emergency",
+                key
+            ))
+        })?),
+        None => return Err(AppError::ConfigError("--emergency-page requires --venue".to_string())),
+    };
+    let blank_rows = args
+        .rows
+        .or_else(|| venue.and_then(|(_, venue)| venue.tables).map(|tables| tables * 4))
+        .unwrap_or(DEFAULT_BLANK_ROWS);
+
+    // A --teacher naming a profile brings that teacher's defaults
+    let teacher = args.teacher.clone().unwrap_or_else(|| DEFAULT_TEACHER.to_string());
+    let profile = settings.teacher(&teacher);
+    let logo = load_header_logo(args, settings, profile, assets)?;
+    let sponsor = settings.sponsor(assets)?;
+
+    let calendar = match &args.schedule {
+        Some(path) if args.calendar => Some(load_schedule(path)?),
+        _ => None,
+    };
+    let waiver = match &args.waiver {
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .map_err(|e| AppError::ConfigError(format!("--waiver {}: {}", path.display(), e)))?,
+        ),
+        None => None,
+    };
+    let hands = match &args.hands {
+        Some(path) => Some(load_deals(path)?),
+        None => None,
+    };
+    let online_attendees = match &args.zoom {
+        Some(meeting) => {
+            let names = fetch_zoom_registrants(meeting, settings.zoom.as_ref())?;
+            println!("✓ Zoom: {} registered to join online", names.len());
+            Some(names)
+        }
+        None => None,
+    };
 
-    // Load logo if provided
-    let logo = load_logo(&args.logo)?;
+    if args.reproducible && args.events.is_none() && args.event_id.is_none() {
+        return Err(AppError::ConfigError("--reproducible requires --event-id".to_string()));
+    }
 
     // Create config
-    let config = AttendanceConfig {
-        class_name: args.name,
-        teacher: args.teacher,
+    Ok(AttendanceConfig {
+        class_name: args.name.clone().unwrap_or_default(),
+        teacher: profile
+            .and_then(|profile| profile.name.clone())
+            .unwrap_or_else(|| teacher.clone()),
+        teacher_email: profile.and_then(|profile| profile.email.clone()),
+        substitute: args.substitute.clone(),
         date,
-        location: args.location,
+        location,
+        wifi: venue.and_then(|(_, venue)| venue.wifi.clone()),
+        emergency: venue.and_then(|(_, venue)| venue.emergency.clone()),
+        banner: args.banner.clone(),
+        feedback_url: args.feedback_url.clone(),
+        emergency_page,
         event_id,
         roster,
-        blank_rows: args.rows,
+        online_attendees,
+        blank_rows,
         mailing_list: !args.no_mailing_list,
         mailing_rows: args.mailing_rows,
+        mailing_placement: args.mailing_placement,
+        mailing_title: profile.and_then(|profile| profile.mailing_title.clone()),
+        title_align: args.title_align,
+        helper_rows: args.helper_rows,
+        coupons: args.coupons,
+        signature_column: args.signature_column,
+        photo_consent: args.photo_consent,
+        time_column: args.time_column,
+        raffle: args.raffle,
+        draw_numbers: args.draw_numbers,
+        announce_birthdays: args.announce_birthdays,
+        display_order: args.display_order,
+        use_nicknames: args.use_nicknames,
+        markers: settings.markers.clone(),
+        calendar,
+        waiver,
+        checklist: args.checklist.then(|| {
+            settings
+                .checklist
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CHECKLIST.iter().map(|item| item.to_string()).collect())
+        }),
+        hands,
+        overflow_page: args.overflow_page,
+        copies: args.copies,
+        mono: args.mono,
+        economy: args.economy,
+        qr_dpi: args.qr_dpi,
+        qr_codes: assets.qr_codes(),
         logo,
-    };
-
-    // Determine output filename
-    let output_file = args.output.unwrap_or_else(|| {
-        let sanitized_name = config
-            .class_name
-            .to_lowercase()
-            .replace(' ', "-")
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-')
-            .collect::<String>();
-        format!("attendance-{}-{}.pdf", config.date.format("%Y-%m-%d"), sanitized_name)
-    });
-
-    // Generate PDF
-    generate_pdf(&config, &output_file)?;
-
-    println!("✓ Generated: {}", output_file);
-    println!("  Class: {}", config.class_name);
-    println!("  Date: {}", format_date_display(&config.date));
-    println!("  Event ID: {}", config.event_id);
-
-    Ok(())
+        sponsor,
+        reproducible: args.reproducible,
+        revision: None,
+        teacher_copy: false,
+    })
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
-
-fn parse_date(date_str: &Option<String>) -> Result<NaiveDate, AppError> {
-    match date_str {
-        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
-            .map_err(|_| AppError::DateError(s.clone())),
-        None => Ok(Local::now().date_naive()),
+/// Each event in an events file becomes its own sheet, sharing the logo;
+/// without one, `config` is the only sheet
+fn event_configs(
+    args: &Args,
+    config: AttendanceConfig,
+    assets: &mut AssetCache,
+) -> Result<Vec<AttendanceConfig>, AppError> {
+    match &args.events {
+        Some(path) => {
+            if matches!(args.format, OutputFormat::Docx | OutputFormat::Xlsx) {
+                return Err(AppError::ConfigError(
+                    "--events supports --format pdf and typst".to_string(),
+                ));
+            }
+            let base_dir = path.parent().unwrap_or(Path::new(""));
+            load_events(path)?
+                .iter()
+                .map(|event| event.to_config(&config, base_dir, assets))
+                .collect()
+        }
+        None => Ok(vec![config]),
     }
 }
 
-fn generate_short_id() -> String {
-    let uuid = Uuid::new_v4();
-    let hex = format!("{:x}", uuid);
-    hex[..8].to_uppercase()
+/// The roster from whichever of --roster, --google-label, --eventbrite or
+/// --signupgenius was given, with --since and --rsvp applied and in display
+/// order
+fn load_generate_roster(
+    args: &Args,
+    settings: &Settings,
+    assets: &mut AssetCache,
+) -> Result<Option<Vec<Student>>, AppError> {
+    let entries = if let Some(label) = &args.google_label {
+        let entries = fetch_label_roster(label, settings.google.as_ref())?;
+        Some((entries, settings.base_dir.clone()))
+    } else if let Some(event) = &args.eventbrite {
+        let entries = fetch_eventbrite_roster(event, settings.eventbrite.as_ref())?;
+        Some((entries, settings.base_dir.clone()))
+    } else if let Some(path) = &args.signupgenius {
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Some((read_signupgenius_roster(path)?, base_dir))
+    } else if let Some(source) = &args.roster {
+        Some((read_roster_source(source, args.roster_format)?, source_dir(source)))
+    } else {
+        None
+    };
+    if entries.is_none() && args.rsvp.is_some() {
+        return Err(AppError::ConfigError(format!("--rsvp needs a roster: {}", ROSTER_SOURCES)));
+    }
+    let mut roster = match (entries, &args.since) {
+        (Some((mut entries, base_dir)), since) => {
+            if let Some(since) = since {
+                let added = diff_rosters(&read_roster_entries(since)?, &entries).added;
+                for entry in entries.iter_mut().filter(|entry| added.contains(&entry.name)) {
+                    entry.new = true;
+                }
+            }
+            if let Some(path) = &args.rsvp {
+                report_rsvps(&mark_confirmed(&mut entries, &load_rsvps(path)?));
+            }
+            Some(roster_students(entries, &base_dir, assets)?)
+        }
+        (None, Some(_)) => {
+            return Err(AppError::ConfigError(format!("--since needs a roster: {}", ROSTER_SOURCES)))
+        }
+        (None, None) => None,
+    };
+    if let Some(roster) = &mut roster {
+        sort_roster(roster, args.display_order);
+    }
+    Ok(roster)
 }
 
-fn format_date_display(date: &NaiveDate) -> String {
-    date.format("%A, %B %-d, %Y").to_string()
+/// The --logo, or else the teacher profile's; an unreadable one is skipped
+/// with a warning under --logo-optional
+fn load_header_logo(
+    args: &Args,
+    settings: &Settings,
+    profile: Option<&TeacherProfile>,
+    assets: &mut AssetCache,
+) -> Result<Option<Arc<PreparedImage>>, AppError> {
+    let logo_source = match (&args.logo, profile.and_then(|profile| profile.logo.as_ref())) {
+        (Some(source), _) => Some(source.clone()),
+        (None, Some(source)) => Some(settings.resolve_source(source)),
+        (None, None) => None,
+    };
+    match &logo_source {
+        Some(source) => match assets.logo(source) {
+            Ok(logo) => Ok(Some(logo)),
+            Err(e) if args.logo_optional => {
+                eprintln!("⚠ Warning: {}; printing without the logo", e);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        },
+        None => Ok(None),
+    }
 }
 
-fn load_roster(path: &Option<String>) -> Result<Option<Vec<String>>, AppError> {
-    match path {
-        Some(p) => {
-            let content = std::fs::read_to_string(p)
-                .map_err(|e| AppError::RosterError(format!("{}: {}", p, e)))?;
-            let entries: Vec<RosterEntry> = serde_json::from_str(&content)
-                .map_err(|e| AppError::RosterError(format!("Invalid JSON: {}", e)))?;
-            Ok(Some(entries.into_iter().map(|e| e.name).collect()))
-        }
-        None => Ok(None),
+/// Reject output options that cannot go together before anything is written
+fn check_output_options(args: &Args) -> Result<(), AppError> {
+    if args.print.is_some() && !matches!(args.format, OutputFormat::Pdf) {
+        return Err(AppError::ConfigError("--print requires --format pdf".to_string()));
+    }
+    if !args.append.is_empty() && !matches!(args.format, OutputFormat::Pdf) {
+        return Err(AppError::ConfigError("--append requires --format pdf".to_string()));
     }
+    if args.booklet && !matches!(args.format, OutputFormat::Pdf) {
+        return Err(AppError::ConfigError("--booklet requires --format pdf".to_string()));
+    }
+    if args.teacher_copy && args.mode != Mode::Sheet {
+        return Err(AppError::ConfigError("--teacher-copy requires --mode sheet".to_string()));
+    }
+
+    if args.mode != Mode::Sheet
+        && (args.events.is_some() || matches!(args.format, OutputFormat::Docx | OutputFormat::Xlsx))
+    {
+        return Err(AppError::ConfigError(format!(
+            "--mode {} supports --format pdf and typst, without --events",
+            args.mode.to_possible_value().expect("no skipped modes").get_name()
+        )));
+    }
+    if !(0.0..=MAX_BLEED_MM).contains(&args.bleed) {
+        return Err(AppError::ConfigError(format!("--bleed must be 0 to {} mm", MAX_BLEED_MM)));
+    }
+    Ok(())
 }
 
-fn load_logo(path: &Option<String>) -> Result<Option<DynamicImage>, AppError> {
-    match path {
-        Some(p) => {
-            let image_bytes = if p.starts_with("http://") || p.starts_with("https://") {
-                // Load from URL
-                let response = ureq::get(p)
-                    .call()
-                    .map_err(|e| AppError::LogoError(format!("Failed to fetch URL: {}", e)))?;
-
-                let mut bytes = Vec::new();
-                response.into_reader()
-                    .read_to_end(&mut bytes)
-                    .map_err(|e| AppError::LogoError(format!("Failed to read response: {}", e)))?;
-                bytes
-            } else {
-                // Load from file
-                std::fs::read(p)
-                    .map_err(|e| AppError::LogoError(format!("{}: {}", p, e)))?
-            };
+/// Where the document goes: --output, or the name template (each mode has
+/// its own default) expanded for `config`, under --output-dir
+fn output_path(args: &Args, config: &AttendanceConfig, extension: &str) -> Result<PathBuf, AppError> {
+    let name_template = match args.mode {
+        Mode::Pass if args.name_template == DEFAULT_NAME_TEMPLATE => PASS_NAME_TEMPLATE,
+        Mode::Receipt if args.name_template == DEFAULT_NAME_TEMPLATE => RECEIPT_NAME_TEMPLATE,
+        Mode::TableCard if args.name_template == DEFAULT_NAME_TEMPLATE => TENT_NAME_TEMPLATE,
+        Mode::Progress if args.name_template == DEFAULT_NAME_TEMPLATE => PROGRESS_NAME_TEMPLATE,
+        Mode::LessonPlan if args.name_template == DEFAULT_NAME_TEMPLATE => LESSON_PLAN_NAME_TEMPLATE,
+        Mode::Travelers if args.name_template == DEFAULT_NAME_TEMPLATE => TRAVELERS_NAME_TEMPLATE,
+        Mode::Scoring if args.name_template == DEFAULT_NAME_TEMPLATE => SCORING_NAME_TEMPLATE,
+        _ => args.name_template.as_str(),
+    };
+    let file_name = match &args.output {
+        Some(output) => output.clone(),
+        None => expand_name_template(name_template, config, extension, args.unicode_filenames)?,
+    };
+    resolve_output_path(args.output_dir.as_deref(), &file_name)
+}
 
-            let img = ::image::load_from_memory(&image_bytes)
-                .map_err(|e| AppError::LogoError(format!("Failed to decode image: {}", e)))?;
+/// Render the document for --mode and write it to `output_path`, with the
+/// teacher's copy of the sheets beside it when there is one
+fn write_documents(
+    args: &Args,
+    configs: &[AttendanceConfig],
+    teacher_configs: &[AttendanceConfig],
+    renderer: &dyn SheetRenderer,
+    output_path: &Path,
+) -> Result<(), AppError> {
+    // A merged PDF of several events goes to disk one event at a time,
+    // unless the whole document is needed to append to or impose
+    let streamed = args.mode == Mode::Sheet
+        && matches!(args.format, OutputFormat::Pdf)
+        && configs.len() > 1
+        && args.append.is_empty()
+        && !args.booklet;
+    if streamed {
+        stream_sheets(output_path, configs, args.force)?;
+    } else {
+        let bytes = render_document(args, configs, renderer)?;
+        write_output(output_path, &bytes, args.force)?;
+    }
 
-            Ok(Some(img))
+    println!("✓ Generated: {}", output_path.display());
+    if !teacher_configs.is_empty() {
+        let path = teacher_copy_path(output_path);
+        if streamed {
+            stream_sheets(&path, teacher_configs, args.force)?;
+        } else {
+            let bytes = render_events_with(renderer, teacher_configs, &mut PhaseTimings::default())?;
+            write_output(&path, &bytes, args.force)?;
         }
-        None => Ok(None),
+        println!("✓ Teacher copy: {}", path.display());
     }
+    Ok(())
 }
 
-// ============================================================================
-// QR Code Generation
-// ============================================================================
+/// Write the sheets of `configs` to `path` as one PDF, an event at a time
+fn stream_sheets(path: &Path, configs: &[AttendanceConfig], force: bool) -> Result<(), AppError> {
+    let out = BufWriter::new(create_output(path, force)?);
+    stream_events_pdf(configs, out, &mut PhaseTimings::default())?;
+    Ok(())
+}
 
-fn generate_qr_image(config: &AttendanceConfig) -> Result<DynamicImage, AppError> {
-    let payload = QrPayload {
-        app: "bridge-attendance".to_string(),
-        event_id: config.event_id.clone(),
-        name: config.class_name.clone(),
-        date: config.date.format("%Y-%m-%d").to_string(),
-        teacher: config.teacher.clone(),
+/// The document for --mode as bytes, with any --append pages and --booklet
+/// imposition applied
+fn render_document(
+    args: &Args,
+    configs: &[AttendanceConfig],
+    renderer: &dyn SheetRenderer,
+) -> Result<Vec<u8>, AppError> {
+    let config = &configs[0];
+    let trim = Trim {
+        crop_marks: args.crop_marks,
+        bleed_mm: args.bleed,
+    };
+    let mut bytes = match args.mode {
+        Mode::Sheet => render_events_with(renderer, configs, &mut PhaseTimings::default())?,
+        Mode::Pass => {
+            let options = PassOptions {
+                punches: args.punches,
+                blank_passes: args.passes,
+                trim,
+            };
+            renderer.render(&layout_passes(config, &options)?, config)?
+        }
+        Mode::Receipt => {
+            let options = ReceiptOptions {
+                amount: args.amount.clone(),
+                blank_receipts: args.receipts,
+                trim,
+            };
+            renderer.render(&layout_receipts(config, &options)?, config)?
+        }
+        Mode::TableCard => {
+            let options = TentOptions {
+                two_sided: args.two_sided,
+                ..TentOptions::for_config(config)
+            };
+            renderer.render(&layout_table_tents(config, &options), config)?
+        }
+        Mode::Progress => {
+            let schedule = match &args.schedule {
+                Some(path) => load_schedule(path)?,
+                None => return Err(AppError::ConfigError("--mode progress needs --schedule".to_string())),
+            };
+            let options = ProgressOptions {
+                topics: schedule.checklist_topics(),
+                per_student: args.per_student,
+            };
+            if options.topics.is_empty() {
+                return Err(AppError::ScheduleError("the schedule lists no topics".to_string()));
+            }
+            renderer.render(&layout_progress(config, &options), config)?
+        }
+        Mode::LessonPlan => {
+            let plan = match &args.plan {
+                Some(path) => load_lesson_plan(path)?,
+                None => return Err(AppError::ConfigError("--mode lesson-plan needs --plan".to_string())),
+            };
+            renderer.render(&layout_lesson_plan(config, &plan, config.hands.as_deref())?, config)?
+        }
+        Mode::Travelers => {
+            let options = TravelerOptions::for_config(config, boards_in_play(args.boards, config));
+            renderer.render(&layout_travelers(config, &options), config)?
+        }
+        Mode::Scoring => {
+            let options = ScoringOptions::for_config(config, boards_in_play(args.boards, config));
+            renderer.render(&layout_scoring(config, &options), config)?
+        }
     };
+    if !args.append.is_empty() {
+        let appendices = args
+            .append
+            .iter()
+            .map(|path| {
+                std::fs::read(path)
+                    .map_err(|e| AppError::PdfError(format!("{}: {}", path.display(), e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        bytes = append_pdfs(&bytes, &appendices)?;
+    }
+    if args.booklet {
+        bytes = impose_booklet(&bytes)?;
+    }
+    Ok(bytes)
+}
 
-    let json = serde_json::to_string(&payload)
-        .map_err(|e| AppError::QrError(e.to_string()))?;
+/// Number each event's printing one past its latest in `previous`
+fn stamp_revisions(configs: &mut [AttendanceConfig], previous: Option<&Manifest>) {
+    let generated = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    for config in configs {
+        let earlier = previous.and_then(|manifest| manifest.event(&config.event_id));
+        config.revision = Some(Revision {
+            number: earlier.map_or(1, |event| event.revision() + 1),
+            generated: generated.clone(),
+        });
+    }
+}
 
-    let code = QrCode::new(json.as_bytes())
-        .map_err(|e| AppError::QrError(e.to_string()))?;
+/// Record the events printed in the manifest beside the document, keeping
+/// the revision history of an earlier printing
+fn write_manifest(
+    configs: &[AttendanceConfig],
+    output_path: &Path,
+    previous: Option<&Manifest>,
+    force: bool,
+) -> Result<(), AppError> {
+    let path = manifest_path(output_path);
+    let mut manifest = Manifest::for_events(configs, output_path);
+    if let Some(previous) = previous {
+        manifest.keep_revisions(previous);
+    }
+    write_output(&path, manifest.to_json()?.as_bytes(), force)?;
+    println!("✓ Manifest: {}", path.display());
+    for revision in configs.iter().filter_map(|config| config.revision.as_ref()) {
+        if revision.number > 1 {
+            println!("  {}", revision.stamp());
+        }
+    }
+    Ok(())
+}
 
-    let image = code.render::<Luma<u8>>().build();
-    Ok(DynamicImage::ImageLuma8(image))
+/// --boards, or one board per --hands deal
+fn boards_in_play(boards: Option<u32>, config: &AttendanceConfig) -> u32 {
+    boards
+        .or_else(|| config.hands.as_ref().map(|deals| deals.len() as u32))
+        .unwrap_or(DEFAULT_BOARDS)
 }
 
 // ============================================================================
-// PDF Generation
+// Roster Files
 // ============================================================================
 
-fn generate_pdf(config: &AttendanceConfig, output_path: &str) -> Result<(), AppError> {
-    // Create document
-    let (doc, page1, layer1) = PdfDocument::new(
-        "Attendance Sheet",
-        Mm(PAGE_WIDTH_MM),
-        Mm(PAGE_HEIGHT_MM),
-        "Layer 1",
-    );
-
-    let mut current_layer = doc.get_page(page1).get_layer(layer1);
-
-    // Load built-in fonts
-    let font_regular = doc.add_builtin_font(BuiltinFont::Helvetica)
-        .map_err(|e| AppError::PdfError(e.to_string()))?;
-    let font_bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)
-        .map_err(|e| AppError::PdfError(e.to_string()))?;
-
-    // Calculate layout
-    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
-    let mut y_pos = PAGE_HEIGHT_MM - MARGIN_MM;
-
-    // Draw header section (QR code + title + info)
-    y_pos = draw_header_section(
-        &doc,
-        &current_layer,
-        &font_regular,
-        &font_bold,
-        config,
-        y_pos,
-        content_width,
-    )?;
-
-    // Calculate available space for attendance grid and mailing list
-    let mailing_height = if config.mailing_list {
-        calculate_mailing_section_height(config.mailing_rows)
-    } else {
-        0.0
-    };
-
-    // For blank mode, use fixed row height and support multiple pages
-    if config.roster.is_none() {
-        let row_height = TABLE_SEAT_ROW_HEIGHT_MM;
-        let first_page_available = y_pos - MARGIN_MM - mailing_height - 5.0;
-        let continuation_page_available = PAGE_HEIGHT_MM - 2.0 * MARGIN_MM;
-
-        let seats = ["North", "South", "East", "West"];
-        let num_tables = (config.blank_rows + 3) / 4;
-        let table_height = row_height * 4.0; // Height needed for one complete table
-
-        let mut space_remaining = first_page_available;
-
-        for table in 1..=num_tables {
-            // Check if we need a new page before starting this table
-            // Keep tables together - don't split across pages
-            if space_remaining < table_height {
-                let (new_page, new_layer) = doc.add_page(
-                    Mm(PAGE_WIDTH_MM),
-                    Mm(PAGE_HEIGHT_MM),
-                    "Layer 1",
-                );
-                current_layer = doc.get_page(new_page).get_layer(new_layer);
-                y_pos = PAGE_HEIGHT_MM - MARGIN_MM;
-                space_remaining = continuation_page_available;
+fn run_roster(command: RosterCommand) -> Result<(), AppError> {
+    match command {
+        RosterCommand::Import { file, group, output, force } => {
+            let entries = import_vcard(&file, group.as_deref())?;
+            let json = roster_json(&entries)?;
+            match output {
+                Some(path) => {
+                    write_output(&path, json.as_bytes(), force)?;
+                    println!("✓ Imported {} students: {}", entries.len(), path.display());
+                }
+                None => print!("{}", json),
             }
-
-            // Draw all 4 seats for this table
-            for (seat_idx, seat) in seats.iter().enumerate() {
-                let current_row = (table - 1) * 4 + seat_idx as u32;
-                if current_row >= config.blank_rows {
-                    break;
+        }
+        RosterCommand::Export { file, format, visibility, output, force } => {
+            let entries: Vec<_> = canonical_roster(read_roster_entries(&file)?)
+                .iter()
+                .map(|entry| entry.redacted(visibility))
+                .collect();
+            let content = match format {
+                RosterFormat::Csv => roster_csv(&entries, visibility),
+                RosterFormat::Json => roster_json(&entries)?,
+            };
+            match output {
+                Some(path) => {
+                    write_output(&path, content.as_bytes(), force)?;
+                    println!("✓ Exported {} students: {}", entries.len(), path.display());
                 }
-
-                let is_first_seat = seat_idx == 0;
-                let is_last_seat = seat_idx == 3;
-                draw_table_seat_row(
-                    &current_layer,
-                    &font_regular,
-                    y_pos,
-                    MARGIN_MM,
-                    content_width,
-                    row_height,
-                    table,
-                    seat,
-                    is_first_seat,
-                    is_last_seat,
+                None => print!("{}", content),
+            }
+        }
+        RosterCommand::Diff { old, new } => {
+            let diff = diff_rosters(&read_roster_entries(&old)?, &read_roster_entries(&new)?);
+            for name in &diff.added {
+                println!("+ {}", name);
+            }
+            for name in &diff.removed {
+                println!("- {}", name);
+            }
+            for (old_name, new_name) in &diff.renamed {
+                println!("~ {} → {}", old_name, new_name);
+            }
+            if diff.is_empty() {
+                println!("✓ No changes");
+            } else {
+                println!(
+                    "✓ {} added, {} removed, {} renamed",
+                    diff.added.len(),
+                    diff.removed.len(),
+                    diff.renamed.len()
                 );
-                y_pos -= row_height;
-                space_remaining -= row_height;
             }
         }
-    } else {
-        // Roster mode - single page with adaptive row height
-        let grid_available_height = y_pos - MARGIN_MM - mailing_height - 5.0;
-        let _ = draw_attendance_grid(
-            &current_layer,
-            &font_regular,
-            &font_bold,
-            config,
-            y_pos,
-            content_width,
-            grid_available_height,
-        )?;
     }
-
-    // Draw mailing list section if enabled (always on first page)
-    if config.mailing_list {
-        let has_starred = config.roster.as_ref()
-            .map(|r| r.iter().any(|name| name.contains('*')))
-            .unwrap_or(false);
-        let first_layer = doc.get_page(page1).get_layer(layer1);
-        draw_mailing_section(
-            &first_layer,
-            &font_regular,
-            &font_bold,
-            config.mailing_rows,
-            MARGIN_MM,
-            content_width,
-            has_starred,
-        )?;
-    }
-
-    // Save PDF
-    let file = File::create(output_path)?;
-    let mut writer = BufWriter::new(file);
-    doc.save(&mut writer)
-        .map_err(|e| AppError::PdfError(e.to_string()))?;
-
     Ok(())
 }
 
 // ============================================================================
-// Header Section
+// Scan
 // ============================================================================
 
-fn draw_header_section(
-    doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-    config: &AttendanceConfig,
-    start_y: f32,
-    content_width: f32,
-) -> Result<f32, AppError> {
-    let y_pos = start_y;
-
-    // Generate and embed QR code
-    let qr_image = generate_qr_image(config)?;
-    embed_qr_code(doc, layer, &qr_image, MARGIN_MM, y_pos - QR_SIZE_MM)?;
-
-    // Title and info to the right of QR code
-    let text_x = MARGIN_MM + QR_SIZE_MM + 8.0;
-    let _text_width = content_width - QR_SIZE_MM - 8.0;
-
-    // Title
-    layer.use_text(
-        "CLASS ATTENDANCE",
-        TITLE_FONT_SIZE,
-        Mm(text_x),
-        Mm(y_pos - 6.0),
-        font_bold,
-    );
-
-    // Class name
-    layer.use_text(
-        &config.class_name,
-        HEADER_FONT_SIZE,
-        Mm(text_x),
-        Mm(y_pos - 14.0),
-        font_bold,
-    );
-
-    // Date
-    layer.use_text(
-        &format_date_display(&config.date),
-        NORMAL_FONT_SIZE,
-        Mm(text_x),
-        Mm(y_pos - 20.0),
-        font_regular,
-    );
-
-    // Teacher
-    layer.use_text(
-        &format!("Instructor: {}", config.teacher),
-        NORMAL_FONT_SIZE,
-        Mm(text_x),
-        Mm(y_pos - 26.0),
-        font_regular,
-    );
-
-    // Location (if provided)
-    let mut info_y = y_pos - 26.0;
-    if !config.location.is_empty() {
-        info_y -= 5.0;
-        layer.use_text(
-            &format!("Location: {}", config.location),
-            NORMAL_FONT_SIZE,
-            Mm(text_x),
-            Mm(info_y),
-            font_regular,
-        );
-    }
-
-    // Logo in top-right (if provided)
-    let logo_max_width = 50.0;
-    let logo_max_height = QR_SIZE_MM;
-    let right_edge = MARGIN_MM + content_width;
-
-    if let Some(ref logo) = config.logo {
-        embed_logo(
-            layer,
-            logo,
-            logo_max_width,
-            logo_max_height,
-            right_edge,
-            y_pos,
-        )?;
+/// Read codes until end of input, ringing the terminal bell for each new
+/// check-in. A bad code is reported and scanning carries on.
+fn run_scan(event_id: &str, log: Option<PathBuf>) -> Result<(), AppError> {
+    let event_id = parse_event_id(event_id)?;
+    let log = log.unwrap_or_else(|| default_log_path(&event_id));
+    println!("Scanning passes for event {} (Ctrl-D to finish)", event_id);
+
+    let mut checked_in = 0;
+    for line in std::io::stdin().lines() {
+        let code = line?;
+        if code.trim().is_empty() {
+            continue;
+        }
+        let time = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+        match record_scan(&log, &event_id, &code, &time) {
+            Ok(ScanOutcome::CheckedIn(checkin)) => {
+                checked_in += 1;
+                println!("\u{7}✓ Checked in {}", checkin.label());
+            }
+            Ok(ScanOutcome::AlreadyCheckedIn(checkin)) => {
+                println!("  {} is already checked in", checkin.label());
+            }
+            Err(e) => eprintln!("✗ {}", e),
+        }
     }
-
-    // Event ID (right-aligned, below logo area)
-    let event_id_text = format!("ID: {}", config.event_id);
-    let right_x = MARGIN_MM + content_width - 25.0;
-    layer.use_text(
-        &event_id_text,
-        SMALL_FONT_SIZE,
-        Mm(right_x),
-        Mm(y_pos - QR_SIZE_MM - 2.0),
-        font_regular,
-    );
-
-    // Return Y position after header (below QR code with some spacing)
-    Ok(y_pos - QR_SIZE_MM - 8.0)
-}
-
-fn embed_qr_code(
-    _doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
-    qr_image: &DynamicImage,
-    x: f32,
-    y: f32,
-) -> Result<(), AppError> {
-    let rgb_image = qr_image.to_rgb8();
-    let (width, height) = rgb_image.dimensions();
-
-    // Convert to raw RGB bytes
-    let raw_pixels = rgb_image.into_raw();
-
-    // Create image for printpdf
-    let image = Image::from(ImageXObject {
-        width: Px(width as usize),
-        height: Px(height as usize),
-        color_space: ColorSpace::Rgb,
-        bits_per_component: ColorBits::Bit8,
-        interpolate: false,
-        image_data: raw_pixels,
-        image_filter: None,
-        clipping_bbox: None,
-        smask: None,
-    });
-
-    // Calculate DPI to achieve desired physical size
-    // QR_SIZE_MM is the desired size, image dimensions are in pixels
-    // DPI = pixels / (mm / 25.4)
-    let dpi = (width as f32) / (QR_SIZE_MM / 25.4);
-
-    image.add_to_layer(
-        layer.clone(),
-        ImageTransform {
-            translate_x: Some(Mm(x)),
-            translate_y: Some(Mm(y)),
-            dpi: Some(dpi),
-            ..Default::default()
-        },
-    );
-
+    println!("✓ {} checked in: {}", checked_in, log.display());
     Ok(())
 }
 
-fn embed_logo(
-    layer: &PdfLayerReference,
-    logo_image: &DynamicImage,
-    max_width_mm: f32,
-    max_height_mm: f32,
-    right_edge_x: f32,
-    top_y: f32,
-) -> Result<(), AppError> {
-    // Convert to RGBA first to handle transparency
-    let rgba_image = logo_image.to_rgba8();
-    let (width_px, height_px) = rgba_image.dimensions();
-
-    // Composite against white background
-    let mut rgb_image = RgbImage::new(width_px, height_px);
-    for (x, y, pixel) in rgba_image.enumerate_pixels() {
-        let Rgba([r, g, b, a]) = *pixel;
-        let alpha = a as f32 / 255.0;
-        let bg = 255.0; // White background
-        let out_r = (r as f32 * alpha + bg * (1.0 - alpha)) as u8;
-        let out_g = (g as f32 * alpha + bg * (1.0 - alpha)) as u8;
-        let out_b = (b as f32 * alpha + bg * (1.0 - alpha)) as u8;
-        rgb_image.put_pixel(x, y, ::image::Rgb([out_r, out_g, out_b]));
+// ============================================================================
+// Wallet Passes
+// ============================================================================
+
+fn run_wallet_pass(args: WalletArgs) -> Result<(), AppError> {
+    let settings = load_settings(args.config.as_deref())?;
+    let wallet = settings.wallet.as_ref();
+    let apple = wallet.and_then(|wallet| wallet.apple.as_ref());
+    let google = wallet.and_then(|wallet| wallet.google.as_ref());
+    if apple.is_none() && google.is_none() {
+        return Err(AppError::WalletError(
+            "add [wallet.apple] and/or [wallet.google] to the config file".to_string(),
+        ));
     }
 
-    // Calculate dimensions preserving aspect ratio
-    let aspect_ratio = width_px as f32 / height_px as f32;
-    let (final_width_mm, final_height_mm) = if max_width_mm / max_height_mm > aspect_ratio {
-        // Height-constrained
-        (max_height_mm * aspect_ratio, max_height_mm)
-    } else {
-        // Width-constrained
-        (max_width_mm, max_width_mm / aspect_ratio)
+    let event_id = match &args.event_id {
+        Some(id) => parse_event_id(id)?,
+        None => generate_short_id(),
     };
-
-    // Calculate position (right-aligned, top-aligned)
-    let x = right_edge_x - final_width_mm;
-    let y = top_y - final_height_mm;
-
-    // Convert to raw RGB bytes
-    let raw_pixels = rgb_image.into_raw();
-
-    // Create image for printpdf
-    let image = Image::from(ImageXObject {
-        width: Px(width_px as usize),
-        height: Px(height_px as usize),
-        color_space: ColorSpace::Rgb,
-        bits_per_component: ColorBits::Bit8,
-        interpolate: true,
-        image_data: raw_pixels,
-        image_filter: None,
-        clipping_bbox: None,
-        smask: None,
-    });
-
-    // Calculate DPI to achieve desired physical size
-    let dpi = (width_px as f32) / (final_width_mm / 25.4);
-
-    image.add_to_layer(
-        layer.clone(),
-        ImageTransform {
-            translate_x: Some(Mm(x)),
-            translate_y: Some(Mm(y)),
-            dpi: Some(dpi),
-            ..Default::default()
-        },
+    let teacher = args.teacher.unwrap_or_else(|| DEFAULT_TEACHER.to_string());
+    let teacher = settings
+        .teacher(&teacher)
+        .and_then(|profile| profile.name.clone())
+        .unwrap_or(teacher);
+    let class = WalletClass {
+        event_id: &event_id,
+        class_name: &args.name,
+        teacher: &teacher,
+        organization: wallet.and_then(|wallet| wallet.organization.as_deref()).unwrap_or(&teacher),
+    };
+    let logo = args.logo.as_deref().map(load_logo).transpose()?;
+    let students = load_roster(&Some(args.roster.clone()), None, &mut AssetCache::default())?.unwrap_or_default();
+
+    std::fs::create_dir_all(&args.output_dir)?;
+    for (i, student) in students.iter().enumerate() {
+        let name = match &student.nickname {
+            Some(nickname) if args.use_nicknames => with_nickname(&student.name, nickname),
+            _ => student.name.clone(),
+        };
+        let pass = WalletPass {
+            class: &class,
+            serial: serial_number(&event_id, i as u32),
+            student: name.replace('*', "").trim().to_string(),
+        };
+        let stem = args.output_dir.join(slugify(&pass.student, false));
+        if let Some(apple) = apple {
+            let bundle = pass.pkpass(apple, &settings.base_dir, logo.as_ref())?;
+            write_output(&stem.with_extension("pkpass"), &bundle, args.force)?;
+        }
+        if let Some(google) = google {
+            let json = serde_json::to_vec_pretty(&pass.google_object(google))
+                .map_err(|e| AppError::WalletError(e.to_string()))?;
+            write_output(&stem.with_extension("google.json"), &json, args.force)?;
+        }
+    }
+    println!(
+        "✓ Wallet passes for {} students (series {}): {}",
+        students.len(),
+        event_id,
+        args.output_dir.display()
     );
-
     Ok(())
 }
 
 // ============================================================================
-// Attendance Grid
+// Reports
 // ============================================================================
 
-fn draw_attendance_grid(
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-    config: &AttendanceConfig,
-    start_y: f32,
-    content_width: f32,
-    available_height: f32,
-) -> Result<f32, AppError> {
-    match &config.roster {
-        Some(roster) => {
-            // Two-column roster layout
-            let col_gap = 6.0; // Gap between columns
-            let col_width = (content_width - col_gap) / 2.0;
-            let header_row_height = 6.0;
-            let blank_rows = 4; // Blank rows per column for walk-ins
-
-            // Split roster into two columns
-            let total_roster = roster.len();
-            let left_count = (total_roster + 1) / 2; // Left gets the extra one if odd
-            let right_count = total_roster - left_count;
-
-            // Total rows per column = roster names + blank rows
-            let left_total = left_count as u32 + blank_rows;
-            let right_total = right_count as u32 + blank_rows;
-            let max_rows = left_total.max(right_total);
-
-            let available_for_data = available_height - header_row_height;
-            let row_height = (available_for_data / max_rows as f32).min(MAX_ROW_HEIGHT_MM);
-
-            // Draw left column
-            let left_x = MARGIN_MM;
-            draw_roster_column(
-                layer,
-                font_regular,
-                font_bold,
-                &roster[..left_count],
-                left_x,
-                col_width,
-                start_y,
-                row_height,
-                header_row_height,
-                blank_rows,
+fn run_report(command: ReportCommand) -> Result<(), AppError> {
+    match command {
+        ReportCommand::Reconcile { event_id, manifest, checkins, output_dir, force } => {
+            let (event, report) = load_reconciliation(&event_id, &manifest, checkins)?;
+            let event_id = &report.event_id;
+            let pdf = PdfRenderer.render(&report.layout(), report_config(&event)?.config())?;
+
+            let stem = format!("reconcile-{}", event_id);
+            let pdf_path = resolve_output_path(output_dir.as_deref(), &format!("{}.pdf", stem))?;
+            let csv_path = resolve_output_path(output_dir.as_deref(), &format!("{}.csv", stem))?;
+            write_output(&pdf_path, &pdf, force)?;
+            write_output(&csv_path, report.csv().as_bytes(), force)?;
+
+            let (tables, extra) = report.tables();
+            println!(
+                "✓ {} attended ({} tables, {} extra), {} no-shows, {} walk-ins, {} juniors",
+                report.attendance(),
+                tables,
+                extra,
+                report.no_shows.len(),
+                report.walk_ins.len(),
+                report.juniors
             );
-
-            // Draw right column
-            let right_x = MARGIN_MM + col_width + col_gap;
-            draw_roster_column(
-                layer,
-                font_regular,
-                font_bold,
-                &roster[left_count..],
-                right_x,
-                col_width,
-                start_y,
-                row_height,
-                header_row_height,
-                blank_rows,
+            println!("  {}", pdf_path.display());
+            println!("  {}", csv_path.display());
+        }
+        ReportCommand::Masterpoints {
+            event_id,
+            manifest,
+            checkins,
+            roster,
+            club_number,
+            award,
+            director,
+            output_dir,
+            force,
+        } => {
+            let (event, report) = load_reconciliation(&event_id, &manifest, checkins)?;
+            let roster = read_roster_entries(&roster)?;
+            let awards = MasterpointSheet::new(&report, &roster, &club_number, &award, director.as_deref());
+            let pdf = PdfRenderer.render(&awards.layout(), report_config(&event)?.config())?;
+
+            let path = resolve_output_path(output_dir.as_deref(), &format!("masterpoints-{}.pdf", report.event_id))?;
+            write_output(&path, &pdf, force)?;
+            let missing = awards.players.iter().filter(|(_, number)| number.is_none()).count();
+            println!("✓ Masterpoint sheet for {} players: {}", awards.players.len(), path.display());
+            if missing > 0 {
+                println!("  {} without an ACBL number on the roster", missing);
+            }
+        }
+        ReportCommand::Trends { manifests, checkins, output_dir, force } => {
+            let sessions = load_sessions(&manifests, None, &checkins)?;
+            let trends = TrendReport::new(&sessions)?;
+            let (latest, _) = sessions
+                .iter()
+                .max_by(|(a, _), (b, _)| a.date.cmp(&b.date))
+                .ok_or_else(|| AppError::ConfigError("the manifests hold no sessions".to_string()))?;
+            let pdf = PdfRenderer.render(&trends.layout(), report_config(latest)?.config())?;
+
+            let path = resolve_output_path(output_dir.as_deref(), "attendance-trends.pdf")?;
+            write_output(&path, &pdf, force)?;
+            println!(
+                "✓ Trends of {} sessions over {} weeks: {}",
+                sessions.len(),
+                trends.weeks.len(),
+                path.display()
             );
-
-            let rows_drawn = max_rows as f32 + 1.0; // +1 for header
-            Ok(start_y - header_row_height - rows_drawn * row_height)
         }
-        None => {
-            // Blank mode is now handled directly in generate_pdf for multi-page support
-            unreachable!("Blank mode should be handled in generate_pdf");
+        ReportCommand::Semester { manifests, from, to, checkins, mailing_list, output_dir, force } => {
+            let (from, to) = (parse_date(&Some(from))?, parse_date(&Some(to))?);
+            if from > to {
+                return Err(AppError::DateError(format!("--from {} is after --to {}", from, to)));
+            }
+            let joined = mailing_list.as_deref().map(read_mailing_list_joined).transpose()?;
+            let sessions = load_sessions(&manifests, None, &checkins)?;
+            let summary = SemesterSummary::new(&sessions, from, to, joined.as_deref())?;
+            let (first_day, last_day) = (from.to_string(), to.to_string());
+            let latest = sessions
+                .iter()
+                .map(|(event, _)| event)
+                .filter(|event| (first_day.as_str()..=last_day.as_str()).contains(&event.date.as_str()))
+                .max_by(|a, b| a.date.cmp(&b.date))
+                .ok_or_else(|| AppError::ConfigError(format!("no sessions from {} to {}", from, to)))?;
+            let pdf = PdfRenderer.render(&summary.layout(), report_config(latest)?.config())?;
+
+            let path = resolve_output_path(output_dir.as_deref(), &format!("semester-{}-to-{}.pdf", from, to))?;
+            write_output(&path, &pdf, force)?;
+            let sessions: usize = summary.classes.iter().map(|class| class.sessions.len()).sum();
+            println!(
+                "✓ Semester summary of {} sessions in {} classes ({} student-sessions): {}",
+                sessions,
+                summary.classes.len(),
+                summary.student_sessions,
+                path.display()
+            );
         }
     }
+    Ok(())
 }
 
-fn draw_roster_column(
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-    names: &[String],
-    x_start: f32,
-    col_width: f32,
-    start_y: f32,
-    row_height: f32,
-    header_row_height: f32,
-    blank_rows: u32,
-) {
-    let name_width = col_width * NAME_COL_RATIO;
-    let table_width = col_width * TABLE_COL_RATIO;
-    let seat_width = col_width * SEAT_COL_RATIO;
-
-    let col_name_x = x_start;
-    let col_table_x = x_start + name_width;
-    let col_seat_x = col_table_x + table_width;
-
-    let mut y_pos = start_y;
-
-    // Draw header
-    draw_grid_header(
-        layer,
-        font_bold,
-        y_pos,
-        col_name_x,
-        col_table_x,
-        col_seat_x,
-        name_width,
-        table_width,
-        seat_width,
-        header_row_height,
-    );
-    y_pos -= header_row_height;
-
-    // Draw roster names
-    for name in names.iter() {
-        draw_roster_row(
-            layer,
-            font_regular,
-            y_pos,
-            col_name_x,
-            col_table_x,
-            col_seat_x,
-            name_width,
-            table_width,
-            seat_width,
-            row_height,
-            name,
-        );
-        y_pos -= row_height;
-    }
+/// The options that give the sheet a roster
+const ROSTER_SOURCES: &str = "--roster, --google-label, --eventbrite or --signupgenius";
 
-    // Draw blank rows for walk-ins
-    for i in 0..blank_rows {
-        draw_blank_row(
-            layer,
-            font_regular,
-            y_pos,
-            col_name_x,
-            col_table_x,
-            col_seat_x,
-            name_width,
-            table_width,
-            seat_width,
-            row_height,
-            names.len() as u32 + i + 1,
-            false,
-        );
-        y_pos -= row_height;
+/// Expected attendance from the RSVPs, and who has not replied
+fn report_rsvps(rsvps: &RsvpMatch) {
+    let expected = rsvps.confirmed.len();
+    println!("✓ RSVPs: {} of {} students confirmed", expected, expected + rsvps.not_replied.len());
+    if !rsvps.not_replied.is_empty() {
+        println!("  No RSVP: {}", rsvps.not_replied.join(", "));
+    }
+    if !rsvps.not_on_roster.is_empty() {
+        eprintln!("⚠ Warning: RSVPs from people not on the roster: {}", rsvps.not_on_roster.join(", "));
     }
 }
 
-fn draw_grid_header(
-    layer: &PdfLayerReference,
-    font_bold: &IndirectFontRef,
-    y: f32,
-    col_name_x: f32,
-    col_table_x: f32,
-    col_seat_x: f32,
-    _name_width: f32,
-    _table_width: f32,
-    seat_width: f32,
-    row_height: f32,
-) {
-    let text_y = y - row_height / 2.0 - 1.5;
-
-    // Column headers
-    layer.use_text("NAME", NORMAL_FONT_SIZE, Mm(col_name_x + 2.0), Mm(text_y), font_bold);
-    layer.use_text("TABLE", SMALL_FONT_SIZE, Mm(col_table_x + 2.0), Mm(text_y), font_bold);
-    layer.use_text("SEAT", SMALL_FONT_SIZE, Mm(col_seat_x + 2.0), Mm(text_y), font_bold);
-
-    // Draw header border
-    let line_color = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
-    layer.set_outline_color(line_color);
-    layer.set_outline_thickness(0.5);
-
-    // Bottom line of header
-    draw_line(layer, col_name_x, y - row_height, col_seat_x + seat_width, y - row_height);
+/// Sign-up date of each member in a mailing list export, from its "joined"
+/// column; blank dates are `None`
+fn read_mailing_list_joined(path: &Path) -> Result<Vec<Option<chrono::NaiveDate>>, AppError> {
+    let error = |message: String| AppError::ConfigError(format!("--mailing-list {}: {}", path.display(), message));
+    let content = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let mut records = csv_records(&content).into_iter();
+    let header = records.next().unwrap_or_default();
+    let column = header
+        .iter()
+        .position(|field| field.trim().eq_ignore_ascii_case("joined"))
+        .ok_or_else(|| error("no \"joined\" column".to_string()))?;
+    records
+        .map(|record| {
+            let joined = record.get(column).map(|field| field.trim()).unwrap_or_default();
+            if joined.is_empty() {
+                return Ok(None);
+            }
+            // Exports often add a time after the date
+            joined
+                .get(..10)
+                .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+                .map(Some)
+                .ok_or_else(|| error(format!("joined date {} is not YYYY-MM-DD", joined)))
+        })
+        .collect()
 }
 
-fn draw_roster_row(
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    y: f32,
-    col_name_x: f32,
-    col_table_x: f32,
-    col_seat_x: f32,
-    _name_width: f32,
-    table_width: f32,
-    seat_width: f32,
-    row_height: f32,
-    name: &str,
-) {
-    let text_y = y - row_height / 2.0 - 1.5;
-    let checkbox_size = 3.0;
-
-    // Draw checkbox
-    draw_checkbox(layer, col_name_x + 1.0, text_y - 0.5, checkbox_size);
-
-    // Draw name
-    layer.use_text(
-        name,
-        NORMAL_FONT_SIZE,
-        Mm(col_name_x + checkbox_size + 3.0),
-        Mm(text_y),
-        font_regular,
-    );
-
-    // Draw table column line
-    draw_line(layer, col_table_x + 2.0, text_y - 0.5, col_table_x + table_width - 1.0, text_y - 0.5);
+fn run_export(command: ExportCommand) -> Result<(), AppError> {
+    match command {
+        ExportCommand::AcblReport { manifests, month, checkins, roster, output, force } => {
+            if let Some(month) = &month {
+                chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+                    .map_err(|_| AppError::DateError(format!("{} (expected YYYY-MM)", month)))?;
+            }
+            let roster = roster.as_deref().map(read_roster_entries).transpose()?.unwrap_or_default();
+            let sessions = load_sessions(&manifests, month.as_deref(), &checkins)?;
+            let csv = monthly_report_csv(&sessions, &roster);
+            match output {
+                Some(path) => {
+                    write_output(&path, csv.as_bytes(), force)?;
+                    println!("✓ Report of {} sessions: {}", sessions.len(), path.display());
+                }
+                None => print!("{}", csv),
+            }
+        }
+        ExportCommand::Transcription { manifest, output, force } => {
+            let csv = transcription_csv(&read_manifest(&manifest)?.events);
+            match output {
+                Some(path) => {
+                    write_output(&path, csv.as_bytes(), force)?;
+                    println!("✓ Transcription template: {}", path.display());
+                }
+                None => print!("{}", csv),
+            }
+        }
+    }
+    Ok(())
+}
 
-    // Draw seat options
-    layer.use_text("N  S  E  W", NORMAL_FONT_SIZE, Mm(col_seat_x + 1.0), Mm(text_y), font_regular);
+/// Every event in `manifests` (only those in `month`, YYYY-MM, if given)
+/// set against the check-in logs, or each event's default log
+fn load_sessions(
+    manifests: &[PathBuf],
+    month: Option<&str>,
+    checkins: &[PathBuf],
+) -> Result<Vec<(ManifestEvent, Reconciliation)>, AppError> {
+    let mut logged = Vec::new();
+    for log in checkins {
+        logged.extend(read_checkins(log)?);
+    }
 
-    // Draw row bottom line
-    let line_color = Color::Rgb(Rgb::new(0.8, 0.8, 0.8, None));
-    layer.set_outline_color(line_color);
-    layer.set_outline_thickness(0.3);
-    draw_line(layer, col_name_x, y - row_height, col_seat_x + seat_width, y - row_height);
+    let mut sessions = Vec::new();
+    for path in manifests {
+        for event in read_manifest(path)?.events {
+            if month.is_some_and(|month| !event.date.starts_with(month)) {
+                continue;
+            }
+            let report = if checkins.is_empty() {
+                reconcile(&event, &read_checkins(&default_log_path(&event.event_id))?)
+            } else {
+                reconcile(&event, &logged)
+            };
+            sessions.push((event, report));
+        }
+    }
+    Ok(sessions)
 }
 
-fn draw_blank_row(
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    y: f32,
-    col_name_x: f32,
-    _col_table_x: f32,
-    col_seat_x: f32,
-    _name_width: f32,
-    _table_width: f32,
-    seat_width: f32,
-    row_height: f32,
-    row_num: u32,
-    show_number: bool,
-) {
-    let text_y = y - row_height / 2.0 - 1.5;
-
-    // Row number or empty
-    if show_number {
-        layer.use_text(
-            &format!("{}.", row_num),
-            SMALL_FONT_SIZE,
-            Mm(col_name_x + 1.0),
-            Mm(text_y),
-            font_regular,
-        );
+/// The manifest's event set against the check-in logs
+fn load_reconciliation(
+    event_id: &str,
+    manifest: &Path,
+    checkins: Vec<PathBuf>,
+) -> Result<(ManifestEvent, Reconciliation), AppError> {
+    let event_id = parse_event_id(event_id)?;
+    let event = read_manifest(manifest)?
+        .events
+        .into_iter()
+        .find(|event| event.event_id == event_id)
+        .ok_or_else(|| AppError::ConfigError(format!("event {} is not in the manifest", event_id)))?;
+
+    let logs = if checkins.is_empty() { vec![default_log_path(&event_id)] } else { checkins };
+    let mut records = Vec::new();
+    for log in &logs {
+        records.extend(read_checkins(log)?);
     }
+    let report = reconcile(&event, &records);
+    Ok((event, report))
+}
 
-    // Seat options
-    layer.use_text("N  S  E  W", NORMAL_FONT_SIZE, Mm(col_seat_x + 1.0), Mm(text_y), font_regular);
-
-    // Row bottom line
-    let line_color = Color::Rgb(Rgb::new(0.8, 0.8, 0.8, None));
-    layer.set_outline_color(line_color);
-    layer.set_outline_thickness(0.3);
-    draw_line(layer, col_name_x, y - row_height, col_seat_x + seat_width, y - row_height);
+/// Report PDFs take their metadata from a sheet config for the event
+fn report_config(event: &ManifestEvent) -> Result<AttendanceSheet, AppError> {
+    AttendanceSheet::builder()
+        .class(&event.class_name)
+        .teacher(&event.teacher)
+        .date(parse_date(&Some(event.date.clone()))?)
+        .event_id(&event.event_id)
+        .build()
 }
 
-fn draw_table_seat_row(
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    y: f32,
-    x_start: f32,
-    content_width: f32,
-    row_height: f32,
-    table_num: u32,
-    seat: &str,
-    is_first_seat: bool,
-    is_last_seat: bool,
-) {
-    let text_y = y - row_height / 2.0 - 1.5; // Center text vertically in row
-    let table_col_width = 22.0; // Width for "Table X" column
-    let seat_col_start = x_start + table_col_width;
-
-    // Draw table number only on first seat (North)
-    if is_first_seat {
-        layer.use_text(
-            &format!("Table {}", table_num),
-            NORMAL_FONT_SIZE,
-            Mm(x_start + 2.0),
-            Mm(text_y),
-            font_regular,
-        );
+// ============================================================================
+// Regenerate
+// ============================================================================
+
+fn run_regenerate(manifest_file: &Path, logo: Option<&str>, output: Option<PathBuf>, force: bool) -> Result<(), AppError> {
+    let manifest = read_manifest(manifest_file)?;
+    let logo = logo.map(|source| AssetCache::default().logo(source)).transpose()?;
+    let mut configs = manifest
+        .events
+        .iter()
+        .map(ManifestEvent::to_config)
+        .collect::<Result<Vec<_>, _>>()?;
+    for config in &mut configs {
+        config.logo = logo.clone();
     }
 
-    // Draw seat name
-    layer.use_text(
-        seat,
-        NORMAL_FONT_SIZE,
-        Mm(seat_col_start + 2.0),
-        Mm(text_y),
-        font_regular,
-    );
+    // Images are not kept in the manifest, so say what will be missing
+    let sheets: Vec<_> = manifest.events.iter().filter_map(|event| event.sheet.as_ref()).collect();
+    if sheets.len() < manifest.events.len() {
+        eprintln!("⚠ Warning: the manifest predates sheet snapshots; only the names and grid options are restored");
+    }
+    if logo.is_none() && sheets.iter().any(|sheet| sheet.logo) {
+        eprintln!("⚠ Warning: the original had a logo; pass --logo to include it");
+    }
+    if sheets.iter().any(|sheet| sheet.sponsor_image) {
+        eprintln!("⚠ Warning: the sponsor image is not kept in the manifest and is left out");
+    }
+    let photos: usize = sheets.iter().map(|sheet| sheet.photos).sum();
+    if photos > 0 {
+        eprintln!("⚠ Warning: {} student photos are not kept in the manifest and are left out", photos);
+    }
+
+    let output_path = output.unwrap_or_else(|| manifest_file.with_file_name(&manifest.output));
+    let extension = output_path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    let format = OutputFormat::value_variants()
+        .iter()
+        .copied()
+        .find(|format| format.renderer().extension() == extension)
+        .unwrap_or(OutputFormat::Pdf);
+    let bytes = render_events_with(format.renderer().as_ref(), &configs, &mut PhaseTimings::default())?;
+    write_output(&output_path, &bytes, force)?;
+
+    println!("✓ Regenerated: {}", output_path.display());
+    for config in &configs {
+        println!("  Class: {}", config.class_name);
+        println!("  Date: {}", format_date_display(&config.date));
+        println!("  Event ID: {}", config.event_id);
+    }
+    Ok(())
+}
 
-    // Draw row separator line (starts at seat column, not table column)
-    // For last seat (West), draw full-width line as table separator
-    let line_color = Color::Rgb(Rgb::new(0.7, 0.7, 0.7, None));
-    layer.set_outline_color(line_color);
-    layer.set_outline_thickness(0.3);
+// ============================================================================
+// NFC
+// ============================================================================
 
-    let line_start = if is_last_seat { x_start } else { seat_col_start };
-    draw_line(layer, line_start, y - row_height, x_start + content_width, y - row_height);
+fn run_nfc(event_id: &str, url: &str, tlv: bool, output: Option<&Path>, force: bool) -> Result<(), AppError> {
+    let url = checkin_url(url, &parse_event_id(event_id)?);
+    let message = ndef_uri_message(&url);
+    let bytes = if tlv { ndef_tlv(&message) } else { message };
+    match output {
+        Some(path) => {
+            write_output(path, &bytes, force)?;
+            println!("✓ NDEF for {} ({} bytes): {}", url, bytes.len(), path.display());
+        }
+        None => {
+            println!("{}", url);
+            println!("{}", hex_bytes(&bytes));
+        }
+    }
+    Ok(())
 }
 
-fn draw_checkbox(layer: &PdfLayerReference, x: f32, y: f32, size: f32) {
-    let line_color = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
-    layer.set_outline_color(line_color);
-    layer.set_outline_thickness(0.4);
+// ============================================================================
+// Init
+// ============================================================================
+
+/// Starter files written by `init`: the shipped examples, so they stay in
+/// step with what the tool reads
+const INIT_FILES: [(&str, &str); 3] = [
+    (DEFAULT_SETTINGS_FILE, include_str!("../examples/attendance.toml")),
+    ("roster.json", include_str!("../examples/roster.json")),
+    ("schedule.toml", include_str!("../examples/schedule.toml")),
+];
+
+fn run_init(dir: &Path, force: bool) -> Result<(), AppError> {
+    // Check everything first so a refusal leaves no half-made setup behind
+    if !force {
+        let existing: Vec<String> = INIT_FILES
+            .iter()
+            .map(|(name, _)| dir.join(name))
+            .filter(|path| path.exists())
+            .map(|path| path.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            return Err(AppError::ConfigError(format!(
+                "{} already exists (use --force to overwrite)",
+                existing.join(", ")
+            )));
+        }
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| AppError::ConfigError(format!("{}: {}", dir.display(), e)))?;
+    for (name, content) in INIT_FILES {
+        let path = dir.join(name);
+        std::fs::write(&path, content)
+            .map_err(|e| AppError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        println!("✓ Created: {}", path.display());
+    }
 
-    // Draw square
-    draw_line(layer, x, y, x + size, y);
-    draw_line(layer, x + size, y, x + size, y + size);
-    draw_line(layer, x + size, y + size, x, y + size);
-    draw_line(layer, x, y + size, x, y);
+    println!();
+    println!("Next steps:");
+    println!("  1. Edit attendance.toml with your club's sponsor, markers, teachers and venues");
+    println!("  2. Replace the names in roster.json with your students");
+    println!("  3. Put your series dates and topics in schedule.toml");
+    println!("  4. attendance-pdf -n \"My Class\" --roster roster.json --schedule schedule.toml --calendar");
+    Ok(())
 }
 
 // ============================================================================
-// Mailing List Section
+// Presets
 // ============================================================================
 
-fn calculate_mailing_section_height(_rows: u32) -> f32 {
-    // Fixed height regardless of row count (equivalent to old 6-row layout)
-    // This keeps the section size consistent while allowing variable row counts
-    47.0
+fn run_preset(command: PresetCommand) -> Result<(), AppError> {
+    let dir = presets_dir();
+    match command {
+        PresetCommand::Save(args) => {
+            let preset = Preset {
+                class: args.name,
+                teacher: args.teacher,
+                location: args.location,
+                venue: args.venue,
+                roster: args.roster.as_deref().map(absolute_source).transpose()?,
+                rows: args.rows,
+                logo: args.logo.as_deref().map(absolute_source).transpose()?,
+            };
+            let path = save_preset(&dir, &args.preset, &preset)?;
+            println!("✓ Saved preset {}: {}", args.preset, path.display());
+        }
+        PresetCommand::Load { name } => {
+            let preset = load_preset(&dir, &name)?;
+            let content = toml::to_string(&preset)
+                .map_err(|e| AppError::PresetError(format!("{}: {}", name, e)))?;
+            print!("{}", content);
+        }
+        PresetCommand::List => {
+            let names = list_presets(&dir)?;
+            if names.is_empty() {
+                println!("No presets in {}", dir.display());
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }
+    }
+    Ok(())
 }
 
-fn draw_mailing_section(
-    layer: &PdfLayerReference,
-    font_regular: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-    rows: u32,
-    x_start: f32,
-    content_width: f32,
-    has_starred: bool,
-) -> Result<(), AppError> {
-    let section_height = calculate_mailing_section_height(rows);
-    let y_bottom = MARGIN_MM;
-    let y_top = y_bottom + section_height;
-
-    // Draw section border
-    let line_color = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
-    layer.set_outline_color(line_color);
-    layer.set_outline_thickness(0.5);
-
-    // Top line
-    draw_line(layer, x_start, y_top, x_start + content_width, y_top);
-
-    // Section header
-    let header_y = y_top - 6.0;
-    let header_text = if has_starred {
-        "* JOIN MY MAILING LIST"
+/// Fill in whatever the command line left unset from the preset
+fn apply_preset(mut args: Args, preset: Preset) -> Args {
+    args.name = args.name.or(preset.class);
+    args.teacher = args.teacher.or(preset.teacher);
+    if args.location.is_empty() {
+        args.location = preset.location.unwrap_or_default();
+    }
+    args.venue = args.venue.or(preset.venue);
+    args.roster = args.roster.or(preset.roster);
+    args.rows = args.rows.or(preset.rows);
+    args.logo = args.logo.or(preset.logo);
+    args
+}
+
+/// Hand the file to the platform's default application without waiting for it
+fn open_in_viewer(path: &Path) -> Result<(), AppError> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
     } else {
-        "JOIN MY MAILING LIST"
+        Command::new("xdg-open")
     };
-    layer.use_text(
-        header_text,
-        NORMAL_FONT_SIZE,
-        Mm(x_start + content_width / 2.0 - 20.0),
-        Mm(header_y),
-        font_bold,
-    );
-
-    // Draw rows - spread evenly in available space
-    let header_space = 10.0; // Space used by header
-    let available_for_rows = section_height - header_space - 3.0; // minus padding
-    let row_height = available_for_rows / rows as f32;
-    let mut y = y_top - header_space;
-
-    for _ in 0..rows {
-        // Name field
-        layer.use_text(
-            "Name:",
-            SMALL_FONT_SIZE,
-            Mm(x_start + 2.0),
-            Mm(y),
-            font_regular,
-        );
-        draw_line(layer, x_start + 15.0, y - 0.5, x_start + content_width * 0.45, y - 0.5);
-
-        // Email field
-        layer.use_text(
-            "Email:",
-            SMALL_FONT_SIZE,
-            Mm(x_start + content_width * 0.48),
-            Mm(y),
-            font_regular,
-        );
-        draw_line(
-            layer,
-            x_start + content_width * 0.48 + 12.0,
-            y - 0.5,
-            x_start + content_width - 2.0,
-            y - 0.5,
-        );
-
-        y -= row_height;
-    }
 
+    command
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
     Ok(())
 }
 
 // ============================================================================
-// Drawing Utilities
+// Benchmark Mode
 // ============================================================================
 
-fn draw_line(layer: &PdfLayerReference, x1: f32, y1: f32, x2: f32, y2: f32) {
-    let points = vec![
-        (Point::new(Mm(x1), Mm(y1)), false),
-        (Point::new(Mm(x2), Mm(y2)), false),
-    ];
-    let line = Line {
-        points,
-        is_closed: false,
-    };
-    layer.add_line(line);
+const BENCH_FIRST_NAMES: [&str; 8] = [
+    "Alice", "Bob", "Carol", "David", "Eleanor", "Frank", "Grace", "Henry",
+];
+const BENCH_LAST_NAMES: [&str; 8] = [
+    "Johnson", "Smith", "Williams", "Brown", "Davis", "Miller", "Wilson", "Moore",
+];
+
+/// Render `count` sheets in memory and print throughput and per-phase timings.
+///
+/// Roster sizes cycle from 8 to 47 names so both sparse and crowded grids are
+/// exercised; every fifth sheet uses blank mode to cover the multi-page path.
+fn run_bench(mut config: AttendanceConfig, count: u32, assets_elapsed: Duration) -> Result<(), AppError> {
+    let mut timings = PhaseTimings::default();
+    let mut total_bytes = 0usize;
+    let start = Instant::now();
+
+    for i in 0..count {
+        config.event_id = generate_short_id();
+        config.roster = if i % 5 == 4 {
+            None
+        } else {
+            Some(synthetic_roster(8 + (i % 40) as usize))
+        };
+        total_bytes += render_pdf(&config, &mut timings)?.len();
+    }
+
+    let elapsed = start.elapsed();
+    let per_sheet_ms = |d: Duration| d.as_secs_f64() * 1000.0 / count.max(1) as f64;
+
+    println!(
+        "Benchmark: {} sheets in {:.2}s ({:.1} sheets/s, {:.1} KB avg)",
+        count,
+        elapsed.as_secs_f64(),
+        count as f64 / elapsed.as_secs_f64(),
+        total_bytes as f64 / 1024.0 / count.max(1) as f64,
+    );
+    println!("  assets     {:>8.2} ms (once)", assets_elapsed.as_secs_f64() * 1000.0);
+    println!("  header     {:>8.2} ms/sheet", per_sheet_ms(timings.header));
+    println!("  grid       {:>8.2} ms/sheet", per_sheet_ms(timings.grid));
+    println!("  mailing    {:>8.2} ms/sheet", per_sheet_ms(timings.mailing));
+    println!("  serialize  {:>8.2} ms/sheet", per_sheet_ms(timings.serialize));
+
+    Ok(())
+}
+
+fn synthetic_roster(size: usize) -> Vec<Student> {
+    (0..size)
+        .map(|i| {
+            let first = BENCH_FIRST_NAMES[i % BENCH_FIRST_NAMES.len()];
+            let last = BENCH_LAST_NAMES[(i / BENCH_FIRST_NAMES.len() + i * 3) % BENCH_LAST_NAMES.len()];
+            Student::from(format!("{} {}", first, last))
+        })
+        .collect()
 }
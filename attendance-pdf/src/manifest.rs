@@ -0,0 +1,384 @@
+// Manifest sidecar: a JSON record of exactly what went into a generated
+// document, kept next to it for teaching-credit reports and reprints
+
+use crate::assets::{AssetCache, QrCache};
+use crate::layout::row_numbers;
+use crate::names::DisplayOrder;
+use crate::qr::DEFAULT_QR_DPI;
+use crate::roster::RosterMarker;
+use crate::roster_file::Visibility;
+use crate::schedule::Schedule;
+use crate::{
+    roster_students, AppError, AttendanceConfig, Deal, EmergencyInfo, MailingPlacement, Revision, RosterEntry, Sponsor, Student,
+    TextAlign,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Bumped when fields change meaning; new optional fields do not bump it
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub app: String,
+    pub version: u32,
+    /// Crate name and version that produced the document
+    pub generator: String,
+    /// File name of the document this manifest describes
+    pub output: String,
+    /// One entry per sheet, in page order
+    pub events: Vec<ManifestEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEvent {
+    pub event_id: String,
+    pub class_name: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Instructor of record
+    pub teacher: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub substitute: Option<String>,
+    pub location: String,
+    /// Names pre-printed on the sheet, or `None` for a blank sheet
+    pub roster: Option<Vec<String>>,
+    pub blank_rows: u32,
+    pub mailing_list: bool,
+    pub mailing_rows: u32,
+    #[serde(default)]
+    pub helper_rows: u32,
+    /// Bring-a-friend coupons printed, serials ending -001, -002, ...
+    #[serde(default)]
+    pub coupons: u32,
+    #[serde(default)]
+    pub signature_column: bool,
+    #[serde(default)]
+    pub photo_consent: bool,
+    #[serde(default)]
+    pub time_column: bool,
+    #[serde(default)]
+    pub raffle: bool,
+    /// Lucky number printed in each grid row, down the left column then the
+    /// right, when the sheet was printed with `--draw-numbers`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draw_numbers: Option<Vec<u32>>,
+    /// Everything else that went into the sheet, for `regenerate`; missing
+    /// from manifests written before it was kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sheet: Option<SheetSnapshot>,
+    /// Each printing of the sheet over this manifest, the current one last
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
+}
+
+/// The rest of a sheet's config. Images are not kept, only a note of which
+/// ones the sheet had.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SheetSnapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub teacher_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wifi: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emergency_page: Option<EmergencyInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mailing_title: Option<String>,
+    #[serde(default)]
+    pub title_align: TextAlign,
+    #[serde(default)]
+    pub mailing_placement: MailingPlacement,
+    /// The roster with each student's details, in printed order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub students: Option<Vec<RosterEntry>>,
+    /// Zoom registrants listed under "Online attendees"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub online_attendees: Option<Vec<String>>,
+    #[serde(default)]
+    pub announce_birthdays: bool,
+    #[serde(default)]
+    pub display_order: DisplayOrder,
+    #[serde(default)]
+    pub use_nicknames: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<RosterMarker>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<Schedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiver: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checklist: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hands: Option<Vec<Deal>>,
+    #[serde(default)]
+    pub overflow_page: bool,
+    pub copies: u32,
+    #[serde(default)]
+    pub mono: bool,
+    #[serde(default)]
+    pub economy: bool,
+    pub qr_dpi: u32,
+    /// Sponsor strip text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsor: Option<String>,
+    #[serde(default)]
+    pub reproducible: bool,
+    /// The sheet had a header logo
+    #[serde(default)]
+    pub logo: bool,
+    /// The sponsor strip had an image
+    #[serde(default)]
+    pub sponsor_image: bool,
+    /// Students printed with a photo
+    #[serde(default)]
+    pub photos: usize,
+    #[serde(default)]
+    pub teacher_copy: bool,
+}
+
+impl Manifest {
+    pub fn for_events(configs: &[AttendanceConfig], output: &Path) -> Self {
+        Manifest {
+            app: "bridge-attendance".to_string(),
+            version: MANIFEST_VERSION,
+            generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            output: output
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            events: configs.iter().map(ManifestEvent::from_config).collect(),
+        }
+    }
+
+    pub fn event(&self, event_id: &str) -> Option<&ManifestEvent> {
+        self.events.iter().find(|event| event.event_id == event_id)
+    }
+
+    /// Carry over the revision history of events printed before under
+    /// `previous`
+    pub fn keep_revisions(&mut self, previous: &Manifest) {
+        for event in &mut self.events {
+            if let Some(earlier) = previous.event(&event.event_id) {
+                let mut revisions = earlier.revisions.clone();
+                revisions.append(&mut event.revisions);
+                event.revisions = revisions;
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, AppError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::ExportError(format!("Failed to write manifest: {}", e)))
+    }
+}
+
+impl ManifestEvent {
+    pub fn from_config(config: &AttendanceConfig) -> Self {
+        ManifestEvent {
+            event_id: config.event_id.clone(),
+            class_name: config.class_name.clone(),
+            date: config.date.format("%Y-%m-%d").to_string(),
+            teacher: config.teacher.clone(),
+            substitute: config.substitute.clone(),
+            location: config.location.clone(),
+            roster: config
+                .roster
+                .as_ref()
+                .map(|students| students.iter().map(|student| student.name.clone()).collect()),
+            blank_rows: config.blank_rows,
+            mailing_list: config.mailing_list,
+            mailing_rows: config.mailing_rows,
+            helper_rows: config.helper_rows,
+            coupons: config.coupons,
+            signature_column: config.signature_column,
+            photo_consent: config.photo_consent,
+            time_column: config.time_column,
+            raffle: config.raffle,
+            draw_numbers: if config.draw_numbers { row_numbers(config) } else { None },
+            sheet: Some(SheetSnapshot::from_config(config)),
+            revisions: config.revision.iter().cloned().collect(),
+        }
+    }
+
+    /// Current printing; manifests from before revisions were kept count as
+    /// the first
+    pub fn revision(&self) -> u32 {
+        self.revisions.last().map_or(1, |revision| revision.number)
+    }
+
+    /// The sheet's config again, for `regenerate`. Older manifests without a
+    /// snapshot give the names and grid options, with defaults for the rest.
+    pub fn to_config(&self) -> Result<AttendanceConfig, AppError> {
+        let date = NaiveDate::parse_from_str(&self.date, "%Y-%m-%d")
+            .map_err(|_| AppError::DateError(format!("{} (event {})", self.date, self.event_id)))?;
+        let sheet = self.sheet.clone();
+        let roster = match (sheet.as_ref().and_then(|sheet| sheet.students.clone()), &self.roster) {
+            // No photos are kept, so there is nothing for the cache to load
+            (Some(entries), _) => Some(roster_students(entries, Path::new(""), &mut AssetCache::default())?),
+            (None, Some(names)) => Some(names.iter().map(|name| Student::from(name.as_str())).collect()),
+            (None, None) => None,
+        };
+        let sheet = sheet.unwrap_or_else(SheetSnapshot::without_details);
+
+        Ok(AttendanceConfig {
+            class_name: self.class_name.clone(),
+            teacher: self.teacher.clone(),
+            teacher_email: sheet.teacher_email,
+            substitute: self.substitute.clone(),
+            date,
+            location: self.location.clone(),
+            wifi: sheet.wifi,
+            emergency: sheet.emergency,
+            banner: sheet.banner,
+            feedback_url: sheet.feedback_url,
+            emergency_page: sheet.emergency_page,
+            event_id: self.event_id.clone(),
+            roster,
+            online_attendees: sheet.online_attendees,
+            blank_rows: self.blank_rows,
+            mailing_list: self.mailing_list,
+            mailing_rows: self.mailing_rows,
+            mailing_title: sheet.mailing_title,
+            title_align: sheet.title_align,
+            mailing_placement: sheet.mailing_placement,
+            helper_rows: self.helper_rows,
+            coupons: self.coupons,
+            signature_column: self.signature_column,
+            photo_consent: self.photo_consent,
+            time_column: self.time_column,
+            raffle: self.raffle,
+            draw_numbers: self.draw_numbers.is_some(),
+            announce_birthdays: sheet.announce_birthdays,
+            display_order: sheet.display_order,
+            use_nicknames: sheet.use_nicknames,
+            markers: sheet.markers,
+            calendar: sheet.calendar,
+            waiver: sheet.waiver,
+            checklist: sheet.checklist,
+            hands: sheet.hands,
+            overflow_page: sheet.overflow_page,
+            copies: sheet.copies.max(1),
+            mono: sheet.mono,
+            economy: sheet.economy,
+            qr_dpi: sheet.qr_dpi,
+            qr_codes: QrCache::default(),
+            logo: None,
+            sponsor: sheet.sponsor.map(|text| Sponsor { text, image: None }),
+            reproducible: sheet.reproducible,
+            revision: self.revisions.last().cloned(),
+            teacher_copy: sheet.teacher_copy,
+        })
+    }
+}
+
+impl SheetSnapshot {
+    pub fn from_config(config: &AttendanceConfig) -> Self {
+        let students = config.roster.as_ref().map(|students| {
+            students
+                .iter()
+                .map(|student| RosterEntry {
+                    name: student.name.clone(),
+                    nickname: student.nickname.clone(),
+                    expires: student.expires.map(|date| date.format("%Y-%m-%d").to_string()),
+                    style: student.bold.then(|| "bold".to_string()),
+                    highlight: student.highlight,
+                    pronouns: student.pronouns.clone(),
+                    note: student.note.clone(),
+                    needs: student.needs.clone(),
+                    new: student.is_new,
+                    junior: student.junior,
+                    confirmed: student.confirmed,
+                    birthday: student.birthday.map(|(month, day)| format!("{:02}-{:02}", month, day)),
+                    ..RosterEntry::default()
+                })
+                .map(|entry| entry.redacted(Visibility::Teacher))
+                .collect()
+        });
+        SheetSnapshot {
+            teacher_email: config.teacher_email.clone(),
+            wifi: config.wifi.clone(),
+            emergency: config.emergency.clone(),
+            banner: config.banner.clone(),
+            feedback_url: config.feedback_url.clone(),
+            emergency_page: config.emergency_page.clone(),
+            mailing_title: config.mailing_title.clone(),
+            title_align: config.title_align,
+            mailing_placement: config.mailing_placement,
+            students,
+            online_attendees: config.online_attendees.clone(),
+            announce_birthdays: config.announce_birthdays,
+            display_order: config.display_order,
+            use_nicknames: config.use_nicknames,
+            markers: config.markers.clone(),
+            calendar: config.calendar.clone(),
+            waiver: config.waiver.clone(),
+            checklist: config.checklist.clone(),
+            hands: config.hands.clone(),
+            overflow_page: config.overflow_page,
+            copies: config.copies,
+            mono: config.mono,
+            economy: config.economy,
+            qr_dpi: config.qr_dpi,
+            sponsor: config.sponsor.as_ref().map(|sponsor| sponsor.text.clone()),
+            reproducible: config.reproducible,
+            logo: config.logo.is_some(),
+            sponsor_image: config.sponsor.as_ref().is_some_and(|sponsor| sponsor.image.is_some()),
+            photos: config.roster.iter().flatten().filter(|student| student.photo.is_some()).count(),
+            teacher_copy: config.teacher_copy,
+        }
+    }
+
+    /// Defaults for a manifest written before snapshots were kept
+    fn without_details() -> Self {
+        SheetSnapshot {
+            teacher_email: None,
+            wifi: None,
+            emergency: None,
+            banner: None,
+            feedback_url: None,
+            emergency_page: None,
+            mailing_title: None,
+            title_align: TextAlign::default(),
+            mailing_placement: MailingPlacement::default(),
+            students: None,
+            online_attendees: None,
+            announce_birthdays: false,
+            display_order: DisplayOrder::default(),
+            use_nicknames: false,
+            markers: Vec::new(),
+            calendar: None,
+            waiver: None,
+            checklist: None,
+            hands: None,
+            overflow_page: false,
+            copies: 1,
+            mono: false,
+            economy: false,
+            qr_dpi: DEFAULT_QR_DPI,
+            sponsor: None,
+            reproducible: false,
+            logo: false,
+            sponsor_image: false,
+            photos: 0,
+            teacher_copy: false,
+        }
+    }
+}
+
+pub fn read_manifest(path: &Path) -> Result<Manifest, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::ExportError(format!("{}: {}", path.display(), e)))?;
+    serde_json::from_str(&content).map_err(|e| AppError::ExportError(format!("{}: {}", path.display(), e)))
+}
+
+/// Sidecar path for `output`: `sheet.pdf` → `sheet.manifest.json`
+pub fn manifest_path(output: &Path) -> PathBuf {
+    output.with_extension("manifest.json")
+}
@@ -0,0 +1,135 @@
+// Text measurement for the built-in Helvetica fonts, so layout can place
+// things after or around text without a font file
+
+use crate::layout::FontStyle;
+
+/// Advance widths (1/1000 em) for ASCII 32..=126, from the Adobe AFM files
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // space-/
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // 0-?
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // @-O
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // P-_
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // `-o
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p-~
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, // space-/
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, // 0-?
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778, // @-O
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556, // P-_
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611, // `-o
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584, // p-~
+];
+
+/// Every suit symbol in the embedded suit font is the same width
+const SUIT_SYMBOL_WIDTH: u16 = 896;
+
+/// Card suits, filled and outlined (\u{2660} to \u{2667}). The built-in fonts
+/// have none, so the PDF sets them in the embedded suit font whatever the
+/// text's own font.
+pub fn is_suit_symbol(c: char) -> bool {
+    ('\u{2660}'..='\u{2667}').contains(&c)
+}
+
+/// Accented letters and symbols outside ASCII are close to a typical
+/// lowercase letter
+const FALLBACK_WIDTH: u16 = 556;
+
+const MM_PER_PT: f32 = 25.4 / 72.0;
+
+/// Width of `text` set at `size_pt`, in mm
+pub fn text_width_mm(text: &str, size_pt: f32, font: FontStyle) -> f32 {
+    let widths = match font {
+        FontStyle::Regular => &HELVETICA_WIDTHS,
+        FontStyle::Bold => &HELVETICA_BOLD_WIDTHS,
+        FontStyle::Symbol => {
+            let units = text.chars().count() as u32 * SUIT_SYMBOL_WIDTH as u32;
+            return units as f32 / 1000.0 * size_pt * MM_PER_PT;
+        }
+    };
+    let units: u32 = text
+        .chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => widths[(code - 32) as usize],
+            _ if is_suit_symbol(c) => SUIT_SYMBOL_WIDTH,
+            _ => FALLBACK_WIDTH,
+        } as u32)
+        .sum();
+    units as f32 / 1000.0 * size_pt * MM_PER_PT
+}
+
+/// `text` broken at spaces into lines no wider than `width_mm`; a single word
+/// wider than that gets a line of its own
+pub fn wrap_text(text: &str, size_pt: f32, font: FontStyle, width_mm: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if current.is_empty() || text_width_mm(&candidate, size_pt, font) <= width_mm {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Like [`wrap_text`], but a word too wide for a line of its own is broken
+/// across lines with a hyphen
+pub fn wrap_hyphenated(text: &str, size_pt: f32, font: FontStyle, width_mm: f32) -> Vec<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .flat_map(|word| hyphenate(word, size_pt, font, width_mm))
+        .collect();
+    wrap_text(&words.join(" "), size_pt, font, width_mm)
+}
+
+/// `word` in pieces that each fit `width_mm`, every piece but the last
+/// ending in a hyphen
+fn hyphenate(word: &str, size_pt: f32, font: FontStyle, width_mm: f32) -> Vec<String> {
+    let fits = |text: &str| text_width_mm(text, size_pt, font) <= width_mm;
+    let mut pieces = Vec::new();
+    let mut rest = word;
+    while !fits(rest) {
+        // The longest start that fits with its hyphen, but at least a letter
+        let mut ends = rest.char_indices().map(|(index, _)| index).skip(1);
+        let mut end = ends.next().unwrap_or(rest.len());
+        for next in ends {
+            if !fits(&format!("{}-", &rest[..next])) {
+                break;
+            }
+            end = next;
+        }
+        if end == rest.len() {
+            break;
+        }
+        pieces.push(format!("{}-", &rest[..end]));
+        rest = &rest[end..];
+    }
+    pieces.push(rest.to_string());
+    pieces
+}
+
+/// `text` cut short with an ellipsis if it is wider than `width_mm`
+pub fn truncate_to_width(text: &str, size_pt: f32, font: FontStyle, width_mm: f32) -> String {
+    if text_width_mm(text, size_pt, font) <= width_mm {
+        return text.to_string();
+    }
+    let mut cut = text.trim_end().to_string();
+    while !cut.is_empty() {
+        cut.pop();
+        let candidate = format!("{}\u{2026}", cut.trim_end());
+        if text_width_mm(&candidate, size_pt, font) <= width_mm {
+            return candidate;
+        }
+    }
+    "\u{2026}".to_string()
+}
@@ -0,0 +1,193 @@
+// Personal names: split a roster name into given and family parts so it can
+// be printed last-name-first and sorted by surname.
+//
+// Accepts "Anna van der Berg", "van der Berg, Anna", "John Smith Jr." and
+// "Smith, John, Jr.". Family-name particles (van, der, de, ...) stay with the
+// family name; suffixes (Jr., III, ...) are kept apart so they print last.
+// Marker symbols written after a name ("Alice Johnson *") stay at the end.
+
+use crate::Student;
+use serde::{Deserialize, Serialize};
+
+/// How names are printed on the sheet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayOrder {
+    /// "Anna van der Berg", in roster order
+    #[default]
+    FirstLast,
+    /// "van der Berg, Anna", sorted by family name
+    LastFirst,
+}
+
+const PARTICLES: [&str; 14] = [
+    "van", "von", "der", "den", "de", "del", "della", "di", "da", "du", "la", "le", "ter", "ten",
+];
+
+const SUFFIXES: [&str; 10] = ["jr", "sr", "ii", "iii", "iv", "v", "phd", "md", "esq", "dds"];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PersonName {
+    pub given: String,
+    /// Includes any particles: "van der Berg"
+    pub family: String,
+    pub suffix: Option<String>,
+    /// Trailing marker symbols such as "*" or "†"
+    pub markers: String,
+}
+
+impl PersonName {
+    /// "Anna van der Berg Jr."
+    pub fn first_last(&self) -> String {
+        let name = [self.given.as_str(), self.family.as_str(), self.suffix.as_deref().unwrap_or_default()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.with_markers(name)
+    }
+
+    /// "van der Berg, Anna, Jr."; a lone name prints as it is
+    pub fn last_first(&self) -> String {
+        if self.given.is_empty() || self.family.is_empty() {
+            return self.first_last();
+        }
+        let mut name = format!("{}, {}", self.family, self.given);
+        if let Some(suffix) = &self.suffix {
+            name = format!("{}, {}", name, suffix);
+        }
+        self.with_markers(name)
+    }
+
+    /// Family name without leading particles, then given name: "van der
+    /// Berg" files under B
+    pub fn sort_key(&self) -> String {
+        let family = self
+            .family
+            .split_whitespace()
+            .skip_while(|word| is_particle(word))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{} {}", family, self.given).to_lowercase()
+    }
+
+    fn with_markers(&self, name: String) -> String {
+        if self.markers.is_empty() {
+            name
+        } else {
+            format!("{} {}", name, self.markers)
+        }
+    }
+}
+
+pub fn parse_name(raw: &str) -> PersonName {
+    // Marker symbols are the trailing words with no letters or digits
+    let mut words: Vec<&str> = raw.split_whitespace().collect();
+    let marker_start = words
+        .iter()
+        .rposition(|word| word.chars().any(char::is_alphanumeric))
+        .map_or(0, |last| last + 1);
+    let markers = words.split_off(marker_start).join(" ");
+    let text = words.join(" ");
+
+    let mut name = match text.split_once(',') {
+        // "Smith, John", "van der Berg, Anna", "Smith, John, Jr."
+        Some((family, rest)) if !is_suffix(rest) => {
+            let (given, suffix) = match rest.split_once(',') {
+                Some((given, suffix)) => (given, Some(suffix.trim().to_string())),
+                None => (rest, None),
+            };
+            let (given, trailing_suffix) = split_suffix(given.trim());
+            PersonName {
+                given,
+                family: family.trim().to_string(),
+                suffix: suffix.or(trailing_suffix),
+                ..PersonName::default()
+            }
+        }
+        // "John Smith, Jr."
+        Some((name, suffix)) => PersonName {
+            suffix: Some(suffix.trim().to_string()),
+            ..parse_given_family(name.trim())
+        },
+        None => parse_given_family(&text),
+    };
+    name.markers = markers;
+    name
+}
+
+/// "Anna van der Berg Jr.": the last word and any particles before it are
+/// the family name
+fn parse_given_family(text: &str) -> PersonName {
+    let (text, suffix) = split_suffix(text);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() < 2 {
+        return PersonName {
+            given: text,
+            suffix,
+            ..PersonName::default()
+        };
+    }
+
+    let mut family_start = words.len() - 1;
+    while family_start > 1 && is_particle(words[family_start - 1]) {
+        family_start -= 1;
+    }
+    PersonName {
+        given: words[..family_start].join(" "),
+        family: words[family_start..].join(" "),
+        suffix,
+        markers: String::new(),
+    }
+}
+
+/// Take a trailing suffix word off `text`
+fn split_suffix(text: &str) -> (String, Option<String>) {
+    match text.rsplit_once(' ') {
+        Some((rest, last)) if is_suffix(last) => (rest.trim().to_string(), Some(last.to_string())),
+        _ => (text.to_string(), None),
+    }
+}
+
+fn is_suffix(word: &str) -> bool {
+    let word = word.trim().trim_end_matches('.').replace('.', "").to_lowercase();
+    SUFFIXES.contains(&word.as_str())
+}
+
+fn is_particle(word: &str) -> bool {
+    PARTICLES.contains(&word.to_lowercase().as_str())
+}
+
+/// In last-first order the roster is sorted by family name so students find
+/// their line alphabetically; first-last keeps the roster's own order
+pub fn sort_roster(roster: &mut [Student], order: DisplayOrder) {
+    if order == DisplayOrder::LastFirst {
+        roster.sort_by_cached_key(|student| parse_name(&student.name).sort_key());
+    }
+}
+
+/// Key for matching one list's names against another's: "Alice Johnson *",
+/// "alice johnson" and "Johnson, Alice" match
+pub fn match_key(name: &str) -> String {
+    let mut name = parse_name(name);
+    name.markers.clear();
+    name.first_last().to_lowercase()
+}
+
+pub fn display(name: &str, order: DisplayOrder) -> String {
+    match order {
+        DisplayOrder::FirstLast => name.to_string(),
+        DisplayOrder::LastFirst => parse_name(name).last_first(),
+    }
+}
+
+/// `name` with `nickname` in place of the given name: "Robert Smith" and
+/// "Bob" give "Bob Smith"
+pub fn with_nickname(name: &str, nickname: &str) -> String {
+    let mut name = parse_name(name);
+    if name.family.is_empty() {
+        name.suffix = None;
+    }
+    name.given = nickname.trim().to_string();
+    name.first_last()
+}
@@ -0,0 +1,159 @@
+// Remote fetches: logos, photos, sponsor images and rosters by URL, and the
+// Google, Eventbrite and Zoom imports all go through here, so `--offline`, the
+// timeouts, the retries and the download limit apply to each of them alike.
+//
+//     [network]
+//     connect_timeout = 10   # seconds
+//     read_timeout = 30      # seconds
+//     retries = 2
+//     max_download_mb = 20
+
+use crate::AppError;
+use serde::Deserialize;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// Wait before the first retry, doubled for each one after
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// `[network]`: how long to wait for a remote resource and how often to try
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkSettings {
+    /// Seconds to wait for a connection
+    pub connect_timeout: u64,
+    /// Seconds to wait on each read of a response
+    pub read_timeout: u64,
+    /// Further attempts after a timeout, a dropped connection or a server
+    /// error; a client error such as 404 is final
+    pub retries: u32,
+    /// Largest response accepted, in megabytes
+    pub max_download_mb: u64,
+}
+
+impl NetworkSettings {
+    pub const DEFAULT: NetworkSettings = NetworkSettings {
+        connect_timeout: 10,
+        read_timeout: 30,
+        retries: 2,
+        max_download_mb: 20,
+    };
+
+    fn max_download_bytes(&self) -> u64 {
+        self.max_download_mb * 1024 * 1024
+    }
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        NetworkSettings::DEFAULT
+    }
+}
+
+/// Set by `--offline` for the rest of the run
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static SETTINGS: RwLock<NetworkSettings> = RwLock::new(NetworkSettings::DEFAULT);
+/// Built on first use from the settings at that time
+static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Use `settings` for the rest of the run; call before the first fetch
+pub fn configure(settings: NetworkSettings) {
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+fn settings() -> NetworkSettings {
+    *SETTINGS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Called before every fetch: fails with [`AppError::OfflineError`] naming
+/// `what` rather than touching the network under `--offline`
+pub fn require_network(what: &str) -> Result<(), AppError> {
+    if OFFLINE.load(Ordering::Relaxed) {
+        Err(AppError::OfflineError(what.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether `source` names something to fetch rather than a local file
+pub fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+fn agent() -> &'static ureq::Agent {
+    AGENT.get_or_init(|| {
+        let settings = settings();
+        ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(settings.connect_timeout))
+            .timeout_read(Duration::from_secs(settings.read_timeout))
+            .build()
+    })
+}
+
+pub fn get(url: &str) -> ureq::Request {
+    agent().get(url)
+}
+
+pub fn post(url: &str) -> ureq::Request {
+    agent().post(url)
+}
+
+/// Send `request`, trying again with growing waits while it times out,
+/// loses its connection or gets a 429 or 5xx response
+pub fn call(request: ureq::Request) -> Result<ureq::Response, Box<ureq::Error>> {
+    with_retries(|| request.clone().call().map_err(Box::new))
+}
+
+/// As [`call`], posting `form`
+pub fn send_form(request: ureq::Request, form: &[(&str, &str)]) -> Result<ureq::Response, Box<ureq::Error>> {
+    with_retries(|| request.clone().send_form(form).map_err(Box::new))
+}
+
+fn with_retries(
+    send: impl Fn() -> Result<ureq::Response, Box<ureq::Error>>,
+) -> Result<ureq::Response, Box<ureq::Error>> {
+    let mut wait = RETRY_BACKOFF;
+    let mut attempts_left = settings().retries;
+    loop {
+        match send() {
+            Err(e) if attempts_left > 0 && is_transient(&e) => {
+                std::thread::sleep(wait);
+                wait *= 2;
+                attempts_left -= 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_transient(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+        ureq::Error::Transport(_) => true,
+    }
+}
+
+/// The response body, refused once it passes the download limit
+pub fn read_bytes(response: ureq::Response) -> std::io::Result<Vec<u8>> {
+    let limit = settings().max_download_bytes();
+    let mut bytes = Vec::new();
+    response.into_reader().take(limit + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        return Err(std::io::Error::other(format!(
+            "larger than the {} MB download limit",
+            settings().max_download_mb
+        )));
+    }
+    Ok(bytes)
+}
+
+/// As [`read_bytes`], as text
+pub fn read_string(response: ureq::Response) -> std::io::Result<String> {
+    String::from_utf8(read_bytes(response)?).map_err(std::io::Error::other)
+}
@@ -0,0 +1,75 @@
+// NFC check-in tags: the event's check-in URL as an NDEF message, ready to
+// program onto a sticker at the door with any NFC writer app or tool.
+//
+// The message is a single URI record (NFC Forum RTD URI), optionally wrapped
+// in the NDEF TLV that Type 2 tags such as NTAG213 store in user memory.
+
+/// Check-in page for an event; `{event_id}` is replaced
+pub const DEFAULT_CHECKIN_URL: &str = "https://attendance.harmonicsystems.com/events/{event_id}/checkin";
+
+/// URI identifier codes from the RTD URI spec; the longest match is used
+const URI_PREFIXES: [(u8, &str); 4] = [
+    (0x01, "http://www."),
+    (0x02, "https://www."),
+    (0x03, "http://"),
+    (0x04, "https://"),
+];
+
+/// TNF 0x01: NFC Forum well-known type
+const TNF_WELL_KNOWN: u8 = 0x01;
+const FLAG_MB: u8 = 0x80;
+const FLAG_ME: u8 = 0x40;
+const FLAG_SR: u8 = 0x10;
+
+const TLV_NDEF: u8 = 0x03;
+const TLV_TERMINATOR: u8 = 0xFE;
+
+pub fn checkin_url(template: &str, event_id: &str) -> String {
+    template.replace("{event_id}", event_id)
+}
+
+/// A one-record NDEF message holding `uri`
+pub fn ndef_uri_message(uri: &str) -> Vec<u8> {
+    let (code, rest) = URI_PREFIXES
+        .iter()
+        .filter(|(_, prefix)| uri.starts_with(prefix))
+        .max_by_key(|(_, prefix)| prefix.len())
+        .map_or((0x00, uri), |(code, prefix)| (*code, &uri[prefix.len()..]));
+
+    let mut payload = vec![code];
+    payload.extend_from_slice(rest.as_bytes());
+
+    let mut record = Vec::with_capacity(payload.len() + 7);
+    let flags = FLAG_MB | FLAG_ME | TNF_WELL_KNOWN;
+    // Short records carry a one-byte payload length
+    match u8::try_from(payload.len()) {
+        Ok(length) => record.extend_from_slice(&[flags | FLAG_SR, 1, length]),
+        Err(_) => {
+            record.extend_from_slice(&[flags, 1]);
+            record.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        }
+    }
+    record.push(b'U');
+    record.extend_from_slice(&payload);
+    record
+}
+
+/// `message` as Type 2 tag memory: NDEF TLV, then the terminator
+pub fn ndef_tlv(message: &[u8]) -> Vec<u8> {
+    let mut tlv = vec![TLV_NDEF];
+    match u8::try_from(message.len()) {
+        Ok(length) if length < 0xFF => tlv.push(length),
+        _ => {
+            tlv.push(0xFF);
+            tlv.extend_from_slice(&(message.len() as u16).to_be_bytes());
+        }
+    }
+    tlv.extend_from_slice(message);
+    tlv.push(TLV_TERMINATOR);
+    tlv
+}
+
+/// "D1 01 2B 55 04 ...", as NFC writer apps accept raw data
+pub fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,123 @@
+// Output file naming: expands `--name-template` placeholders from the event
+// so a semester of sheets can land in predictable, sortable paths
+
+use crate::{AppError, AttendanceConfig};
+use deunicode::deunicode;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/// Template used when neither `--output` nor `--name-template` is given
+pub const DEFAULT_NAME_TEMPLATE: &str = "attendance-{date}-{class}.{ext}";
+
+/// Placeholders recognised in name templates
+pub const PLACEHOLDERS: [&str; 6] = ["date", "class", "event_id", "teacher", "location", "ext"];
+
+/// Lowercase and hyphenate free text so it is safe in a file name.
+///
+/// Accented and non-Latin text is transliterated to ASCII ("Café" → "cafe")
+/// unless `keep_unicode` is set, in which case it is kept in NFC form so the
+/// same name always produces the same bytes on disk.
+pub fn slugify(text: &str, keep_unicode: bool) -> String {
+    let text: String = if keep_unicode {
+        text.nfc().collect()
+    } else {
+        deunicode(text)
+    };
+
+    let mut slug = String::with_capacity(text.len());
+    for c in text.to_lowercase().chars() {
+        let keep = if keep_unicode { c.is_alphanumeric() } else { c.is_ascii_alphanumeric() };
+        if keep {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Substitute `{placeholder}`s in `template`; `ext` is the format's extension
+pub fn expand_name_template(
+    template: &str,
+    config: &AttendanceConfig,
+    ext: &str,
+    keep_unicode: bool,
+) -> Result<String, AppError> {
+    let mut name = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        name.push_str(&rest[..open]);
+        let close = rest[open..].find('}').ok_or_else(|| {
+            AppError::ConfigError(format!("unclosed '{{' in name template: {}", template))
+        })? + open;
+
+        let value = match &rest[open + 1..close] {
+            "date" => config.date.format("%Y-%m-%d").to_string(),
+            "class" => slugify(&config.class_name, keep_unicode),
+            "event_id" => config.event_id.clone(),
+            "teacher" => slugify(&config.teacher, keep_unicode),
+            "location" => slugify(&config.location, keep_unicode),
+            "ext" => ext.to_string(),
+            other => {
+                return Err(AppError::ConfigError(format!(
+                    "unknown placeholder {{{}}} in name template (expected one of: {})",
+                    other,
+                    PLACEHOLDERS.join(", ")
+                )))
+            }
+        };
+        name.push_str(&value);
+        rest = &rest[close + 1..];
+    }
+    name.push_str(rest);
+
+    Ok(name)
+}
+
+/// Join `file_name` onto `dir`, creating the directory if needed
+pub fn resolve_output_path(dir: Option<&Path>, file_name: &str) -> Result<PathBuf, AppError> {
+    match dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            Ok(dir.join(file_name))
+        }
+        None => Ok(PathBuf::from(file_name)),
+    }
+}
+
+/// Path for the teacher's copy of `output`: `sheet.pdf` → `sheet-teacher.pdf`
+pub fn teacher_copy_path(output: &Path) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match output.extension() {
+        Some(ext) => format!("{}-teacher.{}", stem, ext.to_string_lossy()),
+        None => format!("{}-teacher", stem),
+    };
+    output.with_file_name(name)
+}
+
+/// Write `bytes` to `path`. An existing file is only replaced when `force` is
+/// set, since each sheet carries its own event ID and clobbering one loses it.
+pub fn write_output(path: &Path, bytes: &[u8], force: bool) -> Result<(), AppError> {
+    create_output(path, force)?.write_all(bytes)?;
+    Ok(())
+}
+
+/// Open `path` for a document written in pieces, on the same terms as
+/// [`write_output`]
+pub fn create_output(path: &Path, force: bool) -> Result<File, AppError> {
+    if force {
+        return Ok(File::create(path)?);
+    }
+
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => AppError::OutputExistsError(path.display().to_string()),
+            _ => AppError::IoError(e),
+        })
+}
@@ -0,0 +1,200 @@
+// Class passes: wallet-size punch cards for clubs selling lesson packages.
+//
+// Cards are laid out eight to a Letter page (two across, four down) with cut
+// lines, so they go through the same renderers as the attendance sheet.
+
+use crate::assets::PreparedImage;
+use crate::layout::{
+    layout_crop_marks, layout_logo, FontStyle, Page, SheetLayout, Trim, BORDER_STROKE, CHECKBOX_STROKE,
+    ROW_STROKE,
+};
+use crate::qr::{generate_pass_qr_image, PassPayload};
+use crate::roster::preferred_name;
+use crate::{
+    serial_number, AppError, AttendanceConfig, HEADER_FONT_SIZE, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM,
+    PAGE_WIDTH_MM, SMALL_FONT_SIZE,
+};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Credit-card size (ISO/IEC 7810 ID-1)
+pub const PASS_WIDTH_MM: f32 = 85.6;
+pub const PASS_HEIGHT_MM: f32 = 54.0;
+
+pub const DEFAULT_PUNCHES: u32 = 10;
+/// Enough blank cards to fill one page
+pub const DEFAULT_BLANK_PASSES: u32 = 8;
+
+const PASSES_ACROSS: u32 = 2;
+const PASSES_DOWN: u32 = 4;
+
+const PASS_QR_SIZE_MM: f32 = 20.0;
+const PUNCH_DIAMETER_MM: f32 = 7.0;
+/// Room left of the QR code for the punch circles
+const PUNCH_AREA_WIDTH_MM: f32 = 56.0;
+
+/// What to print on each card
+pub struct PassOptions {
+    /// Classes in the package, one punch circle each
+    pub punches: u32,
+    /// Cards to print when there is no roster to name them
+    pub blank_passes: u32,
+    /// Crop marks and bleed between the cards
+    pub trim: Trim,
+}
+
+impl Default for PassOptions {
+    fn default() -> Self {
+        PassOptions {
+            punches: DEFAULT_PUNCHES,
+            blank_passes: DEFAULT_BLANK_PASSES,
+            trim: Trim::default(),
+        }
+    }
+}
+
+/// One named card per roster entry, or `blank_passes` cards with a write-in
+/// name line
+pub fn layout_passes(config: &AttendanceConfig, options: &PassOptions) -> Result<SheetLayout, AppError> {
+    let students: Vec<Option<String>> = match &config.roster {
+        // The mailing-list star means nothing on a pass
+        Some(roster) => roster
+            .iter()
+            .map(|student| Some(preferred_name(student, config).replace('*', "").trim().to_string()))
+            .collect(),
+        None => vec![None; options.blank_passes as usize],
+    };
+
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+
+    // With bleed, cards sit a gutter apart so each can be trimmed on its own
+    let trim = options.trim;
+    let gutter = 2.0 * trim.bleed_mm;
+    let pitch_x = PASS_WIDTH_MM + gutter;
+    let pitch_y = PASS_HEIGHT_MM + gutter;
+    let grid_width = PASSES_ACROSS as f32 * pitch_x - gutter;
+    let grid_height = PASSES_DOWN as f32 * pitch_y - gutter;
+
+    let per_page = PASSES_ACROSS * PASSES_DOWN;
+    let left_x = (PAGE_WIDTH_MM - grid_width) / 2.0;
+    let top_y = PAGE_HEIGHT_MM - (PAGE_HEIGHT_MM - grid_height) / 2.0;
+
+    for (index, student) in students.iter().enumerate() {
+        let index = index as u32;
+        let slot = index % per_page;
+        if slot == 0 {
+            let page = sheet.new_page();
+            if trim.crop_marks {
+                let xs: Vec<f32> = (0..PASSES_ACROSS)
+                    .flat_map(|column| {
+                        let x = left_x + column as f32 * pitch_x;
+                        [x, x + PASS_WIDTH_MM]
+                    })
+                    .collect();
+                let ys: Vec<f32> = (0..PASSES_DOWN)
+                    .flat_map(|row| {
+                        let y = top_y - row as f32 * pitch_y;
+                        [y, y - PASS_HEIGHT_MM]
+                    })
+                    .collect();
+                let bounds = (left_x, left_x + grid_width, top_y - grid_height, top_y);
+                layout_crop_marks(page, &xs, &ys, bounds);
+            }
+        }
+        let page = sheet.pages.last_mut().expect("pass page was just added");
+
+        let x = left_x + (slot % PASSES_ACROSS) as f32 * pitch_x;
+        let y_top = top_y - (slot / PASSES_ACROSS) as f32 * pitch_y;
+        let serial = serial_number(&config.event_id, index);
+        if !trim.crop_marks {
+            layout_cut_outline(page, x, y_top);
+        }
+        layout_pass(page, config, options.punches, &serial, student.as_deref(), x, y_top)?;
+    }
+
+    Ok(sheet)
+}
+
+/// Light outline to cut along with scissors
+fn layout_cut_outline(page: &mut Page, x: f32, y_top: f32) {
+    let right_x = x + PASS_WIDTH_MM;
+    let y_bottom = y_top - PASS_HEIGHT_MM;
+    page.line(x, y_top, right_x, y_top, ROW_STROKE);
+    page.line(x, y_bottom, right_x, y_bottom, ROW_STROKE);
+    page.line(x, y_top, x, y_bottom, ROW_STROKE);
+    page.line(right_x, y_top, right_x, y_bottom, ROW_STROKE);
+}
+
+fn layout_pass(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    punches: u32,
+    serial: &str,
+    student: Option<&str>,
+    x: f32,
+    y_top: f32,
+) -> Result<(), AppError> {
+    let right_x = x + PASS_WIDTH_MM;
+    let y_bottom = y_top - PASS_HEIGHT_MM;
+
+    let text_x = x + 4.0;
+    page.text("CLASS PASS", HEADER_FONT_SIZE, text_x, y_top - 7.0, FontStyle::Bold);
+    page.text(&config.class_name, SMALL_FONT_SIZE, text_x, y_top - 12.0, FontStyle::Regular);
+
+    if let Some(logo) = &config.logo {
+        layout_logo(page, logo, 24.0, 10.0, right_x - 3.0, y_top - 3.0);
+    }
+
+    // Student
+    let name_y = y_top - 19.0;
+    page.text("Name:", SMALL_FONT_SIZE, text_x, name_y, FontStyle::Regular);
+    match student {
+        Some(name) => page.text(name, NORMAL_FONT_SIZE, text_x + 11.0, name_y, FontStyle::Bold),
+        None => page.line(text_x + 11.0, name_y - 1.0, text_x + 52.0, name_y - 1.0, CHECKBOX_STROKE),
+    }
+
+    // Punch circles, numbered, in up to two rows
+    let per_row = if punches > 6 { punches.div_ceil(2) } else { punches };
+    let spacing = (PUNCH_AREA_WIDTH_MM / per_row.max(1) as f32).min(10.0);
+    for punch in 0..punches {
+        let center_x = text_x + PUNCH_DIAMETER_MM / 2.0 + (punch % per_row) as f32 * spacing;
+        let center_y = y_top - 28.0 - (punch / per_row) as f32 * 10.0;
+        layout_circle(page, center_x, center_y, PUNCH_DIAMETER_MM / 2.0);
+        let label = (punch + 1).to_string();
+        let label_x = center_x - 0.9 * label.len() as f32;
+        page.text(label, SMALL_FONT_SIZE, label_x, center_y - 1.2, FontStyle::Regular);
+    }
+
+    // Serial, repeated in the QR code so a scan identifies the card
+    page.text(format!("No. {}", serial), SMALL_FONT_SIZE, text_x, y_bottom + 4.0, FontStyle::Regular);
+    let payload = PassPayload::for_pass(config, serial, student);
+    let qr_image = PreparedImage::from_opaque(&generate_pass_qr_image(&payload, PASS_QR_SIZE_MM, config.qr_dpi)?);
+    page.image(
+        Arc::new(qr_image),
+        right_x - PASS_QR_SIZE_MM - 3.0,
+        y_bottom + 3.0,
+        PASS_QR_SIZE_MM,
+        PASS_QR_SIZE_MM,
+        false,
+    );
+
+    Ok(())
+}
+
+/// Layouts only hold straight lines, so circles are drawn as polygons
+fn layout_circle(page: &mut Page, center_x: f32, center_y: f32, radius: f32) {
+    const SEGMENTS: u32 = 24;
+    let point = |i: u32| {
+        let angle = 2.0 * PI * i as f32 / SEGMENTS as f32;
+        (center_x + radius * angle.cos(), center_y + radius * angle.sin())
+    };
+    for i in 0..SEGMENTS {
+        let (x1, y1) = point(i);
+        let (x2, y2) = point(i + 1);
+        page.line(x1, y1, x2, y2, BORDER_STROKE);
+    }
+}
@@ -0,0 +1,764 @@
+// PDF rendering of a laid-out sheet
+
+use crate::layout::{
+    Element, FontStyle, ImageElement, LineElement, RectElement, Rotation, SheetLayout, TextElement,
+};
+use crate::metrics::{is_suit_symbol, text_width_mm};
+use crate::{AppError, AttendanceConfig};
+use printpdf::path::{PaintMode, WindingOrder};
+use printpdf::*;
+use std::io::Write;
+
+/// DejaVu Sans cut down to the suit symbols (see assets/fonts/LICENSE)
+const SUIT_FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans-Suits.ttf");
+
+/// Fonts registered with one document. The suit font is only embedded when
+/// something uses it.
+struct Fonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    symbol: Option<IndirectFontRef>,
+}
+
+impl Fonts {
+    fn get(&self, style: FontStyle) -> &IndirectFontRef {
+        match style {
+            FontStyle::Regular => &self.regular,
+            FontStyle::Bold => &self.bold,
+            FontStyle::Symbol => self.symbol.as_ref().unwrap_or(&self.regular),
+        }
+    }
+}
+
+/// Draw every page of `layout` into a new PDF document and serialize it
+pub fn write_pdf(layout: &SheetLayout, config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+    // Create document
+    let (doc, page1, layer1) = PdfDocument::new(
+        "Attendance Sheet",
+        Mm(layout.width),
+        Mm(layout.height),
+        "Layer 1",
+    );
+
+    // Load built-in fonts, and the suit font if there are suit symbols
+    let has_suits = |text: &TextElement| text.font == FontStyle::Symbol || text.text.chars().any(is_suit_symbol);
+    let symbol = if layout.texts().any(|(_, text)| has_suits(text)) {
+        Some(doc.add_external_font(SUIT_FONT).map_err(|e| AppError::PdfError(e.to_string()))?)
+    } else {
+        None
+    };
+    let fonts = Fonts {
+        regular: doc.add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| AppError::PdfError(e.to_string()))?,
+        bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| AppError::PdfError(e.to_string()))?,
+        symbol,
+    };
+
+    for (index, page) in layout.pages.iter().enumerate() {
+        let layer = if index == 0 {
+            doc.get_page(page1).get_layer(layer1)
+        } else {
+            let (new_page, new_layer) = doc.add_page(
+                Mm(layout.width),
+                Mm(layout.height),
+                "Layer 1",
+            );
+            doc.get_page(new_page).get_layer(new_layer)
+        };
+
+        for element in &page.elements {
+            match element {
+                Element::Text(text) => draw_text(&layer, &fonts, text),
+                Element::Line(line) => draw_line(&layer, line),
+                Element::Rect(rect) => draw_rect(&layer, rect),
+                Element::Image(image) => draw_image(&layer, image),
+            }
+        }
+    }
+
+    // Serialize PDF (printpdf serializes the whole document at once; image
+    // payloads are kept small by `downsample_to_dpi` so this stays bounded)
+    let bytes = doc.save_to_bytes()
+        .map_err(|e| AppError::PdfError(e.to_string()))?;
+
+    if config.reproducible {
+        make_reproducible(&bytes, config)
+    } else {
+        Ok(bytes)
+    }
+}
+
+/// Replace the wall-clock timestamps and random document IDs printpdf embeds
+/// with values derived from the event, so identical inputs give identical bytes
+fn make_reproducible(bytes: &[u8], config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+    use lopdf::{Object, StringFormat};
+
+    let pdf_err = |e: lopdf::Error| AppError::PdfError(e.to_string());
+    let mut doc = lopdf::Document::load_mem(bytes).map_err(pdf_err)?;
+
+    let timestamp = format!("D:{}000000+00'00'", config.date.format("%Y%m%d"));
+    let info_id = doc.trailer.get(b"Info").and_then(Object::as_reference).map_err(pdf_err)?;
+    let info = doc.get_object_mut(info_id).and_then(Object::as_dict_mut).map_err(pdf_err)?;
+    for key in ["CreationDate", "ModDate"] {
+        if info.has(key.as_bytes()) {
+            info.set(key, Object::String(timestamp.clone().into_bytes(), StringFormat::Literal));
+        }
+    }
+
+    let document_id = format!("bridge-attendance-{}-{}", config.event_id, config.date.format("%Y%m%d"));
+    let id_string = Object::String(document_id.into_bytes(), StringFormat::Literal);
+    doc.trailer.set("ID", Object::Array(vec![id_string.clone(), id_string]));
+
+    let mut out = Vec::new();
+    doc.save_to(&mut out)?;
+    Ok(out)
+}
+
+// ============================================================================
+// Appending Documents
+// ============================================================================
+
+/// Page attributes a page may inherit from its ancestors in the page tree;
+/// copied onto appended pages since they get a new parent
+const INHERITABLE_PAGE_KEYS: [&str; 4] = ["Resources", "MediaBox", "CropBox", "Rotate"];
+
+/// Append every page of each document in `appendices` (e.g. lesson handouts)
+/// after the pages of `base`, so the packet prints as one job
+pub fn append_pdfs(base: &[u8], appendices: &[Vec<u8>]) -> Result<Vec<u8>, AppError> {
+    use lopdf::{Dictionary, Object};
+
+    let pdf_err = |e: lopdf::Error| AppError::PdfError(e.to_string());
+    let mut doc = lopdf::Document::load_mem(base).map_err(pdf_err)?;
+    let pages_id = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(pdf_err)?;
+
+    for bytes in appendices {
+        let mut appendix = lopdf::Document::load_mem(bytes)
+            .map_err(|e| AppError::PdfError(format!("Failed to read appended PDF: {}", e)))?;
+        appendix.renumber_objects_with(doc.max_id + 1);
+
+        // Resolve inherited attributes while the original page tree is intact
+        let mut pages = Vec::new();
+        for page_id in appendix.get_pages().into_values() {
+            let mut page = appendix.get_dictionary(page_id).map_err(pdf_err)?.clone();
+            for key in INHERITABLE_PAGE_KEYS {
+                if !page.has(key.as_bytes()) {
+                    if let Some(value) = inherited_page_attribute(&appendix, &page, key) {
+                        page.set(key, value);
+                    }
+                }
+            }
+            page.set("Parent", Object::Reference(pages_id));
+            pages.push((page_id, page));
+        }
+
+        // Bring over everything except the appendix's own catalog and page tree
+        let skipped: Vec<_> = appendix
+            .objects
+            .iter()
+            .filter(|(_, object)| is_document_root(object))
+            .map(|(id, _)| *id)
+            .collect();
+        for (id, object) in appendix.objects {
+            if !skipped.contains(&id) {
+                doc.objects.insert(id, object);
+            }
+        }
+        doc.max_id = doc.max_id.max(appendix.max_id);
+
+        let page_count = pages.len() as i64;
+        for (page_id, page) in pages {
+            doc.objects.insert(page_id, Object::Dictionary(page));
+            let tree: &mut Dictionary = doc.get_dictionary_mut(pages_id).map_err(pdf_err)?;
+            tree.get_mut(b"Kids")
+                .and_then(Object::as_array_mut)
+                .map_err(pdf_err)?
+                .push(Object::Reference(page_id));
+        }
+        let tree = doc.get_dictionary_mut(pages_id).map_err(pdf_err)?;
+        let count = tree.get(b"Count").and_then(Object::as_i64).map_err(pdf_err)?;
+        tree.set("Count", count + page_count);
+    }
+
+    let mut out = Vec::new();
+    doc.save_to(&mut out)?;
+    Ok(out)
+}
+
+/// The catalog or a page tree node, which a merged document replaces with
+/// its own
+fn is_document_root(object: &lopdf::Object) -> bool {
+    object
+        .as_dict()
+        .and_then(|dict| dict.get(b"Type"))
+        .and_then(lopdf::Object::as_name)
+        .map(|kind| kind == b"Catalog" || kind == b"Pages")
+        .unwrap_or(false)
+}
+
+/// Walk up the page tree from `page` looking for `key`
+fn inherited_page_attribute(
+    doc: &lopdf::Document,
+    page: &lopdf::Dictionary,
+    key: &str,
+) -> Option<lopdf::Object> {
+    let mut parent_id = page.get(b"Parent").and_then(|p| p.as_reference()).ok();
+    while let Some(id) = parent_id {
+        let node = doc.get_dictionary(id).ok()?;
+        if let Ok(value) = node.get(key.as_bytes()) {
+            return Some(value.clone());
+        }
+        parent_id = node.get(b"Parent").and_then(|p| p.as_reference()).ok();
+    }
+    None
+}
+
+// ============================================================================
+// Streaming Documents
+// ============================================================================
+
+/// Object number of the page tree every streamed page hangs from
+const STREAM_PAGES_ID: lopdf::ObjectId = (1, 0);
+
+/// A PDF written to `out` one part at a time, for merged documents too large
+/// to hold in memory. Each part is a complete document, such as one event's
+/// sheet; its objects are written out as soon as it is appended, so only the
+/// page list and the cross-reference offsets are kept until
+/// [`PdfStream::finish`].
+pub struct PdfStream<W: Write> {
+    out: W,
+    written: usize,
+    /// Byte offset of each object written so far, by object number
+    offsets: Vec<(lopdf::ObjectId, usize)>,
+    next_id: u32,
+    kids: Vec<lopdf::Object>,
+    /// Optional content groups (printpdf layers) of every part
+    layers: Vec<lopdf::Object>,
+    /// Catalog, document info and trailer ID of the first part
+    first: Option<(lopdf::Dictionary, Option<lopdf::Dictionary>, Option<lopdf::Object>)>,
+}
+
+impl<W: Write> PdfStream<W> {
+    pub fn new(out: W) -> Self {
+        PdfStream {
+            out,
+            written: 0,
+            offsets: Vec::new(),
+            next_id: STREAM_PAGES_ID.0 + 1,
+            kids: Vec::new(),
+            layers: Vec::new(),
+            first: None,
+        }
+    }
+
+    /// Write every page of the document `bytes` after the pages so far. The
+    /// first part's title, dates and ID stand for the whole document.
+    pub fn append(&mut self, bytes: &[u8]) -> Result<(), AppError> {
+        use lopdf::Object;
+
+        let pdf_err = |e: lopdf::Error| AppError::PdfError(e.to_string());
+        let mut doc = lopdf::Document::load_mem(bytes).map_err(pdf_err)?;
+        doc.renumber_objects_with(self.next_id);
+        self.next_id = doc.max_id + 1;
+
+        // Resolve inherited attributes while the original page tree is intact
+        let mut pages = Vec::new();
+        for page_id in doc.get_pages().into_values() {
+            let mut page = doc.get_dictionary(page_id).map_err(pdf_err)?.clone();
+            for key in INHERITABLE_PAGE_KEYS {
+                if !page.has(key.as_bytes()) {
+                    if let Some(value) = inherited_page_attribute(&doc, &page, key) {
+                        page.set(key, value);
+                    }
+                }
+            }
+            page.set("Parent", Object::Reference(STREAM_PAGES_ID));
+            pages.push((page_id, page));
+        }
+        for (page_id, page) in pages {
+            doc.objects.insert(page_id, Object::Dictionary(page));
+            self.kids.push(Object::Reference(page_id));
+        }
+
+        let catalog = doc.catalog().map_err(pdf_err)?.clone();
+        if let Ok(groups) = catalog
+            .get(b"OCProperties")
+            .and_then(Object::as_dict)
+            .and_then(|properties| properties.get(b"OCGs"))
+            .and_then(Object::as_array)
+        {
+            self.layers.extend(groups.iter().cloned());
+        }
+        let info_id = doc.trailer.get(b"Info").and_then(Object::as_reference).ok();
+        if self.first.is_none() {
+            if self.written == 0 {
+                self.write(format!("%PDF-{}\n", doc.version).as_bytes())?;
+                self.write(b"%\xE2\xE3\xCF\xD3\n")?;
+            }
+            let info = info_id.and_then(|id| doc.get_dictionary(id).ok()).cloned();
+            let document_id = doc.trailer.get(b"ID").ok().cloned();
+            self.first = Some((catalog, info, document_id));
+        }
+
+        for (id, object) in &doc.objects {
+            if Some(*id) != info_id && !is_document_root(object) {
+                self.write_object(*id, object)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the page tree, catalog and cross-reference table, and hand back
+    /// the writer
+    pub fn finish(mut self) -> Result<W, AppError> {
+        use lopdf::{Dictionary, Object};
+
+        let (mut catalog, info, document_id) = self
+            .first
+            .take()
+            .ok_or_else(|| AppError::PdfError("no pages to write".to_string()))?;
+
+        let count = self.kids.len() as i64;
+        let mut pages = Dictionary::new();
+        pages.set("Type", Object::Name(b"Pages".to_vec()));
+        pages.set("Kids", Object::Array(std::mem::take(&mut self.kids)));
+        pages.set("Count", count);
+        self.write_object(STREAM_PAGES_ID, &Object::Dictionary(pages))?;
+
+        catalog.set("Pages", Object::Reference(STREAM_PAGES_ID));
+        if !self.layers.is_empty() {
+            let layers = Object::Array(std::mem::take(&mut self.layers));
+            let mut config = Dictionary::new();
+            config.set("Order", layers.clone());
+            config.set("RBGroups", Object::Array(Vec::new()));
+            config.set("ON", layers.clone());
+            let mut properties = Dictionary::new();
+            properties.set("OCGs", layers);
+            properties.set("D", Object::Dictionary(config));
+            catalog.set("OCProperties", Object::Dictionary(properties));
+        }
+        let catalog_id = (self.next_id, 0);
+        self.write_object(catalog_id, &Object::Dictionary(catalog))?;
+
+        let mut trailer = Dictionary::new();
+        trailer.set("Root", Object::Reference(catalog_id));
+        let mut size = catalog_id.0 + 1;
+        if let Some(info) = info {
+            let info_id = (size, 0);
+            self.write_object(info_id, &Object::Dictionary(info))?;
+            trailer.set("Info", Object::Reference(info_id));
+            size += 1;
+        }
+        if let Some(document_id) = document_id {
+            trailer.set("ID", document_id);
+        }
+        trailer.set("Size", size as i64);
+
+        // Cross-reference table: one 20-byte entry per object number, free
+        // for numbers a part left unused
+        let xref_offset = self.written;
+        let mut offsets = vec![None; size as usize];
+        for (id, offset) in std::mem::take(&mut self.offsets) {
+            offsets[id.0 as usize] = Some((offset, id.1));
+        }
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", size).into_bytes();
+        for entry in offsets.iter().skip(1) {
+            match entry {
+                Some((offset, generation)) => writeln!(xref, "{:010} {:05} n ", offset, generation)?,
+                None => xref.extend_from_slice(b"0000000000 00000 f \n"),
+            }
+        }
+        xref.extend_from_slice(b"trailer\n");
+        serialize_object(&mut xref, &Object::Dictionary(trailer));
+        write!(xref, "\nstartxref\n{}\n%%EOF\n", xref_offset)?;
+        self.write(&xref)?;
+
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_object(&mut self, id: lopdf::ObjectId, object: &lopdf::Object) -> Result<(), AppError> {
+        self.offsets.push((id, self.written));
+        let mut bytes = format!("{} {} obj\n", id.0, id.1).into_bytes();
+        serialize_object(&mut bytes, object);
+        bytes.extend_from_slice(b"\nendobj\n");
+        self.write(&bytes)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), AppError> {
+        self.out.write_all(bytes)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+}
+
+/// PDF syntax for `object`, as lopdf would write it
+fn serialize_object(out: &mut Vec<u8>, object: &lopdf::Object) {
+    use lopdf::{Object, StringFormat};
+
+    match object {
+        Object::Null => out.extend_from_slice(b"null"),
+        Object::Boolean(value) => out.extend_from_slice(if *value { b"true" } else { b"false" }),
+        Object::Integer(value) => out.extend_from_slice(value.to_string().as_bytes()),
+        Object::Real(value) => out.extend_from_slice(value.to_string().as_bytes()),
+        Object::Name(name) => serialize_name(out, name),
+        Object::String(text, StringFormat::Literal) => {
+            out.push(b'(');
+            for &byte in text {
+                match byte {
+                    b'(' | b')' | b'\\' => out.extend_from_slice(&[b'\\', byte]),
+                    b'\r' => out.extend_from_slice(b"\\r"),
+                    _ => out.push(byte),
+                }
+            }
+            out.push(b')');
+        }
+        Object::String(text, StringFormat::Hexadecimal) => {
+            out.push(b'<');
+            for byte in text {
+                out.extend_from_slice(format!("{:02X}", byte).as_bytes());
+            }
+            out.push(b'>');
+        }
+        Object::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b' ');
+                }
+                serialize_object(out, item);
+            }
+            out.push(b']');
+        }
+        Object::Dictionary(dict) => serialize_dictionary(out, dict),
+        Object::Stream(stream) => {
+            let mut dict = stream.dict.clone();
+            dict.set("Length", stream.content.len() as i64);
+            serialize_dictionary(out, &dict);
+            out.extend_from_slice(b"stream\n");
+            out.extend_from_slice(&stream.content);
+            out.extend_from_slice(b"\nendstream");
+        }
+        Object::Reference(id) => out.extend_from_slice(format!("{} {} R", id.0, id.1).as_bytes()),
+    }
+}
+
+fn serialize_dictionary(out: &mut Vec<u8>, dict: &lopdf::Dictionary) {
+    out.extend_from_slice(b"<<");
+    for (key, value) in dict {
+        serialize_name(out, key);
+        out.push(b' ');
+        serialize_object(out, value);
+    }
+    out.extend_from_slice(b">>");
+}
+
+/// Whitespace, delimiters and bytes outside printable ASCII are written as
+/// `#XX` escapes
+fn serialize_name(out: &mut Vec<u8>, name: &[u8]) {
+    out.push(b'/');
+    for &byte in name {
+        if b" \t\n\r\x0C()<>[]{}/%#".contains(&byte) || !(33..=126).contains(&byte) {
+            out.extend_from_slice(format!("#{:02X}", byte).as_bytes());
+        } else {
+            out.push(byte);
+        }
+    }
+}
+
+// ============================================================================
+// Booklet Imposition
+// ============================================================================
+
+/// Reorder and 2-up the pages of `bytes` for saddle-stitch printing: each
+/// landscape sheet holds two pages side by side, printed double-sided and
+/// folded down the middle. The page count is padded with blanks to a
+/// multiple of four.
+pub fn impose_booklet(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
+    use lopdf::{Dictionary, Object, ObjectId, Stream};
+
+    let pdf_err = |e: lopdf::Error| AppError::PdfError(e.to_string());
+    let mut doc = lopdf::Document::load_mem(bytes).map_err(pdf_err)?;
+    let pages_id = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Pages"))
+        .and_then(Object::as_reference)
+        .map_err(pdf_err)?;
+
+    // Each page becomes a form that the sheets draw scaled down
+    let mut forms: Vec<(ObjectId, [f32; 4])> = Vec::new();
+    for page_id in doc.get_pages().into_values() {
+        let page = doc.get_dictionary(page_id).map_err(pdf_err)?.clone();
+        let attribute = |key: &str| {
+            page.get(key.as_bytes())
+                .ok()
+                .cloned()
+                .or_else(|| inherited_page_attribute(&doc, &page, key))
+        };
+        let media_box = attribute("MediaBox")
+            .ok_or_else(|| AppError::PdfError("Page has no MediaBox".to_string()))?;
+        let resources = attribute("Resources").unwrap_or_else(|| Object::Dictionary(Dictionary::new()));
+        let corners = media_box
+            .as_array()
+            .map_err(pdf_err)?
+            .iter()
+            .map(Object::as_float)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(pdf_err)?;
+        let corners: [f32; 4] = corners
+            .try_into()
+            .map_err(|_| AppError::PdfError("Page MediaBox is not four numbers".to_string()))?;
+
+        let mut form = Dictionary::new();
+        form.set("Type", "XObject");
+        form.set("Subtype", "Form");
+        form.set("BBox", media_box);
+        form.set("Resources", resources);
+        let mut stream = Stream::new(form, doc.get_page_content(page_id).map_err(pdf_err)?);
+        let _ = stream.compress();
+        forms.push((doc.add_object(stream), corners));
+    }
+    let Some(&(_, [x0, y0, x1, y1])) = forms.first() else {
+        return Ok(bytes.to_vec());
+    };
+
+    // Sheets are the first page turned landscape, one page to each half
+    let sheet_width = y1 - y0;
+    let sheet_height = x1 - x0;
+    let slot_width = sheet_width / 2.0;
+
+    let page_count = forms.len().div_ceil(4) * 4;
+    let mut kids = Vec::new();
+    for sheet in 0..page_count / 2 {
+        // Sheet 0 carries the last and first pages, its back the second and
+        // second-to-last, and so on inward
+        let front = sheet % 2 == 0;
+        let pair = sheet / 2;
+        let (left, right) = if front {
+            (page_count - 1 - 2 * pair, 2 * pair)
+        } else {
+            (2 * pair + 1, page_count - 2 - 2 * pair)
+        };
+
+        let mut content = String::new();
+        let mut xobjects = Dictionary::new();
+        for (slot, index) in [left, right].into_iter().enumerate() {
+            let Some(&(form_id, [x0, y0, x1, y1])) = forms.get(index) else {
+                continue;
+            };
+            let (width, height) = (x1 - x0, y1 - y0);
+            let scale = (slot_width / width).min(sheet_height / height);
+            let tx = slot as f32 * slot_width + (slot_width - scale * width) / 2.0 - scale * x0;
+            let ty = (sheet_height - scale * height) / 2.0 - scale * y0;
+            let name = format!("P{}", slot);
+            content.push_str(&format!(
+                "q {:.4} 0 0 {:.4} {:.4} {:.4} cm /{} Do Q\n",
+                scale, scale, tx, ty, name
+            ));
+            xobjects.set(name, Object::Reference(form_id));
+        }
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+
+        let mut resources = Dictionary::new();
+        resources.set("XObject", xobjects);
+        let mut page = Dictionary::new();
+        page.set("Type", "Page");
+        page.set("Parent", Object::Reference(pages_id));
+        page.set(
+            "MediaBox",
+            vec![0.into(), 0.into(), Object::Real(sheet_width), Object::Real(sheet_height)],
+        );
+        page.set("Resources", resources);
+        page.set("Contents", Object::Reference(content_id));
+        kids.push(Object::Reference(doc.add_object(page)));
+    }
+
+    let tree = doc.get_dictionary_mut(pages_id).map_err(pdf_err)?;
+    tree.set("Count", kids.len() as i64);
+    tree.set("Kids", kids);
+    // The original pages are no longer reachable from the tree
+    doc.prune_objects();
+
+    let mut out = Vec::new();
+    doc.save_to(&mut out)?;
+    Ok(out)
+}
+
+// ============================================================================
+// Drawing Utilities
+// ============================================================================
+
+fn draw_text(layer: &PdfLayerReference, fonts: &Fonts, text: &TextElement) {
+    // Suit symbols ("2\u{2663} Openings") come from the suit font, the runs
+    // between them from the text's own font, each placed after the last
+    if text.font != FontStyle::Symbol && text.text.chars().any(is_suit_symbol) {
+        let (sin, cos) = text.rotation.degrees().to_radians().sin_cos();
+        let mut offset = 0.0;
+        for (run, suits) in suit_runs(&text.text) {
+            let font = if suits { FontStyle::Symbol } else { text.font };
+            let run_text = TextElement {
+                text: run.to_string(),
+                x: text.x + offset * cos,
+                y: text.y + offset * sin,
+                font,
+                ..text.clone()
+            };
+            draw_text_run(layer, fonts, &run_text);
+            offset += text_width_mm(run, text.size, font);
+        }
+        return;
+    }
+    draw_text_run(layer, fonts, text);
+}
+
+/// `text` split where it changes between suit symbols and anything else;
+/// true marks the symbol runs
+fn suit_runs(text: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut in_suits = false;
+    for (index, c) in text.char_indices() {
+        let suit = is_suit_symbol(c);
+        if index > start && suit != in_suits {
+            runs.push((&text[start..index], in_suits));
+            start = index;
+        }
+        in_suits = suit;
+    }
+    if start < text.len() {
+        runs.push((&text[start..], in_suits));
+    }
+    runs
+}
+
+fn draw_text_run(layer: &PdfLayerReference, fonts: &Fonts, text: &TextElement) {
+    if text.rotation == Rotation::Upright {
+        layer.use_text(&text.text, text.size, Mm(text.x), Mm(text.y), fonts.get(text.font));
+        return;
+    }
+
+    // The text matrix places and turns the text in one step
+    let font = fonts.get(text.font);
+    layer.begin_text_section();
+    layer.set_font(font, text.size);
+    layer.set_text_matrix(TextMatrix::TranslateRotate(
+        Mm(text.x).into(),
+        Mm(text.y).into(),
+        text.rotation.degrees(),
+    ));
+    layer.write_text(&text.text, font);
+    layer.end_text_section();
+}
+
+fn draw_line(layer: &PdfLayerReference, line: &LineElement) {
+    let color = line.stroke.color;
+    layer.set_outline_color(Color::Rgb(Rgb::new(color.r, color.g, color.b, None)));
+    layer.set_outline_thickness(line.stroke.thickness);
+
+    let points = vec![
+        (Point::new(Mm(line.x1), Mm(line.y1)), false),
+        (Point::new(Mm(line.x2), Mm(line.y2)), false),
+    ];
+    layer.add_line(Line {
+        points,
+        is_closed: false,
+    });
+}
+
+/// Bezier handles this far along each side of a rounded corner make a
+/// quarter circle
+const CORNER_HANDLE: f32 = 0.5523;
+
+fn draw_rect(layer: &PdfLayerReference, rect: &RectElement) {
+    let mode = match (rect.style.fill, rect.style.stroke) {
+        (Some(_), Some(_)) => PaintMode::FillStroke,
+        (Some(_), None) => PaintMode::Fill,
+        (None, Some(_)) => PaintMode::Stroke,
+        (None, None) => return,
+    };
+    if let Some(fill) = rect.style.fill {
+        layer.set_fill_color(Color::Rgb(Rgb::new(fill.r, fill.g, fill.b, None)));
+    }
+    if let Some(stroke) = rect.style.stroke {
+        let color = stroke.color;
+        layer.set_outline_color(Color::Rgb(Rgb::new(color.r, color.g, color.b, None)));
+        layer.set_outline_thickness(stroke.thickness);
+    }
+
+    let (left, bottom) = (rect.x, rect.y);
+    let (right, top) = (rect.x + rect.width, rect.y + rect.height);
+    let radius = rect.style.radius.min(rect.width / 2.0).min(rect.height / 2.0);
+    let point = |x: f32, y: f32, handle: bool| (Point::new(Mm(x), Mm(y)), handle);
+    let ring = if radius <= 0.0 {
+        vec![
+            point(left, bottom, false),
+            point(right, bottom, false),
+            point(right, top, false),
+            point(left, top, false),
+        ]
+    } else {
+        // Each side, then its corner as a curve: the point before two handles
+        // is flagged so printpdf joins them with a Bezier
+        let k = radius * CORNER_HANDLE;
+        let r = radius;
+        vec![
+            point(left + r, bottom, false),
+            point(right - r, bottom, true),
+            point(right - r + k, bottom, true),
+            point(right, bottom + r - k, false),
+            point(right, bottom + r, false),
+            point(right, top - r, true),
+            point(right, top - r + k, true),
+            point(right - r + k, top, false),
+            point(right - r, top, false),
+            point(left + r, top, true),
+            point(left + r - k, top, true),
+            point(left, top - r + k, false),
+            point(left, top - r, false),
+            point(left, bottom + r, true),
+            point(left, bottom + r - k, true),
+            point(left + r - k, bottom, false),
+            point(left + r, bottom, false),
+        ]
+    };
+    layer.add_polygon(Polygon {
+        rings: vec![ring],
+        mode,
+        winding_order: WindingOrder::NonZero,
+    });
+}
+
+fn draw_image(layer: &PdfLayerReference, element: &ImageElement) {
+    let prepared = &element.image;
+
+    // Create image for printpdf (each document owns its copy of the pixels)
+    let image = Image::from(ImageXObject {
+        width: Px(prepared.width_px as usize),
+        height: Px(prepared.height_px as usize),
+        color_space: ColorSpace::Rgb,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: element.interpolate,
+        image_data: prepared.rgb.clone(),
+        image_filter: None,
+        clipping_bbox: None,
+        smask: None,
+    });
+
+    // Calculate DPI to achieve desired physical size
+    // DPI = pixels / (mm / 25.4)
+    let dpi = (prepared.width_px as f32) / (element.width / 25.4);
+
+    image.add_to_layer(
+        layer.clone(),
+        ImageTransform {
+            translate_x: Some(Mm(element.x)),
+            translate_y: Some(Mm(element.y)),
+            dpi: Some(dpi),
+            ..Default::default()
+        },
+    );
+}
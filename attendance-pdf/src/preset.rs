@@ -0,0 +1,110 @@
+// Class presets: the options that stay the same week to week, saved under a
+// name so the weekly command is just `--preset tuesday-beginner`.
+//
+// Each preset is a small TOML file in the presets directory. Local roster and
+// logo paths are stored absolute so a preset works from any directory.
+
+use crate::net;
+use crate::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Overrides the presets directory (used by the tests, and handy for keeping
+/// presets in a shared folder)
+pub const PRESETS_DIR_ENV: &str = "ATTENDANCE_PDF_PRESETS";
+
+/// Saved class options; anything not set falls back to the usual default
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    pub class: Option<String>,
+    pub teacher: Option<String>,
+    pub location: Option<String>,
+    pub venue: Option<String>,
+    pub roster: Option<String>,
+    pub rows: Option<u32>,
+    pub logo: Option<String>,
+}
+
+/// `$ATTENDANCE_PDF_PRESETS`, else `attendance-pdf/presets` under
+/// `$XDG_CONFIG_HOME` or `~/.config`
+pub fn presets_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(PRESETS_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default();
+    config_home.join("attendance-pdf").join("presets")
+}
+
+/// Preset names become file names, so keep them to letters, digits, `-`
+/// and `_`
+fn preset_path(dir: &Path, name: &str) -> Result<PathBuf, AppError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(AppError::PresetError(format!(
+            "invalid preset name \"{}\" (use letters, digits, '-' and '_')",
+            name
+        )));
+    }
+    Ok(dir.join(format!("{}.toml", name)))
+}
+
+/// Write `preset` as `name`, replacing any preset of that name
+pub fn save_preset(dir: &Path, name: &str, preset: &Preset) -> Result<PathBuf, AppError> {
+    let path = preset_path(dir, name)?;
+    let content = toml::to_string(preset)
+        .map_err(|e| AppError::PresetError(format!("{}: {}", name, e)))?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| AppError::PresetError(format!("{}: {}", dir.display(), e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::PresetError(format!("{}: {}", path.display(), e)))?;
+    Ok(path)
+}
+
+pub fn load_preset(dir: &Path, name: &str) -> Result<Preset, AppError> {
+    let path = preset_path(dir, name)?;
+    if !path.exists() {
+        return Err(AppError::PresetError(format!(
+            "no preset named \"{}\" in {}",
+            name,
+            dir.display()
+        )));
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::PresetError(format!("{}: {}", path.display(), e)))?;
+    toml::from_str(&content).map_err(|e| AppError::PresetError(format!("{}: {}", path.display(), e)))
+}
+
+/// Saved preset names, sorted; none when the directory does not exist yet
+pub fn list_presets(dir: &Path) -> Result<Vec<String>, AppError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::PresetError(format!("{}: {}", dir.display(), e))),
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Absolute form of a local file path so the preset works from anywhere;
+/// URLs are kept as given
+pub fn absolute_source(source: &str) -> Result<String, AppError> {
+    if net::is_url(source) {
+        return Ok(source.to_string());
+    }
+    std::fs::canonicalize(source)
+        .map(|path| path.to_string_lossy().into_owned())
+        .map_err(|e| AppError::PresetError(format!("{}: {}", source, e)))
+}
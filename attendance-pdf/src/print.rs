@@ -0,0 +1,26 @@
+// Direct printing through the system spooler (CUPS `lp`), which forwards to
+// IPP printers, so scheduled jobs can print without anyone opening the file
+
+use crate::AppError;
+use std::path::Path;
+use std::process::Command;
+
+/// Queue `path` on `printer`, or on the system default printer when `None`
+pub fn print_file(path: &Path, printer: Option<&str>, job_title: &str) -> Result<(), AppError> {
+    let mut command = Command::new("lp");
+    if let Some(printer) = printer {
+        command.arg("-d").arg(printer);
+    }
+    command.arg("-t").arg(job_title).arg("--").arg(path);
+
+    let output = command
+        .output()
+        .map_err(|e| AppError::PrintError(format!("could not run lp: {}", e)))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(AppError::PrintError(stderr.trim().to_string()))
+    }
+}
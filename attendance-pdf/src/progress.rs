@@ -0,0 +1,175 @@
+// Convention card progress: which of the series' topics (Stayman, transfers,
+// weak twos, ...) each student has covered, as a class grid or a page per
+// student. The topics come from the schedule file.
+
+use crate::layout::{
+    layout_checkbox, FontStyle, Page, Rotation, SheetLayout, BORDER_STROKE, CHECKBOX_STROKE, ROW_STROKE,
+};
+use crate::metrics::text_width_mm;
+use crate::roster::display_name;
+use crate::{
+    format_date_display, AttendanceConfig, HEADER_FONT_SIZE, MARGIN_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM,
+    PAGE_WIDTH_MM, SMALL_FONT_SIZE, TITLE_FONT_SIZE,
+};
+
+/// Write-in rows on the class grid when there is no roster
+pub const DEFAULT_BLANK_PROGRESS_ROWS: usize = 16;
+
+const PROGRESS_TITLE: &str = "CONVENTION CARD PROGRESS";
+
+/// Class grid: a name column, then a narrow column per topic headed by the
+/// topic reading up the page
+const GRID_NAME_WIDTH_MM: f32 = 55.0;
+const GRID_TOPIC_MIN_WIDTH_MM: f32 = 8.0;
+const GRID_TOPIC_HEADER_HEIGHT_MM: f32 = 45.0;
+const GRID_ROW_HEIGHT_MM: f32 = 8.0;
+const GRID_BOX_SIZE_MM: f32 = 4.0;
+
+/// Per-student pages: a row per topic with a box and a date line
+const STUDENT_ROW_HEIGHT_MM: f32 = 12.0;
+const STUDENT_BOX_SIZE_MM: f32 = 5.0;
+
+/// What to print
+pub struct ProgressOptions {
+    /// Topics to tick off, in teaching order
+    pub topics: Vec<String>,
+    /// A page per student instead of one grid for the class
+    pub per_student: bool,
+}
+
+/// The class grid, or with `per_student` a checklist page for each student
+pub fn layout_progress(config: &AttendanceConfig, options: &ProgressOptions) -> SheetLayout {
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+
+    let names: Vec<Option<String>> = match &config.roster {
+        Some(roster) => roster.iter().map(|student| Some(display_name(student, config))).collect(),
+        None => vec![None; DEFAULT_BLANK_PROGRESS_ROWS],
+    };
+
+    if options.per_student {
+        for name in &names {
+            layout_student_checklist(&mut sheet, config, &options.topics, name.as_deref());
+        }
+    } else {
+        layout_class_grid(&mut sheet, config, &options.topics, &names);
+    }
+    sheet
+}
+
+/// Title, then the class and date; returns where the content starts
+fn layout_progress_heading(page: &mut Page, config: &AttendanceConfig, continued: bool) -> f32 {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let title = if continued {
+        format!("{} (continued)", PROGRESS_TITLE)
+    } else {
+        PROGRESS_TITLE.to_string()
+    };
+    page.text(title, TITLE_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+    let subtitle = format!("{} \u{2013} {}", config.class_name, format_date_display(&config.date));
+    page.text(subtitle, HEADER_FONT_SIZE, MARGIN_MM, top_y - 14.0, FontStyle::Bold);
+    page.line(MARGIN_MM, top_y - 18.0, MARGIN_MM + content_width, top_y - 18.0, BORDER_STROKE);
+    top_y - 22.0
+}
+
+/// Students down the side, topics across the top. Topics that do not fit
+/// across one page continue on further pages, as do long rosters.
+fn layout_class_grid(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    topics: &[String],
+    names: &[Option<String>],
+) {
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let topics_width = content_width - GRID_NAME_WIDTH_MM;
+    let topics_per_page = ((topics_width / GRID_TOPIC_MIN_WIDTH_MM) as usize).max(1);
+    let grid_top = PAGE_HEIGHT_MM - MARGIN_MM - 22.0 - GRID_TOPIC_HEADER_HEIGHT_MM;
+    let rows_per_page = (((grid_top - MARGIN_MM) / GRID_ROW_HEIGHT_MM) as usize).max(1);
+
+    for topic_chunk in topics.chunks(topics_per_page) {
+        let column_width = (topics_width / topic_chunk.len() as f32).min(2.0 * GRID_TOPIC_MIN_WIDTH_MM);
+        let right_x = MARGIN_MM + GRID_NAME_WIDTH_MM + column_width * topic_chunk.len() as f32;
+        for name_chunk in names.chunks(rows_per_page) {
+            let continued = !sheet.pages.is_empty();
+            let page = sheet.new_page();
+            let header_top = layout_progress_heading(page, config, continued);
+            let header_bottom = header_top - GRID_TOPIC_HEADER_HEIGHT_MM;
+            let grid_bottom = header_bottom - name_chunk.len() as f32 * GRID_ROW_HEIGHT_MM;
+
+            page.text("NAME", NORMAL_FONT_SIZE, MARGIN_MM + 2.0, header_bottom + 2.0, FontStyle::Bold);
+            for (index, topic) in topic_chunk.iter().enumerate() {
+                let x = MARGIN_MM + GRID_NAME_WIDTH_MM + index as f32 * column_width;
+                page.line(x, header_top, x, grid_bottom, ROW_STROKE);
+                // Shrunk to fit the header when long, reading up the page
+                let width = text_width_mm(topic, SMALL_FONT_SIZE, FontStyle::Regular);
+                let room = GRID_TOPIC_HEADER_HEIGHT_MM - 4.0;
+                let size = if width > room { SMALL_FONT_SIZE * room / width } else { SMALL_FONT_SIZE };
+                page.rotated_text(
+                    topic,
+                    size,
+                    x + column_width / 2.0 + 1.0,
+                    header_bottom + 2.0,
+                    FontStyle::Regular,
+                    Rotation::Left,
+                );
+            }
+            page.line(MARGIN_MM, header_bottom, right_x, header_bottom, BORDER_STROKE);
+
+            let mut y = header_bottom;
+            for name in name_chunk {
+                let text_y = y - GRID_ROW_HEIGHT_MM / 2.0 - 1.5;
+                if let Some(name) = name {
+                    page.text(name, NORMAL_FONT_SIZE, MARGIN_MM + 2.0, text_y, FontStyle::Regular);
+                }
+                for index in 0..topic_chunk.len() {
+                    let x = MARGIN_MM + GRID_NAME_WIDTH_MM + (index as f32 + 0.5) * column_width;
+                    let box_y = y - (GRID_ROW_HEIGHT_MM + GRID_BOX_SIZE_MM) / 2.0;
+                    layout_checkbox(page, x - GRID_BOX_SIZE_MM / 2.0, box_y, GRID_BOX_SIZE_MM, false);
+                }
+                y -= GRID_ROW_HEIGHT_MM;
+                page.line(MARGIN_MM, y, right_x, y, ROW_STROKE);
+            }
+        }
+    }
+}
+
+/// One student's topics with a box to tick and a line for the date covered
+fn layout_student_checklist(
+    sheet: &mut SheetLayout,
+    config: &AttendanceConfig,
+    topics: &[String],
+    name: Option<&str>,
+) {
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let right_x = MARGIN_MM + content_width;
+
+    let page = sheet.new_page();
+    let mut y = layout_progress_heading(page, config, false);
+    page.text("Student:", NORMAL_FONT_SIZE, MARGIN_MM, y - 4.0, FontStyle::Regular);
+    match name {
+        Some(name) => page.text(name, HEADER_FONT_SIZE, MARGIN_MM + 18.0, y - 4.0, FontStyle::Bold),
+        None => page.line(MARGIN_MM + 18.0, y - 5.0, MARGIN_MM + 100.0, y - 5.0, CHECKBOX_STROKE),
+    }
+    y -= 8.0;
+
+    for topic in topics {
+        if y - STUDENT_ROW_HEIGHT_MM < MARGIN_MM {
+            let page = sheet.new_page();
+            y = layout_progress_heading(page, config, true);
+        }
+        let page = sheet.pages.last_mut().expect("progress page was just added");
+
+        let box_y = y - (STUDENT_ROW_HEIGHT_MM + STUDENT_BOX_SIZE_MM) / 2.0;
+        layout_checkbox(page, MARGIN_MM + 4.0, box_y, STUDENT_BOX_SIZE_MM, false);
+        let text_y = y - STUDENT_ROW_HEIGHT_MM / 2.0 - 1.5;
+        page.text(topic, NORMAL_FONT_SIZE, MARGIN_MM + 14.0, text_y, FontStyle::Regular);
+        page.text("Date:", SMALL_FONT_SIZE, right_x - 45.0, text_y, FontStyle::Regular);
+        page.line(right_x - 35.0, text_y - 0.5, right_x - 2.0, text_y - 0.5, CHECKBOX_STROKE);
+        y -= STUDENT_ROW_HEIGHT_MM;
+        page.line(MARGIN_MM, y, right_x, y, ROW_STROKE);
+    }
+}
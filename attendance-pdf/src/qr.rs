@@ -0,0 +1,129 @@
+// QR code payload and rasterization
+
+use crate::assets::PreparedImage;
+use crate::{AppError, AttendanceConfig};
+use ::image::{DynamicImage, Luma};
+use qrcode::QrCode;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// QR code data payload
+#[derive(Debug, Serialize)]
+pub struct QrPayload {
+    pub app: String,
+    pub event_id: String,
+    pub name: String,
+    pub date: String,
+    pub teacher: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub substitute: Option<String>,
+}
+
+impl QrPayload {
+    pub fn for_event(config: &AttendanceConfig) -> Self {
+        QrPayload {
+            app: "bridge-attendance".to_string(),
+            event_id: config.event_id.clone(),
+            name: config.class_name.clone(),
+            date: config.date.format("%Y-%m-%d").to_string(),
+            teacher: config.teacher.clone(),
+            substitute: config.substitute.clone(),
+        }
+    }
+}
+
+/// Payload for a class pass, identifying the card by its serial
+#[derive(Debug, Serialize)]
+pub struct PassPayload {
+    pub app: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub serial: String,
+    pub event_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub student: Option<String>,
+}
+
+impl PassPayload {
+    pub fn for_pass(config: &AttendanceConfig, serial: &str, student: Option<&str>) -> Self {
+        PassPayload {
+            app: "bridge-attendance".to_string(),
+            kind: "pass".to_string(),
+            serial: serial.to_string(),
+            event_id: config.event_id.clone(),
+            name: config.class_name.clone(),
+            student: student.map(String::from),
+        }
+    }
+}
+
+/// Printer resolution QR codes are rasterized for by default
+pub const DEFAULT_QR_DPI: u32 = 600;
+
+/// Modules of white border the qrcode crate draws on each side
+const QUIET_ZONE_MODULES: usize = 4;
+
+/// The sheet's QR code, sharp at `config.qr_dpi` when printed `size_mm` across
+pub fn generate_qr_image(config: &AttendanceConfig, size_mm: f32) -> Result<DynamicImage, AppError> {
+    render_qr(&QrPayload::for_event(config), size_mm, config.qr_dpi)
+}
+
+pub fn generate_pass_qr_image(payload: &PassPayload, size_mm: f32, dpi: u32) -> Result<DynamicImage, AppError> {
+    render_qr(payload, size_mm, dpi)
+}
+
+/// Whole pixels per module giving at least `dpi` across `size_mm`, so every
+/// module maps onto the same number of printer dots
+pub fn module_pixels(modules: usize, size_mm: f32, dpi: u32) -> u32 {
+    let target_px = size_mm / 25.4 * dpi as f32;
+    ((target_px / modules as f32).ceil() as u32).max(1)
+}
+
+/// A plain link, such as the club's feedback form, for any phone camera
+pub fn generate_url_qr_image(url: &str, size_mm: f32, dpi: u32) -> Result<DynamicImage, AppError> {
+    render_qr_data(url.as_bytes(), size_mm, dpi)
+}
+
+/// The sheet's QR code, from the run's cache in `config.qr_codes`
+pub fn cached_qr_image(config: &AttendanceConfig, size_mm: f32) -> Result<Arc<PreparedImage>, AppError> {
+    let json = payload_json(&QrPayload::for_event(config))?;
+    cached_data_image(config, json, size_mm)
+}
+
+/// A plain link's QR code, from the run's cache in `config.qr_codes`
+pub fn cached_url_qr_image(
+    config: &AttendanceConfig,
+    url: &str,
+    size_mm: f32,
+) -> Result<Arc<PreparedImage>, AppError> {
+    cached_data_image(config, url.to_string(), size_mm)
+}
+
+fn cached_data_image(
+    config: &AttendanceConfig,
+    data: String,
+    size_mm: f32,
+) -> Result<Arc<PreparedImage>, AppError> {
+    let key = format!("{}@{}mm/{}dpi", data, size_mm, config.qr_dpi);
+    config
+        .qr_codes
+        .get_or_render(key, || render_qr_data(data.as_bytes(), size_mm, config.qr_dpi))
+}
+
+fn payload_json(payload: &impl Serialize) -> Result<String, AppError> {
+    serde_json::to_string(payload).map_err(|e| AppError::QrError(e.to_string()))
+}
+
+fn render_qr(payload: &impl Serialize, size_mm: f32, dpi: u32) -> Result<DynamicImage, AppError> {
+    render_qr_data(payload_json(payload)?.as_bytes(), size_mm, dpi)
+}
+
+fn render_qr_data(data: &[u8], size_mm: f32, dpi: u32) -> Result<DynamicImage, AppError> {
+    let code = QrCode::new(data)
+        .map_err(|e| AppError::QrError(e.to_string()))?;
+
+    let module = module_pixels(code.width() + 2 * QUIET_ZONE_MODULES, size_mm, dpi);
+    let image = code.render::<Luma<u8>>().module_dimensions(module, module).build();
+    Ok(DynamicImage::ImageLuma8(image))
+}
@@ -0,0 +1,149 @@
+// Fee receipts: numbered slips, three to a Letter page, for students paying
+// at the door. Receipt numbers start with the event ID so payments can be
+// reconciled against the attendance sheet for the same class.
+
+use crate::layout::{
+    layout_checkbox, layout_crop_marks, FontStyle, Page, SheetLayout, Trim, CHECKBOX_STROKE, ROW_STROKE,
+};
+use crate::{
+    format_date_display, serial_number, AppError, AttendanceConfig, HEADER_FONT_SIZE, MARGIN_MM,
+    NORMAL_FONT_SIZE, PAGE_HEIGHT_MM, PAGE_WIDTH_MM, SMALL_FONT_SIZE, TITLE_FONT_SIZE,
+};
+
+pub const RECEIPTS_PER_PAGE: u32 = 3;
+/// One page of blank receipts
+pub const DEFAULT_BLANK_RECEIPTS: u32 = RECEIPTS_PER_PAGE;
+
+/// What to print on each receipt
+pub struct ReceiptOptions {
+    /// Printed as given (e.g. "$15.00"); a write-in line when not set
+    pub amount: Option<String>,
+    /// Receipts to print when there is no roster to name the payers
+    pub blank_receipts: u32,
+    /// Crop marks and bleed between the slips
+    pub trim: Trim,
+}
+
+impl Default for ReceiptOptions {
+    fn default() -> Self {
+        ReceiptOptions {
+            amount: None,
+            blank_receipts: DEFAULT_BLANK_RECEIPTS,
+            trim: Trim::default(),
+        }
+    }
+}
+
+/// One receipt per roster entry, or `blank_receipts` with the payer left to
+/// fill in
+pub fn layout_receipts(config: &AttendanceConfig, options: &ReceiptOptions) -> Result<SheetLayout, AppError> {
+    let payers: Vec<Option<String>> = match &config.roster {
+        Some(roster) => roster
+            .iter()
+            .map(|student| Some(student.name.replace('*', "").trim().to_string()))
+            .collect(),
+        None => vec![None; options.blank_receipts as usize],
+    };
+
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+
+    // With bleed, slips sit a gutter apart so each can be trimmed on its own
+    let trim = options.trim;
+    let gutter = 2.0 * trim.bleed_mm;
+    let receipt_height = (PAGE_HEIGHT_MM - gutter * (RECEIPTS_PER_PAGE - 1) as f32) / RECEIPTS_PER_PAGE as f32;
+    let pitch = receipt_height + gutter;
+    for (index, payer) in payers.iter().enumerate() {
+        let index = index as u32;
+        let slot = index % RECEIPTS_PER_PAGE;
+        if slot == 0 {
+            let page = sheet.new_page();
+            if trim.crop_marks {
+                let ys: Vec<f32> = (1..RECEIPTS_PER_PAGE)
+                    .flat_map(|slot| {
+                        let y_top = PAGE_HEIGHT_MM - slot as f32 * pitch;
+                        [y_top, y_top + gutter]
+                    })
+                    .collect();
+                let bounds = (MARGIN_MM, PAGE_WIDTH_MM - MARGIN_MM, 0.0, PAGE_HEIGHT_MM);
+                layout_crop_marks(page, &[], &ys, bounds);
+            }
+        }
+        let page = sheet.pages.last_mut().expect("receipt page was just added");
+
+        let y_top = PAGE_HEIGHT_MM - slot as f32 * pitch;
+        if slot > 0 && !trim.crop_marks {
+            layout_cut_line(page, y_top);
+            if gutter > 0.0 {
+                layout_cut_line(page, y_top + gutter);
+            }
+        }
+        let number = serial_number(&config.event_id, index);
+        layout_receipt(page, config, options, &number, payer.as_deref(), y_top);
+    }
+
+    Ok(sheet)
+}
+
+fn layout_receipt(
+    page: &mut Page,
+    config: &AttendanceConfig,
+    options: &ReceiptOptions,
+    number: &str,
+    payer: Option<&str>,
+    y_top: f32,
+) {
+    let left_x = MARGIN_MM;
+    let right_x = PAGE_WIDTH_MM - MARGIN_MM;
+    let value_x = left_x + 38.0;
+
+    page.text("RECEIPT", TITLE_FONT_SIZE, left_x, y_top - 16.0, FontStyle::Bold);
+    page.text(format!("No. {}", number), HEADER_FONT_SIZE, right_x - 45.0, y_top - 16.0, FontStyle::Bold);
+
+    let mut field = |label: &str, value: Option<&str>, y: f32| {
+        page.text(label, NORMAL_FONT_SIZE, left_x, y, FontStyle::Regular);
+        match value {
+            Some(value) => page.text(value, NORMAL_FONT_SIZE, value_x, y, FontStyle::Bold),
+            None => page.line(value_x, y - 1.0, right_x, y - 1.0, CHECKBOX_STROKE),
+        }
+    };
+    field("Received from:", payer, y_top - 28.0);
+    field("Amount:", options.amount.as_deref(), y_top - 37.0);
+    field("For:", Some(&config.class_name), y_top - 46.0);
+    field("Date:", Some(&format_date_display(&config.date)), y_top - 55.0);
+
+    // Payment method
+    let method_y = y_top - 64.0;
+    page.text("Paid by:", NORMAL_FONT_SIZE, left_x, method_y, FontStyle::Regular);
+    let mut x = value_x;
+    for method in ["Cash", "Check", "Card", "Other"] {
+        layout_checkbox(page, x, method_y - 0.5, 3.5, false);
+        page.text(method, NORMAL_FONT_SIZE, x + 5.0, method_y, FontStyle::Regular);
+        x += 25.0;
+    }
+
+    // Teacher signature
+    let signature_y = y_top - 78.0;
+    page.line(value_x, signature_y, right_x, signature_y, CHECKBOX_STROKE);
+    page.text("Signature:", NORMAL_FONT_SIZE, left_x, signature_y + 1.0, FontStyle::Regular);
+    page.text(&config.teacher, SMALL_FONT_SIZE, value_x, signature_y - 4.0, FontStyle::Regular);
+    page.text(
+        format!("Event ID: {}", config.event_id),
+        SMALL_FONT_SIZE,
+        right_x - 30.0,
+        signature_y - 4.0,
+        FontStyle::Regular,
+    );
+}
+
+/// Dashed rule across the page between receipts
+fn layout_cut_line(page: &mut Page, y: f32) {
+    let mut x = MARGIN_MM / 2.0;
+    while x + 3.0 < PAGE_WIDTH_MM - MARGIN_MM / 2.0 {
+        page.line(x, y, x + 3.0, y, ROW_STROKE);
+        x += 5.0;
+    }
+}
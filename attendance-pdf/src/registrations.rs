@@ -0,0 +1,176 @@
+// Online registrations as the roster: the attendees of an Eventbrite event,
+// fetched on each run, or a SignUpGenius report export, for classes that take
+// sign-ups there rather than keeping a roster file.
+//
+// Eventbrite needs a private token, either in EVENTBRITE_TOKEN or the config
+// file's [eventbrite] table. SignUpGenius has no attendee API on its free
+// plans, so its "Export to CSV" report is read instead.
+
+use crate::net::{self, require_network};
+use crate::roster_file::{canonical_roster, csv_records};
+use crate::rsvp::{field, CsvHeader, NameColumns};
+use crate::{AppError, RosterEntry};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Takes precedence over the [eventbrite] token
+pub const TOKEN_ENV: &str = "EVENTBRITE_TOKEN";
+
+const EVENTBRITE_API: &str = "https://www.eventbriteapi.com/v3";
+/// Guards against a continuation that never ends
+const MAX_PAGES: usize = 20;
+
+const EMAIL_COLUMNS: [&str; 3] = ["email", "email address", "e-mail"];
+const PHONE_COLUMNS: [&str; 3] = ["phone", "phone number", "mobile"];
+
+/// `[eventbrite]`: private token from the account's API keys page
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventbriteSettings {
+    pub token: String,
+}
+
+/// Attendees of Eventbrite event `event_id` holding a ticket that has not
+/// been cancelled or refunded, one entry per person, sorted by name
+pub fn fetch_eventbrite_roster(
+    event_id: &str,
+    settings: Option<&EventbriteSettings>,
+) -> Result<Vec<RosterEntry>, AppError> {
+    require_network("Eventbrite")?;
+    let token = match std::env::var(TOKEN_ENV) {
+        Ok(token) => token,
+        Err(_) => settings.map(|settings| settings.token.clone()).ok_or_else(|| {
+            AppError::RegistrationError(format!(
+                "set {} or add an [eventbrite] token to the config file",
+                TOKEN_ENV
+            ))
+        })?,
+    };
+
+    let url = format!("{}/events/{}/attendees/", EVENTBRITE_API, event_id.trim());
+    let mut entries = Vec::new();
+    let mut continuation: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let mut request = net::get(&url)
+            .set("Authorization", &format!("Bearer {}", token))
+            .query("status", "attending");
+        if let Some(continuation) = &continuation {
+            request = request.query("continuation", continuation);
+        }
+        let response = net::call(request).map_err(|e| AppError::RegistrationError(format!("{}: {}", url, e)))?;
+        let body = net::read_string(response)
+            .map_err(|e| AppError::RegistrationError(format!("Failed to read response: {}", e)))?;
+        let page = parse_eventbrite_attendees(&body)?;
+        entries.extend(page.entries);
+        continuation = page.continuation;
+        if continuation.is_none() {
+            break;
+        }
+    }
+    Ok(canonical_roster(entries))
+}
+
+/// One page of the attendees endpoint
+#[derive(Debug, Default)]
+pub struct AttendeePage {
+    pub entries: Vec<RosterEntry>,
+    /// Where the next page starts, if there is one
+    pub continuation: Option<String>,
+}
+
+pub fn parse_eventbrite_attendees(body: &str) -> Result<AttendeePage, AppError> {
+    let response: AttendeesResponse = serde_json::from_str(body)
+        .map_err(|e| AppError::RegistrationError(format!("Unexpected Eventbrite response: {}", e)))?;
+    let entries = response
+        .attendees
+        .into_iter()
+        .filter(|attendee| !attendee.cancelled && !attendee.refunded)
+        .filter_map(|attendee| {
+            let profile = attendee.profile;
+            let name = profile.name.filter(|name| !name.trim().is_empty()).or_else(|| {
+                let name = format!("{} {}", profile.first_name?, profile.last_name.unwrap_or_default());
+                Some(name.trim().to_string())
+            })?;
+            Some(RosterEntry {
+                name,
+                email: profile.email,
+                phone: profile.cell_phone,
+                ..RosterEntry::default()
+            })
+        })
+        .collect();
+    let continuation = response
+        .pagination
+        .filter(|pagination| pagination.has_more_items)
+        .and_then(|pagination| pagination.continuation);
+    Ok(AttendeePage { entries, continuation })
+}
+
+pub fn read_signupgenius_roster(path: &Path) -> Result<Vec<RosterEntry>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::RegistrationError(format!("{}: {}", path.display(), e)))?;
+    parse_signupgenius_export(&content).map_err(|e| match e {
+        AppError::RegistrationError(message) => {
+            AppError::RegistrationError(format!("{}: {}", path.display(), message))
+        }
+        other => other,
+    })
+}
+
+/// Everyone signed up in a SignUpGenius report export, once each however
+/// many slots they took, sorted by name
+pub fn parse_signupgenius_export(content: &str) -> Result<Vec<RosterEntry>, AppError> {
+    let mut records = csv_records(content).into_iter();
+    let header = CsvHeader::new(records.next().unwrap_or_default());
+    let names = NameColumns::find(&header)
+        .ok_or_else(|| AppError::RegistrationError(NameColumns::MISSING.to_string()))?;
+    let email = header.column(&EMAIL_COLUMNS);
+    let phone = header.column(&PHONE_COLUMNS);
+
+    let optional = |record: &[String], column: Option<usize>| column.map(|index| field(record, index));
+    let entries = records
+        .map(|record| RosterEntry {
+            name: names.name(&record),
+            email: optional(&record, email),
+            phone: optional(&record, phone),
+            ..RosterEntry::default()
+        })
+        .collect();
+    Ok(canonical_roster(entries))
+}
+
+// ============================================================================
+// Eventbrite API Responses
+// ============================================================================
+
+#[derive(Deserialize)]
+struct AttendeesResponse {
+    #[serde(default)]
+    attendees: Vec<Attendee>,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Deserialize)]
+struct Pagination {
+    #[serde(default)]
+    has_more_items: bool,
+    continuation: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Attendee {
+    profile: Profile,
+    #[serde(default)]
+    cancelled: bool,
+    #[serde(default)]
+    refunded: bool,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    name: Option<String>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+    cell_phone: Option<String>,
+}
@@ -0,0 +1,26 @@
+// Output backends. Layout is computed once; each backend only decides how to
+// draw the placed elements, so new formats never duplicate layout logic.
+
+use crate::layout::SheetLayout;
+use crate::{pdf, AppError, AttendanceConfig};
+
+pub trait SheetRenderer {
+    /// File extension (without the dot) for documents this backend produces
+    fn extension(&self) -> &'static str;
+
+    /// Turn a finished layout into the bytes of an output document
+    fn render(&self, layout: &SheetLayout, config: &AttendanceConfig) -> Result<Vec<u8>, AppError>;
+}
+
+/// PDF via printpdf
+pub struct PdfRenderer;
+
+impl SheetRenderer for PdfRenderer {
+    fn extension(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn render(&self, layout: &SheetLayout, config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+        pdf::write_pdf(layout, config)
+    }
+}
@@ -0,0 +1,826 @@
+// Post-class reports for the director: reconciliation (who was on the
+// printed roster, who checked in, and how many tables that makes), the
+// supervised-play masterpoint award sheet, the monthly club report, charts
+// of attendance over the weeks for the club board, the semester summary for
+// the annual general meeting, and a CSV template for typing up a paper
+// sheet.
+//
+// The printed roster comes from the sheet's manifest; check-ins come from
+// `scan` logs. Names are matched ignoring case and roster marker symbols.
+
+use crate::checkin::CheckIn;
+use crate::layout::{
+    Color, FontStyle, Page, SheetLayout, Stroke, BORDER_STROKE, CHECKBOX_STROKE, PT_PER_MM, ROSTER_WALK_IN_ROWS,
+    ROW_STROKE,
+};
+use crate::manifest::ManifestEvent;
+use crate::metrics::text_width_mm;
+use crate::names::match_key;
+use crate::roster_file::csv_field;
+use crate::{
+    AppError, RosterEntry, HEADER_FONT_SIZE, MARGIN_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM, PAGE_WIDTH_MM,
+    SMALL_FONT_SIZE, TITLE_FONT_SIZE,
+};
+use chrono::{DateTime, NaiveDate, Weekday};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Players at a bridge table
+pub const PLAYERS_PER_TABLE: usize = 4;
+
+const LINE_HEIGHT_MM: f32 = 6.0;
+/// Masterpoint rows are tall enough to write an ACBL number in
+const AWARD_ROW_HEIGHT_MM: f32 = 8.0;
+/// Write-in rows after the attendees, for anyone missed at the door
+const AWARD_BLANK_ROWS: usize = 4;
+
+/// One event's roster set against its check-ins
+#[derive(Debug)]
+pub struct Reconciliation {
+    pub event_id: String,
+    pub class_name: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    /// Roster names that checked in, with their check-in
+    pub present: Vec<(String, CheckIn)>,
+    /// Roster names with no check-in
+    pub no_shows: Vec<String>,
+    /// Check-ins not on the roster (blank passes show their serial)
+    pub walk_ins: Vec<CheckIn>,
+    /// Present students marked junior on the printed roster, for youth
+    /// bridge grant reports
+    pub juniors: usize,
+}
+
+pub fn reconcile(event: &ManifestEvent, checkins: &[CheckIn]) -> Reconciliation {
+    let mut checkins: Vec<&CheckIn> = checkins
+        .iter()
+        .filter(|checkin| checkin.event_id == event.event_id)
+        .collect();
+
+    let juniors: HashSet<String> = event
+        .sheet
+        .iter()
+        .flat_map(|sheet| sheet.students.iter().flatten())
+        .filter(|entry| entry.junior)
+        .map(|entry| match_key(&entry.name))
+        .collect();
+
+    let mut present = Vec::new();
+    let mut no_shows = Vec::new();
+    for name in event.roster.iter().flatten() {
+        let key = match_key(name);
+        let found = checkins
+            .iter()
+            .position(|checkin| checkin.student.as_deref().is_some_and(|student| match_key(student) == key));
+        match found {
+            Some(index) => present.push((name.clone(), checkins.remove(index).clone())),
+            None => no_shows.push(name.clone()),
+        }
+    }
+
+    Reconciliation {
+        event_id: event.event_id.clone(),
+        class_name: event.class_name.clone(),
+        date: event.date.clone(),
+        juniors: present.iter().filter(|(name, _)| juniors.contains(&match_key(name))).count(),
+        present,
+        no_shows,
+        walk_ins: checkins.into_iter().cloned().collect(),
+    }
+}
+
+/// "7:02 PM" from the check-in's RFC 3339 time
+fn check_in_time(checkin: &CheckIn) -> String {
+    DateTime::parse_from_rfc3339(&checkin.time)
+        .map(|time| time.format("%-I:%M %p").to_string())
+        .unwrap_or_else(|_| checkin.time.clone())
+}
+
+impl Reconciliation {
+    pub fn attendance(&self) -> usize {
+        self.present.len() + self.walk_ins.len()
+    }
+
+    /// Full tables, and players left over
+    pub fn tables(&self) -> (usize, usize) {
+        (self.attendance() / PLAYERS_PER_TABLE, self.attendance() % PLAYERS_PER_TABLE)
+    }
+
+    /// Everyone who attended (roster order, then walk-ins), with ACBL
+    /// numbers looked up in `roster`
+    pub fn players(&self, roster: &[RosterEntry]) -> Vec<(String, Option<String>)> {
+        let acbl_number = |name: &str| {
+            let key = match_key(name);
+            roster
+                .iter()
+                .find(|entry| match_key(&entry.name) == key)
+                .and_then(|entry| entry.acbl_number.clone())
+        };
+        self.present
+            .iter()
+            .map(|(name, _)| name.replace('*', "").trim().to_string())
+            .chain(self.walk_ins.iter().map(|checkin| checkin.label().to_string()))
+            .map(|name| {
+                let number = acbl_number(&name);
+                (name, number)
+            })
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        let (tables, extra) = self.tables();
+        format!(
+            "Attended: {}   No-shows: {}   Walk-ins: {}   Tables: {}{}   Juniors: {}",
+            self.attendance(),
+            self.no_shows.len(),
+            self.walk_ins.len(),
+            tables,
+            if extra > 0 { format!(" + {} extra", extra) } else { String::new() },
+            self.juniors
+        )
+    }
+
+    /// status,name,serial,time; one row per roster name and walk-in
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("status,name,serial,time\n");
+        let mut row = |status: &str, name: &str, serial: &str, time: &str| {
+            let fields: Vec<String> = [status, name, serial, time].iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        };
+        for (name, checkin) in &self.present {
+            row("present", name, &checkin.serial, &checkin.time);
+        }
+        for checkin in &self.walk_ins {
+            row("walk-in", checkin.label(), &checkin.serial, &checkin.time);
+        }
+        for name in &self.no_shows {
+            row("no-show", name, "", "");
+        }
+        csv
+    }
+
+    /// One-page (or more) summary for the director
+    pub fn layout(&self) -> SheetLayout {
+        let mut sheet = SheetLayout {
+            width: PAGE_WIDTH_MM,
+            height: PAGE_HEIGHT_MM,
+            pages: Vec::new(),
+        };
+        sheet.new_page();
+        let mut y = PAGE_HEIGHT_MM - MARGIN_MM - TITLE_FONT_SIZE * 0.35;
+
+        let page = sheet.pages.last_mut().expect("report page was just added");
+        page.text("Attendance Reconciliation", TITLE_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        y -= 9.0;
+        page.text(
+            format!("{} \u{2013} {} \u{2013} Event {}", self.class_name, self.date, self.event_id),
+            NORMAL_FONT_SIZE,
+            MARGIN_MM,
+            y,
+            FontStyle::Regular,
+        );
+        y -= LINE_HEIGHT_MM;
+        page.text(self.summary(), NORMAL_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        y -= LINE_HEIGHT_MM;
+
+        let present: Vec<(String, String)> = self
+            .present
+            .iter()
+            .map(|(name, checkin)| (name.clone(), check_in_time(checkin)))
+            .collect();
+        let walk_ins: Vec<(String, String)> = self
+            .walk_ins
+            .iter()
+            .map(|checkin| (checkin.label().to_string(), check_in_time(checkin)))
+            .collect();
+        let no_shows: Vec<(String, String)> = self.no_shows.iter().map(|name| (name.clone(), String::new())).collect();
+
+        for (heading, rows) in [("Present", present), ("Walk-ins", walk_ins), ("No-shows", no_shows)] {
+            y -= 4.0;
+            layout_section(&mut sheet, &mut y, &format!("{} ({})", heading, rows.len()), &rows);
+        }
+        sheet
+    }
+}
+
+/// A heading and its name/time rows, starting new pages as needed
+fn layout_section(sheet: &mut SheetLayout, y: &mut f32, heading: &str, rows: &[(String, String)]) {
+    let time_x = PAGE_WIDTH_MM - MARGIN_MM - 25.0;
+    let ensure_room = |sheet: &mut SheetLayout, y: &mut f32| {
+        if *y < MARGIN_MM + LINE_HEIGHT_MM {
+            sheet.new_page();
+            *y = PAGE_HEIGHT_MM - MARGIN_MM;
+        }
+    };
+
+    ensure_room(sheet, y);
+    let page = sheet.pages.last_mut().expect("report has a page");
+    page.text(heading, HEADER_FONT_SIZE, MARGIN_MM, *y, FontStyle::Bold);
+    page.line(MARGIN_MM, *y - 1.5, PAGE_WIDTH_MM - MARGIN_MM, *y - 1.5, ROW_STROKE);
+    *y -= LINE_HEIGHT_MM + 1.0;
+
+    for (name, time) in rows {
+        ensure_room(sheet, y);
+        let page = sheet.pages.last_mut().expect("report has a page");
+        page.text(name, NORMAL_FONT_SIZE, MARGIN_MM + 3.0, *y, FontStyle::Regular);
+        if !time.is_empty() {
+            page.text(time, NORMAL_FONT_SIZE, time_x, *y, FontStyle::Regular);
+        }
+        *y -= LINE_HEIGHT_MM;
+    }
+}
+
+// ============================================================================
+// Masterpoint Award Sheet
+// ============================================================================
+
+/// Supervised-play masterpoint award sheet for one session
+#[derive(Debug)]
+pub struct MasterpointSheet {
+    pub club_number: String,
+    /// Masterpoints per player, as entered ("0.15")
+    pub award: String,
+    pub director: Option<String>,
+    pub class_name: String,
+    pub date: String,
+    pub event_id: String,
+    /// Attendees and their ACBL numbers, where the roster has one
+    pub players: Vec<(String, Option<String>)>,
+}
+
+impl MasterpointSheet {
+    pub fn new(
+        report: &Reconciliation,
+        roster: &[RosterEntry],
+        club_number: &str,
+        award: &str,
+        director: Option<&str>,
+    ) -> Self {
+        MasterpointSheet {
+            club_number: club_number.to_string(),
+            award: award.to_string(),
+            director: director.map(String::from),
+            class_name: report.class_name.clone(),
+            date: report.date.clone(),
+            event_id: report.event_id.clone(),
+            players: report.players(roster),
+        }
+    }
+
+    pub fn layout(&self) -> SheetLayout {
+        let mut sheet = SheetLayout {
+            width: PAGE_WIDTH_MM,
+            height: PAGE_HEIGHT_MM,
+            pages: Vec::new(),
+        };
+        let right = PAGE_WIDTH_MM - MARGIN_MM;
+        let columns = [MARGIN_MM, MARGIN_MM + 12.0, MARGIN_MM + 110.0, right - 30.0];
+
+        let mut y = PAGE_HEIGHT_MM - MARGIN_MM - TITLE_FONT_SIZE * 0.35;
+        let page = sheet.new_page();
+        page.text("Supervised Play Masterpoint Award", TITLE_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        y -= 9.0;
+        page.text(
+            format!(
+                "Club No. {}     {} \u{2013} {}     Event {}",
+                self.club_number, self.class_name, self.date, self.event_id
+            ),
+            NORMAL_FONT_SIZE,
+            MARGIN_MM,
+            y,
+            FontStyle::Regular,
+        );
+        y -= LINE_HEIGHT_MM;
+        page.text(
+            format!("Award per player: {} MP", self.award),
+            NORMAL_FONT_SIZE,
+            MARGIN_MM,
+            y,
+            FontStyle::Bold,
+        );
+        y -= LINE_HEIGHT_MM + 4.0;
+
+        let rows = self
+            .players
+            .iter()
+            .map(|(name, number)| (name.as_str(), number.as_deref().unwrap_or_default(), self.award.as_str()))
+            .chain(std::iter::repeat_n(("", "", ""), AWARD_BLANK_ROWS));
+        let mut header = true;
+        for (index, (name, number, award)) in rows.enumerate() {
+            if y < MARGIN_MM + 30.0 {
+                sheet.new_page();
+                y = PAGE_HEIGHT_MM - MARGIN_MM;
+                header = true;
+            }
+            let page = sheet.pages.last_mut().expect("award sheet has a page");
+            if header {
+                for (x, label) in columns.iter().zip(["#", "Player", "ACBL No.", "Award"]) {
+                    page.text(label, HEADER_FONT_SIZE, *x, y, FontStyle::Bold);
+                }
+                page.line(MARGIN_MM, y - 2.0, right, y - 2.0, ROW_STROKE);
+                y -= AWARD_ROW_HEIGHT_MM;
+                header = false;
+            }
+            page.text((index + 1).to_string(), NORMAL_FONT_SIZE, columns[0], y, FontStyle::Regular);
+            page.text(name, NORMAL_FONT_SIZE, columns[1], y, FontStyle::Regular);
+            page.text(number, NORMAL_FONT_SIZE, columns[2], y, FontStyle::Regular);
+            page.text(award, NORMAL_FONT_SIZE, columns[3], y, FontStyle::Regular);
+            page.line(MARGIN_MM, y - 2.5, right, y - 2.5, ROW_STROKE);
+            y -= AWARD_ROW_HEIGHT_MM;
+        }
+
+        // Director's certification at the foot of the last page
+        let page = sheet.pages.last_mut().expect("award sheet has a page");
+        let y = MARGIN_MM + 10.0;
+        page.text("Director:", NORMAL_FONT_SIZE, MARGIN_MM, y, FontStyle::Regular);
+        match &self.director {
+            Some(director) => page.text(director, NORMAL_FONT_SIZE, MARGIN_MM + 18.0, y, FontStyle::Bold),
+            None => page.line(MARGIN_MM + 18.0, y - 1.0, MARGIN_MM + 75.0, y - 1.0, CHECKBOX_STROKE),
+        }
+        page.text("Signature", SMALL_FONT_SIZE, MARGIN_MM + 80.0, y - 5.0, FontStyle::Regular);
+        page.line(MARGIN_MM + 80.0, y - 1.0, right - 40.0, y - 1.0, CHECKBOX_STROKE);
+        page.text("Date", SMALL_FONT_SIZE, right - 35.0, y - 5.0, FontStyle::Regular);
+        page.line(right - 35.0, y - 1.0, right, y - 1.0, CHECKBOX_STROKE);
+        sheet
+    }
+}
+
+// ============================================================================
+// Monthly Club Report
+// ============================================================================
+
+/// Columns written by [`monthly_report_csv`], in order
+pub const MONTHLY_REPORT_COLUMNS: [&str; 8] =
+    ["date", "class", "teacher", "event_id", "players", "juniors", "tables", "acbl_numbers"];
+
+/// One row per session, in date order: attendance, tables (players / 4, so
+/// 14 players is 3.5 tables) and the ACBL numbers of players on `roster`,
+/// space-separated
+pub fn monthly_report_csv(sessions: &[(ManifestEvent, Reconciliation)], roster: &[RosterEntry]) -> String {
+    let mut sessions: Vec<&(ManifestEvent, Reconciliation)> = sessions.iter().collect();
+    sessions.sort_by(|(a, _), (b, _)| a.date.cmp(&b.date).then_with(|| a.class_name.cmp(&b.class_name)));
+
+    let mut csv = MONTHLY_REPORT_COLUMNS.join(",") + "\n";
+    for (event, report) in sessions {
+        let numbers: Vec<String> = report.players(roster).into_iter().filter_map(|(_, number)| number).collect();
+        let players = report.attendance().to_string();
+        let juniors = report.juniors.to_string();
+        let tables = (report.attendance() as f32 / PLAYERS_PER_TABLE as f32).to_string();
+        let fields = [
+            event.date.as_str(),
+            event.class_name.as_str(),
+            event.teacher.as_str(),
+            event.event_id.as_str(),
+            players.as_str(),
+            juniors.as_str(),
+            tables.as_str(),
+            &numbers.join(" "),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// ============================================================================
+// Attendance Trends
+// ============================================================================
+
+/// Plot area of each chart
+const CHART_HEIGHT_MM: f32 = 70.0;
+/// Room left of the plot for the axis numbers
+const CHART_AXIS_WIDTH_MM: f32 = 10.0;
+/// Gridlines above the baseline
+const CHART_GRIDLINES: usize = 4;
+/// Week labels closer together than this skip some weeks
+const CHART_LABEL_SPACING_MM: f32 = 14.0;
+/// Share of each week's slot the bar fills
+const CHART_BAR_RATIO: f32 = 0.6;
+
+const BAR_COLOR: Color = Color::gray(0.45);
+const RETURNING_STROKE: Stroke = Stroke { color: Color::BLACK, thickness: 1.5 };
+const NEW_STROKE: Stroke = Stroke { color: Color::gray(0.6), thickness: 1.5 };
+
+/// Players in one week, split by whether they came to an earlier session
+#[derive(Debug, PartialEq)]
+pub struct WeekAttendance {
+    /// Monday of the week
+    pub week: NaiveDate,
+    pub sessions: usize,
+    pub players: usize,
+    /// Players at their first session in the history
+    pub new_players: usize,
+}
+
+impl WeekAttendance {
+    pub fn returning_players(&self) -> usize {
+        self.players - self.new_players
+    }
+}
+
+/// Weekly attendance over a run of sessions, charted for the club board
+#[derive(Debug)]
+pub struct TrendReport {
+    /// Every week from the first session to the last, including weeks with
+    /// no class so gaps show in the charts
+    pub weeks: Vec<WeekAttendance>,
+}
+
+impl TrendReport {
+    pub fn new(sessions: &[(ManifestEvent, Reconciliation)]) -> Result<Self, AppError> {
+        let mut dated = sessions
+            .iter()
+            .map(|(event, report)| {
+                NaiveDate::parse_from_str(&event.date, "%Y-%m-%d")
+                    .map(|date| (date, report))
+                    .map_err(|_| AppError::DateError(format!("{} (event {})", event.date, event.event_id)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        dated.sort_by_key(|(date, _)| *date);
+
+        let monday = |date: NaiveDate| date.week(Weekday::Mon).first_day();
+        let (Some((first, _)), Some((last, _))) = (dated.first(), dated.last()) else {
+            return Ok(TrendReport { weeks: Vec::new() });
+        };
+        let (first_week, last_week) = (monday(*first), monday(*last));
+        let mut weeks: Vec<WeekAttendance> = first_week
+            .iter_weeks()
+            .take_while(|week| *week <= last_week)
+            .map(|week| WeekAttendance {
+                week,
+                sessions: 0,
+                players: 0,
+                new_players: 0,
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        for (date, report) in dated {
+            let week = &mut weeks[(monday(date) - first_week).num_weeks() as usize];
+            week.sessions += 1;
+            for (name, _) in report.players(&[]) {
+                week.players += 1;
+                if seen.insert(match_key(&name)) {
+                    week.new_players += 1;
+                }
+            }
+        }
+        Ok(TrendReport { weeks })
+    }
+
+    /// Players per week as bars, then new and returning players as lines
+    pub fn layout(&self) -> SheetLayout {
+        let mut sheet = SheetLayout {
+            width: PAGE_WIDTH_MM,
+            height: PAGE_HEIGHT_MM,
+            pages: Vec::new(),
+        };
+        let page = sheet.new_page();
+        let mut y = PAGE_HEIGHT_MM - MARGIN_MM - TITLE_FONT_SIZE * 0.35;
+        page.text("Attendance Trends", TITLE_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        y -= 9.0;
+
+        let (Some(first), Some(last)) = (self.weeks.first(), self.weeks.last()) else {
+            page.text("No sessions to chart", NORMAL_FONT_SIZE, MARGIN_MM, y, FontStyle::Regular);
+            return sheet;
+        };
+        let sessions: usize = self.weeks.iter().map(|week| week.sessions).sum();
+        let new_players: usize = self.weeks.iter().map(|week| week.new_players).sum();
+        page.text(
+            format!(
+                "Weeks of {} to {} \u{2013} {} sessions, {} different players",
+                first.week.format("%b %-d, %Y"),
+                last.week.format("%b %-d, %Y"),
+                sessions,
+                new_players
+            ),
+            NORMAL_FONT_SIZE,
+            MARGIN_MM,
+            y,
+            FontStyle::Regular,
+        );
+
+        let plot_x = MARGIN_MM + CHART_AXIS_WIDTH_MM;
+        let plot_width = PAGE_WIDTH_MM - MARGIN_MM - plot_x;
+        let slot = plot_width / self.weeks.len() as f32;
+        let center = |index: usize| plot_x + (index as f32 + 0.5) * slot;
+
+        // Players per week
+        y -= 12.0;
+        page.text("Players per week", HEADER_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        let plot_y = y - 6.0 - CHART_HEIGHT_MM;
+        let max_players = self.weeks.iter().map(|week| week.players).max().unwrap_or(0);
+        let scale = layout_chart_axes(page, &self.weeks, max_players, plot_x, plot_y, plot_width);
+        let bar = Stroke {
+            color: BAR_COLOR,
+            thickness: slot * CHART_BAR_RATIO * PT_PER_MM,
+        };
+        for (index, week) in self.weeks.iter().enumerate().filter(|(_, week)| week.players > 0) {
+            let top = plot_y + week.players as f32 * scale;
+            page.line(center(index), plot_y, center(index), top, bar);
+            if slot >= 6.0 {
+                let label = week.players.to_string();
+                page.centered_text(label, SMALL_FONT_SIZE, center(index), top + 1.5, FontStyle::Regular);
+            }
+        }
+
+        // New and returning players
+        y = plot_y - 18.0;
+        page.text("New and returning players", HEADER_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        let plot_y = y - 6.0 - CHART_HEIGHT_MM;
+        let max_split = self
+            .weeks
+            .iter()
+            .map(|week| week.new_players.max(week.returning_players()))
+            .max()
+            .unwrap_or(0);
+        let scale = layout_chart_axes(page, &self.weeks, max_split, plot_x, plot_y, plot_width);
+        let returning: Vec<usize> = self.weeks.iter().map(WeekAttendance::returning_players).collect();
+        let new: Vec<usize> = self.weeks.iter().map(|week| week.new_players).collect();
+        let mut legend_x = plot_x;
+        for (label, stroke, values) in [("Returning", RETURNING_STROKE, returning), ("New", NEW_STROKE, new)] {
+            let point = |index: usize| (center(index), plot_y + values[index] as f32 * scale);
+            for index in 1..self.weeks.len() {
+                let ((x1, y1), (x2, y2)) = (point(index - 1), point(index));
+                page.line(x1, y1, x2, y2, stroke);
+            }
+            // A short dash marks each week, so a lone week still shows
+            for index in 0..self.weeks.len() {
+                let (x, y) = point(index);
+                page.line(x - 1.0, y, x + 1.0, y, stroke);
+            }
+
+            let legend_y = plot_y - 11.0;
+            page.line(legend_x, legend_y + 1.2, legend_x + 8.0, legend_y + 1.2, stroke);
+            page.text(label, SMALL_FONT_SIZE, legend_x + 10.0, legend_y, FontStyle::Regular);
+            legend_x += 10.0 + text_width_mm(label, SMALL_FONT_SIZE, FontStyle::Regular) + 8.0;
+        }
+        sheet
+    }
+}
+
+/// Axes, gridlines and week labels for a chart over `weeks` whose plot area
+/// has its bottom-left corner at (x, y), scaled so `max_value` fits. Returns
+/// the height of one player in mm.
+fn layout_chart_axes(page: &mut Page, weeks: &[WeekAttendance], max_value: usize, x: f32, y: f32, width: f32) -> f32 {
+    let step = max_value.div_ceil(CHART_GRIDLINES).max(1);
+    let scale = CHART_HEIGHT_MM / (step * CHART_GRIDLINES) as f32;
+    for gridline in 0..=CHART_GRIDLINES {
+        let value = (gridline * step).to_string();
+        let line_y = y + (gridline * step) as f32 * scale;
+        let stroke = if gridline == 0 { BORDER_STROKE } else { ROW_STROKE };
+        page.line(x, line_y, x + width, line_y, stroke);
+        page.right_aligned_text(value, SMALL_FONT_SIZE, x - 2.0, line_y - 1.2, FontStyle::Regular);
+    }
+    page.line(x, y, x, y + CHART_HEIGHT_MM, BORDER_STROKE);
+
+    // Label the weeks, skipping some when they would run together
+    let slot = width / weeks.len().max(1) as f32;
+    let every = (CHART_LABEL_SPACING_MM / slot).ceil().max(1.0) as usize;
+    for (index, week) in weeks.iter().enumerate().step_by(every) {
+        let label = week.week.format("%b %-d").to_string();
+        let center_x = x + (index as f32 + 0.5) * slot;
+        page.centered_text(label, SMALL_FONT_SIZE, center_x, y - 4.5, FontStyle::Regular);
+    }
+    scale
+}
+
+// ============================================================================
+// Semester Summary
+// ============================================================================
+
+/// Students listed under "Top attendees"
+pub const TOP_ATTENDEES: usize = 10;
+
+/// One class's sessions in the semester
+#[derive(Debug, PartialEq)]
+pub struct ClassSessions {
+    pub class_name: String,
+    /// Date and players of each session, in date order
+    pub sessions: Vec<(NaiveDate, usize)>,
+}
+
+/// Mailing list members before the semester and at its end
+#[derive(Debug, PartialEq)]
+pub struct MailingGrowth {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Attendance over a semester, for the annual general meeting
+#[derive(Debug)]
+pub struct SemesterSummary {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// By class name
+    pub classes: Vec<ClassSessions>,
+    /// Students with the most sessions, then by name
+    pub top_attendees: Vec<(String, usize)>,
+    /// Every attendance counted: one student at ten sessions is ten
+    pub student_sessions: usize,
+    /// Named students who came at least once
+    pub students: usize,
+    pub mailing: Option<MailingGrowth>,
+}
+
+impl SemesterSummary {
+    /// The sessions dated `from` to `to`, inclusive. `joined` holds the
+    /// sign-up date of each mailing list member, `None` for members from
+    /// before dates were kept.
+    pub fn new(
+        sessions: &[(ManifestEvent, Reconciliation)],
+        from: NaiveDate,
+        to: NaiveDate,
+        joined: Option<&[Option<NaiveDate>]>,
+    ) -> Result<Self, AppError> {
+        let mut classes: BTreeMap<&str, Vec<(NaiveDate, usize)>> = BTreeMap::new();
+        let mut attendance: HashMap<String, (String, usize)> = HashMap::new();
+        let mut student_sessions = 0;
+        for (event, report) in sessions {
+            let date = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d")
+                .map_err(|_| AppError::DateError(format!("{} (event {})", event.date, event.event_id)))?;
+            if date < from || date > to {
+                continue;
+            }
+            classes.entry(&event.class_name).or_default().push((date, report.attendance()));
+            student_sessions += report.attendance();
+
+            // Blank passes carry no name, so they count only in the totals
+            let names = report
+                .present
+                .iter()
+                .map(|(name, _)| name.replace('*', "").trim().to_string())
+                .chain(report.walk_ins.iter().filter_map(|checkin| checkin.student.clone()));
+            for name in names {
+                attendance.entry(match_key(&name)).or_insert((name, 0)).1 += 1;
+            }
+        }
+
+        let students = attendance.len();
+        let mut top_attendees: Vec<(String, usize)> = attendance.into_values().collect();
+        top_attendees.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        top_attendees.truncate(TOP_ATTENDEES);
+
+        let mailing = joined.map(|joined| MailingGrowth {
+            start: joined.iter().filter(|date| date.is_none_or(|date| date < from)).count(),
+            end: joined.iter().filter(|date| date.is_none_or(|date| date <= to)).count(),
+        });
+
+        Ok(SemesterSummary {
+            from,
+            to,
+            classes: classes
+                .into_iter()
+                .map(|(class_name, mut sessions)| {
+                    sessions.sort();
+                    ClassSessions {
+                        class_name: class_name.to_string(),
+                        sessions,
+                    }
+                })
+                .collect(),
+            top_attendees,
+            student_sessions,
+            students,
+            mailing,
+        })
+    }
+
+    /// Overview and top attendees, then a page per class
+    pub fn layout(&self) -> SheetLayout {
+        let mut sheet = SheetLayout {
+            width: PAGE_WIDTH_MM,
+            height: PAGE_HEIGHT_MM,
+            pages: Vec::new(),
+        };
+        let page = sheet.new_page();
+        let mut y = PAGE_HEIGHT_MM - MARGIN_MM - TITLE_FONT_SIZE * 0.35;
+        page.text("Semester Summary", TITLE_FONT_SIZE, MARGIN_MM, y, FontStyle::Bold);
+        y -= 9.0;
+        page.text(
+            format!("{} to {}", self.from.format("%B %-d, %Y"), self.to.format("%B %-d, %Y")),
+            NORMAL_FONT_SIZE,
+            MARGIN_MM,
+            y,
+            FontStyle::Regular,
+        );
+        y -= LINE_HEIGHT_MM + 4.0;
+
+        let sessions: usize = self.classes.iter().map(|class| class.sessions.len()).sum();
+        let mut overview = vec![
+            format!("Classes: {}", self.classes.len()),
+            format!("Sessions: {}", sessions),
+            format!("Student-sessions: {}", self.student_sessions),
+            format!("Different students: {}", self.students),
+        ];
+        if sessions > 0 {
+            overview.push(format!(
+                "Average attendance: {:.1} per session",
+                self.student_sessions as f32 / sessions as f32
+            ));
+        }
+        if let Some(mailing) = &self.mailing {
+            overview.push(format!(
+                "Mailing list: {} members, up from {} (+{})",
+                mailing.end,
+                mailing.start,
+                mailing.end - mailing.start
+            ));
+        }
+        let overview: Vec<(String, String)> = overview.into_iter().map(|line| (line, String::new())).collect();
+        layout_section(&mut sheet, &mut y, "Overview", &overview);
+
+        let top: Vec<(String, String)> = self
+            .top_attendees
+            .iter()
+            .enumerate()
+            .map(|(rank, (name, count))| (format!("{}. {}", rank + 1, name), format!("{} sessions", count)))
+            .collect();
+        y -= 4.0;
+        layout_section(&mut sheet, &mut y, "Top attendees", &top);
+
+        for class in &self.classes {
+            sheet.new_page();
+            let mut y = PAGE_HEIGHT_MM - MARGIN_MM;
+            let total: usize = class.sessions.iter().map(|(_, players)| players).sum();
+            let mut rows: Vec<(String, String)> = class
+                .sessions
+                .iter()
+                .map(|(date, players)| (date.format("%a, %b %-d, %Y").to_string(), format!("{} players", players)))
+                .collect();
+            rows.push((
+                format!("Total {}, average {:.1}", total, total as f32 / class.sessions.len() as f32),
+                String::new(),
+            ));
+            let heading = format!("{} ({} sessions)", class.class_name, class.sessions.len());
+            layout_section(&mut sheet, &mut y, &heading, &rows);
+        }
+        sheet
+    }
+}
+
+// ============================================================================
+// Transcription Template
+// ============================================================================
+
+/// Seats in blank-sheet order
+const SEATS: [&str; 4] = ["N", "S", "E", "W"];
+
+/// One row per grid row of each sheet, in the order they are printed (down
+/// the left roster column, then the right), with the pre-printed name or
+/// table and seat filled in and the rest left to type from the paper. Sheets
+/// printed with `--photo-consent` get a `photo_ok` column for the Y/N answer,
+/// and with `--time-column` a `checked_in` column for the time written at the
+/// door, typed as HH:MM.
+pub fn transcription_csv(events: &[ManifestEvent]) -> String {
+    let photo_consent = events.iter().any(|event| event.photo_consent);
+    let time_column = events.iter().any(|event| event.time_column);
+    let mut columns = vec!["event_id", "row", "name", "present", "table", "seat"];
+    if photo_consent {
+        columns.push("photo_ok");
+    }
+    if time_column {
+        columns.push("checked_in");
+    }
+
+    let mut csv = columns.join(",") + "\n";
+    for event in events {
+        let rows: Vec<(String, String, &str)> = match &event.roster {
+            Some(names) => {
+                let (left, right) = names.split_at(names.len().div_ceil(2));
+                [left, right]
+                    .iter()
+                    .flat_map(|column| {
+                        column
+                            .iter()
+                            .cloned()
+                            .chain((0..ROSTER_WALK_IN_ROWS).map(|_| String::new()))
+                    })
+                    .map(|name| (name, String::new(), ""))
+                    .collect()
+            }
+            None => (0..event.blank_rows as usize)
+                .map(|row| {
+                    let table = (row / PLAYERS_PER_TABLE + 1).to_string();
+                    (String::new(), table, SEATS[row % PLAYERS_PER_TABLE])
+                })
+                .collect(),
+        };
+        for (index, (name, table, seat)) in rows.iter().enumerate() {
+            let row = (index + 1).to_string();
+            let mut fields = vec![event.event_id.as_str(), row.as_str(), name, "", table, seat];
+            if photo_consent {
+                fields.push("");
+            }
+            if time_column {
+                fields.push("");
+            }
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+    }
+    csv
+}
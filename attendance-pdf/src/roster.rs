@@ -0,0 +1,182 @@
+// Per-student annotations: markers printed after a name on the roster, and
+// the legend line explaining the ones used on a sheet
+
+use crate::roster_file::Visibility;
+use crate::{names, AttendanceConfig, Student};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Memberships lapsing within this many days of the class are flagged
+pub const RENEWAL_WINDOW_DAYS: i64 = 30;
+pub const RENEWAL_MARKER: &str = "\u{2021}";
+
+/// Follows junior players' names
+pub const JUNIOR_MARKER: &str = "\u{2022}";
+
+/// Badge printed beside new students' names
+pub const NEW_BADGE: &str = "NEW";
+
+/// Birthday cake for Word and Excel; the PDF has no such glyph and draws one
+pub const BIRTHDAY_CAKE: &str = "\u{1F382}";
+
+/// A symbol written into roster names (e.g. "Alice Johnson †") and the
+/// legend text explaining it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RosterMarker {
+    pub symbol: String,
+    pub legend: String,
+}
+
+impl Student {
+    /// The membership has lapsed, or lapses within the renewal window of `date`
+    pub fn renewal_due(&self, date: NaiveDate) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= date + Duration::days(RENEWAL_WINDOW_DAYS))
+    }
+
+    /// The birthday falls in the Monday-to-Sunday week containing `date`
+    pub fn birthday_in_week(&self, date: NaiveDate) -> bool {
+        let Some((month, day)) = self.birthday else {
+            return false;
+        };
+        let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        (0..7)
+            .map(|offset| monday + Duration::days(offset))
+            .any(|week_day| week_day.month() == month && week_day.day() == day)
+    }
+}
+
+/// The name the student goes by: with `use_nicknames`, the nickname in place
+/// of the given name
+pub fn preferred_name(student: &Student, config: &AttendanceConfig) -> String {
+    match &student.nickname {
+        Some(nickname) if config.use_nicknames => names::with_nickname(&student.name, nickname),
+        _ => student.name.clone(),
+    }
+}
+
+/// Name as printed on the roster, followed by any markers
+pub fn display_name(student: &Student, config: &AttendanceConfig) -> String {
+    let mut name = names::display(&preferred_name(student, config), config.display_order);
+    if student.junior {
+        name = format!("{} {}", name, JUNIOR_MARKER);
+    }
+    if student.renewal_due(config.date) {
+        name = format!("{} {}", name, RENEWAL_MARKER);
+    }
+    name
+}
+
+/// The teacher's copy of a sheet: titled as such, with each student's needs
+/// printed after their note
+pub fn teacher_copy(config: &AttendanceConfig) -> AttendanceConfig {
+    let mut copy = config.clone();
+    copy.teacher_copy = true;
+    for student in copy.roster.iter_mut().flatten() {
+        if let Some(needs) = student.needs.take() {
+            student.note = Some(match student.note.take() {
+                Some(note) => format!("{}; {}", note, needs),
+                None => needs,
+            });
+        }
+    }
+    copy
+}
+
+/// The copy left on the sign-in table when a teacher's copy goes with it:
+/// only public fields, so no notes or needs
+pub fn sign_in_copy(config: &AttendanceConfig) -> AttendanceConfig {
+    let mut copy = config.clone();
+    copy.roster = config
+        .roster
+        .as_ref()
+        .map(|students| students.iter().map(|student| student.redacted(Visibility::Public)).collect());
+    copy
+}
+
+/// One entry per marker that appears on this sheet
+pub fn legend(config: &AttendanceConfig) -> Vec<String> {
+    let Some(roster) = &config.roster else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<String> = config
+        .markers
+        .iter()
+        .filter(|marker| roster.iter().any(|student| student.name.contains(&marker.symbol)))
+        .map(|marker| format!("{} {}", marker.symbol, marker.legend))
+        .collect();
+    let juniors = roster.iter().filter(|student| student.junior).count();
+    if juniors > 0 {
+        entries.push(format!("{} Junior player ({} on the roster)", JUNIOR_MARKER, juniors));
+    }
+    if roster.iter().any(|student| student.renewal_due(config.date)) {
+        let deadline = config.date + Duration::days(RENEWAL_WINDOW_DAYS);
+        entries.push(format!(
+            "{} Membership expires by {}: please remind to renew",
+            RENEWAL_MARKER,
+            deadline.format("%b %-d")
+        ));
+    }
+    let confirmed = roster.iter().filter(|student| student.confirmed).count();
+    if confirmed > 0 {
+        entries.push(format!(
+            "Box already ticked: confirmed online ({} of {} expected)",
+            confirmed,
+            roster.len()
+        ));
+        // Only the teacher's copy names who has not replied
+        let not_replied: Vec<String> = roster
+            .iter()
+            .filter(|student| !student.confirmed)
+            .map(|student| preferred_name(student, config))
+            .collect();
+        if config.teacher_copy && !not_replied.is_empty() {
+            entries.push(format!("No RSVP: {}", not_replied.join(", ")));
+        }
+    }
+    let new_students: Vec<String> = roster
+        .iter()
+        .filter(|student| student.is_new)
+        .map(|student| preferred_name(student, config))
+        .collect();
+    if !new_students.is_empty() {
+        entries.push(format!("{}: please welcome {}", NEW_BADGE, new_students.join(", ")));
+    }
+    if config.announce_birthdays {
+        let birthdays: Vec<String> = roster
+            .iter()
+            .filter(|student| student.birthday_in_week(config.date))
+            .map(|student| preferred_name(student, config))
+            .collect();
+        if !birthdays.is_empty() {
+            entries.push(format!("Happy birthday this week: {}!", birthdays.join(", ")));
+        }
+    }
+    entries
+}
+
+/// Heading over the mailing list sign-up when no teacher profile sets one
+pub const DEFAULT_MAILING_TITLE: &str = "JOIN MY MAILING LIST";
+
+/// The mailing list heading, starred when roster names point to it
+pub fn mailing_heading(config: &AttendanceConfig) -> String {
+    let title = config.mailing_title.as_deref().unwrap_or(DEFAULT_MAILING_TITLE);
+    if mailing_list_starred(config) {
+        format!("* {}", title)
+    } else {
+        title.to_string()
+    }
+}
+
+/// Without a configured meaning, a "*" in a roster name points to the
+/// mailing list sign-up, whose heading is starred to match
+pub fn mailing_list_starred(config: &AttendanceConfig) -> bool {
+    let configured = config.markers.iter().any(|marker| marker.symbol == "*");
+    !configured
+        && config
+            .roster
+            .as_ref()
+            .is_some_and(|roster| roster.iter().any(|student| student.name.contains('*')))
+}
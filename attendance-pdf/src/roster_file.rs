@@ -0,0 +1,414 @@
+// Roster files: reading the roster as JSON or as CSV exported from the club
+// spreadsheet, tidying it, and writing it back out in either format
+
+use crate::net::{self, require_network};
+use crate::rsvp::{field, CsvHeader, NameColumns};
+use crate::{AppError, RosterEntry, Student};
+use std::collections::HashMap;
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+
+/// Columns written by [`roster_csv`], in order
+pub const CSV_COLUMNS: [&str; 17] = [
+    "name", "nickname", "pronouns", "email", "phone", "acbl_number", "photo", "expires", "birthday", "style", "highlight",
+    "new", "junior", "confirmed", "note", "needs", "level",
+];
+
+/// Spreadsheet values that set a flag column; anything else leaves it unset
+const CSV_TRUE: [&str; 5] = ["yes", "y", "true", "x", "1"];
+
+/// How a roster is read or written
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum RosterFormat {
+    /// One row per student under a header row
+    Csv,
+    /// A JSON array of entries, as in the example roster
+    Json,
+}
+
+impl RosterFormat {
+    /// CSV for a file or URL ending in ".csv" (ignoring any query), otherwise
+    /// JSON
+    pub fn detect(source: &str) -> RosterFormat {
+        let path = source.split(['?', '#']).next().unwrap_or_default();
+        if path.to_lowercase().ends_with(".csv") {
+            RosterFormat::Csv
+        } else {
+            RosterFormat::Json
+        }
+    }
+
+    /// CSV for a file with a ".csv" extension, otherwise JSON
+    pub fn of_path(path: &Path) -> RosterFormat {
+        if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv")) {
+            RosterFormat::Csv
+        } else {
+            RosterFormat::Json
+        }
+    }
+}
+
+/// Who a roster field is meant for. Each level also sees the fields of the
+/// levels before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Visibility {
+    /// Names and what is printed beside them on the sign-in sheet
+    Public,
+    /// Adds notes and needs, for the teacher's copy and manifests
+    Teacher,
+    /// Adds email, phone and ACBL number, kept in the club's records
+    #[default]
+    Archive,
+}
+
+/// Visibility of each of the [`CSV_COLUMNS`]
+pub fn column_visibility(column: &str) -> Visibility {
+    match column {
+        "note" | "needs" | "level" => Visibility::Teacher,
+        "email" | "phone" | "acbl_number" => Visibility::Archive,
+        _ => Visibility::Public,
+    }
+}
+
+impl RosterEntry {
+    /// This entry without the fields `visibility` may not see
+    pub fn redacted(&self, visibility: Visibility) -> RosterEntry {
+        let mut entry = self.clone();
+        if visibility < Visibility::Archive {
+            entry.email = None;
+            entry.phone = None;
+            entry.acbl_number = None;
+        }
+        if visibility < Visibility::Teacher {
+            entry.note = None;
+            entry.needs = None;
+            entry.level = None;
+        }
+        entry
+    }
+}
+
+impl Student {
+    /// This student without the fields `visibility` may not see; students
+    /// carry no archive fields
+    pub fn redacted(&self, visibility: Visibility) -> Student {
+        let mut student = self.clone();
+        if visibility < Visibility::Teacher {
+            student.note = None;
+            student.needs = None;
+        }
+        student
+    }
+}
+
+/// Roster entries from a file, as CSV if its name ends in ".csv"
+pub fn read_roster_entries(path: &Path) -> Result<Vec<RosterEntry>, AppError> {
+    read_roster_file(path, RosterFormat::of_path(path))
+}
+
+fn read_roster_file(path: &Path, format: RosterFormat) -> Result<Vec<RosterEntry>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::RosterError(format!("{}: {}", path.display(), e)))?;
+    parse_roster(&content, format, &path.display().to_string())
+}
+
+/// Roster entries from a file, or fetched from an http(s) URL such as the
+/// club website so every teacher prints from the same list; `format`
+/// overrides the one picked from the name
+pub fn read_roster_source(source: &str, format: Option<RosterFormat>) -> Result<Vec<RosterEntry>, AppError> {
+    if !net::is_url(source) {
+        let path = Path::new(source);
+        return read_roster_file(path, format.unwrap_or_else(|| RosterFormat::of_path(path)));
+    }
+    require_network(source)?;
+    let response =
+        net::call(net::get(source)).map_err(|e| AppError::RosterError(format!("{}: {}", source, e)))?;
+    let content = net::read_string(response)
+        .map_err(|e| AppError::RosterError(format!("Failed to read response: {}", e)))?;
+    parse_roster(&content, format.unwrap_or_else(|| RosterFormat::detect(source)), source)
+}
+
+/// Roster entries from `content`; CSV errors name `source`
+fn parse_roster(content: &str, format: RosterFormat, source: &str) -> Result<Vec<RosterEntry>, AppError> {
+    match format {
+        RosterFormat::Json => {
+            serde_json::from_str(content).map_err(|e| AppError::RosterError(format!("Invalid JSON: {}", e)))
+        }
+        RosterFormat::Csv => parse_roster_csv(content).map_err(|e| match e {
+            AppError::RosterError(message) => AppError::RosterError(format!("{}: {}", source, message)),
+            other => other,
+        }),
+    }
+}
+
+/// Roster entries from CSV under a header row, as [`roster_csv`] writes it
+/// or a membership spreadsheet exports it: a "name" column (or "first name"
+/// and "last name") and any of the other [`CSV_COLUMNS`], matched ignoring
+/// case. Other columns and rows without a name are skipped.
+pub fn parse_roster_csv(content: &str) -> Result<Vec<RosterEntry>, AppError> {
+    let mut records = csv_records(content).into_iter();
+    let header = CsvHeader::new(records.next().unwrap_or_default());
+    let names =
+        NameColumns::find(&header).ok_or_else(|| AppError::RosterError(NameColumns::MISSING.to_string()))?;
+    let columns: HashMap<&str, usize> = CSV_COLUMNS
+        .iter()
+        .filter_map(|&column| header.column(&[column]).map(|index| (column, index)))
+        .collect();
+
+    let entries = records
+        .map(|record| {
+            let text = |column: &str| {
+                columns.get(column).map(|&index| field(&record, index)).filter(|value| !value.is_empty())
+            };
+            let flag = |column: &str| {
+                text(column).is_some_and(|value| CSV_TRUE.contains(&value.to_lowercase().as_str()))
+            };
+            RosterEntry {
+                name: names.name(&record),
+                nickname: text("nickname"),
+                pronouns: text("pronouns"),
+                email: text("email"),
+                phone: text("phone"),
+                acbl_number: text("acbl_number"),
+                photo: text("photo"),
+                expires: text("expires"),
+                birthday: text("birthday"),
+                style: text("style"),
+                highlight: flag("highlight"),
+                new: flag("new"),
+                junior: flag("junior"),
+                confirmed: flag("confirmed"),
+                note: text("note"),
+                needs: text("needs"),
+                level: text("level"),
+            }
+        })
+        .filter(|entry| !entry.name.is_empty())
+        .collect();
+    Ok(entries)
+}
+
+/// The roster as it should be kept: names NFC-normalized with single spaces,
+/// blank fields dropped, emails lowercased, duplicates (same name ignoring
+/// case) merged into the first, and sorted by name
+pub fn canonical_roster(entries: Vec<RosterEntry>) -> Vec<RosterEntry> {
+    let mut roster: Vec<RosterEntry> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for mut entry in entries {
+        entry.name = normalize_text(&entry.name);
+        if entry.name.is_empty() {
+            continue;
+        }
+        for field in [
+            &mut entry.nickname,
+            &mut entry.pronouns,
+            &mut entry.photo,
+            &mut entry.expires,
+            &mut entry.style,
+            &mut entry.note,
+            &mut entry.needs,
+            &mut entry.birthday,
+            &mut entry.email,
+            &mut entry.phone,
+            &mut entry.acbl_number,
+            &mut entry.level,
+        ] {
+            *field = field.take().map(|value| normalize_text(&value)).filter(|value| !value.is_empty());
+        }
+        entry.email = entry.email.map(|email| email.to_lowercase());
+
+        match seen.get(&entry.name.to_lowercase()) {
+            Some(&index) => merge_into(&mut roster[index], entry),
+            None => {
+                seen.insert(entry.name.to_lowercase(), roster.len());
+                roster.push(entry);
+            }
+        }
+    }
+
+    roster.sort_by_key(|entry| entry.name.to_lowercase());
+    roster
+}
+
+/// Fill whatever `first` lacks from a later duplicate
+fn merge_into(first: &mut RosterEntry, duplicate: RosterEntry) {
+    first.nickname = first.nickname.take().or(duplicate.nickname);
+    first.pronouns = first.pronouns.take().or(duplicate.pronouns);
+    first.photo = first.photo.take().or(duplicate.photo);
+    first.expires = first.expires.take().or(duplicate.expires);
+    first.style = first.style.take().or(duplicate.style);
+    first.note = first.note.take().or(duplicate.note);
+    first.needs = first.needs.take().or(duplicate.needs);
+    first.birthday = first.birthday.take().or(duplicate.birthday);
+    first.email = first.email.take().or(duplicate.email);
+    first.phone = first.phone.take().or(duplicate.phone);
+    first.acbl_number = first.acbl_number.take().or(duplicate.acbl_number);
+    first.level = first.level.take().or(duplicate.level);
+    first.highlight |= duplicate.highlight;
+    first.new |= duplicate.new;
+    first.junior |= duplicate.junior;
+    first.confirmed |= duplicate.confirmed;
+}
+
+fn normalize_text(text: &str) -> String {
+    text.nfc().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Roster file contents, one entry per line like the example roster
+pub fn roster_json(entries: &[RosterEntry]) -> Result<String, AppError> {
+    let lines = entries
+        .iter()
+        .map(|entry| serde_json::to_string(entry).map(|json| format!("  {}", json)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::RosterError(format!("Failed to write roster: {}", e)))?;
+    if lines.is_empty() {
+        return Ok("[]\n".to_string());
+    }
+    Ok(format!("[\n{}\n]\n", lines.join(",\n")))
+}
+
+/// CSV with a header row of the [`CSV_COLUMNS`] `visibility` may see; flags
+/// are written as "yes" or left empty
+pub fn roster_csv(entries: &[RosterEntry], visibility: Visibility) -> String {
+    let visible: Vec<bool> = CSV_COLUMNS.iter().map(|column| column_visibility(column) <= visibility).collect();
+    let header: Vec<&str> = CSV_COLUMNS
+        .iter()
+        .zip(&visible)
+        .filter(|(_, &shown)| shown)
+        .map(|(column, _)| *column)
+        .collect();
+    let mut csv = header.join(",") + "\n";
+    for entry in entries {
+        let flag = |set: bool| if set { "yes" } else { "" };
+        let fields = [
+            entry.name.as_str(),
+            entry.nickname.as_deref().unwrap_or_default(),
+            entry.pronouns.as_deref().unwrap_or_default(),
+            entry.email.as_deref().unwrap_or_default(),
+            entry.phone.as_deref().unwrap_or_default(),
+            entry.acbl_number.as_deref().unwrap_or_default(),
+            entry.photo.as_deref().unwrap_or_default(),
+            entry.expires.as_deref().unwrap_or_default(),
+            entry.birthday.as_deref().unwrap_or_default(),
+            entry.style.as_deref().unwrap_or_default(),
+            flag(entry.highlight),
+            flag(entry.new),
+            flag(entry.junior),
+            flag(entry.confirmed),
+            entry.note.as_deref().unwrap_or_default(),
+            entry.needs.as_deref().unwrap_or_default(),
+            entry.level.as_deref().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields
+            .iter()
+            .zip(&visible)
+            .filter(|(_, &shown)| shown)
+            .map(|(field, _)| csv_field(field))
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Rows of CSV text with quoting undone, as [`csv_field`] writes it; blank
+/// lines are skipped
+pub fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !quoted => {}
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|record| record.iter().any(|field| !field.trim().is_empty()));
+    records
+}
+
+/// Quote fields holding a comma, quote or line break, doubling any quotes
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Enrollment changes between two rosters, names as written in the newer one
+/// (the older one for removals)
+#[derive(Debug, Default, PartialEq)]
+pub struct RosterDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// (old name, new name) for a student whose email, phone or ACBL number
+    /// stayed the same
+    pub renamed: Vec<(String, String)>,
+}
+
+impl RosterDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+    }
+}
+
+/// Compare names ignoring case, spacing, name order and marker symbols, so
+/// "Smith, John *" still matches "John Smith"
+pub fn diff_rosters(old: &[RosterEntry], new: &[RosterEntry]) -> RosterDiff {
+    let new_keys: Vec<String> = new.iter().map(|entry| name_key(&entry.name)).collect();
+    let old_keys: Vec<String> = old.iter().map(|entry| name_key(&entry.name)).collect();
+    let mut removed: Vec<&RosterEntry> = old
+        .iter()
+        .zip(&old_keys)
+        .filter(|(_, key)| !new_keys.contains(key))
+        .map(|(entry, _)| entry)
+        .collect();
+
+    let mut diff = RosterDiff::default();
+    for (entry, key) in new.iter().zip(&new_keys) {
+        if old_keys.contains(key) {
+            continue;
+        }
+        match removed.iter().position(|old| same_person(old, entry)) {
+            Some(index) => diff.renamed.push((removed.remove(index).name.clone(), entry.name.clone())),
+            None => diff.added.push(entry.name.clone()),
+        }
+    }
+    diff.removed = removed.into_iter().map(|entry| entry.name.clone()).collect();
+    diff
+}
+
+fn name_key(name: &str) -> String {
+    let mut name = crate::names::parse_name(&normalize_text(name));
+    name.markers.clear();
+    name.first_last().to_lowercase()
+}
+
+/// Same contact details under a different name: a married name or a typo fixed
+fn same_person(old: &RosterEntry, new: &RosterEntry) -> bool {
+    let email = |entry: &RosterEntry| entry.email.as_ref().map(|email| email.trim().to_lowercase());
+    let phone = |entry: &RosterEntry| {
+        entry.phone.as_ref().map(|phone| phone.chars().filter(char::is_ascii_digit).collect::<String>())
+    };
+    let acbl = |entry: &RosterEntry| entry.acbl_number.as_ref().map(|number| number.trim().to_string());
+    let matches = |a: Option<String>, b: Option<String>| a.is_some_and(|a| !a.is_empty() && Some(a) == b);
+    matches(email(old), email(new)) || matches(phone(old), phone(new)) || matches(acbl(old), acbl(new))
+}
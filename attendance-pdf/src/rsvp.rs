@@ -0,0 +1,139 @@
+// RSVP imports: who said they are coming, from the CSV a sign-up form or
+// event page exports. Roster students who confirmed get their box printed
+// ticked, so the teacher can see at a glance who to expect.
+//
+//     name,response
+//     Alice Johnson,yes
+//     Bob Smith,no
+//
+// The name may instead be split over "first name" and "last name" columns.
+// Without a response column every row counts as a yes.
+
+use crate::names::match_key;
+use crate::roster_file::csv_records;
+use crate::{AppError, RosterEntry};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Headings of the column holding the whole name
+const NAME_COLUMNS: [&str; 4] = ["name", "full name", "attendee", "student"];
+const FIRST_NAME_COLUMNS: [&str; 2] = ["first name", "given name"];
+const LAST_NAME_COLUMNS: [&str; 3] = ["last name", "surname", "family name"];
+/// Headings of the column saying whether they are coming
+const RESPONSE_COLUMNS: [&str; 5] = ["response", "rsvp", "status", "attending", "going"];
+/// Responses that count as coming
+const YES_RESPONSES: [&str; 8] = ["yes", "y", "attending", "going", "confirmed", "accepted", "true", "1"];
+
+/// How the RSVPs lined up with the roster
+#[derive(Debug, Default, PartialEq)]
+pub struct RsvpMatch {
+    /// Roster students who confirmed, in roster order
+    pub confirmed: Vec<String>,
+    /// Roster students with no confirmation
+    pub not_replied: Vec<String>,
+    /// Confirmations from people not on the roster
+    pub not_on_roster: Vec<String>,
+}
+
+/// Names of everyone in the RSVP file who said yes
+pub fn load_rsvps(path: &Path) -> Result<Vec<String>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::RsvpError(format!("{}: {}", path.display(), e)))?;
+    parse_rsvps(&content).map_err(|e| match e {
+        AppError::RsvpError(message) => AppError::RsvpError(format!("{}: {}", path.display(), message)),
+        other => other,
+    })
+}
+
+pub fn parse_rsvps(content: &str) -> Result<Vec<String>, AppError> {
+    let mut records = csv_records(content).into_iter();
+    let header = CsvHeader::new(records.next().unwrap_or_default());
+    let names = NameColumns::find(&header).ok_or_else(|| AppError::RsvpError(NameColumns::MISSING.to_string()))?;
+    let response_column = header.column(&RESPONSE_COLUMNS);
+
+    let mut confirmed = Vec::new();
+    for record in records {
+        let coming = response_column
+            .is_none_or(|index| YES_RESPONSES.contains(&field(&record, index).to_lowercase().as_str()));
+        let name = names.name(&record);
+        if coming && !name.is_empty() {
+            confirmed.push(name);
+        }
+    }
+    Ok(confirmed)
+}
+
+/// A CSV export's header row, lowercased for matching
+pub(crate) struct CsvHeader(Vec<String>);
+
+impl CsvHeader {
+    pub(crate) fn new(header: Vec<String>) -> Self {
+        CsvHeader(header.iter().map(|heading| heading.trim().to_lowercase()).collect())
+    }
+
+    /// The first column headed by any of `names`
+    pub(crate) fn column(&self, names: &[&str]) -> Option<usize> {
+        self.0.iter().position(|heading| names.contains(&heading.as_str()))
+    }
+}
+
+/// A record's field, trimmed, or empty if the record is short
+pub(crate) fn field(record: &[String], index: usize) -> String {
+    record.get(index).map(|field| field.trim()).unwrap_or_default().to_string()
+}
+
+/// Where a CSV export keeps each person's name: one column, or given and
+/// family names apart
+pub(crate) enum NameColumns {
+    Whole(usize),
+    Split(usize, usize),
+}
+
+impl NameColumns {
+    pub(crate) const MISSING: &'static str = "no \"name\" column (or \"first name\" and \"last name\")";
+
+    pub(crate) fn find(header: &CsvHeader) -> Option<Self> {
+        if let Some(index) = header.column(&NAME_COLUMNS) {
+            return Some(NameColumns::Whole(index));
+        }
+        let first = header.column(&FIRST_NAME_COLUMNS)?;
+        let last = header.column(&LAST_NAME_COLUMNS)?;
+        Some(NameColumns::Split(first, last))
+    }
+
+    /// The record's name with single spaces, empty if it has none
+    pub(crate) fn name(&self, record: &[String]) -> String {
+        let name = match *self {
+            NameColumns::Whole(index) => field(record, index),
+            NameColumns::Split(first, last) => format!("{} {}", field(record, first), field(record, last)),
+        };
+        name.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Mark the roster entries whose names are among `confirmed`, matching
+/// names loosely ("johnson, alice" confirms Alice Johnson)
+pub fn mark_confirmed(entries: &mut [RosterEntry], confirmed: &[String]) -> RsvpMatch {
+    let mut result = RsvpMatch::default();
+    let keys: HashSet<String> = confirmed.iter().map(|name| match_key(name)).collect();
+    let mut matched = HashSet::new();
+    for entry in entries.iter_mut() {
+        let key = match_key(&entry.name);
+        if keys.contains(&key) {
+            entry.confirmed = true;
+            matched.insert(key);
+        }
+        if entry.confirmed {
+            result.confirmed.push(entry.name.clone());
+        } else {
+            result.not_replied.push(entry.name.clone());
+        }
+    }
+    let mut seen = HashSet::new();
+    result.not_on_roster = confirmed
+        .iter()
+        .filter(|name| !matched.contains(&match_key(name)) && seen.insert(match_key(name)))
+        .cloned()
+        .collect();
+    result
+}
@@ -0,0 +1,84 @@
+// Class series schedule (TOML): the date and topic of every session, used for
+// the back-page calendar and the convention card progress checklist
+//
+//     series = "Fall Beginner Series"
+//     topics = ["Stayman", "Jacoby transfers", "Weak twos"]
+//
+//     [[session]]
+//     date = 2025-09-09
+//     topic = "Counting points"
+
+use crate::AppError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::Path;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    /// Shown as the calendar heading
+    pub series: Option<String>,
+    /// Conventions for the progress checklist, in teaching order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    #[serde(default, rename = "session")]
+    pub sessions: Vec<Session>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Session {
+    #[serde(deserialize_with = "deserialize_date", serialize_with = "serialize_date")]
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub topic: String,
+}
+
+pub fn load_schedule(path: &Path) -> Result<Schedule, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::ScheduleError(format!("{}: {}", path.display(), e)))?;
+    let mut schedule: Schedule = toml::from_str(&content)
+        .map_err(|e| AppError::ScheduleError(format!("{}: {}", path.display(), e)))?;
+    schedule.sessions.sort_by_key(|session| session.date);
+    Ok(schedule)
+}
+
+impl Schedule {
+    /// Sessions after `date`, in date order
+    pub fn remaining_after(&self, date: NaiveDate) -> impl Iterator<Item = &Session> {
+        self.sessions.iter().filter(move |session| session.date > date)
+    }
+
+    /// The `topics` list, or failing that each session's topic in date order
+    pub fn checklist_topics(&self) -> Vec<String> {
+        if !self.topics.is_empty() {
+            return self.topics.clone();
+        }
+        self.sessions
+            .iter()
+            .map(|session| session.topic.trim())
+            .filter(|topic| !topic.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// Written as a "YYYY-MM-DD" string, which [`deserialize_date`] reads back
+fn serialize_date<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&date.format("%Y-%m-%d").to_string())
+}
+
+/// Accept both TOML dates (`2025-09-09`) and quoted strings (`"2025-09-09"`)
+fn deserialize_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let text = match toml::Value::deserialize(deserializer)? {
+        toml::Value::String(text) => text,
+        toml::Value::Datetime(datetime) => datetime.to_string(),
+        other => {
+            return Err(serde::de::Error::custom(format!(
+                "expected a YYYY-MM-DD date, found {}",
+                other
+            )))
+        }
+    };
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(serde::de::Error::custom)
+}
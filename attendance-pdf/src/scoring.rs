@@ -0,0 +1,118 @@
+// Score summary: the director's grid for copying results off the travelers
+// after supervised play, a row per pair and a column per board, with the
+// pair's total and place at the end.
+
+use crate::layout::{FontStyle, Page, SheetLayout, BORDER_STROKE, ROW_STROKE};
+use crate::tent::class_tables;
+use crate::{
+    format_date_display, AttendanceConfig, HEADER_FONT_SIZE, MARGIN_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM,
+    PAGE_WIDTH_MM, SMALL_FONT_SIZE, TITLE_FONT_SIZE,
+};
+
+const SCORING_TITLE: &str = "SCORE SUMMARY";
+
+/// Pair number, then a write-in for the names
+const PAIR_COLUMN_WIDTH_MM: f32 = 12.0;
+const NAMES_COLUMN_WIDTH_MM: f32 = 45.0;
+/// Total and place after the boards
+const TOTAL_COLUMN_WIDTH_MM: f32 = 16.0;
+const PLACE_COLUMN_WIDTH_MM: f32 = 13.0;
+const BOARD_MIN_WIDTH_MM: f32 = 9.0;
+const BOARD_MAX_WIDTH_MM: f32 = 16.0;
+const HEADER_ROW_HEIGHT_MM: f32 = 8.0;
+const PAIR_ROW_HEIGHT_MM: f32 = 9.0;
+
+pub struct ScoringOptions {
+    /// Rows numbered 1 to this
+    pub pairs: u32,
+    /// Board columns numbered 1 to this
+    pub boards: u32,
+}
+
+impl ScoringOptions {
+    /// Two pairs at each table of the class
+    pub fn for_config(config: &AttendanceConfig, boards: u32) -> Self {
+        ScoringOptions {
+            pairs: 2 * class_tables(config),
+            boards,
+        }
+    }
+}
+
+/// Pairs down the side, boards across. Boards that do not fit across one
+/// page continue on further pages, with the total and place on the last.
+pub fn layout_scoring(config: &AttendanceConfig, options: &ScoringOptions) -> SheetLayout {
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let fixed = PAIR_COLUMN_WIDTH_MM + NAMES_COLUMN_WIDTH_MM + TOTAL_COLUMN_WIDTH_MM + PLACE_COLUMN_WIDTH_MM;
+    let boards_per_page = (((content_width - fixed) / BOARD_MIN_WIDTH_MM) as u32).max(1);
+    let grid_top = PAGE_HEIGHT_MM - MARGIN_MM - 20.0;
+    let rows_per_page = (((grid_top - HEADER_ROW_HEIGHT_MM - MARGIN_MM) / PAIR_ROW_HEIGHT_MM) as u32).max(1);
+
+    let boards: Vec<u32> = (1..=options.boards.max(1)).collect();
+    let pairs: Vec<u32> = (1..=options.pairs.max(1)).collect();
+    let board_chunks: Vec<&[u32]> = boards.chunks(boards_per_page as usize).collect();
+    for (chunk_index, board_chunk) in board_chunks.iter().enumerate() {
+        let last_chunk = chunk_index + 1 == board_chunks.len();
+        let board_width = ((content_width - fixed) / board_chunk.len() as f32).min(BOARD_MAX_WIDTH_MM);
+        for pair_chunk in pairs.chunks(rows_per_page as usize) {
+            let continued = !sheet.pages.is_empty();
+            let page = sheet.new_page();
+            layout_scoring_heading(page, config, continued);
+
+            // Column edges: pair, names, each board, then total and place
+            let mut columns: Vec<(String, f32)> = vec![
+                ("PAIR".to_string(), PAIR_COLUMN_WIDTH_MM),
+                ("NAMES".to_string(), NAMES_COLUMN_WIDTH_MM),
+            ];
+            columns.extend(board_chunk.iter().map(|board| (board.to_string(), board_width)));
+            if last_chunk {
+                columns.push(("TOTAL".to_string(), TOTAL_COLUMN_WIDTH_MM));
+                columns.push(("PLACE".to_string(), PLACE_COLUMN_WIDTH_MM));
+            }
+            let right_x = MARGIN_MM + columns.iter().map(|(_, width)| width).sum::<f32>();
+            let grid_bottom = grid_top - HEADER_ROW_HEIGHT_MM - pair_chunk.len() as f32 * PAIR_ROW_HEIGHT_MM;
+
+            let header_bottom = grid_top - HEADER_ROW_HEIGHT_MM;
+            let mut x = MARGIN_MM;
+            for (label, width) in &columns {
+                page.line(x, grid_top, x, grid_bottom, ROW_STROKE);
+                page.centered_text(label, SMALL_FONT_SIZE, x + width / 2.0, header_bottom + 2.5, FontStyle::Bold);
+                x += width;
+            }
+            page.line(MARGIN_MM, grid_top, MARGIN_MM, grid_bottom, BORDER_STROKE);
+            page.line(right_x, grid_top, right_x, grid_bottom, BORDER_STROKE);
+            page.line(MARGIN_MM, grid_top, right_x, grid_top, BORDER_STROKE);
+            page.line(MARGIN_MM, header_bottom, right_x, header_bottom, BORDER_STROKE);
+
+            let mut y = header_bottom;
+            for pair in pair_chunk {
+                let text_y = y - PAIR_ROW_HEIGHT_MM / 2.0 - 1.5;
+                page.text(pair.to_string(), NORMAL_FONT_SIZE, MARGIN_MM + 2.0, text_y, FontStyle::Bold);
+                y -= PAIR_ROW_HEIGHT_MM;
+                page.line(MARGIN_MM, y, right_x, y, ROW_STROKE);
+            }
+            page.line(MARGIN_MM, grid_bottom, right_x, grid_bottom, BORDER_STROKE);
+        }
+    }
+    sheet
+}
+
+fn layout_scoring_heading(page: &mut Page, config: &AttendanceConfig, continued: bool) {
+    let top_y = PAGE_HEIGHT_MM - MARGIN_MM;
+    let content_width = PAGE_WIDTH_MM - 2.0 * MARGIN_MM;
+    let title = if continued {
+        format!("{} (continued)", SCORING_TITLE)
+    } else {
+        SCORING_TITLE.to_string()
+    };
+    page.text(title, TITLE_FONT_SIZE, MARGIN_MM, top_y - 7.0, FontStyle::Bold);
+    let subtitle = format!("{} \u{2013} {}", config.class_name, format_date_display(&config.date));
+    page.text(subtitle, HEADER_FONT_SIZE, MARGIN_MM, top_y - 14.0, FontStyle::Bold);
+    page.line(MARGIN_MM, top_y - 18.0, MARGIN_MM + content_width, top_y - 18.0, BORDER_STROKE);
+}
@@ -0,0 +1,193 @@
+// Club config file (TOML) for settings that rarely change between runs, so
+// the weekly command line stays short
+
+use crate::assets::AssetCache;
+use crate::google::GoogleSettings;
+use crate::net::NetworkSettings;
+use crate::registrations::EventbriteSettings;
+use crate::roster::RosterMarker;
+use crate::wallet::WalletSettings;
+use crate::zoom::ZoomSettings;
+use crate::{resolve_source, AppError, EmergencyInfo, Sponsor, SPONSOR_MAX_HEIGHT_MM, SPONSOR_MAX_WIDTH_MM};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Read from the working directory when `--config` is not given
+pub const DEFAULT_SETTINGS_FILE: &str = "attendance.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub sponsor: Option<SponsorSettings>,
+
+    /// `[[marker]]` entries: symbols used in roster names and their legend
+    #[serde(default, rename = "marker")]
+    pub markers: Vec<RosterMarker>,
+
+    /// Items for `--checklist` in place of the default supplies
+    pub checklist: Option<Vec<String>>,
+
+    /// `[teacher.<key>]` profiles, selected with `--teacher <key>`
+    #[serde(default, rename = "teacher")]
+    pub teachers: BTreeMap<String, TeacherProfile>,
+
+    /// OAuth credentials for `--google-label`
+    pub google: Option<GoogleSettings>,
+
+    /// API token for `--eventbrite`
+    pub eventbrite: Option<EventbriteSettings>,
+
+    /// Server-to-Server OAuth app for `--zoom`
+    pub zoom: Option<ZoomSettings>,
+
+    /// Issuer and signing details for `wallet-pass`
+    pub wallet: Option<WalletSettings>,
+
+    /// Timeouts, retries and the download limit for URLs and imports
+    #[serde(default)]
+    pub network: NetworkSettings,
+
+    /// `[venue.<key>]` profiles, selected with `--venue <key>`
+    #[serde(default, rename = "venue")]
+    pub venues: BTreeMap<String, VenueProfile>,
+
+    /// Directory holding the config file; relative paths inside it resolve
+    /// against this
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+/// `[sponsor]`: footer acknowledgment such as "Lessons sponsored by …"
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SponsorSettings {
+    pub text: String,
+    /// Small image shown before the text (file path or URL)
+    pub image: Option<String>,
+}
+
+/// `[teacher.rick]`: one instructor's defaults, so each teacher at the club
+/// gets their own sheet without a wrapper script
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeacherProfile {
+    /// Name as printed; defaults to the profile key
+    pub name: Option<String>,
+    /// Printed after the name on the instructor line
+    pub email: Option<String>,
+    /// Header logo when `--logo` is not given (file path or URL)
+    pub logo: Option<String>,
+    /// Heading over the mailing list sign-up, e.g. "JOIN RICK'S LESSON LIST"
+    pub mailing_title: Option<String>,
+}
+
+/// `[venue.community-center]`: where the class meets and what students and
+/// substitutes need to know there
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VenueProfile {
+    /// Name on the location line; defaults to the profile key
+    pub name: Option<String>,
+    /// Follows the name on the location line
+    pub address: Option<String>,
+    /// Tables in the room; blank sheets get four seats per table unless
+    /// `--rows` is given
+    pub tables: Option<u32>,
+    /// Network and password, printed under the header for students
+    pub wifi: Option<String>,
+    /// Exits, first aid, who to call; printed under the header
+    pub emergency: Option<String>,
+    /// Where the exits are, for `--emergency-page`
+    pub exits: Option<String>,
+    /// Where the defibrillator is, for `--emergency-page`
+    pub aed: Option<String>,
+    /// Numbers to call, for `--emergency-page`: "Building manager: 555-0100"
+    #[serde(default)]
+    pub contacts: Vec<String>,
+}
+
+impl VenueProfile {
+    /// "Community Center, 12 Main St"
+    pub fn location(&self, key: &str) -> String {
+        let name = self.name.as_deref().unwrap_or(key);
+        match &self.address {
+            Some(address) => format!("{}, {}", name, address),
+            None => name.to_string(),
+        }
+    }
+
+    /// The venue's emergency page, if it has anything to put on one
+    pub fn emergency_info(&self, key: &str) -> Option<EmergencyInfo> {
+        if self.exits.is_none() && self.aed.is_none() && self.contacts.is_empty() && self.emergency.is_none() {
+            return None;
+        }
+        Some(EmergencyInfo {
+            location: self.location(key),
+            exits: self.exits.clone(),
+            aed: self.aed.clone(),
+            contacts: self.contacts.clone(),
+            notes: self.emergency.clone(),
+        })
+    }
+}
+
+/// Load `path`, or `attendance.toml` in the working directory if it exists.
+/// With neither, every setting takes its default.
+pub fn load_settings(path: Option<&Path>) -> Result<Settings, AppError> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let default = PathBuf::from(DEFAULT_SETTINGS_FILE);
+            if !default.exists() {
+                return Ok(Settings::default());
+            }
+            default
+        }
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::SettingsError(format!("{}: {}", path.display(), e)))?;
+    let mut settings: Settings = toml::from_str(&content)
+        .map_err(|e| AppError::SettingsError(format!("{}: {}", path.display(), e)))?;
+    settings.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    Ok(settings)
+}
+
+impl Settings {
+    /// Resolve a path from the config file; URLs and absolute paths pass through
+    pub fn resolve_source(&self, source: &str) -> String {
+        resolve_source(&self.base_dir, source)
+    }
+
+    /// The profile for `--teacher <key>`, if one is configured
+    pub fn teacher(&self, key: &str) -> Option<&TeacherProfile> {
+        self.teachers.get(key)
+    }
+
+    /// The profile for `--venue <key>`
+    pub fn venue(&self, key: &str) -> Result<&VenueProfile, AppError> {
+        self.venues.get(key).ok_or_else(|| {
+            AppError::ConfigError(format!("--venue {}: no [venue.{}] in the config file", key, key))
+        })
+    }
+
+    /// The configured sponsor strip, with its image loaded
+    pub fn sponsor(&self, assets: &mut AssetCache) -> Result<Option<Sponsor>, AppError> {
+        let Some(sponsor) = &self.sponsor else {
+            return Ok(None);
+        };
+        let image = match &sponsor.image {
+            Some(source) => Some(assets.image(
+                &self.resolve_source(source),
+                SPONSOR_MAX_WIDTH_MM,
+                SPONSOR_MAX_HEIGHT_MM,
+            )?),
+            None => None,
+        };
+        Ok(Some(Sponsor {
+            text: sponsor.text.clone(),
+            image,
+        }))
+    }
+}
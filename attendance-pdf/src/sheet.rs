@@ -0,0 +1,463 @@
+// Builder-style entry point for applications embedding the generator
+
+use crate::assets::{PreparedImage, QrCache};
+use crate::layout::{layout_sheet, SheetLayout};
+use crate::qr::DEFAULT_QR_DPI;
+use crate::render::SheetRenderer;
+use crate::{
+    generate_short_id, names, parse_event_id, render_pdf, render_with, AppError, AttendanceConfig, Deal,
+    DisplayOrder, EmergencyInfo, MailingPlacement, PhaseTimings, Revision, RosterMarker, Schedule, Sponsor, Student, TextAlign,
+};
+use chrono::{Local, NaiveDate};
+use std::sync::Arc;
+
+/// Defaults shared with the CLI
+pub const DEFAULT_TEACHER: &str = "Rick";
+pub const DEFAULT_BLANK_ROWS: u32 = 32;
+pub const DEFAULT_MAILING_ROWS: u32 = 4;
+
+/// A validated attendance sheet, ready to lay out or render
+pub struct AttendanceSheet {
+    config: AttendanceConfig,
+}
+
+/// Collects sheet options; only the class name is required.
+///
+/// ```no_run
+/// use attendance_pdf::AttendanceSheet;
+///
+/// let pdf: Vec<u8> = AttendanceSheet::builder()
+///     .class("Tuesday Beginner")
+///     .roster(vec!["Alice Johnson".to_string()])
+///     .build()?
+///     .render_pdf()?;
+/// # Ok::<(), attendance_pdf::AppError>(())
+/// ```
+#[derive(Default)]
+pub struct AttendanceSheetBuilder {
+    class_name: Option<String>,
+    teacher: Option<String>,
+    teacher_email: Option<String>,
+    substitute: Option<String>,
+    date: Option<NaiveDate>,
+    location: Option<String>,
+    wifi: Option<String>,
+    emergency: Option<String>,
+    banner: Option<String>,
+    feedback_url: Option<String>,
+    emergency_page: Option<EmergencyInfo>,
+    event_id: Option<String>,
+    roster: Option<Vec<Student>>,
+    online_attendees: Option<Vec<String>>,
+    blank_rows: Option<u32>,
+    no_mailing_list: bool,
+    mailing_rows: Option<u32>,
+    mailing_title: Option<String>,
+    title_align: TextAlign,
+    mailing_placement: MailingPlacement,
+    helper_rows: u32,
+    coupons: u32,
+    signature_column: bool,
+    photo_consent: bool,
+    time_column: bool,
+    raffle: bool,
+    draw_numbers: bool,
+    announce_birthdays: bool,
+    display_order: DisplayOrder,
+    use_nicknames: bool,
+    markers: Vec<RosterMarker>,
+    calendar: Option<Schedule>,
+    waiver: Option<String>,
+    checklist: Option<Vec<String>>,
+    hands: Option<Vec<Deal>>,
+    overflow_page: bool,
+    copies: Option<u32>,
+    mono: bool,
+    economy: bool,
+    qr_dpi: Option<u32>,
+    logo: Option<Arc<PreparedImage>>,
+    sponsor: Option<Sponsor>,
+    reproducible: bool,
+    revision: Option<Revision>,
+}
+
+impl AttendanceSheet {
+    pub fn builder() -> AttendanceSheetBuilder {
+        AttendanceSheetBuilder::default()
+    }
+
+    pub fn config(&self) -> &AttendanceConfig {
+        &self.config
+    }
+
+    pub fn layout(&self) -> Result<SheetLayout, AppError> {
+        layout_sheet(&self.config)
+    }
+
+    pub fn render_pdf(&self) -> Result<Vec<u8>, AppError> {
+        render_pdf(&self.config, &mut PhaseTimings::default())
+    }
+
+    /// Render with any backend, e.g. a custom [`SheetRenderer`]
+    pub fn render(&self, renderer: &dyn SheetRenderer) -> Result<Vec<u8>, AppError> {
+        render_with(renderer, &self.config, &mut PhaseTimings::default())
+    }
+}
+
+impl From<AttendanceConfig> for AttendanceSheet {
+    fn from(config: AttendanceConfig) -> Self {
+        AttendanceSheet { config }
+    }
+}
+
+impl AttendanceSheetBuilder {
+    pub fn class(mut self, name: impl Into<String>) -> Self {
+        self.class_name = Some(name.into());
+        self
+    }
+
+    pub fn teacher(mut self, teacher: impl Into<String>) -> Self {
+        self.teacher = Some(teacher.into());
+        self
+    }
+
+    /// Teacher standing in; the regular teacher stays on record
+    pub fn substitute(mut self, substitute: impl Into<String>) -> Self {
+        self.substitute = Some(substitute.into());
+        self
+    }
+
+    /// Defaults to today
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// 8 hex characters; a random ID is generated if not set
+    pub fn event_id(mut self, event_id: impl Into<String>) -> Self {
+        self.event_id = Some(event_id.into());
+        self
+    }
+
+    /// Pre-printed names; without a roster the sheet uses blank table/seat rows
+    pub fn roster(mut self, names: Vec<String>) -> Self {
+        self.roster = Some(names.into_iter().map(Student::from).collect());
+        self
+    }
+
+    /// As [`Self::roster`], for students with photos
+    pub fn students(mut self, students: Vec<Student>) -> Self {
+        self.roster = Some(students);
+        self
+    }
+
+    /// Students joining over Zoom, listed in an "Online attendees" section
+    pub fn online_attendees(mut self, names: Vec<String>) -> Self {
+        self.online_attendees = Some(names);
+        self
+    }
+
+    pub fn blank_rows(mut self, rows: u32) -> Self {
+        self.blank_rows = Some(rows);
+        self
+    }
+
+    pub fn mailing_list(mut self, enabled: bool) -> Self {
+        self.no_mailing_list = !enabled;
+        self
+    }
+
+    pub fn mailing_rows(mut self, rows: u32) -> Self {
+        self.mailing_rows = Some(rows);
+        self
+    }
+
+    /// Volunteer/caddy sign-in rows above the mailing list; none by default
+    pub fn helper_rows(mut self, rows: u32) -> Self {
+        self.helper_rows = rows;
+        self
+    }
+
+    /// Tear-off "first lesson free" coupons along the bottom; none by default
+    pub fn coupons(mut self, coupons: u32) -> Self {
+        self.coupons = coupons;
+        self
+    }
+
+    /// Students sign in a "Signature" cell instead of ticking a checkbox
+    pub fn signature_column(mut self, enabled: bool) -> Self {
+        self.signature_column = enabled;
+        self
+    }
+
+    /// Add a "Photo OK?" column where students circle Y or N
+    pub fn photo_consent(mut self, enabled: bool) -> Self {
+        self.photo_consent = enabled;
+        self
+    }
+
+    /// Add a narrow "Time" column for the door volunteer to fill in
+    pub fn time_column(mut self, enabled: bool) -> Self {
+        self.time_column = enabled;
+        self
+    }
+
+    /// Number the grid rows and add matching door-prize tickets
+    pub fn raffle(mut self, raffle: bool) -> Self {
+        self.raffle = raffle;
+        self
+    }
+
+    /// Add a "Happy birthday this week" line under the roster
+    pub fn announce_birthdays(mut self, enabled: bool) -> Self {
+        self.announce_birthdays = enabled;
+        self
+    }
+
+    /// Print names "van der Berg, Anna" and sort the roster by family name
+    pub fn display_order(mut self, order: DisplayOrder) -> Self {
+        self.display_order = order;
+        self
+    }
+
+    /// Print students' nicknames in place of their given names
+    pub fn use_nicknames(mut self, enabled: bool) -> Self {
+        self.use_nicknames = enabled;
+        self
+    }
+
+    /// Print a shuffled lucky number in each grid row for the door prize
+    /// draw; cannot be combined with [`Self::raffle`]
+    pub fn draw_numbers(mut self, enabled: bool) -> Self {
+        self.draw_numbers = enabled;
+        self
+    }
+
+    /// Printed after the teacher's name on the instructor line
+    pub fn teacher_email(mut self, email: impl Into<String>) -> Self {
+        self.teacher_email = Some(email.into());
+        self
+    }
+
+    /// Mailing list heading in place of "JOIN MY MAILING LIST"
+    pub fn mailing_title(mut self, title: impl Into<String>) -> Self {
+        self.mailing_title = Some(title.into());
+        self
+    }
+
+    /// Where the mailing list goes when the grid runs onto more pages; the
+    /// bottom of the page it ends on by default
+    pub fn mailing_placement(mut self, placement: MailingPlacement) -> Self {
+        self.mailing_placement = placement;
+        self
+    }
+
+    /// Title centered or right-aligned beside the QR code rather than
+    /// starting at its left
+    pub fn title_align(mut self, align: TextAlign) -> Self {
+        self.title_align = align;
+        self
+    }
+
+    /// Venue Wi-Fi details printed under the header
+    pub fn wifi(mut self, wifi: impl Into<String>) -> Self {
+        self.wifi = Some(wifi.into());
+        self
+    }
+
+    /// Venue emergency information printed under the header
+    pub fn emergency(mut self, emergency: impl Into<String>) -> Self {
+        self.emergency = Some(emergency.into());
+        self
+    }
+
+    /// Boxed notice across the top of the grid, e.g. "CLASS MOVED TO ROOM 204"
+    pub fn banner(mut self, text: impl Into<String>) -> Self {
+        self.banner = Some(text.into());
+        self
+    }
+
+    /// QR code for the club's feedback form, with a comments box beside it
+    pub fn feedback_url(mut self, url: impl Into<String>) -> Self {
+        self.feedback_url = Some(url.into());
+        self
+    }
+
+    /// Add a page of exits, AED location and contact numbers in large type
+    pub fn emergency_page(mut self, info: EmergencyInfo) -> Self {
+        self.emergency_page = Some(info);
+        self
+    }
+
+    /// Explain a symbol used in roster names, e.g. `marker("†", "needs partner")`
+    pub fn marker(mut self, symbol: impl Into<String>, legend: impl Into<String>) -> Self {
+        self.markers.push(RosterMarker {
+            symbol: symbol.into(),
+            legend: legend.into(),
+        });
+        self
+    }
+
+    /// Add a final page listing the series sessions after this one
+    pub fn calendar(mut self, schedule: Schedule) -> Self {
+        self.calendar = Some(schedule);
+        self
+    }
+
+    /// Add waiver pages: `text`, then a name/signature/date line per student
+    pub fn waiver(mut self, text: impl Into<String>) -> Self {
+        self.waiver = Some(text.into());
+        self
+    }
+
+    /// Add a final page of pre-class supplies to tick off
+    pub fn checklist(mut self, items: Vec<String>) -> Self {
+        self.checklist = Some(items);
+        self
+    }
+
+    /// Add pages of practice deals drawn as hand diagrams
+    pub fn hands(mut self, deals: Vec<Deal>) -> Self {
+        self.hands = Some(deals);
+        self
+    }
+
+    /// Add a page of numbered blank rows for late arrivals and walk-ins
+    pub fn overflow_page(mut self, enabled: bool) -> Self {
+        self.overflow_page = enabled;
+        self
+    }
+
+    /// Repeat the sheet; every physical sheet is stamped "Sheet N of M"
+    pub fn copies(mut self, copies: u32) -> Self {
+        self.copies = Some(copies);
+        self
+    }
+
+    /// Print in pure black: no gray rules, shading or logo
+    pub fn mono(mut self, enabled: bool) -> Self {
+        self.mono = enabled;
+        self
+    }
+
+    /// Save paper and toner: compact header, no logo, thinner rules and
+    /// shorter rows
+    pub fn economy(mut self, enabled: bool) -> Self {
+        self.economy = enabled;
+        self
+    }
+
+    /// Rasterize QR codes for this printer resolution (default 600 dpi)
+    pub fn qr_dpi(mut self, dpi: u32) -> Self {
+        self.qr_dpi = Some(dpi);
+        self
+    }
+
+    /// Logo prepared with [`crate::AssetCache`], shown top-right
+    pub fn logo(mut self, logo: Arc<PreparedImage>) -> Self {
+        self.logo = Some(logo);
+        self
+    }
+
+    /// Footer acknowledgment repeated on every page
+    pub fn sponsor(mut self, sponsor: Sponsor) -> Self {
+        self.sponsor = Some(sponsor);
+        self
+    }
+
+    /// Byte-stable output; requires an explicit event ID
+    pub fn reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// Stamp "Rev 2 – generated 09:12" in the footer of a reprinted sheet
+    pub fn revision(mut self, revision: Revision) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    pub fn build(self) -> Result<AttendanceSheet, AppError> {
+        let class_name = self
+            .class_name
+            .filter(|name| !name.trim().is_empty())
+            .ok_or_else(|| AppError::ConfigError("class name is required".to_string()))?;
+
+        if self.raffle && self.draw_numbers {
+            return Err(AppError::ConfigError(
+                "raffle tickets and draw numbers cannot be combined".to_string(),
+            ));
+        }
+
+        let event_id = match self.event_id {
+            Some(id) => parse_event_id(&id)?,
+            None if self.reproducible => {
+                return Err(AppError::ConfigError(
+                    "reproducible output requires an event ID".to_string(),
+                ))
+            }
+            None => generate_short_id(),
+        };
+
+        let mut roster = self.roster;
+        if let Some(roster) = &mut roster {
+            names::sort_roster(roster, self.display_order);
+        }
+
+        Ok(AttendanceSheet {
+            config: AttendanceConfig {
+                class_name,
+                teacher: self.teacher.unwrap_or_else(|| DEFAULT_TEACHER.to_string()),
+                teacher_email: self.teacher_email,
+                substitute: self.substitute,
+                date: self.date.unwrap_or_else(|| Local::now().date_naive()),
+                location: self.location.unwrap_or_default(),
+                wifi: self.wifi,
+                emergency: self.emergency,
+                banner: self.banner,
+                feedback_url: self.feedback_url,
+                emergency_page: self.emergency_page,
+                event_id,
+                roster,
+                online_attendees: self.online_attendees,
+                blank_rows: self.blank_rows.unwrap_or(DEFAULT_BLANK_ROWS),
+                mailing_list: !self.no_mailing_list,
+                mailing_rows: self.mailing_rows.unwrap_or(DEFAULT_MAILING_ROWS),
+                mailing_title: self.mailing_title,
+                title_align: self.title_align,
+                mailing_placement: self.mailing_placement,
+                helper_rows: self.helper_rows,
+                coupons: self.coupons,
+                signature_column: self.signature_column,
+                photo_consent: self.photo_consent,
+                time_column: self.time_column,
+                raffle: self.raffle,
+                draw_numbers: self.draw_numbers,
+                announce_birthdays: self.announce_birthdays,
+                display_order: self.display_order,
+                use_nicknames: self.use_nicknames,
+                markers: self.markers,
+                calendar: self.calendar,
+                waiver: self.waiver,
+                checklist: self.checklist,
+                hands: self.hands,
+                overflow_page: self.overflow_page,
+                copies: self.copies.unwrap_or(1).max(1),
+                mono: self.mono,
+                economy: self.economy,
+                qr_dpi: self.qr_dpi.unwrap_or(DEFAULT_QR_DPI),
+                qr_codes: QrCache::default(),
+                logo: self.logo,
+                sponsor: self.sponsor,
+                reproducible: self.reproducible,
+                revision: self.revision,
+                teacher_copy: false,
+            },
+        })
+    }
+}
@@ -0,0 +1,102 @@
+// Table tents: a Letter page per table, folded across the middle to stand on
+// the table with its number facing the room. They go through the same
+// renderers as the attendance sheet.
+
+use crate::layout::{FontStyle, Page, Rotation, SheetLayout, ROW_STROKE};
+use crate::metrics::text_width_mm;
+use crate::{
+    format_date_display, AttendanceConfig, HEADER_FONT_SIZE, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM, PAGE_WIDTH_MM,
+    SMALL_FONT_SIZE,
+};
+
+/// Players seated at each table
+const SEATS_PER_TABLE: u32 = 4;
+
+/// Big enough to read from across the room
+const TENT_NUMBER_FONT_SIZE: f32 = 160.0;
+
+/// Fold line dashes, faint so they vanish into the crease
+const FOLD_DASH_MM: f32 = 4.0;
+const FOLD_GAP_MM: f32 = 4.0;
+
+/// What to print on the tents
+pub struct TentOptions {
+    /// One tent per table, numbered from 1
+    pub tables: u32,
+    /// Repeat the face upside down on the upper half, so the folded tent
+    /// reads correctly from both sides of the table
+    pub two_sided: bool,
+}
+
+impl TentOptions {
+    pub fn for_config(config: &AttendanceConfig) -> Self {
+        TentOptions {
+            tables: class_tables(config),
+            two_sided: false,
+        }
+    }
+}
+
+/// Enough tables to seat the roster, or the blank rows (four seats a table,
+/// so a venue's table count carries over)
+pub fn class_tables(config: &AttendanceConfig) -> u32 {
+    let players = match &config.roster {
+        Some(roster) => roster.len() as u32,
+        None => config.blank_rows,
+    };
+    players.div_ceil(SEATS_PER_TABLE).max(1)
+}
+
+/// One tent per table. The lower half is the face that reads upright once
+/// the page is folded along the dashed line; with `two_sided` the upper half
+/// is the same face turned around.
+pub fn layout_table_tents(config: &AttendanceConfig, options: &TentOptions) -> SheetLayout {
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+
+    let fold_y = PAGE_HEIGHT_MM / 2.0;
+    for table in 1..=options.tables {
+        let page = sheet.new_page();
+        layout_fold_line(page, fold_y);
+        layout_tent_face(page, config, table, fold_y, Rotation::Upright);
+        if options.two_sided {
+            layout_tent_face(page, config, table, fold_y, Rotation::UpsideDown);
+        }
+    }
+
+    sheet
+}
+
+/// Dashed line across the page with a small "fold" label at each end
+fn layout_fold_line(page: &mut Page, y: f32) {
+    let mut x = 0.0;
+    while x < PAGE_WIDTH_MM {
+        page.line(x, y, (x + FOLD_DASH_MM).min(PAGE_WIDTH_MM), y, ROW_STROKE);
+        x += FOLD_DASH_MM + FOLD_GAP_MM;
+    }
+    page.text("fold", SMALL_FONT_SIZE, 5.0, y + 1.5, FontStyle::Regular);
+    page.right_aligned_text("fold", SMALL_FONT_SIZE, PAGE_WIDTH_MM - 5.0, y + 1.5, FontStyle::Regular);
+}
+
+/// Table number with the class and date under it, centered in the half of
+/// the page below `fold_y`, or turned upside down into the half above it
+fn layout_tent_face(page: &mut Page, config: &AttendanceConfig, table: u32, fold_y: f32, rotation: Rotation) {
+    let centered = |page: &mut Page, text: &str, size: f32, y: f32, font: FontStyle| {
+        let x = (PAGE_WIDTH_MM - text_width_mm(text, size, font)) / 2.0;
+        match rotation {
+            // Half a turn about the middle of the page
+            Rotation::UpsideDown => {
+                page.rotated_text(text, size, PAGE_WIDTH_MM - x, PAGE_HEIGHT_MM - y, font, rotation)
+            }
+            _ => page.text(text, size, x, y, font),
+        }
+    };
+
+    centered(page, "TABLE", HEADER_FONT_SIZE, fold_y - 20.0, FontStyle::Bold);
+    centered(page, &table.to_string(), TENT_NUMBER_FONT_SIZE, fold_y - 80.0, FontStyle::Bold);
+    centered(page, &config.class_name, NORMAL_FONT_SIZE, fold_y - 100.0, FontStyle::Regular);
+    centered(page, &format_date_display(&config.date), SMALL_FONT_SIZE, fold_y - 106.0, FontStyle::Regular);
+}
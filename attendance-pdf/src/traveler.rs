@@ -0,0 +1,134 @@
+// Travelers: a slip per board for supervised play, folded into the board and
+// filled in by North at each table it is played at. There is a row per table
+// of the class, with North-South pair numbers filled in for a Mitchell
+// movement where North-South stay put.
+
+use crate::deals::{board_dealer, board_vulnerability};
+use crate::geometry::PageRect;
+use crate::layout::{FontStyle, Page, SheetLayout, BORDER_STROKE, ROW_STROKE};
+use crate::metrics::text_width_mm;
+use crate::tent::class_tables;
+use crate::{
+    format_date_display, AttendanceConfig, HEADER_FONT_SIZE, MARGIN_MM, NORMAL_FONT_SIZE, PAGE_HEIGHT_MM,
+    PAGE_WIDTH_MM, SMALL_FONT_SIZE,
+};
+
+/// Boards when neither `--boards` nor `--hands` says how many
+pub const DEFAULT_BOARDS: u32 = 8;
+/// More than a session's worth is a typo
+pub const MAX_BOARDS: u32 = 36;
+
+/// Two slips side by side, a cutting gap between them
+const SLIPS_ACROSS: usize = 2;
+const SLIP_GAP_MM: f32 = 6.0;
+const SLIP_HEADER_HEIGHT_MM: f32 = 16.0;
+const COLUMN_HEADER_HEIGHT_MM: f32 = 7.0;
+const SLIP_ROW_HEIGHT_MM: f32 = 7.0;
+
+/// Column headings and their share of the slip width
+const TRAVELER_COLUMNS: [(&str, f32); 7] = [
+    ("NS", 1.0),
+    ("EW", 1.0),
+    ("CONTRACT", 1.6),
+    ("BY", 0.8),
+    ("TRICKS", 1.1),
+    ("NS +", 1.2),
+    ("EW +", 1.2),
+];
+
+pub struct TravelerOptions {
+    /// Slips for boards 1 to this
+    pub boards: u32,
+    /// A row per table
+    pub tables: u32,
+}
+
+impl TravelerOptions {
+    pub fn for_config(config: &AttendanceConfig, boards: u32) -> Self {
+        TravelerOptions {
+            boards,
+            tables: class_tables(config),
+        }
+    }
+}
+
+/// One traveler per board, as many to a page as fit
+pub fn layout_travelers(config: &AttendanceConfig, options: &TravelerOptions) -> SheetLayout {
+    let mut sheet = SheetLayout {
+        width: PAGE_WIDTH_MM,
+        height: PAGE_HEIGHT_MM,
+        pages: Vec::new(),
+    };
+
+    let content = PageRect {
+        x: MARGIN_MM,
+        y: MARGIN_MM,
+        width: PAGE_WIDTH_MM - 2.0 * MARGIN_MM,
+        height: PAGE_HEIGHT_MM - 2.0 * MARGIN_MM,
+    };
+    let columns = content.columns(SLIPS_ACROSS, SLIP_GAP_MM);
+    let slip_height =
+        SLIP_HEADER_HEIGHT_MM + COLUMN_HEADER_HEIGHT_MM + options.tables as f32 * SLIP_ROW_HEIGHT_MM;
+    let slips_down = (((content.height + SLIP_GAP_MM) / (slip_height + SLIP_GAP_MM)) as usize).max(1);
+    let per_page = SLIPS_ACROSS * slips_down;
+
+    for board in 1..=options.boards {
+        let slot = (board - 1) as usize % per_page;
+        if slot == 0 {
+            sheet.new_page();
+        }
+        let page = sheet.pages.last_mut().expect("traveler page was just added");
+        let column = &columns[slot % SLIPS_ACROSS];
+        let y_top = content.top() - (slot / SLIPS_ACROSS) as f32 * (slip_height + SLIP_GAP_MM);
+        let slip = PageRect::from_top(column.x, y_top, column.width, slip_height);
+        layout_traveler(page, config, board, options.tables, &slip);
+    }
+    sheet
+}
+
+fn layout_traveler(page: &mut Page, config: &AttendanceConfig, board: u32, tables: u32, slip: &PageRect) {
+    let (x, y_top, width) = (slip.x, slip.top(), slip.width);
+    let right = slip.right();
+    let bottom = slip.y;
+    page.line(x, y_top, right, y_top, BORDER_STROKE);
+    page.line(right, y_top, right, bottom, BORDER_STROKE);
+    page.line(right, bottom, x, bottom, BORDER_STROKE);
+    page.line(x, bottom, x, y_top, BORDER_STROKE);
+
+    // Board number with its dealer and vulnerability, then the class
+    page.text(format!("BOARD {}", board), HEADER_FONT_SIZE, x + 2.0, y_top - 6.5, FontStyle::Bold);
+    let conditions = format!("Dlr: {}   Vul: {}", board_dealer(board).letter(), board_vulnerability(board));
+    page.right_aligned_text(conditions, SMALL_FONT_SIZE, right - 2.0, y_top - 6.0, FontStyle::Regular);
+    let class = format!("{} \u{2013} {}", config.class_name, format_date_display(&config.date));
+    page.text(class, SMALL_FONT_SIZE, x + 2.0, y_top - 12.5, FontStyle::Regular);
+
+    // Column headings, shrunk to fit when narrow
+    let header_top = y_top - SLIP_HEADER_HEIGHT_MM;
+    let header_bottom = header_top - COLUMN_HEADER_HEIGHT_MM;
+    page.line(x, header_top, right, header_top, BORDER_STROKE);
+    let total_share: f32 = TRAVELER_COLUMNS.iter().map(|(_, share)| share).sum();
+    let mut column_x = x;
+    for (index, (label, share)) in TRAVELER_COLUMNS.iter().enumerate() {
+        let column_width = width * share / total_share;
+        if index > 0 {
+            page.line(column_x, header_top, column_x, bottom, ROW_STROKE);
+        }
+        let label_width = text_width_mm(label, SMALL_FONT_SIZE, FontStyle::Bold);
+        let room = column_width - 2.0;
+        let size = if label_width > room { SMALL_FONT_SIZE * room / label_width } else { SMALL_FONT_SIZE };
+        page.text(*label, size, column_x + 1.0, header_bottom + 2.2, FontStyle::Bold);
+        column_x += column_width;
+    }
+    page.line(x, header_bottom, right, header_bottom, BORDER_STROKE);
+
+    // A row per table, North-South pair numbers filled in
+    let mut y = header_bottom;
+    for table in 1..=tables {
+        let text_y = y - SLIP_ROW_HEIGHT_MM / 2.0 - 1.5;
+        page.text(table.to_string(), NORMAL_FONT_SIZE, x + 2.0, text_y, FontStyle::Regular);
+        y -= SLIP_ROW_HEIGHT_MM;
+        if table < tables {
+            page.line(x, y, right, y, ROW_STROKE);
+        }
+    }
+}
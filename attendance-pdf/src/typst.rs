@@ -0,0 +1,147 @@
+// Typst source export: reproduces the sheet as absolutely placed elements so
+// clubs can hand-tweak it or recompile with their own styles
+
+use crate::layout::{
+    Color, Element, FontStyle, ImageElement, LineElement, RectElement, Rotation, SheetLayout, TextElement,
+};
+use crate::render::SheetRenderer;
+use crate::{AppError, AttendanceConfig};
+use ::image::{ImageFormat, RgbImage};
+use std::fmt::Write;
+use std::io::Cursor;
+
+/// Typst markup (`.typ`); images are embedded inline as PNG bytes
+pub struct TypstRenderer;
+
+impl SheetRenderer for TypstRenderer {
+    fn extension(&self) -> &'static str {
+        "typ"
+    }
+
+    fn render(&self, layout: &SheetLayout, config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+        let mut out = String::new();
+
+        // Writing to a String cannot fail, so fmt results are ignored below
+        let _ = writeln!(out, "// Attendance sheet: {} ({})", config.class_name, config.event_id);
+        let _ = writeln!(out, "// Generated by attendance-pdf; coordinates are from the top-left corner");
+        let _ = writeln!(
+            out,
+            "#set page(width: {}mm, height: {}mm, margin: 0mm)",
+            layout.width, layout.height
+        );
+        // Place text by its baseline, as the PDF renderer does
+        let _ = writeln!(
+            out,
+            "#set text(font: (\"Helvetica\", \"Arial\", \"Liberation Sans\"), top-edge: \"baseline\", bottom-edge: \"descender\")"
+        );
+
+        for (index, page) in layout.pages.iter().enumerate() {
+            if index > 0 {
+                let _ = writeln!(out, "#pagebreak()");
+            }
+            let _ = writeln!(out, "\n// Page {}", index + 1);
+
+            for element in &page.elements {
+                let line = match element {
+                    Element::Text(text) => typst_text(text, layout.height),
+                    Element::Line(line) => typst_line(line, layout.height),
+                    Element::Rect(rect) => typst_rect(rect, layout.height),
+                    Element::Image(image) => typst_image(image, layout.height)?,
+                };
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+
+        Ok(out.into_bytes())
+    }
+}
+
+fn typst_text(text: &TextElement, page_height: f32) -> String {
+    let weight = match text.font {
+        FontStyle::Regular | FontStyle::Symbol => "regular",
+        FontStyle::Bold => "bold",
+    };
+    let body = format!("text(size: {}pt, weight: \"{}\", {})", text.size, weight, typst_string(&text.text));
+    // Typst turns clockwise, the layout counter-clockwise
+    let body = match text.rotation {
+        Rotation::Upright => body,
+        rotation => format!("rotate(-{}deg, origin: top + left, {})", rotation.degrees(), body),
+    };
+    format!("#place(top + left, dx: {:.2}mm, dy: {:.2}mm, {})", text.x, page_height - text.y, body)
+}
+
+fn typst_line(line: &LineElement, page_height: f32) -> String {
+    format!(
+        "#place(top + left, line(start: ({:.2}mm, {:.2}mm), end: ({:.2}mm, {:.2}mm), stroke: {}pt + {}))",
+        line.x1,
+        page_height - line.y1,
+        line.x2,
+        page_height - line.y2,
+        line.stroke.thickness,
+        typst_color(line.stroke.color),
+    )
+}
+
+fn typst_rect(rect: &RectElement, page_height: f32) -> String {
+    let fill = rect.style.fill.map_or("none".to_string(), typst_color);
+    let stroke = rect.style.stroke.map_or("none".to_string(), |stroke| {
+        format!("{}pt + {}", stroke.thickness, typst_color(stroke.color))
+    });
+    format!(
+        "#place(top + left, dx: {:.2}mm, dy: {:.2}mm, rect(width: {:.2}mm, height: {:.2}mm, radius: {:.2}mm, \
+         fill: {}, stroke: {}))",
+        rect.x,
+        page_height - rect.y - rect.height,
+        rect.width,
+        rect.height,
+        rect.style.radius,
+        fill,
+        stroke,
+    )
+}
+
+fn typst_image(image: &ImageElement, page_height: f32) -> Result<String, AppError> {
+    let prepared = &image.image;
+    let rgb = RgbImage::from_raw(prepared.width_px, prepared.height_px, prepared.rgb.clone())
+        .ok_or_else(|| AppError::ExportError("image buffer has the wrong size".to_string()))?;
+
+    let mut png = Vec::new();
+    rgb.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| AppError::ExportError(format!("Failed to encode image: {}", e)))?;
+    let byte_list: Vec<String> = png.iter().map(|b| b.to_string()).collect();
+
+    Ok(format!(
+        "#place(top + left, dx: {:.2}mm, dy: {:.2}mm, image(bytes(({},)), width: {:.2}mm, height: {:.2}mm, scaling: \"{}\"))",
+        image.x,
+        page_height - image.y - image.height,
+        byte_list.join(","),
+        image.width,
+        image.height,
+        if image.interpolate { "smooth" } else { "pixelated" },
+    ))
+}
+
+fn typst_color(color: Color) -> String {
+    format!(
+        "rgb({:.0}%, {:.0}%, {:.0}%)",
+        color.r * 100.0,
+        color.g * 100.0,
+        color.b * 100.0
+    )
+}
+
+/// Quote text as a Typst string literal so markup characters print literally
+fn typst_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,196 @@
+// vCard import: turn contacts exported from a phone or address book (.vcf)
+// into roster entries.
+//
+// Handles vCard 2.1, 3.0 and 4.0 as exported by the common phone and webmail
+// apps: folded lines, backslash escapes and 2.1's quoted-printable values.
+// Contact groups are matched against CATEGORIES.
+
+use crate::{AppError, RosterEntry};
+use std::path::Path;
+
+/// One contact's fields of interest
+#[derive(Debug, Default)]
+struct Contact {
+    formatted_name: Option<String>,
+    /// N: family;given;additional;prefix;suffix
+    structured_name: Option<Vec<String>>,
+    nickname: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    categories: Vec<String>,
+}
+
+impl Contact {
+    /// FN, or "Given Family" from N when the export has no FN
+    fn name(&self) -> Option<String> {
+        let formatted = self
+            .formatted_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        if let Some(name) = formatted {
+            return Some(name.to_string());
+        }
+        let parts = self.structured_name.as_ref()?;
+        let given_family: Vec<&str> = [parts.get(1), parts.first()]
+            .into_iter()
+            .flatten()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect();
+        (!given_family.is_empty()).then(|| given_family.join(" "))
+    }
+}
+
+/// Read a .vcf file into roster entries, in file order. With `group`, only
+/// contacts whose CATEGORIES include it (ignoring case) are kept; contacts
+/// without a name are skipped.
+pub fn import_vcard(path: &Path, group: Option<&str>) -> Result<Vec<RosterEntry>, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::RosterError(format!("{}: {}", path.display(), e)))?;
+    let contacts = parse_vcards(&content);
+    if contacts.is_empty() {
+        return Err(AppError::RosterError(format!("{}: no contacts found", path.display())));
+    }
+
+    Ok(contacts
+        .into_iter()
+        .filter(|contact| {
+            group.is_none_or(|group| contact.categories.iter().any(|c| c.eq_ignore_ascii_case(group)))
+        })
+        .filter_map(|contact| {
+            Some(RosterEntry {
+                name: contact.name()?,
+                nickname: contact.nickname.filter(|nickname| !nickname.trim().is_empty()),
+                email: contact.email,
+                phone: contact.phone,
+                ..RosterEntry::default()
+            })
+        })
+        .collect())
+}
+
+fn parse_vcards(content: &str) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut current: Option<Contact> = None;
+
+    for line in unfold(content) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = key.split(';');
+        // Drop any "item1." group prefix
+        let property = params.next().unwrap_or_default();
+        let property = property.rsplit('.').next().unwrap_or_default().to_ascii_uppercase();
+        let quoted_printable = params.any(|param| param.eq_ignore_ascii_case("ENCODING=QUOTED-PRINTABLE"));
+        let value = if quoted_printable {
+            decode_quoted_printable(value)
+        } else {
+            value.to_string()
+        };
+
+        match property.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VCARD") => current = Some(Contact::default()),
+            "END" if value.eq_ignore_ascii_case("VCARD") => contacts.extend(current.take()),
+            _ => {
+                let Some(contact) = current.as_mut() else {
+                    continue;
+                };
+                match property.as_str() {
+                    "FN" => contact.formatted_name = Some(unescape(&value)),
+                    "NICKNAME" => contact.nickname = split_unescaped(&value, ',').into_iter().next(),
+                    "N" => contact.structured_name = Some(split_unescaped(&value, ';')),
+                    "EMAIL" if contact.email.is_none() => contact.email = Some(unescape(&value)),
+                    "TEL" if contact.phone.is_none() => {
+                        contact.phone = Some(unescape(value.trim_start_matches("tel:")))
+                    }
+                    "CATEGORIES" => contact.categories.extend(split_unescaped(&value, ',')),
+                    _ => {}
+                }
+            }
+        }
+    }
+    contacts
+}
+
+/// Join folded lines: a line starting with a space or tab continues the one
+/// before. Quoted-printable values fold with a trailing "=" instead.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match lines.last_mut() {
+            Some(last) if raw.starts_with([' ', '\t']) => last.push_str(&raw[1..]),
+            Some(last) if last.ends_with('=') && last.to_ascii_uppercase().contains("QUOTED-PRINTABLE") => {
+                last.pop();
+                last.push_str(raw);
+            }
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Split on unescaped `separator`s, unescaping each part
+fn split_unescaped(value: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut escaped = false;
+    for c in value.chars() {
+        if escaped {
+            part.push('\\');
+            part.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == separator {
+            parts.push(unescape(&part));
+            part.clear();
+        } else {
+            part.push(c);
+        }
+    }
+    parts.push(unescape(&part));
+    parts
+}
+
+/// "=C3=A9" style bytes, as vCard 2.1 exports encode non-ASCII names
+fn decode_quoted_printable(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'=')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
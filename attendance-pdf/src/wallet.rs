@@ -0,0 +1,230 @@
+// Phone wallet passes: one Apple Wallet .pkpass and one Google Wallet pass
+// object per student for a class series, carrying the same check-in QR code
+// as a printed class pass so `scan` accepts either.
+//
+// Apple passes must be signed with the club's Pass Type ID certificate; the
+// signature is made with the `openssl` command, like printing goes through
+// `lp`. Google passes are written as generic pass object JSON, ready to post
+// to the Wallet API or wrap in a "Save to Google Wallet" link.
+
+use crate::qr::PassPayload;
+use crate::AppError;
+use ::image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use serde::Deserialize;
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Apple's icon sizes in points; the @2x image doubles them
+const ICON_SIZE_PX: u32 = 29;
+/// Icon color when the club has no logo (dark green, like the card table)
+const ICON_COLOR: Rgb<u8> = Rgb([27, 94, 32]);
+const BACKGROUND_COLOR: &str = "#1B5E20";
+
+/// `[wallet]`: who issues the passes and how they are signed
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WalletSettings {
+    /// Shown as the pass issuer, e.g. "Harmonic Systems Bridge Club"
+    pub organization: Option<String>,
+    pub apple: Option<AppleWalletSettings>,
+    pub google: Option<GoogleWalletSettings>,
+}
+
+/// `[wallet.apple]`: Pass Type ID and its signing certificate (PEM files,
+/// relative to the config file)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppleWalletSettings {
+    pub pass_type_id: String,
+    pub team_id: String,
+    pub certificate: String,
+    pub key: String,
+    /// Apple WWDR intermediate certificate
+    pub wwdr: String,
+}
+
+/// `[wallet.google]`: the issuer account from the Google Pay & Wallet console
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoogleWalletSettings {
+    pub issuer_id: String,
+}
+
+/// The class series the passes are for
+pub struct WalletClass<'a> {
+    pub event_id: &'a str,
+    pub class_name: &'a str,
+    pub teacher: &'a str,
+    pub organization: &'a str,
+}
+
+/// One student's pass contents
+pub struct WalletPass<'a> {
+    pub class: &'a WalletClass<'a>,
+    pub serial: String,
+    pub student: String,
+}
+
+impl WalletPass<'_> {
+    /// The check-in code, identical to the printed pass's QR code
+    pub fn qr_message(&self) -> String {
+        let payload = PassPayload {
+            app: "bridge-attendance".to_string(),
+            kind: "pass".to_string(),
+            serial: self.serial.clone(),
+            event_id: self.class.event_id.to_string(),
+            name: self.class.class_name.to_string(),
+            student: Some(self.student.clone()),
+        };
+        serde_json::to_string(&payload).expect("pass payload serializes")
+    }
+
+    /// pass.json for a generic Apple Wallet pass
+    pub fn apple_pass_json(&self, apple: &AppleWalletSettings) -> serde_json::Value {
+        json!({
+            "formatVersion": 1,
+            "passTypeIdentifier": apple.pass_type_id,
+            "teamIdentifier": apple.team_id,
+            "serialNumber": self.serial,
+            "organizationName": self.class.organization,
+            "description": format!("{} class pass", self.class.class_name),
+            "logoText": self.class.class_name,
+            "backgroundColor": BACKGROUND_COLOR,
+            "foregroundColor": "#FFFFFF",
+            "labelColor": "#C8E6C9",
+            "barcodes": [{
+                "format": "PKBarcodeFormatQR",
+                "message": self.qr_message(),
+                "messageEncoding": "iso-8859-1",
+                "altText": self.serial,
+            }],
+            "generic": {
+                "primaryFields": [{ "key": "student", "label": "STUDENT", "value": self.student }],
+                "secondaryFields": [{ "key": "class", "label": "CLASS", "value": self.class.class_name }],
+                "auxiliaryFields": [{ "key": "teacher", "label": "INSTRUCTOR", "value": self.class.teacher }],
+            },
+        })
+    }
+
+    /// Generic pass object for the Google Wallet API
+    pub fn google_object(&self, google: &GoogleWalletSettings) -> serde_json::Value {
+        let text = |value: &str| json!({ "defaultValue": { "language": "en-US", "value": value } });
+        json!({
+            "id": format!("{}.{}", google.issuer_id, self.serial),
+            "classId": format!("{}.{}", google.issuer_id, self.class.event_id),
+            "state": "ACTIVE",
+            "cardTitle": text(self.class.class_name),
+            "subheader": text("Student"),
+            "header": text(&self.student),
+            "hexBackgroundColor": BACKGROUND_COLOR,
+            "barcode": {
+                "type": "QR_CODE",
+                "value": self.qr_message(),
+                "alternateText": self.serial,
+            },
+            "textModulesData": [
+                { "id": "teacher", "header": "Instructor", "body": self.class.teacher },
+                { "id": "issuer", "header": "Issued by", "body": self.class.organization },
+            ],
+        })
+    }
+
+    /// Zipped, signed .pkpass bundle. `base_dir` resolves the certificate
+    /// paths in the settings.
+    pub fn pkpass(
+        &self,
+        apple: &AppleWalletSettings,
+        base_dir: &Path,
+        logo: Option<&DynamicImage>,
+    ) -> Result<Vec<u8>, AppError> {
+        let pass_json = serde_json::to_vec_pretty(&self.apple_pass_json(apple))
+            .map_err(|e| AppError::WalletError(e.to_string()))?;
+        let files = [
+            ("pass.json", pass_json),
+            ("icon.png", icon_png(logo, ICON_SIZE_PX)?),
+            ("icon@2x.png", icon_png(logo, ICON_SIZE_PX * 2)?),
+        ];
+
+        // manifest.json lists the SHA-1 of every file; its signature covers them all
+        let manifest: serde_json::Map<String, serde_json::Value> = files
+            .iter()
+            .map(|(name, bytes)| (name.to_string(), json!(sha1_hex(bytes))))
+            .collect();
+        let manifest = serde_json::to_vec(&manifest).map_err(|e| AppError::WalletError(e.to_string()))?;
+        let signature = sign_manifest(&manifest, apple, base_dir)?;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, bytes) in files
+            .iter()
+            .map(|(name, bytes)| (*name, bytes.as_slice()))
+            .chain([("manifest.json", manifest.as_slice()), ("signature", signature.as_slice())])
+        {
+            zip.start_file(name, options)
+                .and_then(|_| zip.write_all(bytes).map_err(Into::into))
+                .map_err(|e| AppError::WalletError(format!("{}: {}", name, e)))?;
+        }
+        let bundle = zip.finish().map_err(|e| AppError::WalletError(e.to_string()))?;
+        Ok(bundle.into_inner())
+    }
+}
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    Sha1::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The club logo, or a plain square, at `size` pixels
+fn icon_png(logo: Option<&DynamicImage>, size: u32) -> Result<Vec<u8>, AppError> {
+    let icon = match logo {
+        Some(logo) => logo.resize(size, size, ::image::imageops::FilterType::Lanczos3),
+        None => DynamicImage::ImageRgb8(RgbImage::from_pixel(size, size, ICON_COLOR)),
+    };
+    let mut png = Cursor::new(Vec::new());
+    icon.write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| AppError::WalletError(format!("Failed to encode icon: {}", e)))?;
+    Ok(png.into_inner())
+}
+
+/// Detached PKCS #7 signature of the manifest, in DER
+fn sign_manifest(manifest: &[u8], apple: &AppleWalletSettings, base_dir: &Path) -> Result<Vec<u8>, AppError> {
+    let work_dir = std::env::temp_dir().join(format!("attendance-pdf-wallet-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir)?;
+    let result = run_openssl_sign(manifest, apple, base_dir, &work_dir);
+    std::fs::remove_dir_all(&work_dir).ok();
+    result
+}
+
+fn run_openssl_sign(
+    manifest: &[u8],
+    apple: &AppleWalletSettings,
+    base_dir: &Path,
+    work_dir: &Path,
+) -> Result<Vec<u8>, AppError> {
+    let manifest_path = work_dir.join("manifest.json");
+    let signature_path = work_dir.join("signature");
+    std::fs::write(&manifest_path, manifest)?;
+
+    let resolve = |path: &str| -> PathBuf { base_dir.join(path) };
+    let output = Command::new("openssl")
+        .args(["smime", "-binary", "-sign", "-outform", "DER"])
+        .arg("-signer")
+        .arg(resolve(&apple.certificate))
+        .arg("-inkey")
+        .arg(resolve(&apple.key))
+        .arg("-certfile")
+        .arg(resolve(&apple.wwdr))
+        .arg("-in")
+        .arg(&manifest_path)
+        .arg("-out")
+        .arg(&signature_path)
+        .output()
+        .map_err(|e| AppError::WalletError(format!("could not run openssl: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::WalletError(format!("signing failed: {}", stderr.trim())));
+    }
+    Ok(std::fs::read(&signature_path)?)
+}
@@ -0,0 +1,252 @@
+// Excel (.xlsx) export: one row per student with Present/Table/Seat columns
+// to fill in, set up to print on a single page width. Like the DOCX backend
+// it works from the config, since spreadsheets have no fixed placement.
+
+use crate::layout::SheetLayout;
+use crate::render::SheetRenderer;
+use crate::{
+    format_date_display, instructor_line, roster, sheet_title, venue_line, AppError, AttendanceConfig, HEADER_FONT_SIZE, MARGIN_MM,
+    SIGNATURE_MIN_ROW_HEIGHT_MM, TITLE_FONT_SIZE,
+};
+use chrono::Datelike;
+use rust_xlsxwriter::{
+    DataValidation, DocProperties, ExcelDateTime, Format, FormatAlign, FormatBorder, Workbook,
+    Worksheet, XlsxError,
+};
+
+/// Blank rows left under the roster for walk-ins
+const WALK_IN_ROWS: u32 = 8;
+
+/// Roster rows with a signature cell, in points
+const SIGNATURE_ROW_HEIGHT_PT: f64 = (SIGNATURE_MIN_ROW_HEIGHT_MM / 25.4 * 72.0) as f64;
+
+/// Fill behind highlighted roster names
+const HIGHLIGHT_COLOR: u32 = 0xFFF28C;
+
+/// Letter paper in Excel's page setup numbering
+const PAPER_LETTER: u8 = 1;
+
+/// Row holding the NAME/PRESENT/TABLE/SEAT headings
+const GRID_HEADER_ROW: u32 = 7;
+
+/// Excel workbook (`.xlsx`)
+pub struct XlsxRenderer;
+
+impl SheetRenderer for XlsxRenderer {
+    fn extension(&self) -> &'static str {
+        "xlsx"
+    }
+
+    fn render(&self, _layout: &SheetLayout, config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+        build_workbook(config).map_err(|e| AppError::ExportError(format!("Failed to write XLSX: {}", e)))
+    }
+}
+
+fn build_workbook(config: &AttendanceConfig) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+
+    // The creation time is otherwise the wall clock
+    if config.reproducible {
+        let date = ExcelDateTime::from_ymd(
+            config.date.year() as u16,
+            config.date.month() as u8,
+            config.date.day() as u8,
+        )?;
+        workbook.set_properties(&DocProperties::new().set_creation_datetime(&date));
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Attendance")?;
+    setup_printing(sheet, config);
+    write_header(sheet, config)?;
+    write_grid(sheet, config)?;
+
+    if config.mailing_list {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Mailing List")?;
+        setup_printing(sheet, config);
+        write_mailing_list(sheet, config.mailing_rows)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+// ============================================================================
+// Sections
+// ============================================================================
+
+fn write_header(sheet: &mut Worksheet, config: &AttendanceConfig) -> Result<(), XlsxError> {
+    let title = Format::new().set_bold().set_font_size(TITLE_FONT_SIZE);
+    let class = Format::new().set_bold().set_font_size(HEADER_FONT_SIZE);
+
+    sheet.write_string_with_format(0, 0, sheet_title(config), &title)?;
+    sheet.write_string_with_format(1, 0, &config.class_name, &class)?;
+    sheet.write_string(2, 0, format_date_display(&config.date))?;
+    sheet.write_string(3, 0, instructor_line(config))?;
+    if !config.location.is_empty() {
+        sheet.write_string(4, 0, format!("Location: {}", config.location))?;
+    }
+    sheet.write_string(5, 0, format!("ID: {}", config.event_id))?;
+    if let Some(venue) = venue_line(config) {
+        sheet.write_string(6, 0, venue)?;
+    }
+    Ok(())
+}
+
+/// Roster mode lists each student; blank mode pre-fills table and seat so
+/// players write their names against a position, as on the printed sheet
+fn write_grid(sheet: &mut Worksheet, config: &AttendanceConfig) -> Result<(), XlsxError> {
+    let heading = Format::new()
+        .set_bold()
+        .set_border(FormatBorder::Thin)
+        .set_background_color(0xD9D9D9);
+    let cell = Format::new().set_border(FormatBorder::Thin);
+    let centered = cell.clone().set_align(FormatAlign::Center);
+
+    // Students sign in place of the Present mark when a signature is required
+    let signature = config.signature_column && config.roster.is_some();
+
+    sheet.set_column_width(0, 36)?;
+    sheet.set_column_width(1, if signature { 30 } else { 10 })?;
+    sheet.set_column_width(2, 10)?;
+    sheet.set_column_width(3, 10)?;
+    let present_label = if signature { "SIGNATURE" } else { "PRESENT" };
+    for (col, label) in ["NAME", present_label, "TABLE", "SEAT"].iter().enumerate() {
+        sheet.write_string_with_format(GRID_HEADER_ROW, col as u16, *label, &heading)?;
+    }
+    sheet.set_repeat_rows(GRID_HEADER_ROW, GRID_HEADER_ROW)?;
+
+    let first_row = GRID_HEADER_ROW + 1;
+    let rows = match &config.roster {
+        Some(roster) => roster.len() as u32 + WALK_IN_ROWS,
+        None => config.blank_rows,
+    };
+    if rows == 0 {
+        return Ok(());
+    }
+    let last_row = first_row + rows - 1;
+
+    let seats = ["N", "S", "E", "W"];
+    for index in 0..rows {
+        let row = first_row + index;
+        let student = config.roster.as_ref().and_then(|roster| roster.get(index as usize));
+        let mut name = student
+            .map(|student| roster::display_name(student, config))
+            .unwrap_or_default();
+        if let Some(pronouns) = student.and_then(|student| student.pronouns.as_deref()) {
+            name = format!("{} ({})", name, pronouns);
+        }
+        if student.is_some_and(|student| student.is_new) {
+            name = format!("{} ({})", name, roster::NEW_BADGE);
+        }
+        if student.is_some_and(|student| student.birthday_in_week(config.date)) {
+            name = format!("{} {}", name, roster::BIRTHDAY_CAKE);
+        }
+        let mut name_format = cell.clone();
+        if let Some(note) = student.and_then(|student| student.note.as_deref()) {
+            name = format!("{}\n{}", name, note);
+            name_format = name_format.set_text_wrap();
+        }
+        if student.is_some_and(|student| student.bold) {
+            name_format = name_format.set_bold();
+        }
+        if student.is_some_and(|student| student.highlight) {
+            name_format = name_format.set_background_color(HIGHLIGHT_COLOR);
+        }
+        sheet.write_string_with_format(row, 0, &name, &name_format)?;
+        sheet.write_blank(row, 1, &centered)?;
+        if signature {
+            sheet.set_row_height(row, SIGNATURE_ROW_HEIGHT_PT)?;
+        }
+
+        if config.roster.is_some() {
+            sheet.write_blank(row, 2, &centered)?;
+            sheet.write_blank(row, 3, &centered)?;
+        } else {
+            sheet.write_number_with_format(row, 2, (index / 4 + 1) as f64, &centered)?;
+            sheet.write_string_with_format(row, 3, seats[(index % 4) as usize], &centered)?;
+        }
+    }
+
+    if !signature {
+        let present = DataValidation::new()
+            .allow_list_strings(&["Y"])?
+            .set_input_message("Enter Y when the student is here")?;
+        sheet.add_data_validation(first_row, 1, last_row, 1, &present)?;
+    }
+    if config.roster.is_some() {
+        let seat = DataValidation::new().allow_list_strings(&seats)?;
+        sheet.add_data_validation(first_row, 3, last_row, 3, &seat)?;
+    }
+
+    // Marker legend directly under the grid
+    let mut last_printed = last_row;
+    for entry in roster::legend(config) {
+        last_printed += 1;
+        sheet.write_string(last_printed, 0, entry)?;
+    }
+
+    // Zoom registrants get a Present mark of their own
+    if let Some(names) = config.online_attendees.as_deref().filter(|names| !names.is_empty()) {
+        let header_row = last_printed + 2;
+        sheet.write_string_with_format(header_row, 0, "ONLINE ATTENDEE", &heading)?;
+        sheet.write_string_with_format(header_row, 1, "PRESENT", &heading)?;
+        for (row, name) in (header_row + 1..).zip(names) {
+            sheet.write_string_with_format(row, 0, name, &cell)?;
+            sheet.write_blank(row, 1, &centered)?;
+        }
+        last_printed = header_row + names.len() as u32;
+        let present = DataValidation::new().allow_list_strings(&["Y"])?;
+        sheet.add_data_validation(header_row + 1, 1, last_printed, 1, &present)?;
+    }
+
+    // Volunteers sign in below the students
+    if config.helper_rows > 0 {
+        let header_row = last_printed + 2;
+        sheet.write_string_with_format(header_row, 0, "HELPER / VOLUNTEER", &heading)?;
+        sheet.merge_range(header_row, 1, header_row, 3, "ROLE", &heading)?;
+        for row in header_row + 1..=header_row + config.helper_rows {
+            sheet.write_blank(row, 0, &cell)?;
+            sheet.merge_range(row, 1, row, 3, "", &cell)?;
+        }
+        last_printed = header_row + config.helper_rows;
+    }
+
+    sheet.set_print_area(0, 0, last_printed, 3)?;
+    Ok(())
+}
+
+fn write_mailing_list(sheet: &mut Worksheet, rows: u32) -> Result<(), XlsxError> {
+    let heading = Format::new()
+        .set_bold()
+        .set_border(FormatBorder::Thin)
+        .set_background_color(0xD9D9D9);
+    let cell = Format::new().set_border(FormatBorder::Thin);
+
+    sheet.set_column_width(0, 36)?;
+    sheet.set_column_width(1, 44)?;
+    sheet.write_string_with_format(0, 0, "NAME", &heading)?;
+    sheet.write_string_with_format(0, 1, "EMAIL", &heading)?;
+    for row in 1..=rows {
+        sheet.write_blank(row, 0, &cell)?;
+        sheet.write_blank(row, 1, &cell)?;
+    }
+    Ok(())
+}
+
+/// Portrait Letter, one page wide, with the sheet's page margins and the
+/// sponsor acknowledgment (text only) in the footer
+fn setup_printing(sheet: &mut Worksheet, config: &AttendanceConfig) {
+    let margin_in = (MARGIN_MM / 25.4) as f64;
+    sheet
+        .set_paper_size(PAPER_LETTER)
+        .set_portrait()
+        .set_print_fit_to_pages(1, 0)
+        .set_print_center_horizontally(true)
+        .set_margins(margin_in, margin_in, margin_in, margin_in, 0.3, 0.3)
+        .set_footer(match &config.sponsor {
+            // "&" starts a footer control code, so literal ampersands are doubled
+            Some(sponsor) => format!("&L{}&RPage &P of &N", sponsor.text.replace('&', "&&")),
+            None => "&CPage &P of &N".to_string(),
+        });
+}
@@ -0,0 +1,147 @@
+// Zoom registrants for hybrid classes: the approved registrants of a Zoom
+// meeting, fetched on each run, are listed in an "Online attendees" section
+// of the sheet so the teacher records who joined remotely on the same page
+// as the room.
+//
+// Authentication is an OAuth access token, either given directly in
+// ZOOM_ACCESS_TOKEN or minted from the config file's [zoom] table (the
+// account ID, client ID and client secret of a Server-to-Server OAuth app
+// with the meeting:read scope).
+
+use crate::net::{self, require_network};
+use crate::AppError;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Deserialize;
+
+/// Takes precedence over the [zoom] app credentials
+pub const ACCESS_TOKEN_ENV: &str = "ZOOM_ACCESS_TOKEN";
+
+const TOKEN_URL: &str = "https://zoom.us/oauth/token";
+const ZOOM_API: &str = "https://api.zoom.us/v2";
+/// The most registrants Zoom returns per page
+const PAGE_SIZE: &str = "300";
+/// Guards against a page token that never ends
+const MAX_PAGES: usize = 20;
+
+/// `[zoom]`: Server-to-Server OAuth app for the Zoom API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ZoomSettings {
+    pub account_id: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// Names of the approved registrants of Zoom meeting `meeting_id`, once
+/// each, sorted
+pub fn fetch_zoom_registrants(meeting_id: &str, settings: Option<&ZoomSettings>) -> Result<Vec<String>, AppError> {
+    require_network("Zoom")?;
+    let token = access_token(settings)?;
+
+    // Meeting IDs are often pasted as "812 3456 7890"
+    let meeting_id: String = meeting_id.chars().filter(|c| !c.is_whitespace()).collect();
+    let url = format!("{}/meetings/{}/registrants", ZOOM_API, meeting_id);
+    let mut names = Vec::new();
+    let mut page_token: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let mut request = net::get(&url)
+            .set("Authorization", &format!("Bearer {}", token))
+            .query("status", "approved")
+            .query("page_size", PAGE_SIZE);
+        if let Some(page_token) = &page_token {
+            request = request.query("next_page_token", page_token);
+        }
+        let response = net::call(request).map_err(|e| AppError::RegistrationError(format!("{}: {}", url, e)))?;
+        let body = net::read_string(response)
+            .map_err(|e| AppError::RegistrationError(format!("Failed to read response: {}", e)))?;
+        let page = parse_zoom_registrants(&body)?;
+        names.extend(page.names);
+        page_token = page.next_page_token;
+        if page_token.is_none() {
+            break;
+        }
+    }
+    Ok(online_attendees(names))
+}
+
+/// One page of the meeting registrants endpoint
+#[derive(Debug, Default, PartialEq)]
+pub struct RegistrantPage {
+    pub names: Vec<String>,
+    /// Where the next page starts, if there is one
+    pub next_page_token: Option<String>,
+}
+
+pub fn parse_zoom_registrants(body: &str) -> Result<RegistrantPage, AppError> {
+    let response: RegistrantsResponse = serde_json::from_str(body)
+        .map_err(|e| AppError::RegistrationError(format!("Unexpected Zoom response: {}", e)))?;
+    let names = response
+        .registrants
+        .into_iter()
+        .map(|registrant| format!("{} {}", registrant.first_name, registrant.last_name.unwrap_or_default()))
+        .collect();
+    let next_page_token = response.next_page_token.filter(|token| !token.is_empty());
+    Ok(RegistrantPage { names, next_page_token })
+}
+
+/// Names with single spaces, without blanks or repeats (someone who
+/// registered twice), sorted case-insensitively
+pub fn online_attendees(names: Vec<String>) -> Vec<String> {
+    let mut names: Vec<String> = names
+        .iter()
+        .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    names
+}
+
+fn access_token(settings: Option<&ZoomSettings>) -> Result<String, AppError> {
+    if let Ok(token) = std::env::var(ACCESS_TOKEN_ENV) {
+        return Ok(token);
+    }
+    let settings = settings.ok_or_else(|| {
+        AppError::RegistrationError(format!(
+            "set {} or add a [zoom] account_id, client_id and client_secret to the config file",
+            ACCESS_TOKEN_ENV
+        ))
+    })?;
+
+    let credentials = BASE64.encode(format!("{}:{}", settings.client_id, settings.client_secret));
+    let request = net::post(TOKEN_URL)
+        .set("Authorization", &format!("Basic {}", credentials))
+        .query("grant_type", "account_credentials")
+        .query("account_id", &settings.account_id);
+    let response = net::call(request)
+        .map_err(|e| AppError::RegistrationError(format!("Zoom token request failed: {}", e)))?;
+    let body = net::read_string(response)
+        .map_err(|e| AppError::RegistrationError(format!("Failed to read response: {}", e)))?;
+    let token: TokenResponse = serde_json::from_str(&body)
+        .map_err(|e| AppError::RegistrationError(format!("Unexpected Zoom response: {}", e)))?;
+    Ok(token.access_token)
+}
+
+// ============================================================================
+// Zoom API Responses
+// ============================================================================
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct RegistrantsResponse {
+    #[serde(default)]
+    registrants: Vec<Registrant>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Registrant {
+    #[serde(default)]
+    first_name: String,
+    last_name: Option<String>,
+}
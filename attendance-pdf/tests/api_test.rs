@@ -0,0 +1,449 @@
+use attendance_pdf::{AppError, AttendanceConfig, AttendanceSheet, RosterEntry, SheetLayout, SheetRenderer};
+use attendance_pdf::deals::{parse_lin, parse_pbn, Seat};
+use attendance_pdf::names::{parse_name, with_nickname};
+use attendance_pdf::net;
+use attendance_pdf::nfc::{ndef_tlv, ndef_uri_message};
+use attendance_pdf::{parse_relative_date, read_roster_source, resolve_source, source_dir};
+use attendance_pdf::qr::module_pixels;
+use attendance_pdf::registrations::{parse_eventbrite_attendees, parse_signupgenius_export};
+use attendance_pdf::zoom::{online_attendees, parse_zoom_registrants};
+use attendance_pdf::roster_file::{csv_records, parse_roster_csv, roster_csv, RosterFormat, Visibility};
+use attendance_pdf::rsvp::{mark_confirmed, parse_rsvps};
+use chrono::NaiveDate;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+
+#[test]
+fn test_builder_renders_pdf_bytes() {
+    let pdf = AttendanceSheet::builder()
+        .class("Tuesday Beginner")
+        .date(NaiveDate::from_ymd_opt(2025, 1, 7).unwrap())
+        .roster(vec!["Alice Johnson".to_string(), "Bob Smith".to_string()])
+        .build()
+        .expect("Failed to build sheet")
+        .render_pdf()
+        .expect("Failed to render PDF");
+
+    assert!(pdf.starts_with(b"%PDF"), "Output is not a PDF");
+    assert!(pdf.len() > 1000, "PDF is too small");
+}
+
+#[test]
+fn test_builder_applies_defaults() {
+    let sheet = AttendanceSheet::builder()
+        .class("Advanced")
+        .event_id("abcd1234")
+        .build()
+        .unwrap();
+
+    let config = sheet.config();
+    assert_eq!(config.teacher, "Rick");
+    assert_eq!(config.event_id, "ABCD1234");
+    assert_eq!(config.blank_rows, 32);
+    assert!(config.mailing_list);
+    assert!(sheet.layout().unwrap().find_text("Advanced").is_some());
+}
+
+#[test]
+fn test_builder_requires_class_name() {
+    let result = AttendanceSheet::builder().teacher("Jane").build();
+    assert!(matches!(result, Err(AppError::ConfigError(_))));
+}
+
+#[test]
+fn test_builder_rejects_invalid_event_id() {
+    let result = AttendanceSheet::builder().class("Test").event_id("xyz").build();
+    assert!(matches!(result, Err(AppError::EventIdError(_))));
+}
+
+#[test]
+fn test_builder_reproducible_output_is_stable() {
+    let render = || {
+        AttendanceSheet::builder()
+            .class("Stable")
+            .date(NaiveDate::from_ymd_opt(2025, 1, 7).unwrap())
+            .event_id("ABCD1234")
+            .reproducible(true)
+            .build()
+            .unwrap()
+            .render_pdf()
+            .unwrap()
+    };
+    assert!(render() == render(), "Reproducible builds differ");
+}
+
+/// Minimal backend listing text placements, one per line
+struct PlainTextRenderer;
+
+impl SheetRenderer for PlainTextRenderer {
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn render(&self, layout: &SheetLayout, _config: &AttendanceConfig) -> Result<Vec<u8>, AppError> {
+        let lines: Vec<String> = layout
+            .texts()
+            .map(|(page, text)| format!("{} {}", page + 1, text.text))
+            .collect();
+        Ok(lines.join("\n").into_bytes())
+    }
+}
+
+#[test]
+fn test_custom_renderer_backend() {
+    let output = AttendanceSheet::builder()
+        .class("Renderer Test")
+        .event_id("ABCD1234")
+        .roster(vec!["Alice Johnson".to_string()])
+        .build()
+        .unwrap()
+        .render(&PlainTextRenderer)
+        .unwrap();
+
+    let text = String::from_utf8(output).unwrap();
+    assert!(text.contains("1 Renderer Test"));
+    assert!(text.contains("1 Alice Johnson"));
+    assert!(text.contains("1 ID: ABCD1234"));
+}
+
+#[test]
+fn test_qr_payload_records_substitute() {
+    use attendance_pdf::qr::QrPayload;
+
+    let regular = AttendanceSheet::builder().class("Advanced").build().unwrap();
+    let json = serde_json::to_string(&QrPayload::for_event(regular.config())).unwrap();
+    assert!(!json.contains("substitute"), "Field should be omitted without a substitute");
+
+    let covered = AttendanceSheet::builder()
+        .class("Advanced")
+        .teacher("Rick")
+        .substitute("Pat")
+        .build()
+        .unwrap();
+    let payload = QrPayload::for_event(covered.config());
+    assert_eq!(payload.teacher, "Rick");
+    assert_eq!(payload.substitute.as_deref(), Some("Pat"));
+}
+
+#[test]
+fn test_relative_dates() {
+    let tuesday = NaiveDate::from_ymd_opt(2025, 1, 7).unwrap();
+    let date = |text| parse_relative_date(text, tuesday);
+    assert_eq!(date("today"), Some(tuesday));
+    assert_eq!(date("tomorrow"), NaiveDate::from_ymd_opt(2025, 1, 8));
+    assert_eq!(date("next-thursday"), NaiveDate::from_ymd_opt(2025, 1, 9));
+    assert_eq!(date("next-monday"), NaiveDate::from_ymd_opt(2025, 1, 13));
+    assert_eq!(date("next-Tuesday"), NaiveDate::from_ymd_opt(2025, 1, 14), "A week on, not today");
+    assert_eq!(date("next-someday"), None);
+}
+
+#[test]
+fn test_parse_name() {
+    let last_first = |raw| parse_name(raw).last_first();
+    assert_eq!(last_first("Anna van der Berg"), "van der Berg, Anna");
+    assert_eq!(last_first("van der Berg, Anna"), "van der Berg, Anna");
+    assert_eq!(last_first("John Smith Jr."), "Smith, John, Jr.");
+    assert_eq!(last_first("John Smith, Jr."), "Smith, John, Jr.");
+    assert_eq!(last_first("Smith, John, III"), "Smith, John, III");
+    assert_eq!(last_first("Mary Ann Lee *"), "Lee, Mary Ann *");
+    assert_eq!(last_first("Cher"), "Cher");
+
+    assert_eq!(parse_name("van der Berg, Anna").first_last(), "Anna van der Berg");
+    assert_eq!(parse_name("Anna van der Berg").sort_key(), "berg anna");
+
+    assert_eq!(with_nickname("Robert Smith Jr. *", "Bob"), "Bob Smith Jr. *");
+    assert_eq!(with_nickname("Smith, Robert", "Bob"), "Bob Smith");
+    assert_eq!(with_nickname("Roberto", "Bob"), "Bob");
+}
+
+#[test]
+fn test_ndef_uri_message() {
+    let message = ndef_uri_message("https://example.com/c");
+    // Short well-known record, type "U", https:// abbreviated to 0x04
+    assert_eq!(message[..5], [0xD1, 0x01, 0x0E, b'U', 0x04]);
+    assert_eq!(&message[5..], b"example.com/c");
+
+    let tlv = ndef_tlv(&message);
+    assert_eq!(tlv[..2], [0x03, message.len() as u8]);
+    assert_eq!(tlv.last(), Some(&0xFE));
+
+    let long = ndef_uri_message(&format!("https://example.com/{}", "x".repeat(300)));
+    assert_eq!(long[0], 0xC1, "Payloads over 255 bytes need a full-length record");
+    assert_eq!(ndef_tlv(&long)[..2], [0x03, 0xFF]);
+}
+
+#[test]
+fn test_qr_module_pixels_reach_target_dpi() {
+    // 30 mm at 600 dpi is 709 px; 41 modules need 18 px each to reach it
+    assert_eq!(module_pixels(41, 30.0, 600), 18);
+    assert_eq!(module_pixels(41, 30.0, 300), 9);
+    assert_eq!(module_pixels(200, 5.0, 72), 1, "Never less than a pixel per module");
+}
+
+#[test]
+fn test_csv_records_undo_quoting() {
+    let records = csv_records("email,joined\r\n\"smith, j\"@example.com,2025-01-07\n\n\"say \"\"hi\"\"\",\n");
+    assert_eq!(
+        records,
+        [
+            vec!["email".to_string(), "joined".to_string()],
+            vec!["smith, j@example.com".to_string(), "2025-01-07".to_string()],
+            vec!["say \"hi\"".to_string(), String::new()],
+        ]
+    );
+}
+
+#[test]
+fn test_roster_read_from_csv() {
+    let csv = "\
+Name,EMAIL,Level,Junior,Highlight,Paid\r
+\"Johnson, Alice\",alice@example.com,Beginner,yes,\r
+Bob Smith,,Intermediate,no,X,yes\r
+,,,,,\r
+";
+    let entries = parse_roster_csv(csv).unwrap();
+    assert_eq!(entries.len(), 2, "Rows without a name are skipped");
+    assert_eq!(entries[0].name, "Johnson, Alice");
+    assert_eq!(entries[0].email.as_deref(), Some("alice@example.com"));
+    assert_eq!(entries[0].level.as_deref(), Some("Beginner"));
+    assert!(entries[0].junior && !entries[0].highlight);
+    assert_eq!(entries[1].email, None);
+    assert!(!entries[1].junior && entries[1].highlight);
+
+    // What `roster export` writes reads back the same
+    let exported = roster_csv(&entries, Visibility::Archive);
+    let reread = parse_roster_csv(&exported).unwrap();
+    assert_eq!(serde_json::to_string(&reread).unwrap(), serde_json::to_string(&entries).unwrap());
+
+    assert!(matches!(parse_roster_csv("email,level\na@example.com,Beginner\n"), Err(AppError::RosterError(_))));
+
+    assert_eq!(RosterFormat::detect("members.CSV"), RosterFormat::Csv);
+    assert_eq!(RosterFormat::detect("https://club.example.org/roster.csv?week=3"), RosterFormat::Csv);
+    assert_eq!(RosterFormat::detect("roster.json"), RosterFormat::Json);
+    assert_eq!(RosterFormat::detect("roster"), RosterFormat::Json);
+    assert_eq!(RosterFormat::of_path(Path::new("http://members.csv")), RosterFormat::Csv);
+    assert_eq!(RosterFormat::of_path(Path::new("members.csv.json")), RosterFormat::Json);
+}
+
+#[test]
+fn test_roster_fields_redacted_by_visibility() {
+    let entry = RosterEntry {
+        name: "Alice Johnson".to_string(),
+        nickname: Some("Al".to_string()),
+        note: Some("owes $10".to_string()),
+        needs: Some("gluten-free".to_string()),
+        email: Some("alice@example.com".to_string()),
+        acbl_number: Some("K123456".to_string()),
+        ..RosterEntry::default()
+    };
+
+    let teacher = entry.redacted(Visibility::Teacher);
+    assert_eq!(teacher.needs.as_deref(), Some("gluten-free"));
+    assert_eq!(teacher.email, None);
+    assert_eq!(teacher.acbl_number, None);
+
+    let public = entry.redacted(Visibility::Public);
+    assert_eq!(public.nickname.as_deref(), Some("Al"));
+    assert!(public.note.is_none() && public.needs.is_none());
+
+    let csv = roster_csv(&[public], Visibility::Public);
+    assert_eq!(
+        csv.lines().next(),
+        Some("name,nickname,pronouns,photo,expires,birthday,style,highlight,new,junior,confirmed")
+    );
+    assert_eq!(csv.lines().nth(1), Some("Alice Johnson,Al,,,,,,,,,"));
+}
+
+#[test]
+fn test_parse_pbn_deals() {
+    let pbn = r#"% practice set
+[Board "7"]
+[Dealer "W"]
+[Vulnerable "All"]
+[Deal "W:743.864.632.T753 AKQ2.K73.Q85.A42 T96.QJ52.K94.KJ6 J85.AT9.AJT7.Q98"]
+{Lead the top of a sequence.
+  Then count winners.}
+[Auction "W"]
+Pass 1NT Pass 3NT
+
+[Board "8"]
+[Deal "S:J85.AT9.AJT7.Q98 - AKQ2.K73.Q85.A42 -"]
+"#;
+    let deals = parse_pbn(pbn).unwrap();
+    assert_eq!(deals.len(), 2);
+    assert_eq!(deals[0].board.as_deref(), Some("7"));
+    assert_eq!(deals[0].dealer, Some(Seat::West));
+    assert_eq!(deals[0].vulnerable.as_deref(), Some("All"));
+    assert_eq!(deals[0].hand(Seat::North).suits[0], "AKQ2", "Hands run clockwise from the first seat");
+    assert_eq!(deals[0].hand(Seat::West).suits[3], "T753");
+    assert_eq!(deals[1].hand(Seat::South).suits[2], "AJT7");
+    assert!(deals[1].hand(Seat::East).is_empty());
+
+    let bad = parse_pbn("[Board \"3\"]\n[Deal \"N:AKQ2.K73.Q85 - - -\"]\n");
+    assert!(matches!(bad, Err(AppError::DealError(message)) if message.starts_with("board 3:")));
+}
+
+#[test]
+fn test_parse_lin_deals() {
+    let lin = "qx|o4|st||md|4SJ85HAT9DAJT7CQ98,S743H864D632CT753,SAKQ2HK73DQ85CA42,|rh||sv|b|mb|1N|pg||\n\
+               qx|o5|md|1SAHKQJT98765432DC,,,|ah|Board 5|";
+    let deals = parse_lin(lin).unwrap();
+    assert_eq!(deals.len(), 2);
+    assert_eq!(deals[0].board.as_deref(), Some("4"));
+    assert_eq!(deals[0].dealer, Some(Seat::East));
+    assert_eq!(deals[0].vulnerable.as_deref(), Some("All"));
+    assert_eq!(deals[0].hand(Seat::South).suits[0], "J85", "Hands run clockwise from South");
+    assert_eq!(deals[0].hand(Seat::North).suits[3], "A42");
+    assert_eq!(deals[0].hand(Seat::East).suits, ["T96", "QJ52", "K94", "KJ6"], "The missing hand gets the rest");
+    assert_eq!(deals[1].board.as_deref(), Some("5"));
+    assert!(deals[1].hand(Seat::East).is_empty(), "Only one hand given, so East stays unknown");
+
+    assert!(matches!(parse_lin("md|3S2X,,,|"), Err(AppError::DealError(_))));
+}
+
+#[test]
+fn test_suit_font_embedded_only_when_used() {
+    let builder = || {
+        AttendanceSheet::builder()
+            .class("Tuesday Beginner")
+            .date(NaiveDate::from_ymd_opt(2025, 1, 7).unwrap())
+    };
+    let plain = builder().build().unwrap().render_pdf().unwrap();
+    let deals = parse_pbn("[Deal \"N:AKQ2.K73.Q85.A42 - J85.AT9.AJT7.Q98 -\"]").unwrap();
+    let with_hands = builder().hands(deals).build().unwrap().render_pdf().unwrap();
+
+    let embeds_font = |pdf: &[u8]| pdf.windows(9).any(|window| window == b"FontFile2");
+    assert!(!embeds_font(&plain));
+    assert!(embeds_font(&with_hands));
+
+    // Suits in a class name or topic use it too
+    let clubs = AttendanceSheet::builder()
+        .class("2\u{2663} Openings")
+        .build()
+        .unwrap()
+        .render_pdf()
+        .unwrap();
+    assert!(embeds_font(&clubs));
+    assert!(with_hands.len() < plain.len() + 40_000, "Only the cut-down suit font is embedded");
+}
+
+#[test]
+fn test_rsvps_confirm_roster_students() {
+    let rsvps = parse_rsvps("First Name,Last Name,Status\nAlice,Johnson,Attending\nBob,Smith,Declined\nIvy,Chen,yes\n")
+        .unwrap();
+    assert_eq!(rsvps, ["Alice Johnson", "Ivy Chen"]);
+    assert!(matches!(parse_rsvps("email\nalice@example.com\n"), Err(AppError::RsvpError(_))));
+
+    let mut entries: Vec<RosterEntry> = ["Alice Johnson", "Bob Smith", "Carol Davis"]
+        .iter()
+        .map(|name| RosterEntry { name: name.to_string(), ..RosterEntry::default() })
+        .collect();
+    let matched = mark_confirmed(&mut entries, &["johnson, alice".to_string(), "Ivy Chen".to_string()]);
+    assert_eq!(matched.confirmed, ["Alice Johnson"]);
+    assert_eq!(matched.not_replied, ["Bob Smith", "Carol Davis"]);
+    assert_eq!(matched.not_on_roster, ["Ivy Chen"]);
+    assert!(entries[0].confirmed && !entries[1].confirmed);
+}
+
+#[test]
+fn test_registrations_as_roster_entries() {
+    let body = r#"{
+        "pagination": {"has_more_items": true, "continuation": "dGhlIG5leHQgcGFnZQ"},
+        "attendees": [
+            {"profile": {"name": "Alice Johnson", "email": "alice@example.com", "cell_phone": "555-0101"}},
+            {"profile": {"first_name": "Bob", "last_name": "Smith"}, "cancelled": false},
+            {"profile": {"name": "Carol Davis"}, "refunded": true}
+        ]
+    }"#;
+    let page = parse_eventbrite_attendees(body).unwrap();
+    let names: Vec<&str> = page.entries.iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(names, ["Alice Johnson", "Bob Smith"], "Refunded tickets are not on the roster");
+    assert_eq!(page.entries[0].email.as_deref(), Some("alice@example.com"));
+    assert_eq!(page.continuation.as_deref(), Some("dGhlIG5leHQgcGFnZQ"));
+    assert!(matches!(parse_eventbrite_attendees("<html>"), Err(AppError::RegistrationError(_))));
+
+    let export = "Item,Name,Email Address\nSeat,Bob Smith,\nSeat,Alice Johnson,ALICE@example.com\nSeat,bob smith,bob@example.com\n";
+    let entries = parse_signupgenius_export(export).unwrap();
+    let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(names, ["Alice Johnson", "Bob Smith"]);
+    assert_eq!(entries[0].email.as_deref(), Some("alice@example.com"));
+    assert_eq!(entries[1].email.as_deref(), Some("bob@example.com"), "Duplicates fill in each other's gaps");
+}
+
+#[test]
+fn test_zoom_registrants_as_online_attendees() {
+    let body = r#"{
+        "page_size": 300,
+        "next_page_token": "Tva2CuIdTgsv8wAnhyAdU3m06Y2HuLQtlh3",
+        "registrants": [
+            {"first_name": "Liam", "last_name": "Young", "email": "liam@example.com", "status": "approved"},
+            {"first_name": "Ivy", "last_name": "Chen", "email": "ivy@example.com", "status": "approved"},
+            {"first_name": "ivy", "last_name": " chen", "email": "ivy@example.com", "status": "approved"}
+        ]
+    }"#;
+    let page = parse_zoom_registrants(body).unwrap();
+    assert_eq!(page.next_page_token.as_deref(), Some("Tva2CuIdTgsv8wAnhyAdU3m06Y2HuLQtlh3"));
+    assert_eq!(online_attendees(page.names), ["Ivy Chen", "Liam Young"], "Registering twice lists once");
+
+    let last = parse_zoom_registrants(r#"{"next_page_token": "", "registrants": []}"#).unwrap();
+    assert!(last.names.is_empty() && last.next_page_token.is_none());
+    assert!(matches!(parse_zoom_registrants("Not Found"), Err(AppError::RegistrationError(_))));
+}
+
+/// Answer a request with each canned response in turn on a local port, then
+/// stop listening; the thread returns how many it answered
+fn local_server(responses: Vec<&'static str>) -> (String, std::thread::JoinHandle<usize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let mut answered = 0;
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request);
+            stream.write_all(response.as_bytes()).unwrap();
+            answered += 1;
+        }
+        answered
+    });
+    (url, server)
+}
+
+#[test]
+fn test_fetch_retries_server_errors_only() {
+    let (url, server) = local_server(vec![
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+    ]);
+    let response = net::call(net::get(&format!("{}/logo.png", url))).expect("The retry should succeed");
+    assert_eq!(net::read_string(response).unwrap(), "ok");
+    assert_eq!(server.join().unwrap(), 2);
+
+    // Not found is final: a retry would find the port closed instead
+    let not_found = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let (url, server) = local_server(vec![not_found]);
+    let error = net::call(net::get(&format!("{}/logo.png", url))).unwrap_err();
+    assert!(matches!(*error, ureq::Error::Status(404, _)));
+    assert_eq!(server.join().unwrap(), 1);
+}
+
+#[test]
+fn test_roster_fetched_from_url() {
+    let (url, server) = local_server(vec![concat!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 55\r\nConnection: close\r\n\r\n",
+        r#"[{"name": "Alice Smith"}, {"name": "Bob", "new": true}]"#,
+    )]);
+    let roster_url = format!("{}/rosters/tuesday.json", url);
+    let entries = read_roster_source(&roster_url, None).expect("The roster should be fetched");
+    assert_eq!(server.join().unwrap(), 1);
+    let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(names, ["Alice Smith", "Bob"]);
+    assert!(entries[1].new);
+
+    // Photos beside the roster are fetched from the same place
+    let base = source_dir(&roster_url);
+    assert_eq!(resolve_source(&base, "photos/bob.jpg"), format!("{}/rosters/photos/bob.jpg", url));
+    assert_eq!(resolve_source(&base, "https://cdn.example.org/a.jpg"), "https://cdn.example.org/a.jpg");
+    assert_eq!(resolve_source(&source_dir("rosters/tuesday.json"), "bob.jpg"), "rosters/bob.jpg");
+}
@@ -1,6 +1,8 @@
+use attendance_pdf::checkin::read_checkins;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 fn cargo_bin() -> Command {
     Command::new(env!("CARGO_BIN_EXE_attendance-pdf"))
@@ -21,6 +23,17 @@ fn cleanup_file(name: &str) {
     }
 }
 
+/// The text of each page of a generated PDF, runs of whitespace collapsed
+/// to one space so the tests don't depend on how lines were split
+fn pdf_pages(name: &str) -> Vec<String> {
+    let bytes = fs::read(output_dir().join(name)).expect("Failed to read PDF");
+    pdf_extract::extract_text_from_mem_by_pages(&bytes)
+        .expect("Failed to extract PDF text")
+        .iter()
+        .map(|page| page.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
 #[test]
 fn test_basic_blank_form() {
     setup();
@@ -43,6 +56,11 @@ fn test_basic_blank_form() {
 
     let metadata = fs::metadata(&path).expect("Failed to get file metadata");
     assert!(metadata.len() > 1000, "PDF file is too small, likely empty or corrupt");
+
+    let pages = pdf_pages(output_file);
+    assert!(pages[0].contains("Tuesday Beginner Bridge"), "Class name missing: {}", pages[0]);
+    assert!(pages[0].contains("Instructor: Rick"));
+    assert!(pages[0].contains("Table 1 North"), "Blank tables missing: {}", pages[0]);
 }
 
 #[test]
@@ -92,6 +110,12 @@ fn test_with_roster() {
 
     let metadata = fs::metadata(&path).expect("Failed to get file metadata");
     assert!(metadata.len() > 1000, "PDF file is too small");
+
+    let pages = pdf_pages(output_file);
+    assert_eq!(pages.len(), 1);
+    for name in ["Alice Johnson", "Henry Taylor", "NAME TABLE SEAT"] {
+        assert!(pages[0].contains(name), "{} missing: {}", name, pages[0]);
+    }
 }
 
 #[test]
@@ -188,3 +212,1600 @@ fn test_invalid_date_format() {
 
     assert!(!output.status.success(), "Command should have failed for invalid date");
 }
+
+#[test]
+fn test_bench_mode() {
+    let output = cargo_bin()
+        .args(["-n", "Bench", "--bench", "3"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Benchmark: 3 sheets"), "Unexpected output: {}", stdout);
+    assert!(stdout.contains("serialize"), "Missing phase timings: {}", stdout);
+}
+
+#[test]
+fn test_reproducible_output() {
+    setup();
+    let files = ["test-reproducible-1.pdf", "test-reproducible-2.pdf"];
+
+    for file in files {
+        cleanup_file(file);
+        let output = cargo_bin()
+            .args([
+                "-n", "Reproducible Class",
+                "-d", "2025-01-07",
+                "--event-id", "ABCD1234",
+                "--reproducible",
+                "--roster", "examples/roster.json",
+                "-o", &format!("tests/output/{}", file),
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "Command failed: {:?}", output);
+    }
+
+    let first = fs::read(output_dir().join(files[0])).expect("Failed to read first PDF");
+    let second = fs::read(output_dir().join(files[1])).expect("Failed to read second PDF");
+    assert!(first == second, "Reproducible runs produced different bytes");
+}
+
+#[test]
+fn test_pdf_text_on_expected_pages() {
+    setup();
+    let output_file = "test-pdf-text.pdf";
+    cleanup_file(output_file);
+    cleanup_file("manifest.json");
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--event-id", "ABCD1234",
+            "--reproducible",
+            "--roster", "examples/roster.json",
+            "--overflow-page",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let pages = pdf_pages(output_file);
+    assert_eq!(pages.len(), 2, "Sheet and overflow page: {:?}", pages);
+    for text in ["CLASS ATTENDANCE", "Tuesday, January 7, 2025", "ID: ABCD1234", "Alice Johnson", "Henry Taylor"] {
+        assert!(pages[0].contains(text), "{} missing from page 1: {}", text, pages[0]);
+    }
+    assert!(pages[0].contains("JOIN MY MAILING LIST"));
+    assert!(pages[1].starts_with("Late arrivals / walk-ins Tuesday Beginner"), "{}", pages[1]);
+    assert!(!pages[1].contains("Alice Johnson"), "Roster names belong on the sheet only");
+    assert!(pages[1].ends_with("Sheet 2 of 2 \u{2013} ABCD1234"), "{}", pages[1]);
+
+    // The whole text against the golden copy; UPDATE_GOLDEN=1 rewrites it
+    // after an intended layout change
+    let golden_path = Path::new("tests/golden/roster-sheet.txt");
+    let text: String = pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| format!("--- page {} ---\n{}\n", index + 1, page))
+        .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(golden_path, &text).expect("Failed to write golden text");
+    }
+    let golden = fs::read_to_string(golden_path).expect("Failed to read golden text");
+    assert!(text == golden, "PDF text differs from {}:\n{}", golden_path.display(), text);
+}
+
+#[test]
+fn test_invalid_event_id() {
+    let output = cargo_bin()
+        .args([
+            "-n", "Test",
+            "--event-id", "not-hex!",
+            "-o", "tests/output/should-not-exist.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Command should have failed for invalid event ID");
+}
+
+#[test]
+fn test_typst_export() {
+    setup();
+    let output_file = "test-export.typ";
+    cleanup_file(output_file);
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Typst \"Quoted\" Class",
+            "--roster", "examples/roster.json",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let source = fs::read_to_string(output_dir().join(output_file)).expect("Failed to read .typ");
+    assert!(source.contains("#set page(width: 215.9mm"), "Missing page setup");
+    assert!(source.contains(r#""Typst \"Quoted\" Class""#), "Class name not escaped");
+    assert!(source.contains("\"Alice Johnson\""), "Roster name missing");
+    assert!(source.contains("image(bytes(("), "QR code not embedded");
+}
+
+#[test]
+fn test_docx_export() {
+    setup();
+    let output_file = "test-export.docx";
+    cleanup_file(output_file);
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Word Class",
+            "--roster", "examples/roster.json",
+            "--format", "docx",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let bytes = fs::read(output_dir().join(output_file)).expect("Failed to read .docx");
+    assert!(bytes.starts_with(b"PK"), "Output is not a ZIP container");
+
+    let document = docx_rs::read_docx(&bytes).expect("Output is not a readable DOCX").json();
+    assert!(document.contains("Word Class"), "Class name missing");
+    assert!(document.contains("Alice Johnson"), "Roster name missing");
+    assert!(document.contains("JOIN MY MAILING LIST"), "Mailing list missing");
+}
+
+#[test]
+fn test_xlsx_export() {
+    setup();
+    let output_file = "test-export.xlsx";
+    cleanup_file(output_file);
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Excel Class",
+            "--roster", "examples/roster.json",
+            "--format", "xlsx",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let bytes = fs::read(output_dir().join(output_file)).expect("Failed to read .xlsx");
+    assert!(bytes.starts_with(b"PK"), "Output is not a ZIP container");
+
+    // Attendance plus mailing list worksheets, named in the ZIP directory
+    let contains = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+    assert!(contains(b"xl/worksheets/sheet1.xml"), "Attendance worksheet missing");
+    assert!(contains(b"xl/worksheets/sheet2.xml"), "Mailing list worksheet missing");
+}
+
+#[test]
+fn test_print_requires_pdf_format() {
+    let output = cargo_bin()
+        .args([
+            "-n", "Print Class",
+            "--format", "typst",
+            "--print",
+            "-o", "tests/output/test-print.typ",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Printing non-PDF output should be rejected");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--print requires --format pdf"), "Unexpected error: {}", stderr);
+}
+
+#[test]
+fn test_output_dir_and_name_template() {
+    setup();
+    let dir = output_dir().join("templated");
+    let expected = dir.join("2025-01-07-tuesday-beginner-ABCD1234.pdf");
+    if expected.exists() {
+        fs::remove_file(&expected).ok();
+    }
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--event-id", "abcd1234",
+            "--output-dir", "tests/output/templated",
+            "--name-template", "{date}-{class}-{event_id}.{ext}",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(expected.exists(), "Templated file was not created");
+}
+
+#[test]
+fn test_unknown_name_template_placeholder() {
+    let output = cargo_bin()
+        .args(["-n", "Test", "--name-template", "{room}.pdf"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Unknown placeholder should be rejected");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("{room}"), "Error should name the placeholder: {}", stderr);
+}
+
+#[test]
+fn test_refuses_to_overwrite_without_force() {
+    setup();
+    let output_file = "test-overwrite.pdf";
+    cleanup_file(output_file);
+    let path = format!("tests/output/{}", output_file);
+
+    let run = |extra: &[&str]| {
+        cargo_bin()
+            .args(["-n", "Overwrite Class", "-o", &path])
+            .args(extra)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    assert!(run(&[]).status.success(), "First run should succeed");
+    let first = fs::read(&path).unwrap();
+
+    let second = run(&[]);
+    assert!(!second.status.success(), "Second run should refuse to overwrite");
+    assert!(String::from_utf8_lossy(&second.stderr).contains("--force"));
+    assert_eq!(fs::read(&path).unwrap(), first, "Existing file was modified");
+
+    assert!(run(&["--force"]).status.success(), "--force should overwrite");
+    assert_ne!(fs::read(&path).unwrap(), first, "File was not replaced");
+}
+
+#[test]
+fn test_multi_event_document() {
+    setup();
+    let output_file = "test-multi-event.typ";
+    cleanup_file(output_file);
+
+    let output = cargo_bin()
+        .args([
+            "--events", "examples/events.json",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let source = fs::read_to_string(output_dir().join(output_file)).expect("Failed to read .typ");
+    let morning = source.find("\"Morning Beginner\"").expect("First event missing");
+    let afternoon = source.find("\"Afternoon Intermediate\"").expect("Second event missing");
+    assert!(morning < afternoon, "Events should appear in file order");
+    assert!(source.contains("\"ID: A1B2C3D4\"") && source.contains("\"ID: E5F6A7B8\""));
+    assert!(source.contains("\"Instructor: Jane\""), "Per-event teacher not applied");
+    assert!(source.contains("\"Alice Johnson\""), "Roster path not resolved");
+}
+
+#[test]
+fn test_multi_event_pdf_written_per_event() {
+    setup();
+    let output_file = "test-multi-event.pdf";
+    cleanup_file(output_file);
+
+    let run = || {
+        cargo_bin()
+            .args([
+                "--events", "examples/events.json",
+                "--reproducible",
+                "--force",
+                "-o", &format!("tests/output/{}", output_file),
+            ])
+            .output()
+            .expect("Failed to execute command")
+    };
+    let output = run();
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let first = fs::read(output_dir().join(output_file)).unwrap();
+
+    let pages = pdf_pages(output_file);
+    let morning = pages.iter().position(|page| page.contains("Morning Beginner")).expect("First event missing");
+    let afternoon = pages
+        .iter()
+        .position(|page| page.contains("Afternoon Intermediate"))
+        .expect("Second event missing");
+    assert!(morning < afternoon, "Events should appear in file order");
+    assert!(pages[morning].contains("Alice Johnson"), "Roster path not resolved");
+    let merged = printpdf::lopdf::Document::load(output_dir().join(output_file)).unwrap();
+    assert_eq!(merged.get_pages().len(), pages.len());
+
+    assert!(run().status.success());
+    assert_eq!(fs::read(output_dir().join(output_file)).unwrap(), first, "Merged output is byte-stable");
+}
+
+#[test]
+fn test_manifest_records_substitute() {
+    setup();
+    let output_file = "test-manifest.pdf";
+    let manifest_file = "test-manifest.manifest.json";
+    cleanup_file(output_file);
+    cleanup_file(manifest_file);
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Manifest Class",
+            "-t", "Rick",
+            "--substitute", "Pat",
+            "--event-id", "ABCD1234",
+            "--roster", "examples/roster.json",
+            "--manifest",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let json = fs::read_to_string(output_dir().join(manifest_file)).expect("Manifest not written");
+    let manifest: serde_json::Value = serde_json::from_str(&json).expect("Manifest is not JSON");
+    assert_eq!(manifest["output"], output_file);
+    let event = &manifest["events"][0];
+    assert_eq!(event["event_id"], "ABCD1234");
+    assert_eq!(event["teacher"], "Rick");
+    assert_eq!(event["substitute"], "Pat");
+    assert_eq!(event["roster"][0], "Alice Johnson");
+}
+
+#[test]
+fn test_sponsor_strip_from_config_file() {
+    setup();
+    let output_file = "test-sponsor.typ";
+    cleanup_file(output_file);
+    fs::write(
+        output_dir().join("sponsor.toml"),
+        "[sponsor]\ntext = \"Lessons sponsored by Harmonic Systems\"\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Sponsored Class",
+            "--config", "tests/output/sponsor.toml",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let source = fs::read_to_string(output_dir().join(output_file)).unwrap();
+    assert!(source.contains("\"Lessons sponsored by Harmonic Systems\""), "Sponsor text missing");
+}
+
+#[test]
+fn test_teacher_profile_from_config_file() {
+    setup();
+    let output_file = "test-teacher-profile.typ";
+    cleanup_file(output_file);
+    fs::write(
+        output_dir().join("teachers.toml"),
+        "[teacher.jane]\nname = \"Jane Doe\"\nemail = \"jane@example.com\"\nmailing_title = \"JOIN JANE'S LIST\"\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Profile Class",
+            "-t", "jane",
+            "--config", "tests/output/teachers.toml",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let source = fs::read_to_string(output_dir().join(output_file)).unwrap();
+    assert!(source.contains("Instructor: Jane Doe \u{2013} jane@example.com"), "Profile name or email missing");
+    assert!(source.contains("JOIN JANE'S LIST"), "Profile mailing title missing");
+    assert!(!source.contains("JOIN MY MAILING LIST"));
+}
+
+#[test]
+fn test_venue_profile_from_config_file() {
+    setup();
+    let output_file = "test-venue-profile.typ";
+    cleanup_file(output_file);
+    fs::write(
+        output_dir().join("venues.toml"),
+        "[venue.hall]\nname = \"Town Hall\"\naddress = \"1 Oak Ave\"\ntables = 2\nwifi = \"HallNet\"\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Venue Class",
+            "--venue", "hall",
+            "--config", "tests/output/venues.toml",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let source = fs::read_to_string(output_dir().join(output_file)).unwrap();
+    assert!(source.contains("\"Location: Town Hall, 1 Oak Ave\""), "Venue location missing");
+    assert!(source.contains("\"Wi-Fi: HallNet\""), "Venue Wi-Fi missing");
+    assert!(source.contains("\"Table 2\"") && !source.contains("\"Table 3\""), "Two tables expected");
+
+    let output = cargo_bin()
+        .args(["-n", "Venue Class", "--venue", "nowhere", "--config", "tests/output/venues.toml"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[venue.nowhere]"));
+}
+
+#[test]
+fn test_preset_save_list_and_generate() {
+    setup();
+    let presets = output_dir().join("presets");
+    let output_file = "test-preset.typ";
+    cleanup_file(output_file);
+    fs::remove_dir_all(&presets).ok();
+
+    let output = cargo_bin()
+        .env("ATTENDANCE_PDF_PRESETS", &presets)
+        .args([
+            "preset", "save", "tuesday-beginner",
+            "-n", "Tuesday Beginner",
+            "-t", "Jane",
+            "--roster", "examples/roster.json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let saved = fs::read_to_string(presets.join("tuesday-beginner.toml")).unwrap();
+    assert!(!saved.contains("\"examples/roster.json\""), "Roster path should be absolute");
+
+    let output = cargo_bin()
+        .env("ATTENDANCE_PDF_PRESETS", &presets)
+        .args(["preset", "list"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "tuesday-beginner");
+
+    let output = cargo_bin()
+        .env("ATTENDANCE_PDF_PRESETS", &presets)
+        .args([
+            "generate",
+            "--preset", "tuesday-beginner",
+            "--date", "next-tuesday",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let source = fs::read_to_string(output_dir().join(output_file)).unwrap();
+    assert!(source.contains("\"Tuesday Beginner\""));
+    assert!(source.contains("\"Instructor: Jane\""));
+    assert!(source.contains("\"Alice Johnson\""), "Preset roster not loaded");
+    assert!(source.contains("Tuesday, "), "next-tuesday should land on a Tuesday");
+
+    let output = cargo_bin()
+        .env("ATTENDANCE_PDF_PRESETS", &presets)
+        .args(["--preset", "missing", "-o", "tests/output/unused.pdf"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no preset named \"missing\""));
+}
+
+#[test]
+fn test_init_creates_starter_files() {
+    setup();
+    let dir = output_dir().join("init");
+    fs::remove_dir_all(&dir).ok();
+
+    let output = cargo_bin()
+        .args(["init", "--dir", "tests/output/init"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    for name in ["attendance.toml", "roster.json", "schedule.toml"] {
+        assert!(dir.join(name).exists(), "{} not created", name);
+    }
+
+    // The starter files work as they are
+    let output = cargo_bin()
+        .args([
+            "-n", "Starter",
+            "--config", "tests/output/init/attendance.toml",
+            "--roster", "tests/output/init/roster.json",
+            "--schedule", "tests/output/init/schedule.toml",
+            "--calendar",
+            "-o", "tests/output/init/starter.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    // A second run refuses to clobber the edited files
+    let output = cargo_bin()
+        .args(["init", "--dir", "tests/output/init"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+}
+
+#[test]
+fn test_append_handout_pdf() {
+    setup();
+    let handout = "test-handout.pdf";
+    let output_file = "test-with-handout.pdf";
+    cleanup_file(handout);
+    cleanup_file(output_file);
+
+    // Any generated sheet serves as a stand-in handout (two pages in blank mode)
+    let status = cargo_bin()
+        .args(["-n", "Handout", "--mailing-placement", "first-page", "-o", &format!("tests/output/{}", handout)])
+        .status()
+        .expect("Failed to execute command");
+    assert!(status.success());
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Packet Class",
+            "--roster", "examples/roster.json",
+            "--append", &format!("tests/output/{}", handout),
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let merged = printpdf::lopdf::Document::load(output_dir().join(output_file))
+        .expect("Merged output is not a readable PDF");
+    assert_eq!(merged.get_pages().len(), 3, "Expected 1 sheet page + 2 handout pages");
+}
+
+#[test]
+fn test_booklet_imposition() {
+    setup();
+    let handout = "test-booklet-handout.pdf";
+    let output_file = "test-booklet.pdf";
+    cleanup_file(handout);
+    cleanup_file(output_file);
+
+    let status = cargo_bin()
+        .args(["-n", "Handout", "-o", &format!("tests/output/{}", handout)])
+        .status()
+        .expect("Failed to execute command");
+    assert!(status.success());
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Booklet Class",
+            "--roster", "examples/roster.json",
+            "--append", &format!("tests/output/{}", handout),
+            "--booklet",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    // Three pages pad to four, two to a sheet
+    let booklet = printpdf::lopdf::Document::load(output_dir().join(output_file))
+        .expect("Booklet output is not a readable PDF");
+    let pages = booklet.get_pages();
+    assert_eq!(pages.len(), 2, "Expected two landscape sheets");
+    for page_id in pages.into_values() {
+        let page = booklet.get_dictionary(page_id).unwrap();
+        let media_box = page.get(b"MediaBox").and_then(|b| b.as_array()).unwrap();
+        let width = media_box[2].as_float().unwrap();
+        let height = media_box[3].as_float().unwrap();
+        assert!(width > height, "Booklet sheets should be landscape");
+    }
+
+    let output = cargo_bin()
+        .args(["-n", "Booklet Class", "--booklet", "--format", "typst", "-o", "tests/output/booklet.typ"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--booklet requires --format pdf"));
+}
+
+#[test]
+fn test_pass_mode() {
+    setup();
+    let dir = output_dir().join("passes");
+    fs::remove_dir_all(&dir).ok();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Ten Class Package",
+            "-d", "2025-01-07",
+            "--mode", "pass",
+            "--roster", "examples/roster.json",
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let path = dir.join("passes-2025-01-07-ten-class-package.pdf");
+    assert!(path.exists(), "Pass PDF should use the passes file name");
+}
+
+#[test]
+fn test_progress_mode_needs_schedule() {
+    setup();
+    let dir = output_dir().join("progress");
+    fs::remove_dir_all(&dir).ok();
+
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "-d", "2025-01-07", "--mode", "progress"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--mode progress needs --schedule"), "Unexpected error: {}", stderr);
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--mode", "progress",
+            "--schedule", "examples/schedule.toml",
+            "--roster", "examples/roster.json",
+            "--per-student",
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(dir.join("progress-2025-01-07-tuesday-beginner.pdf").exists());
+}
+
+#[test]
+fn test_lesson_plan_mode() {
+    setup();
+    let dir = output_dir().join("lesson-plan");
+    fs::remove_dir_all(&dir).ok();
+
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--mode", "lesson-plan"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--mode lesson-plan needs --plan"), "Unexpected error: {}", stderr);
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--mode", "lesson-plan",
+            "--plan", "examples/lesson-plan.md",
+            "--hands", "examples/hands.pbn",
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(dir.join("lesson-plan-2025-01-07-tuesday-beginner.pdf").exists());
+}
+
+#[test]
+fn test_travelers_and_scoring_modes() {
+    setup();
+    let dir = output_dir().join("travelers");
+    fs::remove_dir_all(&dir).ok();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--mode", "travelers",
+            "--roster", "examples/roster.json",
+            "--boards", "12",
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(dir.join("travelers-2025-01-07-tuesday-beginner.pdf").exists());
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--mode", "scoring",
+            "--hands", "examples/hands.pbn",
+            "--output-dir", dir.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(dir.join("scoring-2025-01-07-tuesday-beginner.pdf").exists());
+
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--mode", "travelers", "--boards", "0"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success(), "No boards is rejected");
+}
+
+#[test]
+fn test_receipt_mode_rejects_docx() {
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--mode", "receipt", "--format", "docx"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--mode receipt supports"), "Unexpected error: {}", stderr);
+}
+
+#[test]
+fn test_missing_logo_with_logo_optional() {
+    setup();
+    let output_file = "test-logo-optional.pdf";
+    cleanup_file(output_file);
+    cleanup_file("manifest.json");
+    let args = [
+        "-n", "Tuesday Beginner",
+        "--logo", "tests/output/no-such-logo.png",
+        "-o", &format!("tests/output/{}", output_file),
+    ];
+
+    let output = cargo_bin().args(args).output().expect("Failed to execute command");
+    assert!(!output.status.success(), "A missing logo should stop the run by default");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to load logo"), "Unexpected error: {}", stderr);
+
+    let output = cargo_bin()
+        .args(args)
+        .arg("--logo-optional")
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("printing without the logo"), "Missing warning: {}", stderr);
+    assert!(pdf_pages(output_file)[0].contains("Tuesday Beginner"));
+}
+
+#[test]
+fn test_offline_refuses_network_sources() {
+    setup();
+    let output_file = "test-offline.pdf";
+    cleanup_file(output_file);
+    cleanup_file("manifest.json");
+    let output_path = format!("tests/output/{}", output_file);
+
+    let refused = [
+        vec!["--logo", "https://example.invalid/logo.png"],
+        vec!["--zoom", "81234567890"],
+        vec!["--eventbrite", "123456789"],
+        vec!["--roster", "https://example.invalid/rosters/tuesday.json"],
+    ];
+    for extra in refused {
+        let output = cargo_bin()
+            .args(["-n", "Tuesday Beginner", "--offline", "-o", &output_path])
+            .args(&extra)
+            .env("ZOOM_ACCESS_TOKEN", "token")
+            .env("EVENTBRITE_TOKEN", "token")
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success(), "{:?} should be refused offline", extra);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("which --offline forbids"), "Unexpected error for {:?}: {}", extra, stderr);
+        assert!(!output_dir().join(output_file).exists());
+    }
+
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--offline", "--roster", "examples/roster.json", "-o", &output_path])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Local files need no network: {:?}", output);
+
+    // Subcommands take the flag too
+    let dir = output_dir().join("test-offline-wallet");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("attendance.toml"), "[wallet.google]\nissuer_id = \"3388000000012345678\"\n").unwrap();
+    let output = cargo_bin()
+        .args(["wallet-pass", "--offline", "-n", "Tuesday Beginner"])
+        .args(["--config", dir.join("attendance.toml").to_str().unwrap()])
+        .args(["--roster", "https://example.invalid/rosters/tuesday.json"])
+        .args(["--output-dir", dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("which --offline forbids"), "{:?}", output);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_roster_with_photos() {
+    setup();
+    let dir = output_dir().join("photos");
+    fs::create_dir_all(&dir).unwrap();
+    image::RgbImage::from_pixel(64, 64, image::Rgb([200, 120, 80]))
+        .save(dir.join("alice.png"))
+        .unwrap();
+    fs::write(
+        dir.join("roster.json"),
+        r#"[{"name": "Alice Johnson", "photo": "alice.png"}, {"name": "Bob Smith"}]"#,
+    )
+    .unwrap();
+    let output_file = dir.join("photos.pdf");
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "--roster", dir.join("roster.json").to_str().unwrap(),
+            "--force",
+            "-o", output_file.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(output_file.exists());
+}
+
+#[test]
+fn test_roster_rejects_unknown_style() {
+    setup();
+    let roster = output_dir().join("bad-style-roster.json");
+    fs::write(&roster, r#"[{"name": "Alice Johnson", "style": "italic"}]"#).unwrap();
+
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--roster", roster.to_str().unwrap(), "-o", "tests/output/bad-style.pdf"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown style \"italic\""), "Unexpected error: {}", stderr);
+}
+
+#[test]
+fn test_roster_import_from_vcard() {
+    setup();
+    let contacts = "test-contacts.vcf";
+    let output_file = "test-imported-roster.json";
+    cleanup_file(contacts);
+    cleanup_file(output_file);
+    fs::write(
+        output_dir().join(contacts),
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alice Johnson\r\nN:Johnson;Alice;;;\r\n\
+         EMAIL;TYPE=INTERNET:alice@example.com\r\nTEL;TYPE=CELL:555-0101\r\n\
+         CATEGORIES:myContacts,Bridge Students\r\nEND:VCARD\r\n\
+         BEGIN:VCARD\r\nVERSION:2.1\r\nN;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:M=C3=BCller;J=C3=BCrgen;;;\r\n\
+         CATEGORIES:Bridge Students\r\nEND:VCARD\r\n\
+         BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Plumber\r\nCATEGORIES:Home\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "roster", "import", "tests/output/test-contacts.vcf",
+            "--group", "bridge students",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let json = fs::read_to_string(output_dir().join(output_file)).unwrap();
+    let roster: serde_json::Value = serde_json::from_str(&json).expect("Roster is not JSON");
+    assert_eq!(roster.as_array().map(Vec::len), Some(2), "Only the group's contacts");
+    assert_eq!(roster[0]["name"], "Alice Johnson");
+    assert_eq!(roster[0]["email"], "alice@example.com");
+    assert_eq!(roster[0]["phone"], "555-0101");
+    assert_eq!(roster[1]["name"], "Jürgen Müller", "Name built from quoted-printable N");
+
+    // The imported file is a working roster
+    let output = cargo_bin()
+        .args([
+            "-n", "Imported",
+            "--roster", &format!("tests/output/{}", output_file),
+            "-o", "tests/output/test-imported.pdf",
+            "--force",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+}
+
+#[test]
+fn test_google_label_requires_credentials() {
+    let output = cargo_bin()
+        .env_remove("GOOGLE_ACCESS_TOKEN")
+        .args(["-n", "Google Class", "--google-label", "Bridge Students", "-o", "tests/output/unused.pdf"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("GOOGLE_ACCESS_TOKEN") && stderr.contains("[google]"), "{}", stderr);
+}
+
+#[test]
+fn test_eventbrite_requires_token() {
+    let output = cargo_bin()
+        .env_remove("EVENTBRITE_TOKEN")
+        .args(["-n", "Eventbrite Class", "--eventbrite", "123456789", "-o", "tests/output/unused.pdf"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("EVENTBRITE_TOKEN") && stderr.contains("[eventbrite]"), "{}", stderr);
+}
+
+#[test]
+fn test_zoom_requires_credentials() {
+    let output = cargo_bin()
+        .env_remove("ZOOM_ACCESS_TOKEN")
+        .args(["-n", "Hybrid Class", "--zoom", "812 3456 7890", "-o", "tests/output/unused.pdf"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("ZOOM_ACCESS_TOKEN") && stderr.contains("[zoom]"), "{}", stderr);
+}
+
+#[test]
+fn test_signupgenius_export_as_roster() {
+    setup();
+    cleanup_file("test-signupgenius.pdf");
+    cleanup_file("test-signupgenius.manifest.json");
+    let output = cargo_bin()
+        .args([
+            "-n", "Beginner Bridge Series",
+            "-d", "2025-01-07",
+            "--event-id", "AB120004",
+            "--signupgenius", "examples/signupgenius.csv",
+            "--manifest",
+            "-o", "tests/output/test-signupgenius.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let manifest = fs::read_to_string(output_dir().join("test-signupgenius.manifest.json")).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    let names: Vec<&str> = manifest["events"][0]["sheet"]["students"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|student| student["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Alice Johnson", "Bob Smith", "Carol Davis"], "Alice signed up twice");
+}
+
+#[test]
+fn test_roster_export_tidies_to_csv() {
+    setup();
+    let roster_file = "test-messy-roster.json";
+    cleanup_file(roster_file);
+    fs::write(
+        output_dir().join(roster_file),
+        r#"[
+  {"name": "  Carol   Davis ", "email": "Carol@Example.com"},
+  {"name": "Alice Johnson", "note": "owes $10, pays Tuesday"},
+  {"name": "carol davis", "nickname": "Caz", "phone": "555-0103", "new": true},
+  {"name": "   "}
+]"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args(["roster", "export", "tests/output/test-messy-roster.json", "--format", "csv"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let csv = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "name,nickname,pronouns,email,phone,acbl_number,photo,expires,birthday,style,highlight,new,junior,confirmed,\
+         note,needs,level"
+    );
+    assert_eq!(lines[1], "Alice Johnson,,,,,,,,,,,,,,\"owes $10, pays Tuesday\",,");
+    assert_eq!(lines[2], "Carol Davis,Caz,,carol@example.com,555-0103,,,,,,,yes,,,,,");
+    assert_eq!(lines.len(), 3, "Duplicate and blank entries should be dropped");
+}
+
+#[test]
+fn test_csv_roster() {
+    setup();
+    let output_file = "test-csv-roster.pdf";
+    cleanup_file(output_file);
+    let dir = output_dir().join("test-csv-roster");
+    fs::create_dir_all(&dir).unwrap();
+    let csv = "First Name,Last Name,Email,Level,New\n\
+               Alice,Johnson,alice@example.com,Beginner,\n\
+               Bob,Smith,,Intermediate,yes\n";
+    fs::write(dir.join("members.csv"), csv).unwrap();
+    fs::write(dir.join("members.txt"), csv).unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "--roster", "tests/output/test-csv-roster/members.csv",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let pages = pdf_pages(output_file);
+    assert!(pages[0].contains("Alice Johnson"), "CSV roster names missing: {}", pages[0]);
+    assert!(pages[0].contains("Bob Smith"));
+
+    // Any other name is read as JSON unless the format is given
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--roster", "tests/output/test-csv-roster/members.txt"])
+        .args(["-o", &format!("tests/output/{}", output_file)])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid JSON"));
+
+    cleanup_file(output_file);
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--roster", "tests/output/test-csv-roster/members.txt"])
+        .args(["--roster-format", "csv", "-o", &format!("tests/output/{}", output_file)])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(pdf_pages(output_file)[0].contains("Bob Smith"));
+
+    fs::remove_dir_all(&dir).ok();
+    cleanup_file(output_file);
+    cleanup_file("manifest.json");
+}
+
+#[test]
+fn test_roster_diff_flags_new_students() {
+    setup();
+    let dir = output_dir().join("test-roster-diff");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("old.json"),
+        r#"[
+  {"name": "Alice Johnson"},
+  {"name": "Bob Smith"},
+  {"name": "Carol Davis", "email": "carol@example.com"}
+]"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("new.json"),
+        r#"[
+  {"name": "Johnson, Alice *"},
+  {"name": "Carol Lee", "email": "Carol@Example.com"},
+  {"name": "Dan Brown"}
+]"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "roster", "diff",
+            "tests/output/test-roster-diff/old.json",
+            "tests/output/test-roster-diff/new.json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        ["+ Dan Brown", "- Bob Smith", "~ Carol Davis → Carol Lee", "✓ 1 added, 1 removed, 1 renamed"]
+    );
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-14",
+            "--event-id", "AB120002",
+            "--roster", "tests/output/test-roster-diff/new.json",
+            "--since", "tests/output/test-roster-diff/old.json",
+            "--manifest",
+            "-o", "tests/output/test-roster-diff/sheet.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let manifest = fs::read_to_string(dir.join("sheet.manifest.json")).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    let students = &manifest["events"][0]["sheet"]["students"];
+    let new: Vec<&str> = students
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|student| student["new"] == true)
+        .map(|student| student["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(new, ["Dan Brown"], "Only students missing from last week's roster are new");
+}
+
+#[test]
+fn test_rsvp_marks_confirmed_students() {
+    setup();
+    cleanup_file("test-rsvp.pdf");
+    cleanup_file("test-rsvp.manifest.json");
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-14",
+            "--event-id", "AB120003",
+            "--roster", "examples/roster.json",
+            "--rsvp", "examples/rsvps.csv",
+            "--manifest",
+            "-o", "tests/output/test-rsvp.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✓ RSVPs: 3 of 8 students confirmed"), "{}", stdout);
+    assert!(stdout.contains("No RSVP: Carol Davis, David Wilson, Eve Martinez, Frank Brown, Henry Taylor"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not on the roster: Ivy Chen"));
+
+    let manifest = fs::read_to_string(output_dir().join("test-rsvp.manifest.json")).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    let confirmed: Vec<&str> = manifest["events"][0]["sheet"]["students"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|student| student["confirmed"] == true)
+        .map(|student| student["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(confirmed, ["Alice Johnson", "Bob Smith", "Grace Lee"]);
+
+    let output = cargo_bin()
+        .args(["-n", "Tuesday Beginner", "--rsvp", "examples/rsvps.csv", "-o", "tests/output/test-rsvp-alone.pdf"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--rsvp needs a roster"));
+}
+
+#[test]
+fn test_scan_records_each_pass_once() {
+    setup();
+    let log = "test-checkins.jsonl";
+    cleanup_file(log);
+    let pass = r#"{"app":"bridge-attendance","type":"pass","serial":"A1B2C3D4-002","event_id":"A1B2C3D4","name":"Tuesday Beginner","student":"Bob Smith"}"#;
+
+    let mut child = cargo_bin()
+        .args(["scan", "--event-id", "c0ffee01", "--log", "tests/output/test-checkins.jsonl"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    let input = format!("{}\nhello\n{}\n", pass, pass);
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Checked in Bob Smith"), "{}", stdout);
+    assert!(stdout.contains("Bob Smith is already checked in"), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a class pass code"));
+
+    let checkins = read_checkins(&output_dir().join(log)).unwrap();
+    assert_eq!(checkins.len(), 1);
+    assert_eq!(checkins[0].event_id, "C0FFEE01");
+    assert_eq!(checkins[0].serial, "A1B2C3D4-002");
+}
+
+#[test]
+fn test_wallet_passes_for_roster() {
+    setup();
+    let dir = output_dir().join("test-wallet");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    // A self-signed certificate stands in for the Pass Type ID and WWDR ones
+    let status = Command::new("openssl")
+        .args(["req", "-x509", "-newkey", "rsa:2048", "-nodes", "-days", "1", "-subj", "/CN=Test Pass"])
+        .args(["-keyout", "tests/output/test-wallet/pass.key", "-out", "tests/output/test-wallet/pass.pem"])
+        .output()
+        .expect("Failed to run openssl");
+    assert!(status.status.success(), "{:?}", status);
+    fs::write(
+        dir.join("attendance.toml"),
+        r#"[wallet]
+organization = "Test Bridge Club"
+
+[wallet.apple]
+pass_type_id = "pass.com.example.bridge"
+team_id = "ABCDE12345"
+certificate = "pass.pem"
+key = "pass.key"
+wwdr = "pass.pem"
+
+[wallet.google]
+issuer_id = "3388000000012345678"
+"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "wallet-pass",
+            "--config",
+            "tests/output/test-wallet/attendance.toml",
+            "-n",
+            "Tuesday Beginner",
+            "--roster",
+            "examples/roster.json",
+            "--event-id",
+            "C0FFEE01",
+            "--output-dir",
+            "tests/output/test-wallet",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let bundle = fs::File::open(dir.join("alice-johnson.pkpass")).unwrap();
+    let mut archive = zip::ZipArchive::new(bundle).unwrap();
+    for name in ["pass.json", "manifest.json", "signature", "icon.png", "icon@2x.png"] {
+        assert!(archive.by_name(name).is_ok(), "{} missing from the pass", name);
+    }
+    let pass: serde_json::Value = serde_json::from_reader(archive.by_name("pass.json").unwrap()).unwrap();
+    assert_eq!(pass["serialNumber"], "C0FFEE01-001");
+    assert_eq!(pass["generic"]["primaryFields"][0]["value"], "Alice Johnson");
+    let message = pass["barcodes"][0]["message"].as_str().unwrap();
+    assert!(message.contains("\"type\":\"pass\"") && message.contains("Alice Johnson"));
+
+    let google: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("alice-johnson.google.json")).unwrap()).unwrap();
+    assert_eq!(google["id"], "3388000000012345678.C0FFEE01-001");
+    assert_eq!(google["barcode"]["value"], message);
+}
+
+#[test]
+fn test_nfc_prints_checkin_ndef() {
+    let output = cargo_bin()
+        .args(["nfc", "--event-id", "c0ffee01", "--url", "https://example.com/{event_id}"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "https://example.com/C0FFEE01");
+    assert!(lines[1].starts_with("D1 01 15 55 04 65 78"), "{}", lines[1]);
+}
+
+#[test]
+fn test_report_reconcile_against_manifest() {
+    setup();
+    let dir = output_dir().join("test-reconcile");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Reconcile Class",
+            "--event-id", "FACE0001",
+            "--roster", "examples/roster.json",
+            "--manifest",
+            "-o", "tests/output/test-reconcile/sheet.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let checkin = |serial: &str, student: &str| {
+        format!(
+            r#"{{"event_id":"FACE0001","serial":"{}","student":"{}","time":"2025-01-07T19:02:00-08:00"}}"#,
+            serial, student
+        )
+    };
+    let log = [
+        checkin("A1B2C3D4-001", "alice johnson"),
+        checkin("A1B2C3D4-002", "Bob Smith"),
+        checkin("A1B2C3D4-009", "Zoe Walker"),
+        r#"{"event_id":"0000BEEF","serial":"A1B2C3D4-003","student":"Carol Davis","time":"2025-01-07T19:05:00-08:00"}"#
+            .to_string(),
+    ];
+    fs::write(dir.join("checkins.jsonl"), log.join("\n") + "\n").unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "report", "reconcile",
+            "--event-id", "FACE0001",
+            "--manifest", "tests/output/test-reconcile/sheet.manifest.json",
+            "--checkins", "tests/output/test-reconcile/checkins.jsonl",
+            "--output-dir", "tests/output/test-reconcile",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("3 attended (0 tables, 3 extra), 6 no-shows, 1 walk-ins"), "{}", stdout);
+
+    assert!(fs::read(dir.join("reconcile-FACE0001.pdf")).unwrap().starts_with(b"%PDF"));
+    let csv = fs::read_to_string(dir.join("reconcile-FACE0001.csv")).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "status,name,serial,time");
+    assert_eq!(lines[1], "present,Alice Johnson,A1B2C3D4-001,2025-01-07T19:02:00-08:00");
+    assert!(lines.contains(&"walk-in,Zoe Walker,A1B2C3D4-009,2025-01-07T19:02:00-08:00"));
+    assert!(lines.contains(&"no-show,Carol Davis,,"), "Check-ins for other events do not count");
+}
+
+#[test]
+fn test_regenerate_from_manifest() {
+    setup();
+    let dir = output_dir().join("test-regenerate");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "-d", "2025-01-07",
+            "--event-id", "AB120001",
+            "--roster", "examples/roster.json",
+            "--mono",
+            "--raffle",
+            "--reproducible",
+            "--manifest",
+            "-o", "tests/output/test-regenerate/sheet.pdf",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let original = fs::read(dir.join("sheet.pdf")).unwrap();
+    fs::remove_file(dir.join("sheet.pdf")).unwrap();
+
+    let output = cargo_bin()
+        .args(["regenerate", "tests/output/test-regenerate/sheet.manifest.json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("AB120001"));
+
+    let regenerated = fs::read(dir.join("sheet.pdf")).expect("The sheet should be written next to the manifest");
+    assert!(regenerated == original, "The regenerated sheet should match the original byte for byte");
+}
+
+#[test]
+fn test_teacher_copy_written_beside_sheet() {
+    setup();
+    let dir = output_dir().join("test-teacher-copy");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("roster.json"),
+        r#"[{"name": "Alice Johnson", "needs": "wheelchair access"}, {"name": "Bob Smith", "note": "owes $10"}]"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Tuesday Beginner",
+            "--roster", "tests/output/test-teacher-copy/roster.json",
+            "--teacher-copy",
+            "--format", "typst",
+            "-o", "tests/output/test-teacher-copy/sheet.typ",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("sheet-teacher.typ"));
+
+    let sign_in = fs::read_to_string(dir.join("sheet.typ")).unwrap();
+    assert!(!sign_in.contains("wheelchair") && !sign_in.contains("owes"));
+    let teacher = fs::read_to_string(dir.join("sheet-teacher.typ")).unwrap();
+    assert!(teacher.contains("TEACHER COPY"));
+    assert!(teacher.contains("wheelchair access") && teacher.contains("owes $10"));
+}
+
+#[test]
+fn test_reprint_records_next_revision() {
+    setup();
+    let dir = output_dir().join("test-revision");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    let generate = || {
+        cargo_bin()
+            .args([
+                "-n", "Tuesday Beginner",
+                "-d", "2025-01-07",
+                "--event-id", "AB120003",
+                "--roster", "examples/roster.json",
+                "--manifest",
+                "--force",
+                "-o", "tests/output/test-revision/sheet.pdf",
+            ])
+            .output()
+            .expect("Failed to execute command")
+    };
+    let output = generate();
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Rev "));
+    let output = generate();
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Rev 2 \u{2013} generated "));
+
+    let manifest = fs::read_to_string(dir.join("sheet.manifest.json")).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    let revisions = manifest["events"][0]["revisions"].as_array().unwrap();
+    let numbers: Vec<u64> = revisions.iter().map(|revision| revision["number"].as_u64().unwrap()).collect();
+    assert_eq!(numbers, [1, 2], "The manifest keeps every printing");
+}
+
+#[test]
+fn test_export_acbl_report_for_month() {
+    setup();
+    let dir = output_dir().join("test-acbl-report");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    for (event_id, date) in [("AC000001", "2025-01-07"), ("AC000002", "2025-01-14"), ("AC000003", "2025-02-04")] {
+        let output = cargo_bin()
+            .args([
+                "-n", "Tuesday Beginner",
+                "-d", date,
+                "--event-id", event_id,
+                "--roster", "examples/roster.json",
+                "--manifest",
+                "-o", &format!("tests/output/test-acbl-report/{}.pdf", event_id),
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "Command failed: {:?}", output);
+    }
+    let checkin = |event_id: &str, student: &str| {
+        format!(
+            r#"{{"event_id":"{}","serial":"X-{}","student":"{}","time":"2025-01-07T19:00:00-08:00"}}"#,
+            event_id, student, student
+        )
+    };
+    let mut log = Vec::new();
+    for student in ["Alice Johnson", "Bob Smith", "Carol Davis", "David Wilson", "Eve Martinez", "Grace Lee"] {
+        log.push(checkin("AC000001", student));
+    }
+    log.push(checkin("AC000002", "Alice Johnson"));
+    log.push(checkin("AC000003", "Bob Smith"));
+    fs::write(dir.join("checkins.jsonl"), log.join("\n")).unwrap();
+    fs::write(
+        dir.join("roster.json"),
+        r#"[{"name": "Alice Johnson", "acbl_number": "K123456"}, {"name": "Grace Lee", "acbl_number": "L654321"}]"#,
+    )
+    .unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "export", "acbl-report",
+            "tests/output/test-acbl-report/AC000001.manifest.json",
+            "tests/output/test-acbl-report/AC000002.manifest.json",
+            "tests/output/test-acbl-report/AC000003.manifest.json",
+            "--month", "2025-01",
+            "--checkins", "tests/output/test-acbl-report/checkins.jsonl",
+            "--roster", "tests/output/test-acbl-report/roster.json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let csv = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "date,class,teacher,event_id,players,juniors,tables,acbl_numbers");
+    assert_eq!(lines[1], "2025-01-07,Tuesday Beginner,Rick,AC000001,6,0,1.5,K123456 L654321");
+    assert_eq!(lines[2], "2025-01-14,Tuesday Beginner,Rick,AC000002,1,0,0.25,K123456");
+    assert_eq!(lines.len(), 3, "February is left out");
+
+    let output = cargo_bin()
+        .args([
+            "report", "trends",
+            "tests/output/test-acbl-report/AC000001.manifest.json",
+            "tests/output/test-acbl-report/AC000002.manifest.json",
+            "tests/output/test-acbl-report/AC000003.manifest.json",
+            "--checkins", "tests/output/test-acbl-report/checkins.jsonl",
+            "--output-dir", "tests/output/test-acbl-report",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Trends of 3 sessions over 5 weeks"));
+    assert!(dir.join("attendance-trends.pdf").exists());
+}
+
+#[test]
+fn test_waiver_from_file() {
+    setup();
+    let output_file = "test-waiver.typ";
+    cleanup_file(output_file);
+    fs::write(output_dir().join("waiver.txt"), "I take part at my own risk.\n").unwrap();
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Waiver Class",
+            "--roster", "examples/roster.json",
+            "--waiver", "tests/output/waiver.txt",
+            "--format", "typst",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let source = fs::read_to_string(output_dir().join(output_file)).unwrap();
+    assert!(source.contains("\"I take part at my own risk.\""), "Waiver text missing");
+
+    let output = cargo_bin()
+        .args(["-n", "Waiver Class", "--waiver", "tests/output/no-such-waiver.txt", "-o", "tests/output/x.pdf"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--waiver"));
+}
+
+#[test]
+fn test_export_transcription_template() {
+    setup();
+    let output_file = "test-transcription.pdf";
+    cleanup_file(output_file);
+    cleanup_file("test-transcription.manifest.json");
+
+    let output = cargo_bin()
+        .args([
+            "-n", "Photo Class",
+            "--event-id", "7E57C0DE",
+            "--roster", "examples/roster.json",
+            "--photo-consent",
+            "--time-column",
+            "--manifest",
+            "-o", &format!("tests/output/{}", output_file),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let output = cargo_bin()
+        .args(["export", "transcription", "tests/output/test-transcription.manifest.json"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let csv = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "event_id,row,name,present,table,seat,photo_ok,checked_in");
+    assert_eq!(lines[1], "7E57C0DE,1,Alice Johnson,,,,,");
+    assert!(
+        lines.iter().any(|line| line.starts_with("7E57C0DE,") && line.contains(",,,,,")),
+        "Walk-in rows are blank"
+    );
+}
@@ -0,0 +1,1757 @@
+use attendance_pdf::checkin::CheckIn;
+use attendance_pdf::deals::{layout_handout, load_deals, SUIT_SYMBOLS};
+use attendance_pdf::geometry::{ColumnSpec, PageRect, RowBand};
+use attendance_pdf::layout::{
+    layout_sheet, roster_overflow, row_numbers, Element, FontStyle, Rotation, RosterOverflow, Trim, DEFAULT_CHECKLIST,
+    paginate, plan_first_page, Block, Keep, Page, ONLINE_HEADING, OVERFLOW_PAGE_TITLE, PRACTICE_HANDS_TITLE,
+};
+use attendance_pdf::lesson::{layout_lesson_plan, load_lesson_plan, parse_lesson_plan};
+use attendance_pdf::metrics::{text_width_mm, wrap_hyphenated, wrap_text};
+use attendance_pdf::manifest::ManifestEvent;
+use attendance_pdf::pass::{layout_passes, PassOptions};
+use attendance_pdf::progress::{layout_progress, ProgressOptions};
+use attendance_pdf::receipt::{layout_receipts, ReceiptOptions};
+use attendance_pdf::report::{reconcile, MailingGrowth, MasterpointSheet, Reconciliation, SemesterSummary, TrendReport};
+use attendance_pdf::roster::{sign_in_copy, teacher_copy};
+use attendance_pdf::schedule::load_schedule;
+use attendance_pdf::scoring::{layout_scoring, ScoringOptions};
+use attendance_pdf::tent::{layout_table_tents, TentOptions};
+use attendance_pdf::traveler::{layout_travelers, TravelerOptions};
+use attendance_pdf::{
+    AttendanceConfig, AttendanceSheet, DisplayOrder, EmergencyInfo, MailingPlacement, PreparedImage, QrCache,
+    Revision, RosterEntry, RosterMarker, SheetLayout, Sponsor, Student, TextAlign, MARGIN_MM, MIN_ROW_HEIGHT_MM,
+    PAGE_HEIGHT_MM, PAGE_WIDTH_MM,
+    SIGNATURE_MIN_ROW_HEIGHT_MM,
+};
+use proptest::prelude::*;
+use proptest::test_runner::FileFailurePersistence;
+use std::path::Path;
+use std::sync::Arc;
+use chrono::NaiveDate;
+
+fn config(roster: Option<Vec<&str>>) -> AttendanceConfig {
+    AttendanceConfig {
+        class_name: "Tuesday Beginner".to_string(),
+        teacher: "Rick".to_string(),
+        teacher_email: None,
+        substitute: None,
+        date: NaiveDate::from_ymd_opt(2025, 1, 7).unwrap(),
+        location: String::new(),
+        wifi: None,
+        emergency: None,
+        banner: None,
+        feedback_url: None,
+        emergency_page: None,
+        event_id: "ABCD1234".to_string(),
+        roster: roster.map(|names| names.into_iter().map(Student::from).collect()),
+        online_attendees: None,
+        blank_rows: 32,
+        mailing_list: true,
+        mailing_rows: 4,
+        mailing_title: None,
+        title_align: TextAlign::Left,
+        mailing_placement: MailingPlacement::LastPage,
+        helper_rows: 0,
+        coupons: 0,
+        signature_column: false,
+        photo_consent: false,
+        time_column: false,
+        raffle: false,
+        draw_numbers: false,
+        announce_birthdays: false,
+        display_order: DisplayOrder::FirstLast,
+        use_nicknames: false,
+        markers: Vec::new(),
+        calendar: None,
+        waiver: None,
+        checklist: None,
+        hands: None,
+        overflow_page: false,
+        copies: 1,
+        mono: false,
+        economy: false,
+        qr_dpi: 600,
+        qr_codes: QrCache::default(),
+        logo: None,
+        sponsor: None,
+        reproducible: false,
+        revision: None,
+        teacher_copy: false,
+    }
+}
+
+const ROSTER: [&str; 8] = [
+    "Alice Johnson",
+    "Bob Smith",
+    "Carol Davis",
+    "David Wilson",
+    "Eve Martinez",
+    "Frank Brown",
+    "Grace Lee",
+    "Henry Taylor",
+];
+
+#[test]
+fn test_roster_names_fill_left_column_in_order() {
+    let layout = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+
+    let (_, alice) = layout.find_text("Alice Johnson").expect("Alice missing");
+    let (_, bob) = layout.find_text("Bob Smith").expect("Bob missing");
+    let (_, david) = layout.find_text("David Wilson").expect("David missing");
+
+    let row_height = alice.y - bob.y;
+    assert!(row_height > 0.0, "Bob should be below Alice");
+    assert!((alice.y - david.y - 3.0 * row_height).abs() < 0.01, "David should be row 4");
+    assert_eq!(alice.x, bob.x);
+    assert!(alice.x < MARGIN_MM + 10.0, "Left column should start at the margin");
+}
+
+#[test]
+fn test_roster_splits_into_two_columns() {
+    let layout = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, eve) = layout.find_text("Eve Martinez").unwrap();
+
+    assert!(eve.x > alice.x + 50.0, "Eve should start the right column");
+    assert!((eve.y - alice.y).abs() < 0.01, "Columns should share row positions");
+}
+
+#[test]
+fn test_header_contains_event_details() {
+    let layout = layout_sheet(&config(None)).unwrap();
+
+    assert_eq!(layout.find_text("Tuesday Beginner").map(|(page, _)| page), Some(0));
+    assert!(layout.find_text("ID: ABCD1234").is_some());
+    assert!(layout.find_text("Instructor: Rick").is_some());
+    assert!(layout.find_text("Tuesday, January 7, 2025").is_some());
+
+    let qr_codes = layout.pages[0].images().count();
+    assert_eq!(qr_codes, 1, "Only the QR code should be placed without a logo");
+}
+
+#[test]
+fn test_long_class_name_wraps_onto_two_lines() {
+    let short = layout_sheet(&config(None)).unwrap();
+    let mut long_config = config(None);
+    long_config.class_name =
+        "Tuesday Evening Intermediate Bidding and Declarer Play Workshop for Returning Players".to_string();
+    let long = layout_sheet(&long_config).unwrap();
+
+    let (_, first) = long.texts().find(|(_, text)| text.text.starts_with("Tuesday Evening")).unwrap();
+    let (_, second) = long.texts().find(|(_, text)| text.text.ends_with("Returning Players")).unwrap();
+    assert!(second.y < first.y, "The rest of the name should go on a second line");
+    let right_edge = long.width - MARGIN_MM;
+    for line in [first, second] {
+        assert!(line.x + text_width_mm(&line.text, line.size, line.font) <= right_edge);
+    }
+
+    // The date follows the name down
+    let date = "Tuesday, January 7, 2025";
+    let short_date = short.find_text(date).unwrap().1.y;
+    let long_date = long.find_text(date).unwrap().1.y;
+    assert!(long_date < short_date);
+    assert!(long_date < second.y);
+}
+
+#[test]
+fn test_aligned_text_measures_width() {
+    let layout = layout_sheet(&config(None)).unwrap();
+    let right_edge = layout.width - MARGIN_MM;
+    let (_, id) = layout.find_text("ID: ABCD1234").unwrap();
+    let id_end = id.x + text_width_mm(&id.text, id.size, id.font);
+    assert!((id_end - right_edge).abs() < 0.01, "Event ID should end at the margin");
+
+    let (_, heading) = layout.find_text("JOIN MY MAILING LIST").unwrap();
+    let heading_middle = heading.x + text_width_mm(&heading.text, heading.size, heading.font) / 2.0;
+    assert!((heading_middle - layout.width / 2.0).abs() < 0.01);
+
+    let mut page = Page::default();
+    page.aligned_text("Centered", 12.0, 100.0, 50.0, FontStyle::Bold, TextAlign::Center);
+    let text = page.texts().next().unwrap();
+    assert!((text.x + text_width_mm("Centered", 12.0, FontStyle::Bold) / 2.0 - 100.0).abs() < 0.01);
+}
+
+#[test]
+fn test_title_alignment_beside_qr_code() {
+    let left = layout_sheet(&config(None)).unwrap();
+    let mut centered_config = config(None);
+    centered_config.title_align = TextAlign::Center;
+    let centered = layout_sheet(&centered_config).unwrap();
+    let mut right_config = config(None);
+    right_config.title_align = TextAlign::Right;
+    let right = layout_sheet(&right_config).unwrap();
+
+    let title_x = |layout: &SheetLayout| layout.find_text("CLASS ATTENDANCE").unwrap().1.x;
+    assert!(title_x(&left) < title_x(&centered));
+    assert!(title_x(&centered) < title_x(&right));
+    let (_, title) = right.find_text("CLASS ATTENDANCE").unwrap();
+    let title_end = title.x + text_width_mm(&title.text, title.size, title.font);
+    assert!((title_end - (right.width - MARGIN_MM)).abs() < 0.01);
+
+    // The class name stays where it was
+    let name_x = |layout: &SheetLayout| layout.find_text("Tuesday Beginner").unwrap().1.x;
+    assert_eq!(name_x(&left), name_x(&right));
+}
+
+#[test]
+fn test_wrap_hyphenated_breaks_overlong_words() {
+    let lines = wrap_hyphenated("Supercalifragilisticexpialidocious bridge", 12.0, FontStyle::Bold, 30.0);
+    assert!(lines.len() > 1);
+    assert!(lines[0].ends_with('-'));
+    for line in &lines {
+        assert!(text_width_mm(line, 12.0, FontStyle::Bold) <= 30.0, "{} is too wide", line);
+    }
+    assert_eq!(lines.concat().replace(['-', ' '], ""), "Supercalifragilisticexpialidociousbridge");
+}
+
+#[test]
+fn test_blank_mode_keeps_tables_together_across_pages() {
+    let mut blank_config = config(None);
+    blank_config.mailing_placement = MailingPlacement::FirstPage;
+    let layout = layout_sheet(&blank_config).unwrap();
+
+    assert_eq!(layout.pages.len(), 2);
+    assert_eq!(layout.find_text("Table 3").map(|(page, _)| page), Some(0));
+    assert_eq!(layout.find_text("Table 4").map(|(page, _)| page), Some(1));
+    assert_eq!(layout.find_text("Table 8").map(|(page, _)| page), Some(1));
+}
+
+#[test]
+fn test_paginate_keeps_headings_with_rows() {
+    let rows = [10.0; 10];
+    let keep = Keep::with_next(5.0, 2);
+    assert_eq!(paginate(&rows, keep, 27.0, 110.0), vec![0..2, 2..10]);
+    assert_eq!(paginate(&rows, keep, 20.0, 110.0), vec![0..0, 0..10], "A heading over one row moves on");
+    assert_eq!(paginate(&rows, keep, 20.0, 100.0), vec![0..0, 0..8, 8..10], "Two rows carried, not one");
+    assert_eq!(paginate(&rows[..5], Keep::with_next(0.0, 2), 45.0, 100.0), vec![0..3, 3..5], "No widow");
+    assert_eq!(paginate(&rows[..3], Keep::WHOLE, 25.0, 100.0), vec![0..0, 0..3]);
+    assert_eq!(paginate(&rows[..3], Keep::ROWS, 25.0, 100.0), vec![0..2, 2..3]);
+
+    // A fresh page that cannot honor the rule still takes what fits
+    assert_eq!(paginate(&[50.0; 3], Keep::with_next(0.0, 2), 100.0, 100.0), vec![0..2, 2..3]);
+    assert!(paginate(&[], keep, 0.0, 100.0).is_empty());
+}
+
+#[test]
+fn test_mailing_section_follows_the_grid() {
+    let mailing_page = |placement: MailingPlacement, rows: u32| {
+        let mut blank_config = config(None);
+        blank_config.blank_rows = rows;
+        blank_config.mailing_placement = placement;
+        let layout = layout_sheet(&blank_config).unwrap();
+        let (page, heading) = layout.find_text("JOIN MY MAILING LIST").unwrap();
+        let last_table = layout
+            .texts()
+            .filter(|(_, t)| t.text.starts_with("Table "))
+            .last()
+            .map(|(page, t)| (page, t.y))
+            .unwrap();
+        (page, heading.y, last_table, layout.pages.len())
+    };
+
+    // Twelve tables end part way down the second page
+    let (page, first_y, _, _) = mailing_page(MailingPlacement::FirstPage, 48);
+    assert_eq!(page, 0);
+    let (page, bottom_y, (table_page, _), pages) = mailing_page(MailingPlacement::LastPage, 48);
+    assert_eq!((page, table_page, pages), (2, 2, 3), "The list should end up on the grid's last page");
+    assert_eq!(bottom_y, first_y, "Still at the foot of the page");
+    let (page, after_y, (_, table_y), _) = mailing_page(MailingPlacement::AfterGrid, 48);
+    assert_eq!(page, 2);
+    assert!(after_y > bottom_y && after_y < table_y, "Directly under the last table");
+
+    // A grid that fits on one page keeps the list at its foot
+    for placement in [MailingPlacement::LastPage, MailingPlacement::AfterGrid] {
+        let (page, y, _, pages) = mailing_page(placement, 8);
+        assert_eq!((page, pages), (0, 1));
+        assert_eq!(y, first_y);
+    }
+
+    // No room under the last table: a page of its own, headed like the rest
+    let (page, _, (table_page, _), pages) = mailing_page(MailingPlacement::LastPage, 32);
+    assert_eq!((page, table_page, pages), (2, 1, 3));
+}
+
+#[test]
+fn test_first_page_plan_stacks_blocks() {
+    let mut plan_config = config(None);
+    plan_config.mailing_placement = MailingPlacement::FirstPage;
+    plan_config.banner = Some("Room change".to_string());
+    plan_config.coupons = 3;
+    let plan = plan_first_page(&plan_config);
+
+    let blocks: Vec<Block> = plan.blocks.iter().map(|placed| placed.block).collect();
+    assert_eq!(blocks, [Block::Header, Block::Banner, Block::Grid, Block::Mailing, Block::Coupons]);
+    assert_eq!(plan.blocks[0].top, PAGE_HEIGHT_MM - MARGIN_MM);
+    assert_eq!(plan.blocks.last().unwrap().bottom, plan.content_bottom);
+    for pair in plan.blocks.windows(2) {
+        assert_eq!(pair[0].bottom, pair[1].top, "{:?} and {:?} should meet", pair[0].block, pair[1].block);
+    }
+
+    // Each footer section comes out of the grid's share
+    plan_config.helper_rows = 4;
+    let with_helpers = plan_first_page(&plan_config);
+    let helpers = with_helpers.block(Block::Helpers).unwrap();
+    assert_eq!(with_helpers.grid().height(), plan.grid().height() - helpers.height());
+    assert_eq!(helpers.bottom, with_helpers.block(Block::Mailing).unwrap().top);
+
+    // A mailing list that goes with the grid leaves the first page
+    plan_config.mailing_placement = MailingPlacement::LastPage;
+    plan_config.blank_rows = 48;
+    assert!(plan_first_page(&plan_config).block(Block::Mailing).is_none());
+}
+
+#[test]
+fn test_column_spec_divides_the_width() {
+    for (signature, consent, time) in [(false, false, false), (true, false, false), (true, true, true)] {
+        let cells = ColumnSpec::new(20.0, 90.0, signature, consent, time);
+        assert!((cells.right() - 110.0).abs() < 0.01, "{:?} should span the column", cells);
+        assert!(cells.x < cells.signature_x() && cells.signature_x() <= cells.consent_x());
+        assert!(cells.consent_x() <= cells.time_x() && cells.time_x() <= cells.table_x());
+        assert!(cells.table_x() < cells.seat_x());
+    }
+
+    // Consent and time cells come out of the name, not TABLE or SEAT
+    let plain = ColumnSpec::new(0.0, 100.0, false, false, false);
+    let both = ColumnSpec::new(0.0, 100.0, false, true, true);
+    assert!((plain.name_width - both.name_width - both.consent_width - both.time_width).abs() < 0.01);
+    assert_eq!((plain.table_x(), plain.seat_width), (both.table_x(), both.seat_width));
+    assert_eq!(plain.signature_width, 0.0);
+}
+
+#[test]
+fn test_page_rect_columns_and_row_bands() {
+    let area = PageRect::from_top(10.0, 200.0, 100.0, 150.0);
+    assert_eq!((area.y, area.top(), area.right()), (50.0, 200.0, 110.0));
+    let columns = area.columns(3, 5.0);
+    assert_eq!(columns.len(), 3);
+    assert_eq!(columns[0].x, 10.0);
+    assert_eq!(columns[2].right(), 110.0);
+    assert_eq!(columns[1].x, columns[0].right() + 5.0);
+    assert!(columns.iter().all(|column| column.y == area.y && column.height == area.height));
+
+    let row = RowBand { top: 100.0, height: 8.0 };
+    assert_eq!(row.bottom(), 92.0);
+    assert_eq!(row.text_y(), 94.5);
+    assert_eq!(row.next(6.0), RowBand { top: 92.0, height: 6.0 });
+    assert_eq!(row.extended(3.5).next(8.0).top, 88.5);
+}
+
+#[test]
+fn test_mailing_section_toggle() {
+    let with_mailing = layout_sheet(&config(None)).unwrap();
+    assert!(with_mailing.find_text("JOIN MY MAILING LIST").is_some());
+
+    let mut no_mailing_config = config(None);
+    no_mailing_config.mailing_list = false;
+    let without_mailing = layout_sheet(&no_mailing_config).unwrap();
+    assert!(without_mailing.find_text("JOIN MY MAILING LIST").is_none());
+}
+
+#[test]
+fn test_elements_stay_on_page() {
+    let layout = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+
+    for page in &layout.pages {
+        for element in &page.elements {
+            let (x, y) = match element {
+                Element::Text(t) => (t.x, t.y),
+                Element::Line(l) => (l.x1.max(l.x2), l.y1.min(l.y2)),
+                Element::Rect(r) => (r.x + r.width, r.y),
+                Element::Image(i) => (i.x + i.width, i.y),
+            };
+            assert!(x <= layout.width - MARGIN_MM + 0.01, "Element past right margin: {:?}", element);
+            assert!(y >= MARGIN_MM - 0.01, "Element below bottom margin: {:?}", element);
+        }
+    }
+}
+
+#[test]
+fn test_substitute_teacher_annotation() {
+    let mut sub_config = config(None);
+    sub_config.substitute = Some("Pat".to_string());
+    let layout = layout_sheet(&sub_config).unwrap();
+
+    assert!(layout.find_text("Instructor: Pat (sub for Rick)").is_some());
+    assert!(layout.find_text("Instructor: Rick").is_none());
+}
+
+#[test]
+fn test_helper_rows_sit_above_mailing_list() {
+    let mut helper_config = config(Some(ROSTER.to_vec()));
+    helper_config.helper_rows = 3;
+    let layout = layout_sheet(&helper_config).unwrap();
+
+    let (page, heading) = layout.find_text("HELPERS & VOLUNTEERS").expect("Helper block missing");
+    let (_, mailing) = layout.find_text("JOIN MY MAILING LIST").unwrap();
+    let (_, last_student) = layout.find_text("Henry Taylor").unwrap();
+    assert_eq!(page, 0);
+    assert!(heading.y > mailing.y, "Helpers should be above the mailing list");
+    assert!(heading.y < last_student.y, "Helpers should be below the roster");
+
+    let helper_rows = layout.pages[0].texts().filter(|t| t.text == "Role:").count();
+    assert_eq!(helper_rows, 3);
+
+    assert!(layout_sheet(&config(None)).unwrap().find_text("HELPERS & VOLUNTEERS").is_none());
+}
+
+#[test]
+fn test_online_attendees_listed_below_roster() {
+    let mut hybrid_config = config(Some(ROSTER.to_vec()));
+    hybrid_config.helper_rows = 2;
+    let online = ["Ivy Chen", "Jack Moore", "Kim Park", "Liam Young"];
+    hybrid_config.online_attendees = Some(online.iter().map(|name| name.to_string()).collect());
+    let layout = layout_sheet(&hybrid_config).unwrap();
+
+    let (page, heading) = layout.find_text(ONLINE_HEADING).expect("Online section missing");
+    let (_, helpers) = layout.find_text("HELPERS & VOLUNTEERS").unwrap();
+    let (_, last_student) = layout.find_text("Henry Taylor").unwrap();
+    assert_eq!(page, 0);
+    assert!(heading.y < last_student.y, "Online attendees should be below the roster");
+    assert!(heading.y > helpers.y, "Online attendees should be above the helpers");
+
+    // Three columns, down then across, a box to tick beside each name
+    let (_, ivy) = layout.find_text("Ivy Chen").unwrap();
+    let (_, jack) = layout.find_text("Jack Moore").unwrap();
+    let (_, kim) = layout.find_text("Kim Park").unwrap();
+    assert_eq!(ivy.x, jack.x);
+    assert!(jack.y < ivy.y && kim.x > ivy.x);
+    let boxes = layout.pages[0].rects().filter(|rect| rect.style.radius > 0.0).count();
+    assert_eq!(boxes, ROSTER.len() + online.len());
+
+    hybrid_config.online_attendees = Some(Vec::new());
+    assert!(layout_sheet(&hybrid_config).unwrap().find_text(ONLINE_HEADING).is_none());
+}
+
+#[test]
+fn test_sponsor_strip_repeats_below_content() {
+    let mut sponsor_config = config(None);
+    sponsor_config.sponsor = Some(Sponsor {
+        text: "Lessons sponsored by Harmonic Systems".to_string(),
+        image: Some(Arc::new(PreparedImage { width_px: 2, height_px: 1, rgb: vec![0; 6] })),
+    });
+    let layout = layout_sheet(&sponsor_config).unwrap();
+
+    for page in &layout.pages {
+        let strip = page
+            .texts()
+            .find(|t| t.text == "Lessons sponsored by Harmonic Systems")
+            .expect("Sponsor text missing from a page");
+        let image = page.images().find(|i| i.y < MARGIN_MM + 1.0).expect("Sponsor image missing");
+        assert!(strip.x > image.x + image.width, "Text should follow the image");
+
+        let lowest_content = page
+            .texts()
+            .filter(|t| t.text != strip.text && !t.text.starts_with("Sheet ") && t.x > MARGIN_MM)
+            .map(|t| t.y)
+            .fold(f32::MAX, f32::min);
+        assert!(lowest_content > image.y + image.height, "Content overlaps the sponsor strip");
+    }
+}
+
+#[test]
+fn test_raffle_numbers_match_tickets() {
+    let mut raffle_config = config(Some(ROSTER.to_vec()));
+    raffle_config.raffle = true;
+    let layout = layout_sheet(&raffle_config).unwrap();
+
+    // 8 names plus 4 walk-in rows per column
+    assert_eq!(attendance_pdf::layout::raffle_ticket_count(&raffle_config), 16);
+    assert_eq!(layout.pages.len(), 2, "Tickets should follow on their own page");
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (page, first) = layout.find_text("#1").expect("Grid row number missing");
+    assert_eq!(page, 0);
+    assert!((first.y - alice.y).abs() < 0.01, "#1 should be on Alice's row");
+    assert_eq!(layout.find_text("#16").map(|(page, _)| page), Some(0));
+
+    assert_eq!(layout.find_text("DOOR PRIZE TICKETS").map(|(page, _)| page), Some(1));
+    let stubs = layout.pages[1].texts().filter(|t| t.text == "No. 16").count();
+    assert_eq!(stubs, 2, "Ticket 16 needs both a stub and a ticket half");
+    assert!(layout.find_text("No. 17").is_none());
+}
+
+#[test]
+fn test_draw_numbers_shuffle_rows_without_tickets() {
+    let mut draw_config = config(Some(ROSTER.to_vec()));
+    draw_config.draw_numbers = true;
+    let layout = layout_sheet(&draw_config).unwrap();
+    assert_eq!(layout.pages.len(), 1, "Draw numbers replace the ticket pages");
+
+    let numbers = row_numbers(&draw_config).unwrap();
+    let mut sorted = numbers.clone();
+    sorted.sort();
+    assert_eq!(sorted, (1..=16).collect::<Vec<u32>>(), "Each row gets a distinct number");
+    assert_ne!(numbers, sorted, "Numbers should be shuffled");
+    assert_eq!(row_numbers(&draw_config), Some(numbers.clone()), "Same event, same numbers");
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, first) = layout.find_text(&format!("#{}", numbers[0])).unwrap();
+    assert!((first.y - alice.y).abs() < 0.01, "Alice's row gets the first number");
+}
+
+#[test]
+fn test_copies_stamp_each_sheet() {
+    let layout = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+    assert!(
+        !layout.texts().any(|(_, text)| text.text.starts_with("Sheet ")),
+        "A single sheet needs no stamp"
+    );
+
+    let mut copies_config = config(Some(ROSTER.to_vec()));
+    copies_config.copies = 3;
+    let layout = layout_sheet(&copies_config).unwrap();
+    assert_eq!(layout.pages.len(), 3);
+    assert_eq!(layout.find_text("Sheet 2 of 3 \u{2013} ABCD1234").map(|(page, _)| page), Some(1));
+    let (_, stamp) = layout.find_text("Sheet 3 of 3 \u{2013} ABCD1234").unwrap();
+    assert!(stamp.y < MARGIN_MM, "Stamp belongs in the bottom margin");
+    assert_eq!(layout.pages[2].texts().filter(|text| text.text == "Alice Johnson").count(), 1);
+}
+
+#[test]
+fn test_event_id_reads_up_left_margin() {
+    let mut copies_config = config(Some(ROSTER.to_vec()));
+    copies_config.copies = 2;
+    let layout = layout_sheet(&copies_config).unwrap();
+    let ids: Vec<_> = layout.texts().filter(|(_, text)| text.text == "ABCD1234").collect();
+    assert_eq!(ids.iter().map(|(page, _)| *page).collect::<Vec<_>>(), [0, 1], "Every page has one");
+    assert!(ids.iter().all(|(_, text)| text.rotation == Rotation::Left && text.x < MARGIN_MM));
+}
+
+#[test]
+fn test_revision_stamp_on_reprints() {
+    let mut reprint = config(Some(ROSTER.to_vec()));
+    reprint.revision = Some(Revision {
+        number: 1,
+        generated: "2025-01-07T08:30:00-05:00".to_string(),
+    });
+    let layout = layout_sheet(&reprint).unwrap();
+    assert!(!layout.texts().any(|(_, text)| text.text.starts_with("Rev ")), "The first printing needs no stamp");
+
+    reprint.revision = Some(Revision {
+        number: 2,
+        generated: "2025-01-07T09:12:00-05:00".to_string(),
+    });
+    reprint.copies = 2;
+    let layout = layout_sheet(&reprint).unwrap();
+    let stamps: Vec<_> =
+        layout.texts().filter(|(_, text)| text.text == "Rev 2 \u{2013} generated 09:12").collect();
+    assert_eq!(stamps.iter().map(|(page, _)| *page).collect::<Vec<_>>(), [0, 1], "Every page is stamped");
+    assert!(stamps[0].1.y < MARGIN_MM, "Stamp belongs in the bottom margin");
+}
+
+#[test]
+fn test_calendar_lists_remaining_sessions() {
+    let schedule = load_schedule(Path::new("examples/schedule.toml")).unwrap();
+    let mut calendar_config = config(Some(ROSTER.to_vec()));
+    calendar_config.calendar = Some(schedule);
+    let layout = layout_sheet(&calendar_config).unwrap();
+
+    assert_eq!(layout.pages.len(), 2, "Calendar should be its own final page");
+    assert_eq!(layout.find_text("UPCOMING SESSIONS").map(|(page, _)| page), Some(1));
+    assert!(layout.find_text("Winter Beginner Series").is_some());
+
+    // Today's session (Jan 7) is not listed; later ones are, under their month
+    assert!(layout.find_text("Counting points and opening 1NT").is_none());
+    let (_, stayman) = layout.find_text("Stayman").unwrap();
+    let (_, leads) = layout.find_text("Opening leads").unwrap();
+    assert!(stayman.y > leads.y, "Sessions should run down the page in date order");
+    assert!(layout.find_text("January 2025").is_some());
+    assert!(layout.find_text("February 2025").is_some());
+}
+
+#[test]
+fn test_calendar_on_last_session() {
+    let mut calendar_config = config(None);
+    calendar_config.date = NaiveDate::from_ymd_opt(2025, 2, 11).unwrap();
+    calendar_config.calendar = Some(load_schedule(Path::new("examples/schedule.toml")).unwrap());
+    let layout = layout_sheet(&calendar_config).unwrap();
+
+    let (page, _) = layout
+        .find_text("This is the last session of the series. Thanks for coming!")
+        .expect("Last session should say so instead of listing nothing");
+    assert_eq!(page, layout.pages.len() - 1);
+}
+
+#[test]
+fn test_checklist_page() {
+    let mut checklist_config = config(Some(ROSTER.to_vec()));
+    checklist_config.checklist = Some(DEFAULT_CHECKLIST.iter().map(|item| item.to_string()).collect());
+    let layout = layout_sheet(&checklist_config).unwrap();
+
+    assert_eq!(layout.pages.len(), 2, "Checklist should be its own final page");
+    assert_eq!(layout.find_text("BEFORE CLASS").map(|(page, _)| page), Some(1));
+    let (_, boards) = layout.find_text("Boards dealt").unwrap();
+    let (_, tags) = layout.find_text("Name tags").unwrap();
+    assert!(boards.y > tags.y, "Items should run down the page in order");
+
+    // One box per item, beside its text
+    assert_eq!(layout.pages[1].rects().count(), DEFAULT_CHECKLIST.len());
+}
+
+#[test]
+fn test_practice_hands_pages() {
+    let deals = load_deals(Path::new("examples/hands.pbn")).unwrap();
+    let mut hands_config = config(Some(ROSTER.to_vec()));
+    hands_config.hands = Some(deals.iter().cycle().take(8).cloned().collect());
+    let layout = layout_sheet(&hands_config).unwrap();
+
+    assert_eq!(layout.find_text(PRACTICE_HANDS_TITLE).map(|(page, _)| page), Some(1));
+    assert_eq!(layout.pages.len(), 3, "Six diagrams fit a page, so eight deals take two");
+    assert!(layout.find_text(&format!("{} (continued)", PRACTICE_HANDS_TITLE)).is_some());
+
+    // North's spades are above South's, with tens written out
+    let page = &layout.pages[1];
+    let north = page.texts().find(|text| text.text == "AKQ2").expect("North's spades");
+    let south = page.texts().find(|text| text.text == "J85").expect("South's spades");
+    assert!(north.y > south.y);
+    assert!((north.x - south.x).abs() < 0.01, "North and South line up");
+    assert!(page.texts().any(|text| text.text == "AJ107"));
+    assert!(page.texts().any(|text| text.text == "Board 1"));
+    assert!(page.texts().any(|text| text.text == "Vul: NS"));
+    let spade = page.texts().find(|text| text.text == SUIT_SYMBOLS[0]).expect("Spade symbol");
+    assert_eq!(spade.font, FontStyle::Symbol, "Suits are set in the embedded symbol font");
+
+    // Board 2 leaves East-West out
+    let east = page.texts().filter(|text| text.text == "1096").count();
+    assert_eq!(east, 3, "East's spades appear only on the three copies of board 1 on this page");
+}
+
+#[test]
+fn test_handout_from_lin() {
+    let deals = load_deals(Path::new("examples/hands.lin")).unwrap();
+    assert_eq!(deals, load_deals(Path::new("examples/hands.pbn")).unwrap()[..1]);
+
+    let handout = layout_handout("Opening 1NT", "Week 3", &deals);
+    assert_eq!(handout.pages.len(), 1);
+    assert!(handout.find_text("Opening 1NT").is_some());
+    let symbols = handout.texts().filter(|(_, text)| text.font == FontStyle::Symbol).count();
+    assert_eq!(symbols, 16, "Four suits for each of the four hands");
+}
+
+#[test]
+fn test_emergency_page() {
+    let mut emergency_config = config(None);
+    emergency_config.emergency_page = Some(EmergencyInfo {
+        location: "Senior Center, 4 Elm St".to_string(),
+        exits: Some("Front doors; fire exit behind the stage, past the kitchen and the storage room".to_string()),
+        aed: Some("Lobby, left of the elevator".to_string()),
+        contacts: vec!["Emergency: 911".to_string(), "Front desk: 555-0100".to_string()],
+        notes: None,
+    });
+    let layout = layout_sheet(&emergency_config).unwrap();
+
+    let last = layout.pages.len() - 1;
+    assert_eq!(layout.find_text("IN AN EMERGENCY").map(|(page, _)| page), Some(last));
+    let (_, call) = layout.find_text("Emergency: 911").unwrap();
+    assert_eq!(call.size, 20.0, "Contacts should be printed large");
+    assert!(layout.find_text("Lobby, left of the elevator").is_some());
+    assert!(layout.find_text("ALSO").is_none(), "No notes, no block");
+
+    // The long exits line wraps within the margins
+    let exits: Vec<_> = layout.pages[last]
+        .texts()
+        .filter(|text| text.size == 20.0 && text.y < call.y)
+        .collect();
+    assert!(exits.len() >= 3, "Exits should wrap onto a second line");
+    for text in exits {
+        let right = text.x + text_width_mm(&text.text, text.size, text.font);
+        assert!(right <= attendance_pdf::PAGE_WIDTH_MM - MARGIN_MM, "{} overflows", text.text);
+    }
+}
+
+#[test]
+fn test_waiver_pages() {
+    let mut waiver_config = config(Some(ROSTER.to_vec()));
+    waiver_config.waiver = Some("I understand that bridge\nis a game of skill.\n\nI release the club.".to_string());
+    let layout = layout_sheet(&waiver_config).unwrap();
+
+    assert_eq!(layout.pages.len(), 2, "Waiver should follow the sheet");
+    let (page, first) = layout.find_text("I understand that bridge is a game of skill.").unwrap();
+    assert_eq!(page, 1);
+    let (_, second) = layout.find_text("I release the club.").unwrap();
+    assert!(first.y > second.y, "Paragraphs should run down the page");
+
+    // A row per student below the text, in roster order, then walk-in rows
+    let (_, alice) = layout.texts().find(|(page, text)| *page == 1 && text.text == "Alice Johnson").unwrap();
+    let (_, henry) = layout.texts().find(|(page, text)| *page == 1 && text.text == "Henry Taylor").unwrap();
+    assert!(second.y > alice.y && alice.y > henry.y);
+    let write_in_lines = layout.pages[1]
+        .lines()
+        .filter(|line| line.stroke.thickness == 0.4 && line.x1 == MARGIN_MM)
+        .count();
+    assert_eq!(write_in_lines, 4, "Walk-ins write their names in");
+
+    let mut blank_config = config(None);
+    blank_config.waiver = Some("I release the club.".to_string());
+    let layout = layout_sheet(&blank_config).unwrap();
+    let (first_waiver_page, _) = layout.texts().find(|(_, text)| text.text.starts_with("WAIVER")).unwrap();
+    let signature_lines: usize = layout.pages[first_waiver_page..]
+        .iter()
+        .map(|page| page.lines().filter(|line| line.stroke.thickness == 0.4).count())
+        .sum();
+    assert_eq!(signature_lines, 3 * 32, "Blank sheets get a row per blank row");
+    assert!(
+        layout.texts().any(|(_, text)| text.text.ends_with("(continued)")),
+        "32 rows need a second page"
+    );
+}
+
+#[test]
+fn test_wrap_text_breaks_at_spaces() {
+    let width = text_width_mm("one two", 11.0, FontStyle::Regular);
+    let lines = wrap_text("one two three four", 11.0, FontStyle::Regular, width);
+    assert_eq!(lines, ["one two", "three", "four"]);
+    assert_eq!(wrap_text("unbreakable", 11.0, FontStyle::Regular, 1.0), ["unbreakable"]);
+    assert!(wrap_text("   ", 11.0, FontStyle::Regular, 50.0).is_empty());
+}
+
+#[test]
+fn test_suit_symbols_measured_at_suit_font_width() {
+    let two = text_width_mm("2", 12.0, FontStyle::Bold);
+    let club = text_width_mm("\u{2663}", 12.0, FontStyle::Symbol);
+    let both = text_width_mm("2\u{2663}", 12.0, FontStyle::Bold);
+    assert!((both - (two + club)).abs() < 0.001, "A suit in bold text takes the suit font's width");
+}
+
+#[test]
+fn test_passes_one_per_student() {
+    let layout = layout_passes(&config(Some(ROSTER.to_vec())), &PassOptions::default()).unwrap();
+
+    assert_eq!(layout.pages.len(), 1, "Eight passes fit on one page");
+    assert_eq!(layout.pages[0].texts().filter(|t| t.text == "CLASS PASS").count(), 8);
+    assert!(layout.find_text("Henry Taylor").is_some());
+    assert!(layout.find_text("No. ABCD1234-001").is_some());
+    assert!(layout.find_text("No. ABCD1234-008").is_some());
+    assert_eq!(layout.pages[0].images().count(), 8, "Each pass needs its own QR code");
+
+    // Ten numbered punch circles per card
+    assert_eq!(layout.pages[0].texts().filter(|t| t.text == "10").count(), 8);
+    assert!(layout.find_text("11").is_none());
+}
+
+#[test]
+fn test_blank_passes_fill_pages() {
+    let options = PassOptions { punches: 5, blank_passes: 10, ..Default::default() };
+    let layout = layout_passes(&config(None), &options).unwrap();
+
+    assert_eq!(layout.pages.len(), 2);
+    assert_eq!(layout.pages[1].texts().filter(|t| t.text == "CLASS PASS").count(), 2);
+    assert!(layout.find_text("No. ABCD1234-010").is_some());
+    assert!(layout.find_text("6").is_none());
+}
+
+#[test]
+fn test_receipts_three_per_page() {
+    let options = ReceiptOptions { amount: Some("$15.00".to_string()), ..Default::default() };
+    let layout = layout_receipts(&config(Some(ROSTER.to_vec())), &options).unwrap();
+
+    assert_eq!(layout.pages.len(), 3, "8 receipts at 3 per page");
+    assert_eq!(layout.pages[2].texts().filter(|t| t.text == "RECEIPT").count(), 2);
+    let (page, _) = layout.find_text("Henry Taylor").unwrap();
+    assert_eq!(page, 2);
+    assert_eq!(layout.find_text("No. ABCD1234-008").map(|(page, _)| page), Some(2));
+    assert_eq!(layout.texts().filter(|(_, t)| t.text == "$15.00").count(), 8);
+    assert_eq!(layout.texts().filter(|(_, t)| t.text == "Event ID: ABCD1234").count(), 8);
+}
+
+#[test]
+fn test_lesson_plan_segments_and_deals() {
+    let plan = load_lesson_plan(Path::new("examples/lesson-plan.md")).unwrap();
+    assert_eq!(plan.title.as_deref(), Some("Opening 1NT"));
+    assert_eq!(plan.segments.len(), 4);
+    assert_eq!(plan.segments[1].minutes, Some(20));
+    assert_eq!(plan.segments[2].boards, ["1", "2"]);
+    assert_eq!(plan.total_minutes(), 60);
+
+    let config = config(None);
+    let layout = layout_lesson_plan(&config, &plan, None).unwrap();
+    assert_eq!(layout.pages.len(), 1);
+    assert!(layout.find_text("LESSON PLAN").is_some());
+    let (_, review) = layout.find_text("0:00\u{2013}0:10").unwrap();
+    let (_, practice) = layout.find_text("0:30\u{2013}0:55").unwrap();
+    assert!(review.y > practice.y, "Segments run down the page with their elapsed times");
+    assert!(layout.find_text("\u{2022} 15-17 high card points").is_some());
+    assert!(layout.find_text("Total: 1:00").is_some());
+
+    // With the hands file, the deals the plan names follow it
+    let deals = load_deals(Path::new("examples/hands.pbn")).unwrap();
+    let with_deals = layout_lesson_plan(&config, &plan, Some(&deals)).unwrap();
+    assert_eq!(with_deals.find_text("DEALS FOR THIS LESSON").map(|(page, _)| page), Some(1));
+    assert!(with_deals.pages[1].texts().any(|text| text.text == "Board 2"));
+
+    let missing = parse_lesson_plan("## Play (10 min)\nDeals: 9\n").unwrap();
+    assert!(layout_lesson_plan(&config, &missing, Some(&deals)).is_err());
+}
+
+#[test]
+fn test_travelers_per_board_with_row_per_table() {
+    // Four players a table, so ten students need three
+    let mut roster = ROSTER.to_vec();
+    roster.extend(["Ivy Moss", "Jack Reed"]);
+    let class = config(Some(roster));
+    let options = TravelerOptions::for_config(&class, 10);
+    assert_eq!(options.tables, 3);
+    let layout = layout_travelers(&class, &options);
+
+    assert_eq!(layout.pages.len(), 1, "Three-table slips fit five down");
+    assert_eq!(layout.texts().filter(|(_, text)| text.text.starts_with("BOARD ")).count(), 10);
+    let (_, first) = layout.find_text("BOARD 1").unwrap();
+    let (_, second) = layout.find_text("BOARD 2").unwrap();
+    assert!((first.y - second.y).abs() < 0.01 && first.x < second.x, "Two slips side by side");
+    assert!(layout.find_text("Dlr: N   Vul: None").is_some());
+    assert!(layout.find_text("Dlr: W   Vul: All").is_some(), "Board 4 follows the duplicate cycle");
+
+    // North-South pair numbers down the first column of each slip
+    let pairs = layout.pages[0].texts().filter(|text| text.text == "3" && text.x < first.x + 5.0).count();
+    assert_eq!(pairs, 5, "Pair 3 on each of the five left-hand slips");
+}
+
+#[test]
+fn test_scoring_grid_pairs_by_boards() {
+    let class = config(Some(ROSTER.to_vec()));
+    let options = ScoringOptions::for_config(&class, 6);
+    assert_eq!(options.pairs, 4, "Two tables seat four pairs");
+    let layout = layout_scoring(&class, &options);
+
+    assert_eq!(layout.pages.len(), 1);
+    assert!(layout.find_text("SCORE SUMMARY").is_some());
+    let (_, board_one) = layout.find_text("1").unwrap();
+    let (_, board_six) = layout.find_text("6").unwrap();
+    let (_, total) = layout.find_text("TOTAL").unwrap();
+    assert!(board_one.x < board_six.x && board_six.x < total.x, "Boards run across before the total");
+    let pair_rows = layout.pages[0].texts().filter(|text| text.x < 20.0 && text.text.parse::<u32>().is_ok()).count();
+    assert_eq!(pair_rows, 4);
+
+    // Too many boards for one page continue on a second, total at the end
+    let long = layout_scoring(&class, &ScoringOptions { pairs: 4, boards: 20 });
+    assert_eq!(long.pages.len(), 2);
+    assert_eq!(long.find_text("TOTAL").map(|(page, _)| page), Some(1));
+}
+
+#[test]
+fn test_progress_grid_and_student_pages() {
+    let schedule = load_schedule(Path::new("examples/schedule.toml")).unwrap();
+    let topics = schedule.checklist_topics();
+    assert_eq!(topics[1], "Stayman", "The schedule's topics list comes first");
+    let config = config(Some(ROSTER.to_vec()));
+
+    let grid = layout_progress(&config, &ProgressOptions { topics: topics.clone(), per_student: false });
+    assert_eq!(grid.pages.len(), 1);
+    let headings: Vec<_> = grid.pages[0].texts().filter(|t| topics.contains(&t.text)).collect();
+    assert_eq!(headings.len(), topics.len());
+    assert!(headings.iter().all(|t| t.rotation == Rotation::Left), "Topics read up the column headers");
+    assert!(grid.find_text("Alice Johnson").is_some());
+
+    let pages = layout_progress(&config, &ProgressOptions { topics: topics.clone(), per_student: true });
+    assert_eq!(pages.pages.len(), ROSTER.len(), "A page per student");
+    let (page, _) = pages.find_text("Bob Smith").unwrap();
+    assert_eq!(page, 1);
+    assert_eq!(pages.pages[1].texts().filter(|t| t.text == "Date:").count(), topics.len());
+
+    let mut untitled = schedule.clone();
+    untitled.topics.clear();
+    assert_eq!(untitled.checklist_topics()[2], "Stayman", "Session topics stand in for a missing list");
+}
+
+#[test]
+fn test_table_tents_one_per_table() {
+    let roster: Vec<&str> = ROSTER.iter().copied().cycle().take(9).collect();
+    let config = config(Some(roster));
+    let options = TentOptions::for_config(&config);
+    assert_eq!(options.tables, 3, "Nine players need three tables of four");
+
+    let layout = layout_table_tents(&config, &options);
+    assert_eq!(layout.pages.len(), 3);
+    let (page, number) = layout.find_text("3").unwrap();
+    assert_eq!(page, 2);
+    assert!(number.y < layout.height / 2.0, "The number reads upright below the fold");
+
+    let fold = layout.height / 2.0;
+    let dashes = layout.pages[0]
+        .lines()
+        .filter(|line| line.y1 == fold && line.y2 == fold)
+        .count();
+    assert!(dashes > 20, "The fold is a dashed line across the page");
+    assert!(!layout.texts().any(|(_, text)| text.rotation != Rotation::Upright));
+
+    let two_sided = TentOptions { two_sided: true, ..options };
+    let layout = layout_table_tents(&config, &two_sided);
+    let faces: Vec<_> = layout.pages[2].texts().filter(|text| text.text == "3").collect();
+    assert_eq!(faces.len(), 2);
+    assert_eq!(faces[1].rotation, Rotation::UpsideDown);
+    assert!(faces[1].y > fold, "The far face is above the fold");
+    assert!(
+        (faces[0].x + faces[1].x - layout.width).abs() < 0.01,
+        "The far face is the near one turned about the middle of the page"
+    );
+}
+
+#[test]
+fn test_crop_marks_replace_cut_lines() {
+    let trim = Trim { crop_marks: true, bleed_mm: 3.0 };
+    let layout = layout_passes(&config(None), &PassOptions { trim, ..Default::default() }).unwrap();
+
+    // 4 vertical cuts marked top and bottom, 8 horizontal cuts marked left and right
+    let lines: Vec<_> = layout.pages[0].lines().filter(|line| line.stroke.thickness == 0.25).collect();
+    assert_eq!(lines.len(), 2 * 4 + 2 * 8);
+    assert!(
+        layout.pages[0].lines().all(|line| line.stroke.thickness != 0.3),
+        "No cut outlines with crop marks"
+    );
+    // Bleed spreads the cards: the two columns' cuts are 6 mm apart
+    let mut xs: Vec<f32> = lines.iter().filter(|line| line.x1 == line.x2).map(|line| line.x1).collect();
+    xs.sort_by(f32::total_cmp);
+    xs.dedup();
+    assert_eq!(xs.len(), 4);
+    assert!((xs[2] - xs[1] - 6.0).abs() < 0.01);
+
+    let options = ReceiptOptions { trim, ..Default::default() };
+    let layout = layout_receipts(&config(None), &options).unwrap();
+    let marks = layout.pages[0].lines().filter(|line| line.stroke.thickness == 0.25).count();
+    assert_eq!(marks, 2 * 4, "Two gaps, each cut on both sides, marked left and right");
+}
+
+#[test]
+fn test_roster_photos_beside_names() {
+    let mut photo_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = photo_config.roster.as_mut() {
+        roster[0].photo = Some(Arc::new(PreparedImage { width_px: 1, height_px: 1, rgb: vec![0; 3] }));
+    }
+    let layout = layout_sheet(&photo_config).unwrap();
+
+    let photos: Vec<_> = layout.pages[0].images().filter(|image| image.interpolate).collect();
+    assert_eq!(photos.len(), 1, "Only Alice has a photo");
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, bob) = layout.find_text("Bob Smith").unwrap();
+    assert!(photos[0].x + photos[0].width < alice.x, "Photo should sit left of the name");
+    assert!((alice.x - bob.x).abs() < 0.01, "Names should stay aligned with or without a photo");
+}
+
+#[test]
+fn test_signature_column_replaces_checkbox() {
+    let mut signature_config = config(Some(ROSTER.to_vec()));
+    signature_config.signature_column = true;
+    let layout = layout_sheet(&signature_config).unwrap();
+
+    let headings: Vec<_> = layout.pages[0].texts().filter(|t| t.text == "SIGNATURE").collect();
+    assert_eq!(headings.len(), 2, "Each roster column needs a signature heading");
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, bob) = layout.find_text("Bob Smith").unwrap();
+    assert!(alice.y - bob.y >= SIGNATURE_MIN_ROW_HEIGHT_MM - 0.01, "Rows should leave room to sign");
+    assert!(headings[0].x > alice.x, "Signature cell should follow the name");
+}
+
+#[test]
+fn test_photo_consent_column() {
+    let mut consent_config = config(Some(ROSTER.to_vec()));
+    consent_config.photo_consent = true;
+    let layout = layout_sheet(&consent_config).unwrap();
+
+    let headings: Vec<_> = layout.pages[0].texts().filter(|t| t.text == "PHOTO OK?").collect();
+    assert_eq!(headings.len(), 2, "Each roster column needs a consent heading");
+    let answers = layout.pages[0].texts().filter(|t| t.text == "Y   N").count();
+    assert_eq!(answers, ROSTER.len() + 8, "Every row, walk-ins included, gets Y/N");
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let table = layout.pages[0].texts().find(|t| t.text == "TABLE").unwrap();
+    assert!(alice.x < headings[0].x && headings[0].x < table.x, "Consent sits between name and table");
+}
+
+#[test]
+fn test_time_column() {
+    let mut time_config = config(Some(ROSTER.to_vec()));
+    time_config.time_column = true;
+    time_config.photo_consent = true;
+    let layout = layout_sheet(&time_config).unwrap();
+
+    let headings: Vec<_> = layout.pages[0].texts().filter(|t| t.text == "TIME").collect();
+    assert_eq!(headings.len(), 2, "Each roster column needs a time heading");
+    let consent = layout.pages[0].texts().find(|t| t.text == "PHOTO OK?").unwrap();
+    let table = layout.pages[0].texts().find(|t| t.text == "TABLE").unwrap();
+    assert!(consent.x < headings[0].x && headings[0].x < table.x, "Time sits just before the table column");
+
+    let plain = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+    assert!(plain.find_text("TIME").is_none(), "No time column unless asked for");
+}
+
+#[test]
+fn test_overflow_page_of_numbered_rows() {
+    let mut overflow_config = config(Some(ROSTER.to_vec()));
+    overflow_config.overflow_page = true;
+    let layout = layout_sheet(&overflow_config).unwrap();
+    let plain = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+    assert_eq!(layout.pages.len(), plain.pages.len() + 1, "One extra page");
+
+    let (page, _) = layout.find_text(OVERFLOW_PAGE_TITLE).expect("overflow page is titled");
+    assert_eq!(page, 1);
+    let overflow = &layout.pages[1];
+    assert_eq!(overflow.images().count(), 1, "The page repeats the event QR code");
+    assert!(overflow.texts().any(|t| t.text == "1."), "Rows are numbered");
+    let numbered = overflow
+        .texts()
+        .filter(|t| t.text.strip_suffix('.').is_some_and(|number| number.parse::<u32>().is_ok()))
+        .count();
+    assert!(numbered >= 40, "The page is filled with blank rows");
+    assert_eq!(overflow.texts().filter(|t| t.text == "NAME").count(), 2);
+}
+
+#[test]
+fn test_banner_above_grid() {
+    let mut banner_config = config(Some(ROSTER.to_vec()));
+    banner_config.banner = Some("CLASS MOVED TO ROOM 204".to_string());
+    let layout = layout_sheet(&banner_config).unwrap();
+
+    let (page, banner) = layout.find_text("CLASS MOVED TO ROOM 204").expect("banner is printed");
+    assert_eq!(page, 0);
+    let (_, class_name) = layout.find_text("Tuesday Beginner").unwrap();
+    let name_heading = layout.pages[0].texts().find(|t| t.text == "NAME").unwrap();
+    assert!(class_name.y > banner.y && banner.y > name_heading.y, "Banner sits between header and grid");
+    assert!(banner.size > 18.0, "Banner is printed large");
+
+    let long = "A".repeat(80);
+    banner_config.banner = Some(long.clone());
+    let layout = layout_sheet(&banner_config).unwrap();
+    let (_, banner) = layout.find_text(&long).unwrap();
+    let width = text_width_mm(&long, banner.size, FontStyle::Bold);
+    assert!(
+        banner.x >= MARGIN_MM && banner.x + width <= attendance_pdf::PAGE_WIDTH_MM - MARGIN_MM + 0.01,
+        "Long banners shrink to fit"
+    );
+}
+
+#[test]
+fn test_coupon_strip_along_bottom() {
+    let mut coupon_config = config(Some(ROSTER.to_vec()));
+    coupon_config.coupons = 3;
+    let layout = layout_sheet(&coupon_config).unwrap();
+
+    let coupons: Vec<_> = layout.pages[0].texts().filter(|t| t.text == "FIRST LESSON FREE").collect();
+    assert_eq!(coupons.len(), 3);
+    let serials: Vec<&str> = layout.pages[0]
+        .texts()
+        .filter(|t| t.text.starts_with("No. "))
+        .map(|t| t.text.as_str())
+        .collect();
+    assert_eq!(serials, ["No. ABCD1234-001", "No. ABCD1234-002", "No. ABCD1234-003"]);
+    assert_eq!(layout.pages[0].images().count(), 4, "Each coupon carries the class QR code");
+
+    let mailing = layout.pages[0].texts().find(|t| t.text.contains("MAILING LIST")).unwrap();
+    assert!(coupons.iter().all(|coupon| coupon.y < mailing.y), "Coupons tear off below the mailing list");
+}
+
+#[test]
+fn test_feedback_qr_and_comments_box() {
+    let mut feedback_config = config(Some(ROSTER.to_vec()));
+    feedback_config.feedback_url = Some("https://forms.gle/example".to_string());
+    let layout = layout_sheet(&feedback_config).unwrap();
+
+    let (page, heading) = layout.find_text("HOW WAS TODAY'S LESSON?").expect("feedback heading is printed");
+    assert_eq!(page, 0);
+    assert_eq!(layout.pages[0].images().count(), 2, "The feedback QR joins the class QR");
+    let mailing = layout.pages[0].texts().find(|t| t.text.contains("MAILING LIST")).unwrap();
+    assert!(heading.y < mailing.y, "Feedback sits below the mailing list");
+
+    let plain = layout_sheet(&config(Some(ROSTER.to_vec()))).unwrap();
+    assert!(plain.find_text("HOW WAS TODAY'S LESSON?").is_none());
+}
+
+#[test]
+fn test_signature_column_rejects_oversized_roster() {
+    let names: Vec<String> = (1..=60).map(|i| format!("Student {}", i)).collect();
+    let mut signature_config = config(Some(names.iter().map(String::as_str).collect()));
+    signature_config.signature_column = true;
+
+    let err = layout_sheet(&signature_config).unwrap_err().to_string();
+    assert!(err.contains("--signature-column fits at most"), "Unexpected error: {}", err);
+}
+
+#[test]
+fn test_oversized_roster_overflows_with_warning() {
+    let roster_config = |count: usize| {
+        let names: Vec<String> = (1..=count).map(|i| format!("Student {}", i)).collect();
+        let mut config = config(Some(names.iter().map(String::as_str).collect()));
+        config.mailing_placement = MailingPlacement::FirstPage;
+        config
+    };
+
+    assert_eq!(roster_overflow(&roster_config(40)), None);
+
+    // Too many for two columns: three narrower ones on the first page
+    let three = roster_config(60);
+    assert_eq!(roster_overflow(&three), Some(RosterOverflow::ThreeColumns { names: 60 }));
+    let layout = layout_sheet(&three).unwrap();
+    let mut columns: Vec<f32> = (1..=60)
+        .map(|i| {
+            let (page, text) = layout.find_text(&format!("Student {}", i)).unwrap();
+            assert_eq!(page, 0, "Student {} should be on the first page", i);
+            text.x
+        })
+        .collect();
+    columns.dedup();
+    assert_eq!(columns.len(), 3, "Names should run down three columns");
+
+    // Too many for three: the roster continues onto more pages
+    let long = roster_config(200);
+    let Some(RosterOverflow::Pages { names: 200, pages }) = roster_overflow(&long) else {
+        panic!("Expected the roster to continue over more pages");
+    };
+    assert!(pages > 1);
+    let layout = layout_sheet(&long).unwrap();
+    let (last_page, _) = layout.find_text("Student 200").unwrap();
+    assert_eq!(last_page, pages - 1);
+    assert!(roster_overflow(&long).unwrap().to_string().contains(&format!("over {} pages", pages)));
+}
+
+#[test]
+fn test_expiring_membership_marker_and_legend() {
+    let mut expiry_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = expiry_config.roster.as_mut() {
+        roster[0].expires = NaiveDate::from_ymd_opt(2025, 1, 20);
+        roster[1].expires = NaiveDate::from_ymd_opt(2025, 6, 1);
+    }
+    let layout = layout_sheet(&expiry_config).unwrap();
+
+    assert!(layout.find_text("Alice Johnson \u{2021}").is_some(), "Alice renews within 30 days");
+    assert!(layout.find_text("Bob Smith").is_some(), "Bob's membership is current");
+
+    let legend = layout
+        .texts()
+        .find(|(_, t)| t.text.starts_with("\u{2021} Membership expires by Feb 6"))
+        .map(|(_, t)| t.clone())
+        .expect("Legend should explain the marker");
+    let (_, henry) = layout.find_text("Henry Taylor").unwrap();
+    assert!(legend.y < henry.y, "Legend goes under the grid");
+}
+
+#[test]
+fn test_configured_markers_legend() {
+    let mut marker_config = config(Some(vec!["Alice Johnson *", "Bob Smith \u{2020}", "Carol Davis"]));
+    marker_config.markers = vec![
+        RosterMarker { symbol: "*".to_string(), legend: "new student".to_string() },
+        RosterMarker { symbol: "\u{2020}".to_string(), legend: "needs partner".to_string() },
+        RosterMarker { symbol: "#".to_string(), legend: "unused".to_string() },
+    ];
+    let layout = layout_sheet(&marker_config).unwrap();
+
+    let legend = layout
+        .texts()
+        .find(|(_, t)| t.text.starts_with("* new student"))
+        .map(|(_, t)| t.text.clone())
+        .expect("Legend should list the markers in use");
+    assert!(legend.contains("\u{2020} needs partner"));
+    assert!(!legend.contains("unused"), "Markers nobody has are left out");
+
+    // "*" now means "new student", so the mailing list heading is unstarred
+    assert!(layout.find_text("JOIN MY MAILING LIST").is_some());
+    assert!(layout.find_text("* JOIN MY MAILING LIST").is_none());
+}
+
+#[test]
+fn test_mono_is_pure_black() {
+    let mut mono_config = config(Some(ROSTER.to_vec()));
+    mono_config.logo = Some(Arc::new(PreparedImage { width_px: 2, height_px: 1, rgb: vec![0; 6] }));
+    if let Some(roster) = mono_config.roster.as_mut() {
+        roster[1].highlight = true;
+    }
+    let color_layout = layout_sheet(&mono_config).unwrap();
+    mono_config.mono = true;
+    let layout = layout_sheet(&mono_config).unwrap();
+
+    let lines: Vec<_> = layout.pages[0].lines().collect();
+    assert!(lines.iter().all(|line| line.stroke.color.r == 0.0 && line.stroke.color.g == 0.0));
+    assert!(lines.iter().all(|line| line.stroke.thickness >= 0.6), "Thin rules should be thickened");
+    assert!(lines.iter().all(|line| line.stroke.thickness < 2.0), "Highlight band should be dropped");
+    assert_eq!(
+        layout.pages[0].images().count() + 1,
+        color_layout.pages[0].images().count(),
+        "Logo should be left off, QR code kept"
+    );
+}
+
+#[test]
+fn test_blank_continuation_pages_have_header() {
+    let mut blank_config = config(None);
+    blank_config.blank_rows = 64;
+    let layout = layout_sheet(&blank_config).unwrap();
+    assert!(layout.pages.len() > 2);
+
+    let heading = "Tuesday Beginner (continued)";
+    assert!(layout.pages[0].texts().all(|t| t.text != heading));
+    for page in &layout.pages[1..] {
+        let header = page.texts().find(|t| t.text == heading).expect("Continuation header missing");
+        assert!(page.texts().any(|t| t.text == "Tuesday, January 7, 2025   Event ABCD1234"));
+        let first_table = page.texts().find(|t| t.text.starts_with("Table ")).unwrap();
+        assert!(first_table.y < header.y - 6.0, "Rows should start below the header");
+    }
+
+    // Five tables still fit on each page the rows continue onto
+    let tables = layout.pages[1].texts().filter(|t| t.text.starts_with("Table ")).count();
+    assert_eq!(tables, 5);
+}
+
+#[test]
+fn test_economy_packs_more_rows() {
+    let mut economy_config = config(None);
+    economy_config.blank_rows = 64;
+    economy_config.logo = Some(Arc::new(PreparedImage { width_px: 2, height_px: 1, rgb: vec![0; 6] }));
+    let full = layout_sheet(&economy_config).unwrap();
+    economy_config.economy = true;
+    let economy = layout_sheet(&economy_config).unwrap();
+
+    assert!(economy.pages.len() < full.pages.len(), "Shorter rows should need fewer pages");
+    assert_eq!(economy.pages[0].images().count(), 1, "Only the QR code, no logo");
+    let qr = economy.pages[0].images().next().unwrap();
+    assert_eq!(qr.width, 20.0);
+
+    let (_, title) = economy.find_text("CLASS ATTENDANCE").unwrap();
+    let (_, full_title) = full.find_text("CLASS ATTENDANCE").unwrap();
+    assert!(title.size < full_title.size, "Header should be smaller");
+
+    let thickest = |layout: &SheetLayout| {
+        layout.pages[0].lines().map(|line| line.stroke.thickness).fold(0.0, f32::max)
+    };
+    assert!(thickest(&economy) < thickest(&full), "Rules should be thinner");
+}
+
+#[test]
+fn test_qr_rasterized_for_print_resolution() {
+    let mut qr_config = config(None);
+    let qr_width = |config: &AttendanceConfig| {
+        let layout = layout_sheet(config).unwrap();
+        let width = layout.pages[0].images().next().unwrap().image.width_px;
+        width
+    };
+    let at_600 = qr_width(&qr_config);
+    assert!(at_600 as f32 >= 30.0 / 25.4 * 600.0, "{} px is short of 600 dpi", at_600);
+    qr_config.qr_dpi = 300;
+    let at_300 = qr_width(&qr_config);
+    assert!(at_300 < at_600 && at_300 as f32 >= 30.0 / 25.4 * 300.0);
+}
+
+#[test]
+fn test_qr_codes_shared_across_event_configs() {
+    let mut base = config(None);
+    base.feedback_url = Some("https://forms.gle/example".to_string());
+    let mut other_event = base.clone();
+    other_event.event_id = "EFEF5678".to_string();
+
+    let images = |config: &AttendanceConfig| {
+        let layout = layout_sheet(config).unwrap();
+        layout.pages[0].images().map(|image| Arc::clone(&image.image)).collect::<Vec<_>>()
+    };
+    let (first, second, again) = (images(&base), images(&other_event), images(&base));
+    assert!(!Arc::ptr_eq(&first[0], &second[0]), "Each event has its own class QR");
+    assert!(Arc::ptr_eq(&first[1], &second[1]), "The feedback QR is rasterized once per run");
+    assert!(Arc::ptr_eq(&first[0], &again[0]), "A sheet rendered again reuses its class QR");
+}
+
+#[test]
+fn test_roster_style_hints() {
+    let mut style_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = style_config.roster.as_mut() {
+        roster[0].bold = true;
+        roster[1].highlight = true;
+    }
+    let layout = layout_sheet(&style_config).unwrap();
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, bob) = layout.find_text("Bob Smith").unwrap();
+    let (_, carol) = layout.find_text("Carol Davis").unwrap();
+    assert_eq!(alice.font, FontStyle::Bold);
+    assert_eq!(bob.font, FontStyle::Regular);
+    assert_eq!(carol.font, FontStyle::Regular);
+
+    // One thick band behind Bob's name, drawn before the name itself
+    let elements = &layout.pages[0].elements;
+    let band = elements
+        .iter()
+        .position(|e| matches!(e, Element::Line(l) if l.stroke.thickness > 5.0))
+        .expect("Highlighted row should have a band");
+    let bob_index = elements
+        .iter()
+        .position(|e| matches!(e, Element::Text(t) if t.text == "Bob Smith"))
+        .unwrap();
+    assert!(band < bob_index, "Band must be drawn under the text");
+    let bands = elements
+        .iter()
+        .filter(|e| matches!(e, Element::Line(l) if l.stroke.thickness > 5.0))
+        .count();
+    assert_eq!(bands, 1);
+}
+
+#[test]
+fn test_roster_note_under_name() {
+    let mut note_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = note_config.roster.as_mut() {
+        roster[0].note = Some("owes $10".to_string());
+    }
+    let layout = layout_sheet(&note_config).unwrap();
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, note) = layout.find_text("owes $10").expect("Note should be printed");
+    let (_, bob) = layout.find_text("Bob Smith").unwrap();
+    let (_, carol) = layout.find_text("Carol Davis").unwrap();
+
+    assert!((note.x - alice.x).abs() < 0.01, "Note lines up under the name");
+    assert!(note.y < alice.y && note.y > bob.y, "Note sits between Alice and the next row");
+    assert!(
+        alice.y - bob.y > bob.y - carol.y + 1.0,
+        "Alice's row should be taller than a row without a note"
+    );
+}
+
+#[test]
+fn test_teacher_copy_keeps_private_fields() {
+    let mut private_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = private_config.roster.as_mut() {
+        roster[0].note = Some("owes $10".to_string());
+        roster[0].needs = Some("wheelchair access".to_string());
+        roster[1].needs = Some("gluten-free".to_string());
+    }
+    let layout = layout_sheet(&private_config).unwrap();
+    assert!(layout.find_text("gluten-free").is_none(), "Needs are never on an ordinary sheet");
+
+    let layout = layout_sheet(&teacher_copy(&private_config)).unwrap();
+    assert!(layout.find_text("CLASS ATTENDANCE \u{2013} TEACHER COPY").is_some());
+    assert!(layout.find_text("owes $10; wheelchair access").is_some());
+    assert!(layout.find_text("gluten-free").is_some());
+
+    let layout = layout_sheet(&sign_in_copy(&private_config)).unwrap();
+    assert!(layout.find_text("CLASS ATTENDANCE").is_some());
+    assert!(
+        !layout.texts().any(|(_, text)| text.text.contains("owes") || text.text.contains("gluten")),
+        "The sign-in copy leaves out notes and needs"
+    );
+}
+
+#[test]
+fn test_new_student_badge_and_welcome() {
+    let mut new_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = new_config.roster.as_mut() {
+        roster[0].is_new = true;
+    }
+    let layout = layout_sheet(&new_config).unwrap();
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, badge) = layout.find_text("NEW").expect("New student should be badged");
+    let name_width = text_width_mm("Alice Johnson", alice.size, alice.font);
+    assert!(badge.x > alice.x + name_width, "Badge should follow the name");
+    assert!((badge.y - alice.y).abs() < 0.01);
+
+    assert!(layout.find_text("NEW: please welcome Alice Johnson").is_some());
+}
+
+#[test]
+fn test_confirmed_students_have_ticked_boxes() {
+    let mut rsvp_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = rsvp_config.roster.as_mut() {
+        roster[1].confirmed = true;
+    }
+    let layout = layout_sheet(&rsvp_config).unwrap();
+
+    // A rounded box per student, only Bob's shaded and ticked
+    let boxes: Vec<_> = layout.pages[0].rects().filter(|rect| rect.style.radius > 0.0).collect();
+    assert_eq!(boxes.len(), ROSTER.len());
+    let filled: Vec<_> = boxes.iter().filter(|rect| rect.style.fill.is_some()).collect();
+    assert_eq!(filled.len(), 1);
+    let (_, bob) = layout.find_text("Bob Smith").unwrap();
+    assert!((filled[0].y - bob.y).abs() < 1.0);
+    let ticks = layout.pages[0]
+        .lines()
+        .filter(|line| line.stroke.thickness == 1.0)
+        .filter(|line| line.x1 >= filled[0].x && line.x1 <= filled[0].x + filled[0].width)
+        .count();
+    assert_eq!(ticks, 2);
+    assert!(layout.find_text("Box already ticked: confirmed online (1 of 8 expected)").is_some());
+
+    // Mono keeps the tick but not the shading
+    rsvp_config.mono = true;
+    let layout = layout_sheet(&rsvp_config).unwrap();
+    assert!(layout.pages[0].rects().all(|rect| rect.style.fill.is_none()));
+}
+
+#[test]
+fn test_junior_marker_and_count() {
+    let mut junior_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = junior_config.roster.as_mut() {
+        roster[1].junior = true;
+        roster[2].junior = true;
+    }
+    let layout = layout_sheet(&junior_config).unwrap();
+    assert!(layout.find_text("Bob Smith \u{2022}").is_some(), "Juniors are marked after the name");
+    assert!(layout.find_text("Alice Johnson").is_some());
+    assert!(layout.texts().any(|(_, text)| text.text.contains("\u{2022} Junior player (2 on the roster)")));
+
+    let event = ManifestEvent::from_config(&junior_config);
+    let checkins: Vec<CheckIn> = ["Alice Johnson", "Bob Smith", "Zoe Walker"]
+        .iter()
+        .map(|student| CheckIn {
+            event_id: event.event_id.clone(),
+            serial: format!("X-{}", student),
+            student: Some(student.to_string()),
+            time: "2025-01-07T19:00:00-08:00".to_string(),
+        })
+        .collect();
+    let report = reconcile(&event, &checkins);
+    assert_eq!(report.juniors, 1, "Only Bob is a junior who came; Carol stayed home");
+}
+
+#[test]
+fn test_pronouns_follow_name() {
+    let mut pronoun_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = pronoun_config.roster.as_mut() {
+        roster[0].pronouns = Some("she/her".to_string());
+    }
+    let layout = layout_sheet(&pronoun_config).unwrap();
+    let (_, name) = layout.find_text("Alice Johnson").expect("name is printed");
+    let (_, pronouns) = layout.find_text("(she/her)").expect("pronouns are printed");
+    assert!(pronouns.x > name.x, "Pronouns come after the name");
+    assert_eq!(pronouns.y, name.y, "Pronouns share the name's line");
+    assert!(pronouns.size < name.size, "Pronouns are printed small");
+}
+
+#[test]
+fn test_long_name_fits_its_cell() {
+    let long_name = "Maximiliana Wilhelmina Bartholomew-Fitzgerald-Montgomery";
+    let mut long_config = config(Some(vec![long_name, "Bob Li"]));
+    long_config.signature_column = true;
+    if let Some(roster) = long_config.roster.as_mut() {
+        roster[0].pronouns = Some("she/her".to_string());
+        roster[0].is_new = true;
+        roster[1].pronouns = Some("he/him".to_string());
+    }
+    let layout = layout_sheet(&long_config).unwrap();
+    // Where the signature cell starts in the column of a name at `x`
+    let signature_x = |x: f32| {
+        let headers = layout.texts().filter(|(_, text)| text.text == "SIGNATURE" && text.x > x);
+        headers.map(|(_, text)| text.x - 2.0).fold(f32::INFINITY, f32::min)
+    };
+
+    // Cut short at the smallest size, its marks dropped for the name
+    let (_, name) = layout.texts().find(|(_, text)| text.text.starts_with("Maximiliana")).unwrap();
+    assert!(name.text.ends_with('\u{2026}'), "{} should be cut short", name.text);
+    assert!(name.x + text_width_mm(&name.text, name.size, name.font) <= signature_x(name.x));
+    assert!(name.size < 11.0);
+    assert!(layout.find_text("(she/her)").is_none() && layout.find_text("NEW").is_none());
+
+    // A name that fits keeps its size and marks
+    let (_, short) = layout.find_text("Bob Li").unwrap();
+    let (_, pronouns) = layout.find_text("(he/him)").unwrap();
+    assert_eq!(short.size, 11.0);
+    assert!(pronouns.x + text_width_mm(&pronouns.text, pronouns.size, pronouns.font) <= signature_x(short.x));
+}
+
+#[test]
+fn test_text_width_measurement() {
+    // "W" is the widest capital; 1000 units = 1 em
+    let em = text_width_mm("W", 10.0, FontStyle::Regular) / 0.944;
+    assert!((em - 10.0 * 25.4 / 72.0).abs() < 0.01);
+    assert!(text_width_mm("Bridge", 11.0, FontStyle::Bold) > text_width_mm("Bridge", 11.0, FontStyle::Regular));
+    assert_eq!(text_width_mm("", 11.0, FontStyle::Regular), 0.0);
+}
+
+#[test]
+fn test_birthday_cake_and_announcement() {
+    // 2025-01-07 is a Tuesday, so the class week runs Jan 6 to Jan 12
+    let mut birthday_config = config(Some(ROSTER.to_vec()));
+    birthday_config.announce_birthdays = true;
+    if let Some(roster) = birthday_config.roster.as_mut() {
+        roster[0].birthday = Some((1, 12));
+        roster[1].birthday = Some((1, 13));
+    }
+    let layout = layout_sheet(&birthday_config).unwrap();
+
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let name_end = alice.x + text_width_mm("Alice Johnson", alice.size, alice.font);
+    let cake_lines = layout.pages[0]
+        .lines()
+        .filter(|line| line.x1 > name_end && line.x1 < name_end + 8.0 && (line.y1 - alice.y).abs() < 4.0)
+        .count();
+    assert!(cake_lines > 0, "Cake should follow Alice's name");
+
+    let (_, bob) = layout.find_text("Bob Smith").unwrap();
+    let bob_end = bob.x + text_width_mm("Bob Smith", bob.size, bob.font);
+    assert!(!layout.pages[0]
+        .lines()
+        .any(|line| line.x1 > bob_end && line.x1 < bob_end + 8.0 && (line.y1 - bob.y).abs() < 2.0));
+
+    assert!(layout.find_text("Happy birthday this week: Alice Johnson!").is_some());
+
+    birthday_config.announce_birthdays = false;
+    let layout = layout_sheet(&birthday_config).unwrap();
+    assert!(!layout.texts().any(|(_, text)| text.text.starts_with("Happy birthday")));
+}
+
+#[test]
+fn test_last_first_display_order_sorts_by_family_name() {
+    let sheet = AttendanceSheet::builder()
+        .class("Tuesday Beginner")
+        .roster(vec![
+            "Bob Smith".to_string(),
+            "Anna van der Berg".to_string(),
+            "Adams, Carol".to_string(),
+        ])
+        .display_order(DisplayOrder::LastFirst)
+        .build()
+        .unwrap();
+    let names: Vec<&str> = sheet
+        .config()
+        .roster
+        .iter()
+        .flatten()
+        .map(|student| student.name.as_str())
+        .collect();
+    assert_eq!(names, ["Adams, Carol", "Anna van der Berg", "Bob Smith"]);
+
+    let layout = sheet.layout().unwrap();
+    let (_, adams) = layout.find_text("Adams, Carol").unwrap();
+    let (_, berg) = layout.find_text("van der Berg, Anna").unwrap();
+    assert!(adams.y > berg.y, "Adams should be listed above Berg");
+    assert!(layout.find_text("Smith, Bob").is_some());
+}
+
+#[test]
+fn test_nicknames_replace_given_names() {
+    let mut nickname_config = config(Some(ROSTER.to_vec()));
+    if let Some(roster) = nickname_config.roster.as_mut() {
+        roster[0].name = "Robert Smith".to_string();
+        roster[0].nickname = Some("Bob".to_string());
+        roster[0].is_new = true;
+    }
+    let layout = layout_sheet(&nickname_config).unwrap();
+    assert!(layout.find_text("Robert Smith").is_some(), "Nicknames are opt-in");
+
+    nickname_config.use_nicknames = true;
+    let layout = layout_sheet(&nickname_config).unwrap();
+    assert!(layout.find_text("Bob Smith").is_some());
+    assert!(layout.find_text("Robert Smith").is_none());
+    assert!(layout.texts().any(|(_, text)| text.text.ends_with("please welcome Bob Smith")));
+
+    nickname_config.display_order = DisplayOrder::LastFirst;
+    let layout = layout_sheet(&nickname_config).unwrap();
+    assert!(layout.find_text("Smith, Bob").is_some());
+}
+
+#[test]
+fn test_masterpoint_sheet_lists_attendees_with_acbl_numbers() {
+    let event = ManifestEvent::from_config(&config(Some(ROSTER.to_vec())));
+    let checkin = |serial: &str, student: &str| CheckIn {
+        event_id: event.event_id.clone(),
+        serial: serial.to_string(),
+        student: Some(student.to_string()),
+        time: "2025-01-07T19:02:00-08:00".to_string(),
+    };
+    let report = reconcile(&event, &[checkin("X-001", "Alice Johnson"), checkin("X-002", "Zoe Walker")]);
+    let roster = [RosterEntry {
+        name: "Alice Johnson".to_string(),
+        acbl_number: Some("K123456".to_string()),
+        ..RosterEntry::default()
+    }];
+
+    let sheet = MasterpointSheet::new(&report, &roster, "123456", "0.15", Some("Rick"));
+    assert_eq!(
+        sheet.players,
+        [
+            ("Alice Johnson".to_string(), Some("K123456".to_string())),
+            ("Zoe Walker".to_string(), None),
+        ]
+    );
+
+    let layout = sheet.layout();
+    let (_, alice) = layout.find_text("Alice Johnson").unwrap();
+    let (_, number) = layout.find_text("K123456").unwrap();
+    assert!((alice.y - number.y).abs() < 0.1 && number.x > alice.x);
+    assert_eq!(layout.texts().filter(|(_, text)| text.text == "0.15").count(), 2);
+    assert!(layout.find_text("Award per player: 0.15 MP").is_some());
+    assert!(layout.find_text("Rick").is_some());
+}
+
+/// A session of the test roster's class where `students` checked in
+fn session(event_id: &str, date: &str, students: &[&str]) -> (ManifestEvent, Reconciliation) {
+    let mut event = ManifestEvent::from_config(&config(Some(ROSTER.to_vec())));
+    event.event_id = event_id.to_string();
+    event.date = date.to_string();
+    let checkins: Vec<CheckIn> = students
+        .iter()
+        .map(|student| CheckIn {
+            event_id: event_id.to_string(),
+            serial: format!("X-{}", student),
+            student: Some(student.to_string()),
+            time: format!("{}T19:00:00-08:00", date),
+        })
+        .collect();
+    let report = reconcile(&event, &checkins);
+    (event, report)
+}
+
+#[test]
+fn test_trend_report_charts_weekly_attendance() {
+    let sessions = [
+        session("TR000002", "2025-01-21", &["Alice Johnson", "Zoe Walker", "Bob Smith"]),
+        session("TR000001", "2025-01-07", &["Alice Johnson", "Bob Smith"]),
+    ];
+
+    let trends = TrendReport::new(&sessions).unwrap();
+    let weeks: Vec<(String, usize, usize)> = trends
+        .weeks
+        .iter()
+        .map(|week| (week.week.to_string(), week.players, week.new_players))
+        .collect();
+    assert_eq!(
+        weeks,
+        [
+            ("2025-01-06".to_string(), 2, 2),
+            ("2025-01-13".to_string(), 0, 0),
+            ("2025-01-20".to_string(), 3, 1),
+        ],
+        "Weeks without a class stay in, and only Zoe is new in the last"
+    );
+
+    let layout = trends.layout();
+    assert!(layout.find_text("Players per week").is_some());
+    assert!(layout.find_text("New and returning players").is_some());
+    assert!(layout.find_text("Jan 20").is_some());
+    // Two bars: strokes as wide as most of a week's slot
+    let bars = layout.pages[0].lines().filter(|line| line.stroke.thickness > 20.0).count();
+    assert_eq!(bars, 2);
+}
+
+#[test]
+fn test_semester_summary() {
+    let mut other_class = session("SM000003", "2025-02-04", &["Bob Smith", "Zoe Walker"]);
+    other_class.0.class_name = "Thursday Play".to_string();
+    let sessions = [
+        session("SM000001", "2025-01-07", &["Alice Johnson", "Bob Smith"]),
+        session("SM000002", "2025-01-14", &["Alice Johnson", "Bob Smith", "Carol Davis"]),
+        other_class,
+        session("SM000004", "2025-07-01", &["Alice Johnson"]),
+    ];
+    let date = |text: &str| NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap();
+    let joined = [None, Some(date("2024-12-01")), Some(date("2025-01-07")), Some(date("2025-08-01"))];
+
+    let summary = SemesterSummary::new(&sessions, date("2025-01-01"), date("2025-06-30"), Some(&joined)).unwrap();
+    assert_eq!(summary.student_sessions, 7, "The July session is outside the semester");
+    assert_eq!(summary.students, 4);
+    assert_eq!(
+        summary.top_attendees[..2],
+        [("Bob Smith".to_string(), 3), ("Alice Johnson".to_string(), 2)]
+    );
+    assert_eq!(summary.classes.len(), 2);
+    assert_eq!(summary.classes[0].class_name, "Thursday Play");
+    assert_eq!(summary.classes[1].sessions, [(date("2025-01-07"), 2), (date("2025-01-14"), 3)]);
+    assert_eq!(summary.mailing, Some(MailingGrowth { start: 2, end: 3 }));
+
+    let layout = summary.layout();
+    assert_eq!(layout.pages.len(), 3, "Overview, then a page per class");
+    assert!(layout.find_text("Student-sessions: 7").is_some());
+    assert!(layout.find_text("Mailing list: 3 members, up from 2 (+1)").is_some());
+    let (page, _) = layout.find_text("Tuesday Beginner (2 sessions)").unwrap();
+    assert_eq!(page, 2);
+}
+
+// ============================================================================
+// Layout Properties
+// ============================================================================
+
+/// Everything drawn stays inside the page margins, but for the stamps along
+/// the bottom margin and the event ID up the side
+fn assert_within_margins(layout: &SheetLayout) -> Result<(), TestCaseError> {
+    let (left, right) = (MARGIN_MM - 0.01, PAGE_WIDTH_MM - MARGIN_MM + 0.01);
+    let (bottom, top) = (MARGIN_MM - 0.01, PAGE_HEIGHT_MM - MARGIN_MM + 0.01);
+    for (page, text) in layout.texts() {
+        let bottom = if text.y == MARGIN_MM / 2.0 { 0.0 } else { bottom };
+        if text.rotation != Rotation::Upright {
+            prop_assert!(text.x > 0.0 && text.x < PAGE_WIDTH_MM, "{:?} on page {} is off the page", text, page);
+            continue;
+        }
+        let end = text.x + text_width_mm(&text.text, text.size, text.font);
+        prop_assert!(text.x >= left && end <= right, "{:?} on page {} runs past a side margin", text, page);
+        prop_assert!(text.y >= bottom && text.y <= top, "{:?} on page {} is off the page", text, page);
+    }
+    for (index, page) in layout.pages.iter().enumerate() {
+        for line in page.lines() {
+            let (x1, x2) = (line.x1.min(line.x2), line.x1.max(line.x2));
+            let (y1, y2) = (line.y1.min(line.y2), line.y1.max(line.y2));
+            prop_assert!(x1 >= left && x2 <= right && y1 >= bottom && y2 <= top, "{:?} on page {}", line, index);
+        }
+    }
+    Ok(())
+}
+
+/// Distinct names in sorted order; a hash set's order would change from run
+/// to run, so a saved failure would not replay
+fn roster_names() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::btree_set("[A-Z][a-z]{1,10} [A-Z][a-z]{1,12}", 0..120)
+        .prop_map(|names| names.into_iter().collect())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        // Beside this file: the default looks for src/lib.rs and skips tests/
+        failure_persistence: Some(Box::new(FileFailurePersistence::WithSource("proptest-regressions"))),
+        ..ProptestConfig::with_cases(48)
+    })]
+
+    #[test]
+    fn prop_roster_sheet_keeps_every_name_in_bounds(
+        names in roster_names(),
+        economy in any::<bool>(),
+        signature in any::<bool>(),
+    ) {
+        let mut roster_config = config(Some(names.iter().map(String::as_str).collect()));
+        roster_config.economy = economy;
+        roster_config.signature_column = signature;
+        // Only a signature column turns a roster away, when there is no room
+        // left to sign
+        let Ok(layout) = layout_sheet(&roster_config) else {
+            prop_assert!(signature);
+            return Ok(());
+        };
+        assert_within_margins(&layout)?;
+
+        // Every name once, in full or cut short to fit its cell, and rows no
+        // closer than the minimum
+        let min_row_height = if signature { SIGNATURE_MIN_ROW_HEIGHT_MM } else { MIN_ROW_HEIGHT_MM };
+        let shows = |text: &str, name: &str| {
+            text == name || text.strip_suffix('\u{2026}').is_some_and(|cut| name.starts_with(cut.trim_end()))
+        };
+        let mut placed = Vec::new();
+        for name in &names {
+            let found: Vec<_> = layout.texts().filter(|(_, text)| shows(&text.text, name)).collect();
+            prop_assert_eq!(found.len(), 1, "{} should appear once", name);
+            let (page, text) = found[0];
+            placed.push((page, text.x.to_bits(), text.y));
+        }
+        placed.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(b.2.total_cmp(&a.2)));
+        for pair in placed.windows(2) {
+            if (pair[0].0, pair[0].1) == (pair[1].0, pair[1].1) {
+                prop_assert!(pair[0].2 - pair[1].2 >= min_row_height - 0.01, "Rows too close: {:?}", pair);
+            }
+        }
+    }
+
+    #[test]
+    fn prop_blank_sheet_has_every_table_in_bounds(
+        rows in 1u32..120,
+        economy in any::<bool>(),
+        placement in prop::sample::select(vec![
+            MailingPlacement::FirstPage,
+            MailingPlacement::LastPage,
+            MailingPlacement::AfterGrid,
+        ]),
+    ) {
+        let mut blank_config = config(None);
+        blank_config.blank_rows = rows;
+        blank_config.economy = economy;
+        blank_config.mailing_placement = placement;
+        let layout = layout_sheet(&blank_config).unwrap();
+        assert_within_margins(&layout)?;
+
+        let tables = layout.texts().filter(|(_, text)| text.text.starts_with("Table ")).count() as u32;
+        prop_assert_eq!(tables, rows.div_ceil(4));
+        prop_assert!(layout.find_text("JOIN MY MAILING LIST").is_some());
+    }
+}
@@ -0,0 +1,80 @@
+use attendance_pdf::output::{expand_name_template, slugify, DEFAULT_NAME_TEMPLATE};
+use attendance_pdf::{AttendanceConfig, DisplayOrder, MailingPlacement, QrCache, TextAlign};
+use chrono::NaiveDate;
+
+fn config(class_name: &str) -> AttendanceConfig {
+    AttendanceConfig {
+        class_name: class_name.to_string(),
+        teacher: "Rick".to_string(),
+        teacher_email: None,
+        substitute: None,
+        date: NaiveDate::from_ymd_opt(2025, 1, 7).unwrap(),
+        location: String::new(),
+        wifi: None,
+        emergency: None,
+        banner: None,
+        feedback_url: None,
+        emergency_page: None,
+        event_id: "ABCD1234".to_string(),
+        roster: None,
+        online_attendees: None,
+        blank_rows: 32,
+        mailing_list: true,
+        mailing_rows: 4,
+        mailing_title: None,
+        title_align: TextAlign::Left,
+        mailing_placement: MailingPlacement::LastPage,
+        helper_rows: 0,
+        coupons: 0,
+        signature_column: false,
+        photo_consent: false,
+        time_column: false,
+        raffle: false,
+        draw_numbers: false,
+        announce_birthdays: false,
+        display_order: DisplayOrder::FirstLast,
+        use_nicknames: false,
+        markers: Vec::new(),
+        calendar: None,
+        waiver: None,
+        checklist: None,
+        hands: None,
+        overflow_page: false,
+        copies: 1,
+        mono: false,
+        economy: false,
+        qr_dpi: 600,
+        qr_codes: QrCache::default(),
+        logo: None,
+        sponsor: None,
+        reproducible: false,
+        revision: None,
+        teacher_copy: false,
+    }
+}
+
+#[test]
+fn test_slugify_transliterates_to_ascii() {
+    assert_eq!(slugify("Café Bridge", false), "cafe-bridge");
+    assert_eq!(slugify("Müller's Größe Klasse", false), "mullers-grosse-klasse");
+    assert_eq!(slugify("  Tuesday -- Beginner ", false), "tuesday-beginner");
+}
+
+#[test]
+fn test_slugify_keeps_unicode_normalized() {
+    // "e" followed by a combining acute accent composes to a single "é"
+    assert_eq!(slugify("Cafe\u{301} Bridge", true), "caf\u{e9}-bridge");
+    assert_eq!(slugify("Café Bridge", true), "café-bridge");
+}
+
+#[test]
+fn test_default_template_uses_slugged_class() {
+    let name = expand_name_template(
+        DEFAULT_NAME_TEMPLATE,
+        &config("Café Bridge"),
+        "pdf",
+        false,
+    )
+    .unwrap();
+    assert_eq!(name, "attendance-2025-01-07-cafe-bridge.pdf");
+}
@@ -25,7 +25,7 @@ FORMAT 2 — "roster" (pre-printed names):
 - After the roster names, there may be blank rows where additional students wrote their names
 
 BOTH formats may have:
-- A QR code in the top-left corner encoding JSON with fields: app, event_id, name, date, teacher
+- A QR code in the top-left corner encoding JSON with fields: app, event_id, name, date, teacher (and substitute, when a substitute taught)
 - A "JOIN MY MAILING LIST" section at the bottom with Name/Email rows
 - A header showing "CLASS ATTENDANCE", the class name, date, instructor, and event ID
 
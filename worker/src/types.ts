@@ -141,6 +141,8 @@ export interface QrPayload {
   name: string;
   date: string;
   teacher: string;
+  /** Teacher who stood in; `teacher` remains the instructor of record */
+  substitute?: string;
 }
 
 /** Structured result from Claude Vision OCR */